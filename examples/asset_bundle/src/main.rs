@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use picoserve::{
+    response::{Directory, File},
+    routing::get,
+};
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let port = 8000;
+
+    let app = std::rc::Rc::new(
+        // All requests which don't match a route are served from the asset bundle, including "/", which serves "index.html"
+        picoserve::Router::from_service(
+            const {
+                Directory {
+                    files: &[("index.html", File::html(include_str!("static/index.html")))],
+                    sub_directories: &[
+                        (
+                            "styles",
+                            Directory {
+                                files: &[(
+                                    "index.css",
+                                    File::css(include_str!("static/styles/index.css")),
+                                )],
+                                ..Directory::DEFAULT
+                            },
+                        ),
+                        (
+                            "scripts",
+                            Directory {
+                                files: &[(
+                                    "index.js",
+                                    File::javascript(include_str!("static/scripts/index.js")),
+                                )],
+                                ..Directory::DEFAULT
+                            },
+                        ),
+                    ],
+                    index_file: Some("index.html"),
+                    ..Directory::DEFAULT
+                }
+            },
+        )
+        .route("/api/greeting", get(|| async { "Hello World" })),
+    );
+
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
+    .keep_connection_alive();
+
+    let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
+
+    println!("http://localhost:{port}/");
+
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            loop {
+                let (stream, remote_address) = socket.accept().await?;
+
+                println!("Connection from {remote_address}");
+
+                let app = app.clone();
+                let config = config.clone();
+
+                tokio::task::spawn_local(async move {
+                    match picoserve::serve(&app, &config, &mut [0; 2048], stream).await {
+                        Ok(handled_requests_count) => {
+                            println!(
+                                "{handled_requests_count} requests handled from {remote_address}"
+                            )
+                        }
+                        Err(err) => println!("{err:?}"),
+                    }
+                });
+            }
+        })
+        .await
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use picoserve::{
+    extract::BoundedBody,
+    io::Read,
+    response::IntoResponse,
+    routing::{post, post_service},
+};
+
+/// A rolling Adler-32 checksum, which can be updated as each chunk of the body arrives.
+struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    const MODULUS: u32 = 65521;
+
+    fn new() -> Self {
+        Self { a: 1, b: 0 }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.a = (self.a + u32::from(byte)) % Self::MODULUS;
+            self.b = (self.b + self.a) % Self::MODULUS;
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+/// Streams the body through the checksum, so the body may be much larger than any buffer.
+struct StreamingChecksum;
+
+impl picoserve::routing::RequestHandlerService<()> for StreamingChecksum {
+    async fn call_request_handler_service<
+        R: Read,
+        W: picoserve::response::ResponseWriter<Error = R::Error>,
+    >(
+        &self,
+        (): &(),
+        (): (),
+        mut request: picoserve::request::Request<'_, R>,
+        response_writer: W,
+    ) -> Result<picoserve::ResponseSent, W::Error> {
+        let mut reader = request.body_connection.body().reader();
+
+        let mut buffer = [0; 256];
+
+        let mut checksum = Adler32::new();
+
+        loop {
+            let read_size = reader.read(&mut buffer).await?;
+            if read_size == 0 {
+                break;
+            }
+
+            checksum.update(&buffer[..read_size]);
+        }
+
+        format!("{:08x}\r\n", checksum.finish())
+            .write_to(request.body_connection.finalize().await?, response_writer)
+            .await
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let port = 8000;
+
+    let app = std::rc::Rc::new(
+        picoserve::Router::new()
+            .route("/stream", post_service(StreamingChecksum))
+            .route(
+                "/bounded",
+                post(|BoundedBody(body): BoundedBody<4096>| async move {
+                    let mut checksum = Adler32::new();
+                    checksum.update(&body);
+                    format!("{:08x}\r\n", checksum.finish())
+                }),
+            ),
+    );
+
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
+    .keep_connection_alive();
+
+    let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
+
+    println!("curl --data-binary @file http://localhost:{port}/stream");
+    println!("curl --data-binary @file http://localhost:{port}/bounded");
+
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            loop {
+                let (stream, remote_address) = socket.accept().await?;
+
+                println!("Connection from {remote_address}");
+
+                let app = app.clone();
+                let config = config.clone();
+
+                tokio::task::spawn_local(async move {
+                    match picoserve::serve(&app, &config, &mut [0; 1024], stream).await {
+                        Ok(handled_requests_count) => {
+                            println!(
+                                "{handled_requests_count} requests handled from {remote_address}"
+                            )
+                        }
+                        Err(err) => println!("{err:?}"),
+                    }
+                });
+            }
+        })
+        .await
+}
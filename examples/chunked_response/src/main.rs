@@ -43,11 +43,11 @@ async fn main() -> anyhow::Result<()> {
         }),
     ));
 
-    let config = picoserve::Config::new(picoserve::Timeouts {
-        start_read_request: Some(Duration::from_secs(5)),
-        read_request: Some(Duration::from_secs(1)),
-        write: Some(Duration::from_secs(1)),
-    })
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
     .keep_connection_alive();
 
     let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
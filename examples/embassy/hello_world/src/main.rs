@@ -137,11 +137,11 @@ async fn main(spawner: embassy_executor::Spawner) {
 
     let config = make_static!(
         picoserve::Config<Duration>,
-        picoserve::Config::new(picoserve::Timeouts {
-            start_read_request: Some(Duration::from_secs(5)),
-            read_request: Some(Duration::from_secs(1)),
-            write: Some(Duration::from_secs(1)),
-        })
+        picoserve::Config::new(picoserve::Timeouts::new(
+            Some(Duration::from_secs(5)),
+            Some(Duration::from_secs(1)),
+            Some(Duration::from_secs(1)),
+        ))
         .keep_connection_alive()
     );
 
@@ -125,9 +125,9 @@ impl ws::WebSocketCallback for WebsocketEcho {
                         ws::ReadMessageError::Io(err) => return Err(err),
                         ws::ReadMessageError::ReadFrameError(_)
                         | ws::ReadMessageError::MessageStartsWithContinuation
-                        | ws::ReadMessageError::UnexpectedMessageStart => 1002,
-                        ws::ReadMessageError::ReservedOpcode(_) => 1003,
-                        ws::ReadMessageError::TextIsNotUtf8 => 1007,
+                        | ws::ReadMessageError::UnexpectedMessageStart => ws::CloseCode::ProtocolError,
+                        ws::ReadMessageError::ReservedOpcode(_) => ws::CloseCode::UnsupportedData,
+                        ws::ReadMessageError::TextIsNotUtf8 => ws::CloseCode::InvalidPayload,
                     };
 
                     break Some((code, "Websocket Error"));
@@ -204,11 +204,11 @@ async fn main(spawner: embassy_executor::Spawner) {
 
     let config = make_static!(
         picoserve::Config::<Duration>,
-        picoserve::Config::new(picoserve::Timeouts {
-            start_read_request: Some(Duration::from_secs(5)),
-            read_request: Some(Duration::from_secs(1)),
-            write: Some(Duration::from_secs(1)),
-        })
+        picoserve::Config::new(picoserve::Timeouts::new(
+            Some(Duration::from_secs(5)),
+            Some(Duration::from_secs(1)),
+            Some(Duration::from_secs(1)),
+        ))
         .keep_connection_alive()
     );
 
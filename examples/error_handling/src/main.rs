@@ -0,0 +1,131 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use picoserve::{
+    extract::State,
+    response::{DebugValue, HandlerError, StatusCode},
+    routing::{get, parse_path_segment, CatchErrorLayer},
+};
+
+const SLOT_COUNT: usize = 4;
+
+/// Errors returned by handlers, which are mapped to responses in a single place by the `CatchErrorLayer`.
+#[derive(Debug)]
+enum AppError {
+    NoSuchSlot(usize),
+    SlotIsEmpty(usize),
+    SlotIsFull(usize),
+}
+
+/// The JSON body of error responses.
+#[derive(serde::Serialize)]
+struct ErrorBody {
+    message: &'static str,
+    slot: usize,
+}
+
+type Slots = Rc<RefCell<[Option<u32>; SLOT_COUNT]>>;
+
+fn slot(slots: &mut [Option<u32>; SLOT_COUNT], index: usize) -> Result<&mut Option<u32>, AppError> {
+    slots.get_mut(index).ok_or(AppError::NoSuchSlot(index))
+}
+
+async fn get_slot(
+    index: usize,
+    State(slots): State<Slots>,
+) -> Result<DebugValue<u32>, HandlerError<AppError>> {
+    let value = slot(&mut slots.borrow_mut(), index)?.ok_or(AppError::SlotIsEmpty(index))?;
+
+    Ok(DebugValue(value))
+}
+
+async fn fill_slot(
+    (index, value): (usize, u32),
+    State(slots): State<Slots>,
+) -> Result<&'static str, HandlerError<AppError>> {
+    let mut slots = slots.borrow_mut();
+    let slot = slot(&mut slots, index)?;
+
+    if slot.is_some() {
+        return Err(AppError::SlotIsFull(index).into());
+    }
+
+    *slot = Some(value);
+
+    Ok("Slot filled\n")
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let port = 8000;
+
+    let app = Rc::new(
+        picoserve::Router::new()
+            .route(("/slot", parse_path_segment::<usize>()), get(get_slot))
+            .route(
+                (
+                    "/slot",
+                    parse_path_segment::<usize>(),
+                    "/fill",
+                    parse_path_segment::<u32>(),
+                ),
+                get(fill_slot),
+            )
+            .layer(CatchErrorLayer::new(|error: AppError| {
+                println!("Handler error: {error:?}");
+
+                let (status_code, message, slot) = match error {
+                    AppError::NoSuchSlot(slot) => (StatusCode::NOT_FOUND, "No such slot", slot),
+                    AppError::SlotIsEmpty(slot) => (StatusCode::NOT_FOUND, "Slot is empty", slot),
+                    AppError::SlotIsFull(slot) => {
+                        (StatusCode::CONFLICT, "Slot is already full", slot)
+                    }
+                };
+
+                picoserve::response::Json(ErrorBody { message, slot })
+                    .into_response()
+                    .with_status_code(status_code)
+            })),
+    );
+
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
+    .keep_connection_alive();
+
+    let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
+
+    println!("curl -i http://localhost:{port}/slot/0");
+    println!("curl -i http://localhost:{port}/slot/0/fill/42");
+    println!("curl -i http://localhost:{port}/slot/9");
+
+    let slots = Slots::default();
+
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            loop {
+                let (stream, remote_address) = socket.accept().await?;
+
+                println!("Connection from {remote_address}");
+
+                let app = app.clone();
+                let config = config.clone();
+                let slots = slots.clone();
+
+                tokio::task::spawn_local(async move {
+                    match picoserve::serve_with_state(&app, &config, &mut [0; 2048], stream, &slots)
+                        .await
+                    {
+                        Ok(handled_requests_count) => {
+                            println!(
+                                "{handled_requests_count} requests handled from {remote_address}"
+                            )
+                        }
+                        Err(err) => println!("{err:?}"),
+                    }
+                });
+            }
+        })
+        .await
+}
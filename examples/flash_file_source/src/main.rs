@@ -0,0 +1,138 @@
+use std::{ops::Range, time::Duration};
+
+use picoserve::{
+    response::fs::{FileInfo, FileSource, FileSystemService},
+    routing::get,
+};
+
+/// The size of a flash page, which is the smallest unit which can be read.
+const PAGE_SIZE: usize = 64;
+
+#[derive(Debug)]
+struct ReadOutOfBounds;
+
+/// A mock of a filesystem stored in external flash memory, which is read a page at a time rather than being mapped into memory.
+///
+/// The files are stored one after another, and a table records the path, content type, and location of each file.
+/// On a real device, the table would be read from flash too, for example using `sequential-storage`.
+struct MockFlash {
+    image: Vec<u8>,
+    table: Vec<(&'static str, &'static str, Range<usize>)>,
+}
+
+impl MockFlash {
+    fn new(files: &[(&'static str, &'static str, &str)]) -> Self {
+        let mut image = Vec::new();
+        let mut table = Vec::new();
+
+        for &(path, content_type, content) in files {
+            let start = image.len();
+            image.extend_from_slice(content.as_bytes());
+            table.push((path, content_type, start..image.len()));
+        }
+
+        Self { image, table }
+    }
+
+    async fn read_page(&self, address: usize, buffer: &mut [u8]) -> Result<usize, ReadOutOfBounds> {
+        // Simulate the time taken to read from the flash chip
+        tokio::time::sleep(Duration::from_millis(1)).await;
+
+        let page_end = (address / PAGE_SIZE + 1) * PAGE_SIZE;
+        let data = self
+            .image
+            .get(address..page_end.min(self.image.len()))
+            .ok_or(ReadOutOfBounds)?;
+
+        let length = data.len().min(buffer.len());
+        buffer[..length].copy_from_slice(&data[..length]);
+
+        Ok(length)
+    }
+}
+
+impl FileSource for MockFlash {
+    type Handle = Range<usize>;
+    type Error = ReadOutOfBounds;
+
+    async fn open(&self, path: &str) -> Result<Option<FileInfo<Self::Handle>>, Self::Error> {
+        Ok(self
+            .table
+            .iter()
+            .find(|(file_path, ..)| *file_path == path)
+            .map(|(_, content_type, range)| FileInfo {
+                handle: range.clone(),
+                length: range.len(),
+                content_type,
+            }))
+    }
+
+    async fn read(
+        &self,
+        handle: &Self::Handle,
+        offset: usize,
+        buffer: &mut [u8],
+    ) -> Result<usize, Self::Error> {
+        let address = handle.start + offset;
+        let length = buffer.len().min(handle.end - address);
+
+        self.read_page(address, &mut buffer[..length]).await
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let port = 8000;
+
+    let flash = MockFlash::new(&[
+        (
+            "index.html",
+            "text/html; charset=utf-8",
+            include_str!("index.html"),
+        ),
+        ("assets/style.css", "text/css", include_str!("style.css")),
+    ]);
+
+    // Routes added to the router are matched before the files
+    let app = std::rc::Rc::new(
+        picoserve::Router::from_service(
+            FileSystemService::new(flash).with_index_file("index.html"),
+        )
+        .route("/api/status", get(|| async { "OK\n" })),
+    );
+
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
+    .keep_connection_alive();
+
+    let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
+
+    println!("http://localhost:{port}/");
+
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            loop {
+                let (stream, remote_address) = socket.accept().await?;
+
+                println!("Connection from {remote_address}");
+
+                let app = app.clone();
+                let config = config.clone();
+
+                tokio::task::spawn_local(async move {
+                    match picoserve::serve(&app, &config, &mut [0; 2048], stream).await {
+                        Ok(handled_requests_count) => {
+                            println!(
+                                "{handled_requests_count} requests handled from {remote_address}"
+                            )
+                        }
+                        Err(err) => println!("{err:?}"),
+                    }
+                });
+            }
+        })
+        .await
+}
@@ -3,7 +3,7 @@ use std::time::{Duration, Instant};
 use picoserve::{
     io::Read,
     request::Path,
-    response::ResponseWriter,
+    response::{IntoResponse, ResponseWriter},
     routing::{get, parse_path_segment},
 };
 
@@ -32,12 +32,19 @@ impl<'r, W: ResponseWriter> ResponseWriter for TimedResponseWriter<'r, W> {
             .write_response(connection, response)
             .await;
 
-        println!(
-            "Path: {}; Status Code: {}; Response Time: {}ms",
-            self.path,
-            status_code,
-            self.start_time.elapsed().as_secs_f32() * 1000.0
-        );
+        let response_time = self.start_time.elapsed().as_secs_f32() * 1000.0;
+
+        if status_code.is_client_error() || status_code.is_server_error() {
+            eprintln!(
+                "Path: {}; Error Status Code: {}; Response Time: {}ms",
+                self.path, status_code, response_time
+            );
+        } else {
+            println!(
+                "Path: {}; Status Code: {}; Response Time: {}ms",
+                self.path, status_code, response_time
+            );
+        }
 
         result
     }
@@ -77,6 +84,48 @@ impl<State, PathParameters> picoserve::routing::Layer<State, PathParameters> for
     }
 }
 
+/// Responds with "Unauthorized" without running the handler unless the request has the expected "Authorization" header.
+struct AuthLayer {
+    token: &'static str,
+}
+
+impl<State, PathParameters> picoserve::routing::Layer<State, PathParameters> for AuthLayer {
+    type NextState = State;
+    type NextPathParameters = PathParameters;
+
+    async fn call_layer<
+        'a,
+        R: Read + 'a,
+        NextLayer: picoserve::routing::Next<'a, R, Self::NextState, Self::NextPathParameters>,
+        W: ResponseWriter<Error = R::Error>,
+    >(
+        &self,
+        next: NextLayer,
+        state: &State,
+        path_parameters: PathParameters,
+        request_parts: picoserve::request::RequestParts<'_>,
+        response_writer: W,
+    ) -> Result<picoserve::ResponseSent, W::Error> {
+        let is_authorized = request_parts
+            .headers()
+            .get("Authorization")
+            .and_then(|authorization| authorization.as_raw().strip_prefix(b"Bearer "))
+            .is_some_and(|token| token == self.token.as_bytes());
+
+        if is_authorized {
+            next.run(state, path_parameters, response_writer).await
+        } else {
+            (
+                picoserve::response::StatusCode::UNAUTHORIZED,
+                ("WWW-Authenticate", "Bearer"),
+                "Unauthorized\n",
+            )
+                .write_to(next.into_connection().await?, response_writer)
+                .await
+        }
+    }
+}
+
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> anyhow::Result<()> {
     let port = 8000;
@@ -91,14 +140,18 @@ async fn main() -> anyhow::Result<()> {
                     format!("Waited {millis}ms")
                 }),
             )
+            .route(
+                "/secret",
+                get(|| async { "The secret is 42" }).layer(AuthLayer { token: "hunter2" }),
+            )
             .layer(TimeLayer),
     );
 
-    let config = picoserve::Config::new(picoserve::Timeouts {
-        start_read_request: Some(Duration::from_secs(5)),
-        read_request: Some(Duration::from_secs(1)),
-        write: Some(Duration::from_secs(1)),
-    })
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
     .keep_connection_alive();
 
     let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
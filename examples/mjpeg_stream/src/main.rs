@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use picoserve::{
+    response::{File, MultipartStream},
+    routing::{get, get_service},
+};
+
+/// The frames of the stream. On a real device, these would be captured from a camera.
+static FRAMES: [&[u8]; 4] = [
+    include_bytes!("frames/frame0.jpg"),
+    include_bytes!("frames/frame1.jpg"),
+    include_bytes!("frames/frame2.jpg"),
+    include_bytes!("frames/frame3.jpg"),
+];
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let port = 8000;
+
+    let app = std::rc::Rc::new(
+        picoserve::Router::new()
+            .route("/", get_service(File::html(include_str!("index.html"))))
+            .route(
+                "/stream",
+                get(|| async {
+                    // Each frame replaces the previous frame, and the stream never ends
+                    MultipartStream::new("frame", 0, |frame_index| async move {
+                        tokio::time::sleep(Duration::from_millis(250)).await;
+
+                        Some((
+                            ("image/jpeg", FRAMES[frame_index]),
+                            (frame_index + 1) % FRAMES.len(),
+                        ))
+                    })
+                }),
+            ),
+    );
+
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
+    .keep_connection_alive();
+
+    let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
+
+    println!("http://localhost:{port}/");
+
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            loop {
+                let (stream, remote_address) = socket.accept().await?;
+
+                println!("Connection from {remote_address}");
+
+                let app = app.clone();
+                let config = config.clone();
+
+                tokio::task::spawn_local(async move {
+                    match picoserve::serve(&app, &config, &mut [0; 2048], stream).await {
+                        Ok(handled_requests_count) => {
+                            println!(
+                                "{handled_requests_count} requests handled from {remote_address}"
+                            )
+                        }
+                        Err(err) => println!("{err:?}"),
+                    }
+                });
+            }
+        })
+        .await
+}
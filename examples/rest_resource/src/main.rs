@@ -0,0 +1,197 @@
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc, time::Duration};
+
+use picoserve::{
+    extract::{Json, State},
+    response::{self, StatusCode},
+    routing::{get, parse_path_segment},
+};
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct Sensor {
+    name: String,
+    threshold: f32,
+    enabled: bool,
+}
+
+/// A partial [Sensor], sent as the body of `PATCH` requests. Fields which are missing are left unchanged.
+#[derive(serde::Deserialize)]
+struct SensorPatch {
+    name: Option<String>,
+    threshold: Option<f32>,
+    enabled: Option<bool>,
+}
+
+impl Sensor {
+    fn apply(
+        &mut self,
+        SensorPatch {
+            name,
+            threshold,
+            enabled,
+        }: SensorPatch,
+    ) {
+        if let Some(name) = name {
+            self.name = name;
+        }
+
+        if let Some(threshold) = threshold {
+            self.threshold = threshold;
+        }
+
+        if let Some(enabled) = enabled {
+            self.enabled = enabled;
+        }
+    }
+}
+
+#[derive(Default)]
+struct Sensors {
+    next_id: u32,
+    sensors: BTreeMap<u32, Sensor>,
+}
+
+type SharedSensors = Rc<RefCell<Sensors>>;
+
+const NOT_FOUND: (StatusCode, &str) = (StatusCode::NOT_FOUND, "No such sensor\n");
+
+async fn list_sensors(State(sensors): State<SharedSensors>) -> impl response::IntoResponse {
+    response::Json(sensors.borrow().sensors.clone())
+}
+
+async fn create_sensor(
+    State(sensors): State<SharedSensors>,
+    Json(sensor): Json<Sensor, 64>,
+) -> impl response::IntoResponse {
+    let mut sensors = sensors.borrow_mut();
+
+    let id = sensors.next_id;
+    sensors.next_id += 1;
+    sensors.sensors.insert(id, sensor);
+
+    response::Json(id)
+        .into_response()
+        .with_status_code(StatusCode::CREATED)
+        .with_header("Location", format!("/sensors/{id}"))
+}
+
+async fn get_sensor(
+    id: u32,
+    State(sensors): State<SharedSensors>,
+) -> Result<response::Json<Sensor>, (StatusCode, &'static str)> {
+    sensors
+        .borrow()
+        .sensors
+        .get(&id)
+        .cloned()
+        .map(response::Json)
+        .ok_or(NOT_FOUND)
+}
+
+/// Replace the entire sensor, which must already exist.
+async fn replace_sensor(
+    id: u32,
+    State(sensors): State<SharedSensors>,
+    Json(new_sensor): Json<Sensor, 64>,
+) -> Result<response::Json<Sensor>, (StatusCode, &'static str)> {
+    let mut sensors = sensors.borrow_mut();
+    let sensor = sensors.sensors.get_mut(&id).ok_or(NOT_FOUND)?;
+
+    *sensor = new_sensor;
+
+    Ok(response::Json(sensor.clone()))
+}
+
+/// Merge the fields which are present in the body into the sensor.
+async fn update_sensor(
+    id: u32,
+    State(sensors): State<SharedSensors>,
+    Json(patch): Json<SensorPatch, 64>,
+) -> Result<response::Json<Sensor>, (StatusCode, &'static str)> {
+    let mut sensors = sensors.borrow_mut();
+    let sensor = sensors.sensors.get_mut(&id).ok_or(NOT_FOUND)?;
+
+    sensor.apply(patch);
+
+    Ok(response::Json(sensor.clone()))
+}
+
+async fn delete_sensor(
+    id: u32,
+    State(sensors): State<SharedSensors>,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    sensors
+        .borrow_mut()
+        .sensors
+        .remove(&id)
+        .map(|_| StatusCode::NO_CONTENT)
+        .ok_or(NOT_FOUND)
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let port = 8000;
+
+    let app = std::rc::Rc::new(
+        picoserve::Router::new()
+            .route("/sensors", get(list_sensors).post(create_sensor))
+            .route(
+                ("/sensors", parse_path_segment::<u32>()),
+                get(get_sensor)
+                    .put(replace_sensor)
+                    .patch(update_sensor)
+                    .delete(delete_sensor),
+            ),
+    );
+
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
+    .keep_connection_alive();
+
+    let sensors = SharedSensors::default();
+
+    let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
+
+    println!("http://localhost:{port}/sensors");
+    println!(
+        "curl -X PATCH -d '{{\"threshold\":25.0}}' http://localhost:{port}/sensors/0 after creating a sensor with"
+    );
+    println!(
+        "curl -d '{{\"name\":\"Kitchen\",\"threshold\":20.0,\"enabled\":true}}' http://localhost:{port}/sensors"
+    );
+
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            loop {
+                let (stream, remote_address) = socket.accept().await?;
+
+                println!("Connection from {remote_address}");
+
+                let app = app.clone();
+                let config = config.clone();
+                let sensors = sensors.clone();
+
+                tokio::task::spawn_local(async move {
+                    match picoserve::serve_with_state(
+                        &app,
+                        &config,
+                        &mut [0; 2048],
+                        stream,
+                        &sensors,
+                    )
+                    .await
+                    {
+                        Ok(handled_requests_count) => {
+                            println!(
+                                "{handled_requests_count} requests handled from {remote_address}"
+                            )
+                        }
+                        Err(err) => println!("{err:?}"),
+                    }
+                });
+            }
+        })
+        .await
+}
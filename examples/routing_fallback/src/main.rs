@@ -16,12 +16,29 @@ impl picoserve::routing::PathRouterService<()> for CustomNotFound {
         request: picoserve::request::Request<'_, R>,
         response_writer: W,
     ) -> Result<picoserve::ResponseSent, W::Error> {
-        (
-            picoserve::response::StatusCode::NOT_FOUND,
-            format_args!("{:?} not found\n", path.encoded()),
-        )
-            .write_to(request.body_connection.finalize().await?, response_writer)
-            .await
+        // Routes which matched the path but not the method, such as "POST /", record the methods they would have accepted
+        let allowed_methods = request.parts.allowed_methods();
+
+        if allowed_methods.is_empty() {
+            (
+                picoserve::response::StatusCode::NOT_FOUND,
+                format_args!("{:?} not found\n", path.encoded()),
+            )
+                .write_to(request.body_connection.finalize().await?, response_writer)
+                .await
+        } else {
+            (
+                picoserve::response::StatusCode::METHOD_NOT_ALLOWED,
+                ("Allow", allowed_methods),
+                format_args!(
+                    "{} not allowed for {:?}\n",
+                    request.parts.method(),
+                    path.encoded()
+                ),
+            )
+                .write_to(request.body_connection.finalize().await?, response_writer)
+                .await
+        }
     }
 }
 
@@ -33,11 +50,11 @@ async fn main() -> anyhow::Result<()> {
         picoserve::Router::from_service(CustomNotFound).route("/", get(|| async { "Hello World" })),
     );
 
-    let config = picoserve::Config::new(picoserve::Timeouts {
-        start_read_request: Some(Duration::from_secs(5)),
-        read_request: Some(Duration::from_secs(1)),
-        write: Some(Duration::from_secs(1)),
-    })
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
     .keep_connection_alive();
 
     let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use picoserve::{
+    response::Response,
+    routing::{get, SetResponseHeaderLayer},
+};
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let port = 8000;
+
+    let app = std::rc::Rc::new(
+        picoserve::Router::new()
+            .route("/", get(|| async { "Hello World" }))
+            .route(
+                "/embeddable",
+                get(|| async {
+                    // Overrides the default "X-Frame-Options" header
+                    Response::ok("This page may be embedded in a frame on the same origin")
+                        .with_header("X-Frame-Options", "SAMEORIGIN")
+                }),
+            )
+            .layer(SetResponseHeaderLayer::new([
+                ("X-Content-Type-Options", "nosniff"),
+                ("X-Frame-Options", "DENY"),
+                ("Referrer-Policy", "no-referrer"),
+            ])),
+    );
+
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
+    .keep_connection_alive();
+
+    let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
+
+    println!("curl -i http://localhost:{port}/");
+    println!("curl -i http://localhost:{port}/embeddable");
+
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            loop {
+                let (stream, remote_address) = socket.accept().await?;
+
+                println!("Connection from {remote_address}");
+
+                let app = app.clone();
+                let config = config.clone();
+
+                tokio::task::spawn_local(async move {
+                    match picoserve::serve(&app, &config, &mut [0; 2048], stream).await {
+                        Ok(handled_requests_count) => {
+                            println!(
+                                "{handled_requests_count} requests handled from {remote_address}"
+                            )
+                        }
+                        Err(err) => println!("{err:?}"),
+                    }
+                });
+            }
+        })
+        .await
+}
@@ -28,3 +28,8 @@ events.addEventListener("error", function (ev) {
 events.addEventListener("message_changed", function (ev) {
   output.innerText = ev.data;
 })
+
+events.addEventListener("server_closing", function (ev) {
+  events.close();
+  output.innerText = ev.data;
+})
@@ -58,30 +58,46 @@ impl<'r, State> picoserve::extract::FromRequest<'r, State> for NewMessage {
 
 struct Events(tokio::sync::watch::Receiver<String>);
 
-impl response::sse::EventSource for Events {
+impl response::sse::EventSourceWithShutdownSignal for Events {
     async fn write_events<W: picoserve::io::Write>(
         mut self,
         mut writer: response::sse::EventWriter<W>,
+        shutdown_signal: impl std::future::Future<Output = ()>,
     ) -> Result<(), W::Error> {
+        let mut shutdown_signal = std::pin::pin!(shutdown_signal);
+
         loop {
-            match tokio::time::timeout(std::time::Duration::from_secs(15), self.0.changed()).await {
-                Ok(Ok(())) => {
-                    writer
-                        .write_event("message_changed", self.0.borrow_and_update().as_str())
-                        .await?
+            tokio::select! {
+                () = &mut shutdown_signal => {
+                    return writer.write_event("server_closing", "Server is shutting down").await;
+                }
+                changed = tokio::time::timeout(std::time::Duration::from_secs(15), self.0.changed()) => match changed {
+                    Ok(Ok(())) => {
+                        writer
+                            .write_event("message_changed", self.0.borrow_and_update().as_str())
+                            .await?
+                    }
+                    Ok(Err(_)) => return Ok(()),
+                    Err(_) => writer.write_keepalive().await?,
                 }
-                Ok(Err(_)) => return Ok(()),
-                Err(_) => writer.write_keepalive().await?,
             }
         }
     }
 }
 
+/// Completes once the server has started shutting down.
+async fn wait_for_shutdown(mut shutdown_rx: tokio::sync::watch::Receiver<bool>) {
+    let _ = shutdown_rx
+        .wait_for(|&is_shutting_down| is_shutting_down)
+        .await;
+}
+
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> anyhow::Result<()> {
     let port = 8000;
 
     let (messages_tx, messages_rx) = tokio::sync::watch::channel(String::new());
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
 
     let app =
         std::rc::Rc::new(
@@ -100,7 +116,10 @@ async fn main() -> anyhow::Result<()> {
                 )
                 .route(
                     "/events",
-                    get(move || response::EventStream(Events(messages_rx.clone()))),
+                    get(move || response::EventStreamWithShutdownSignal {
+                        source: Events(messages_rx.clone()),
+                        shutdown_signal: wait_for_shutdown(shutdown_rx.clone()),
+                    }),
                 )
                 .nest_service(
                     "/static",
@@ -129,16 +148,17 @@ async fn main() -> anyhow::Result<()> {
                                     },
                                 ),
                             ],
+                            ..response::Directory::DEFAULT
                         }
                     },
                 ),
         );
 
-    let config = picoserve::Config::new(picoserve::Timeouts {
-        start_read_request: Some(Duration::from_secs(5)),
-        read_request: Some(Duration::from_secs(1)),
-        write: Some(Duration::from_secs(1)),
-    })
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
     .keep_connection_alive();
 
     let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
@@ -147,15 +167,24 @@ async fn main() -> anyhow::Result<()> {
 
     tokio::task::LocalSet::new()
         .run_until(async {
+            let mut connections = tokio::task::JoinSet::new();
+
             loop {
-                let (stream, remote_address) = socket.accept().await?;
+                let (stream, remote_address) = tokio::select! {
+                    connection = socket.accept() => connection?,
+                    Some(_) = connections.join_next() => continue,
+                    shutdown = tokio::signal::ctrl_c() => {
+                        shutdown?;
+                        break;
+                    }
+                };
 
                 println!("Connection from {remote_address}");
 
                 let app = app.clone();
                 let config = config.clone();
 
-                tokio::task::spawn_local(async move {
+                connections.spawn_local(async move {
                     match picoserve::serve(&app, &config, &mut [0; 2048], stream).await {
                         Ok(handled_requests_count) => {
                             println!(
@@ -166,6 +195,15 @@ async fn main() -> anyhow::Result<()> {
                     }
                 });
             }
+
+            println!("Shutting down");
+
+            // Notify event streams that the server is shutting down, and wait for them to send their final events
+            shutdown_tx.send_replace(true);
+
+            while connections.join_next().await.is_some() {}
+
+            Ok(())
         })
         .await
 }
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use picoserve::{io::FuturesIoSocket, routing::get};
+
+/// A [Timer](picoserve::Timer) using the timers provided by smol.
+struct SmolTimer;
+
+impl picoserve::Timer for SmolTimer {
+    type Duration = Duration;
+    type TimeoutError = ();
+
+    async fn run_with_timeout<F: core::future::Future>(
+        &mut self,
+        duration: Self::Duration,
+        future: F,
+    ) -> Result<F::Output, Self::TimeoutError> {
+        smol::future::or(async { Ok(future.await) }, async {
+            smol::Timer::after(duration).await;
+            Err(())
+        })
+        .await
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let port = 8000;
+
+    let app = std::rc::Rc::new(
+        picoserve::Router::new().route("/", get(|| async { "Hello World from smol" })),
+    );
+
+    let config = std::rc::Rc::new(
+        picoserve::Config::new(picoserve::Timeouts::new(
+            Some(Duration::from_secs(5)),
+            Some(Duration::from_secs(1)),
+            Some(Duration::from_secs(1)),
+        ))
+        .keep_connection_alive(),
+    );
+
+    let executor = smol::LocalExecutor::new();
+
+    smol::block_on(executor.run(async {
+        let socket = smol::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
+
+        println!("http://localhost:{port}/");
+
+        loop {
+            let (stream, remote_address) = socket.accept().await?;
+
+            println!("Connection from {remote_address}");
+
+            let app = app.clone();
+            let config = config.clone();
+
+            executor
+                .spawn(async move {
+                    match picoserve::serve(
+                        &app,
+                        SmolTimer,
+                        &config,
+                        &mut [0; 2048],
+                        FuturesIoSocket::new(stream),
+                    )
+                    .await
+                    {
+                        Ok(handled_requests_count) => {
+                            println!(
+                                "{handled_requests_count} requests handled from {remote_address}"
+                            )
+                        }
+                        Err(err) => println!("{err:?}"),
+                    }
+                })
+                .detach();
+        }
+    }))
+}
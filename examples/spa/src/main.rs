@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use picoserve::{
+    response::{Directory, File},
+    routing::get,
+};
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let port = 8000;
+
+    let app = std::rc::Rc::new(
+        // The gzip-compressed copies of each file are generated at build time, and sent to browsers which accept them.
+        // Paths which don't match a file, such as "/settings", are served "index.html", and handled by client-side routing.
+        picoserve::Router::spa(
+            const {
+                Directory {
+                    files: &[(
+                        "index.html",
+                        File::html(include_str!("static/index.html"))
+                            .with_gzip(include_bytes!("static/index.html.gz")),
+                    )],
+                    sub_directories: &[(
+                        "assets",
+                        Directory {
+                            files: &[(
+                                "app.js",
+                                File::javascript(include_str!("static/assets/app.js"))
+                                    .with_gzip(include_bytes!("static/assets/app.js.gz")),
+                            )],
+                            ..Directory::DEFAULT
+                        },
+                    )],
+                    ..Directory::DEFAULT
+                }
+            },
+            "index.html",
+        )
+        // API routes are matched before the application
+        .route("/api/greeting", get(|| async { "Hello World" })),
+    );
+
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
+    .keep_connection_alive();
+
+    let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
+
+    println!("http://localhost:{port}/");
+
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            loop {
+                let (stream, remote_address) = socket.accept().await?;
+
+                println!("Connection from {remote_address}");
+
+                let app = app.clone();
+                let config = config.clone();
+
+                tokio::task::spawn_local(async move {
+                    match picoserve::serve(&app, &config, &mut [0; 2048], stream).await {
+                        Ok(handled_requests_count) => {
+                            println!(
+                                "{handled_requests_count} requests handled from {remote_address}"
+                            )
+                        }
+                        Err(err) => println!("{err:?}"),
+                    }
+                });
+            }
+        })
+        .await
+}
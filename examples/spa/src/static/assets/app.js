@@ -0,0 +1,35 @@
+"use strict";
+
+const page = document.getElementById("page");
+
+async function render() {
+    switch (location.pathname) {
+        case "/":
+            const response = await fetch("/api/greeting");
+            page.textContent = await response.text();
+            break;
+        case "/settings":
+            page.textContent = "Settings";
+            break;
+        case "/about":
+            page.textContent = "A Single-Page Application served by picoserve";
+            break;
+        default:
+            page.textContent = `No page at ${location.pathname}`;
+            break;
+    }
+}
+
+document.addEventListener("click", (event) => {
+    const link = event.target.closest("a");
+
+    if (link && link.origin === location.origin) {
+        event.preventDefault();
+        history.pushState(null, "", link.href);
+        render();
+    }
+});
+
+window.addEventListener("popstate", render);
+
+render();
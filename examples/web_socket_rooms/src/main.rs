@@ -0,0 +1,156 @@
+use std::{rc::Rc, time::Duration};
+
+use picoserve::{
+    response::ws,
+    routing::{get, get_service, parse_path_segment},
+};
+
+const ROOM_COUNT: usize = 4;
+
+/// The index of a room, which is parsed from the path, so requests for rooms which don't exist are "Not Found".
+#[derive(Clone, Copy)]
+struct RoomId(usize);
+
+impl std::str::FromStr for RoomId {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse() {
+            Ok(id) if id < ROOM_COUNT => Ok(Self(id)),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for RoomId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+type Rooms = Rc<[tokio::sync::broadcast::Sender<String>; ROOM_COUNT]>;
+
+struct RoomHandler {
+    room_id: RoomId,
+    rooms: Rooms,
+}
+
+impl ws::WebSocketCallback for RoomHandler {
+    async fn run<R: picoserve::io::Read, W: picoserve::io::Write<Error = R::Error>>(
+        self,
+        mut rx: ws::SocketRx<R>,
+        mut tx: ws::SocketTx<W>,
+    ) -> Result<(), W::Error> {
+        use picoserve::response::ws::Message;
+
+        let room = &self.rooms[self.room_id.0];
+        let mut room_messages = room.subscribe();
+
+        tx.send_display(format_args!("Joined room {}", self.room_id))
+            .await?;
+
+        let mut message_buffer = [0; 128];
+
+        let close_reason = loop {
+            tokio::select! {
+                room_message = room_messages.recv() => match room_message {
+                    Ok(message) => tx.send_text(&message).await?,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => tx.send_display(format_args!("Missed {n} messages")).await?,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break None,
+                },
+                new_message = rx.next_message(&mut message_buffer) => match new_message {
+                    Ok(Message::Text(new_message)) => { let _ = room.send(new_message.into()); },
+                    Ok(Message::Binary(message)) => println!("Ignoring binary message: {message:?}"),
+                    Ok(Message::Close(reason)) => {
+                        eprintln!("Websocket close reason: {reason:?}");
+                        break None;
+                    }
+                    Ok(Message::Ping(ping)) => tx.send_pong(ping).await?,
+                    Ok(Message::Pong(_)) => (),
+                    Err(err) => {
+                        eprintln!("Websocket Error: {err:?}");
+
+                        let code = match err {
+                            ws::ReadMessageError::Io(err) => return Err(err),
+                            ws::ReadMessageError::ReadFrameError(_)
+                            | ws::ReadMessageError::MessageStartsWithContinuation
+                            | ws::ReadMessageError::UnexpectedMessageStart => ws::CloseCode::ProtocolError,
+                            ws::ReadMessageError::ReservedOpcode(_) => ws::CloseCode::UnsupportedData,
+                            ws::ReadMessageError::TextIsNotUtf8 => ws::CloseCode::InvalidPayload,
+                        };
+
+                        break Some((code, "Websocket Error"));
+                    }
+                }
+            }
+        };
+
+        tx.close(close_reason).await
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let port = 8000;
+
+    let rooms: Rooms = Rc::new(std::array::from_fn(|_| {
+        tokio::sync::broadcast::channel(16).0
+    }));
+
+    let app = Rc::new(
+        picoserve::Router::new()
+            .route(
+                "/",
+                get_service(picoserve::response::File::html(include_str!("index.html"))),
+            )
+            .route(
+                ("/rooms", parse_path_segment::<RoomId>()),
+                get_service(picoserve::response::File::html(include_str!("room.html"))),
+            )
+            .route(
+                ("/rooms", parse_path_segment::<RoomId>(), "/ws"),
+                get(move |room_id: RoomId, upgrade: ws::WebSocketUpgrade| {
+                    // The room id captured from the path is moved into the callback, which runs once the connection has been upgraded
+                    upgrade.on_upgrade(RoomHandler {
+                        room_id,
+                        rooms: rooms.clone(),
+                    })
+                }),
+            ),
+    );
+
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
+    .keep_connection_alive();
+
+    let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
+
+    println!("http://localhost:{port}/");
+
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            loop {
+                let (stream, remote_address) = socket.accept().await?;
+
+                println!("Connection from {remote_address}");
+
+                let app = app.clone();
+                let config = config.clone();
+
+                tokio::task::spawn_local(async move {
+                    match picoserve::serve(&app, &config, &mut [0; 2048], stream).await {
+                        Ok(handled_requests_count) => {
+                            println!(
+                                "{handled_requests_count} requests handled from {remote_address}"
+                            )
+                        }
+                        Err(err) => println!("{err:?}"),
+                    }
+                });
+            }
+        })
+        .await
+}
@@ -43,9 +43,9 @@ impl ws::WebSocketCallback for WebsocketHandler {
                             ws::ReadMessageError::Io(err) => return Err(err),
                             ws::ReadMessageError::ReadFrameError(_)
                             | ws::ReadMessageError::MessageStartsWithContinuation
-                            | ws::ReadMessageError::UnexpectedMessageStart => 1002,
-                            ws::ReadMessageError::ReservedOpcode(_) => 1003,
-                            ws::ReadMessageError::TextIsNotUtf8 => 1007,
+                            | ws::ReadMessageError::UnexpectedMessageStart => ws::CloseCode::ProtocolError,
+                            ws::ReadMessageError::ReservedOpcode(_) => ws::CloseCode::UnsupportedData,
+                            ws::ReadMessageError::TextIsNotUtf8 => ws::CloseCode::InvalidPayload,
                         };
 
                         break Some((code, "Websocket Error"));
@@ -132,11 +132,11 @@ async fn main() -> anyhow::Result<()> {
             ),
     );
 
-    let config = picoserve::Config::new(picoserve::Timeouts {
-        start_read_request: Some(Duration::from_secs(5)),
-        read_request: Some(Duration::from_secs(1)),
-        write: Some(Duration::from_secs(1)),
-    })
+    let config = picoserve::Config::new(picoserve::Timeouts::new(
+        Some(Duration::from_secs(5)),
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    ))
     .keep_connection_alive();
 
     let socket = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port)).await?;
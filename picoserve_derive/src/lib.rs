@@ -0,0 +1,73 @@
+//! Derive macros for [picoserve](https://docs.rs/picoserve). Use the re-exports in `picoserve` rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::quote;
+
+/// Implement `picoserve::extract::FromRef<Self>` for the type of each field, by cloning the field.
+///
+/// Fields can be skipped with `#[from_ref(skip)]`, which is required if two fields have the same type,
+/// or if the type of the field is a type parameter of the struct.
+///
+/// See `picoserve::extract::FromRef` for an example.
+#[proc_macro_derive(FromRef, attributes(from_ref))]
+pub fn derive_from_ref(input: TokenStream) -> TokenStream {
+    expand_from_ref(syn::parse_macro_input!(input as syn::DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn is_skipped(field: &syn::Field) -> syn::Result<bool> {
+    let mut skip = false;
+
+    for attr in field.attrs.iter() {
+        if attr.path().is_ident("from_ref") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown from_ref attribute, expected `skip`"))
+                }
+            })?;
+        }
+    }
+
+    Ok(skip)
+}
+
+fn expand_from_ref(input: syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let syn::Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "FromRef can only be derived for structs",
+        ));
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut impls = proc_macro2::TokenStream::new();
+
+    for (index, field) in data.fields.iter().enumerate() {
+        if is_skipped(field)? {
+            continue;
+        }
+
+        let member = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(index.into()),
+        };
+
+        let ty = &field.ty;
+
+        impls.extend(quote! {
+            impl #impl_generics ::picoserve::extract::FromRef<#ident #ty_generics> for #ty #where_clause {
+                fn from_ref(input: &#ident #ty_generics) -> Self {
+                    ::core::clone::Clone::clone(&input.#member)
+                }
+            }
+        });
+    }
+
+    Ok(impls)
+}
@@ -0,0 +1,130 @@
+//! Base64 encoding and decoding using the standard alphabet with padding, as described in [RFC 4648](https://www.rfc-editor.org/rfc/rfc4648#section-4),
+//! as used by the "Sec-WebSocket-Accept" header, "Authorization: Basic" credentials, and data URIs.
+//!
+//! Values are encoded into and decoded from caller-provided buffers, or encoded while being formatted using [Encoded], so no allocator is required.
+//!
+//! ```
+//! use picoserve::base64;
+//!
+//! let mut buffer = [0; 16];
+//!
+//! assert_eq!(base64::encode_into(b"user:pass", &mut buffer).unwrap(), "dXNlcjpwYXNz");
+//! assert_eq!(base64::decode_into(b"dXNlcjpwYXNz", &mut buffer).unwrap(), b"user:pass");
+//! assert_eq!(
+//!     format_args!("Basic {}", base64::Encoded(b"user:pass")).to_string(),
+//!     "Basic dXNlcjpwYXNz"
+//! );
+//! ```
+
+use core::fmt;
+
+const BASE64: data_encoding::Encoding = data_encoding::BASE64;
+
+/// The error returned if the buffer is too small to hold the encoded or decoded value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BufferTooSmall;
+
+impl fmt::Display for BufferTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Buffer is too small")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BufferTooSmall {}
+
+/// The error returned when decoding fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DecodeError {
+    /// The buffer is smaller than [decoded_len_max] of the length of the input.
+    BufferTooSmall,
+    /// The length of the input is not a multiple of 4.
+    InvalidLength,
+    /// The input contains a character which is not in the base64 alphabet, or badly placed padding, at `position`.
+    InvalidCharacter { position: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BufferTooSmall => write!(f, "Buffer is too small"),
+            Self::InvalidLength => write!(f, "Length is not a multiple of 4"),
+            Self::InvalidCharacter { position } => {
+                write!(f, "Invalid base64 character at position {position}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// The length of the encoding of `input_length` bytes, including padding.
+pub const fn encoded_len(input_length: usize) -> usize {
+    input_length.div_ceil(3) * 4
+}
+
+/// The maximum length of the value decoded from `input_length` characters, which is shorter if the input is padded.
+pub const fn decoded_len_max(input_length: usize) -> usize {
+    input_length / 4 * 3
+}
+
+/// Encode `input` into `buffer`, returning the encoded string, or an error if `buffer` is shorter than [encoded_len] of the length of `input`.
+pub fn encode_into<'b>(input: &[u8], buffer: &'b mut [u8]) -> Result<&'b str, BufferTooSmall> {
+    let output = buffer
+        .get_mut(..encoded_len(input.len()))
+        .ok_or(BufferTooSmall)?;
+
+    BASE64.encode_mut(input, output);
+
+    // The base64 alphabet is ASCII, so the output is always valid UTF-8
+    core::str::from_utf8(output).map_err(|_| BufferTooSmall)
+}
+
+/// Decode `input` into `buffer`, returning the decoded bytes.
+///
+/// `buffer` must be at least [decoded_len_max] of the length of `input`, even if the decoded value is shorter.
+pub fn decode_into<'b>(input: &[u8], buffer: &'b mut [u8]) -> Result<&'b mut [u8], DecodeError> {
+    if input.len() % 4 != 0 {
+        return Err(DecodeError::InvalidLength);
+    }
+
+    let output = buffer
+        .get_mut(..decoded_len_max(input.len()))
+        .ok_or(DecodeError::BufferTooSmall)?;
+
+    let decoded_length = BASE64.decode_mut(input, output).map_err(
+        |data_encoding::DecodePartial { error, .. }| DecodeError::InvalidCharacter {
+            position: error.position,
+        },
+    )?;
+
+    Ok(&mut output[..decoded_length])
+}
+
+/// Formats bytes as base64, without a buffer for the encoded string, for example as a header value.
+///
+/// ```
+/// use picoserve::{base64, response::Response};
+///
+/// const TOKEN: &[u8] = &[0xde, 0xad, 0xbe, 0xef];
+///
+/// let response = Response::ok("Hello").with_header("X-Token", base64::Encoded(TOKEN));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoded<'a>(pub &'a [u8]);
+
+impl<'a> fmt::Display for Encoded<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Each 48 byte chunk encodes to 64 characters without padding, so the chunks can be formatted independently
+        let mut buffer = [0; 64];
+
+        for chunk in self.0.chunks(48) {
+            f.write_str(encode_into(chunk, &mut buffer).map_err(|BufferTooSmall| fmt::Error)?)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,2 @@
+/// A CBOR encoded value. When serializing, the value might be serialized several times during sending, so the value must be serialized in the same way each time.
+pub struct Cbor<T>(pub T);
@@ -6,6 +6,8 @@
 //!
 //! + [`State<T>`] will extract part or all of the application state.
 //! + [`Form<T: serde::DeserializeOwned>`] will extract the body of a request as Form data.
+//! + [`Path<T: serde::DeserializeOwned>`] will deserialize the path parameters captured by the route.
+//! + [`Body<T, Format>`] will deserialize the body of a request in a [format](body::BodyFormat) such as JSON.
 //!
 //! For an example of how to implement [FromRequest], see [custom_extractor](https://github.com/sammhicks/picoserve/blob/main/examples/custom_extractor/src/main.rs)
 //!
@@ -13,6 +15,8 @@
 //!
 //! Although [RequestHandlerFunctions](crate::routing::RequestHandlerFunction) may not borrow from request due to restrictions with Higher-Order-Lifetime-Bounds, by using [from_request](crate::from_request) and [from_request_parts](crate::from_request_parts), [RequestHandlerServices](crate::routing::RequestHandlerService) and [PathRouterServices](crate::routing::PathRouterService) may do so.
 
+use core::marker::PhantomData;
+
 use crate::{
     io::{Read, ReadExt},
     request::{RequestBody, RequestParts},
@@ -28,12 +32,29 @@ pub mod json {
 
 pub use crate::json::Json;
 
+#[cfg(feature = "cbor")]
+pub use crate::cbor::Cbor;
+
+#[cfg(feature = "derive")]
+pub use picoserve_derive::FromRef;
+
+mod path;
+
+pub use path::{Path, PathRejection};
+
+pub mod body;
+
+pub use body::Body;
+
 mod private {
     pub struct ViaRequest;
     pub struct ViaParts;
 }
 
 /// Types that can be created from requests parts (everything except the request body).
+///
+/// If a handler has several arguments which implement `FromRequestParts`, they are extracted from left to right,
+/// and extraction stops at the first rejection. See [RequestHandlerFunction](crate::routing::RequestHandlerFunction).
 pub trait FromRequestParts<'r, State>: Sized {
     /// If the extractor fails this “rejection” type is returned, which converted into a response and returned.
     type Rejection: IntoResponse + 'static;
@@ -52,9 +73,9 @@ macro_rules! from_request_parts {
     ($state:ident, $request:ident, $response_writer:ident $(,$name:ty)* $(,)?) => {
         (
             $(
-                match <$name as $crate::extract::FromRequestParts>::from_request_parts($state, &$request.parts).await {
+                match <$name as $crate::extract::FromRequestParts<_>>::from_request_parts($state, &$request.parts).await {
                     Ok(value) => value,
-                    Err(err) => return err.write_to($request.body.finalize().await?, $response_writer).await,
+                    Err(err) => return $crate::response::IntoResponse::write_to(err, $request.body_connection.finalize().await?, $response_writer).await,
                 }
             ),*
         )
@@ -79,18 +100,21 @@ pub trait FromRequest<'r, State, M = private::ViaRequest>: Sized {
 /// If extraction is rejected, the rejection is written to `$response_writer` and the function returns.
 macro_rules! from_request {
     ($state:ident, $request:ident, $response_writer:ident, $name:ty $(,)?) => {
-        match <$name as $crate::extract::FromRequest>::from_request(
+        match <$name as $crate::extract::FromRequest<_, _>>::from_request(
             $state,
             $request.parts,
-            $request.body.body(),
+            $request.body_connection.body(),
         )
         .await
         {
             Ok(value) => value,
             Err(err) => {
-                return err
-                    .write_to($request.body.finalize().await?, $response_writer)
-                    .await
+                return $crate::response::IntoResponse::write_to(
+                    err,
+                    $request.body_connection.finalize().await?,
+                    $response_writer,
+                )
+                .await
             }
         }
     };
@@ -238,6 +262,63 @@ impl<'r, State> FromRequest<'r, State> for alloc::borrow::Cow<'r, [u8]> {
     }
 }
 
+/// Extracts the entire body into an owned buffer with a capacity of `N` bytes, so that it does not need to fit into the HTTP buffer.
+///
+/// Bodies longer than `N` bytes are rejected with "Payload Too Large" without being read.
+///
+/// The body reader itself cannot be extracted, as [FromRequest] must produce the same type for every connection type.
+/// To stream the body, such as hashing it or forwarding it elsewhere, implement [RequestHandlerService](crate::routing::RequestHandlerService)
+/// and use the reader returned by [RequestBody::reader].
+/// See [body_checksum](https://github.com/sammhicks/picoserve/blob/main/examples/body_checksum/src/main.rs) for an example.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundedBody<const N: usize>(pub heapless::Vec<u8, N>);
+
+impl<const N: usize> core::ops::Deref for BoundedBody<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize> core::ops::DerefMut for BoundedBody<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'r, State, const N: usize> FromRequest<'r, State> for BoundedBody<N> {
+    type Rejection = FailedToExtractEntireBodyError;
+
+    async fn from_request<R: Read>(
+        _state: &'r State,
+        _request_parts: RequestParts<'r>,
+        request_body: RequestBody<'r, R>,
+    ) -> Result<Self, Self::Rejection> {
+        let content_length = request_body.content_length();
+
+        request_body
+            .read_all_into::<N>()
+            .await
+            .map(Self)
+            .map_err(|err| match err {
+                crate::request::ReadAllBodyError::BufferIsTooSmall => {
+                    FailedToExtractEntireBodyError::BufferIsTooSmall {
+                        content_length,
+                        buffer_length: N,
+                    }
+                }
+                err => {
+                    log_error!(
+                        "Failed to read body: {}",
+                        crate::logging::Debug2Format(&err)
+                    );
+                    FailedToExtractEntireBodyError::IoError
+                }
+            })
+    }
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 /// Errors arising while reading the entire body as a UTF-8 String
@@ -353,6 +434,416 @@ where
     }
 }
 
+/// Extracts a copy of the entire [RequestParts], giving access to the method, path, query, fragments, HTTP version, and headers.
+///
+/// As [RequestParts] borrows from the request, it cannot be used as an argument to a [RequestHandlerFunction](crate::routing::RequestHandlerFunction),
+/// but can be extracted by [RequestHandlerServices](crate::routing::RequestHandlerService) using [from_request_parts](crate::from_request_parts).
+/// Handler functions can use [AllParts] instead, which copies the parts into owned storage.
+impl<'r, State> FromRequestParts<'r, State> for RequestParts<'r> {
+    type Rejection = core::convert::Infallible;
+
+    async fn from_request_parts(
+        _state: &'r State,
+        request_parts: &RequestParts<'r>,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(*request_parts)
+    }
+}
+
+/// Extracts an owned copy of the method, path, query, and headers of the request, stored in a buffer of `N` bytes.
+///
+/// Unlike [RequestParts], which borrows from the request, this can be used as an argument to a handler function,
+/// for handlers which need read access to all of the request parts rather than using an extractor for each part.
+/// If the method, path, query, and headers don't fit into `N` bytes, the request is rejected with "431 Request Header Fields Too Large".
+///
+/// ```
+/// use picoserve::{extract::AllParts, routing::get};
+///
+/// async fn describe_request(parts: AllParts<512>) -> impl picoserve::response::IntoResponse {
+///     let user_agent = parts
+///         .headers()
+///         .get("User-Agent")
+///         .and_then(|user_agent| user_agent.as_str().ok().map(heapless::String::<64>::try_from))
+///         .and_then(Result::ok);
+///
+///     picoserve::response::DebugValue((
+///         heapless::String::<8>::try_from(parts.method()).ok(),
+///         parts.path().segments().count(),
+///         parts.query().is_some(),
+///         user_agent,
+///     ))
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route("/", get(describe_request));
+/// ```
+#[derive(Clone)]
+pub struct AllParts<const N: usize> {
+    buffer: heapless::Vec<u8, N>,
+    method_length: usize,
+    path_length: usize,
+    query_length: Option<usize>,
+}
+
+impl<const N: usize> AllParts<N> {
+    fn str_at(&self, range: core::ops::Range<usize>) -> &str {
+        // The method, path, and query are copied from strings, so are always valid UTF-8
+        core::str::from_utf8(&self.buffer[range]).unwrap_or_default()
+    }
+
+    fn path_end(&self) -> usize {
+        self.method_length + self.path_length
+    }
+
+    fn query_end(&self) -> usize {
+        self.path_end() + self.query_length.unwrap_or(0)
+    }
+
+    /// Return the method as sent by the client
+    pub fn method(&self) -> &str {
+        self.str_at(0..self.method_length)
+    }
+
+    /// Return the request path, without the query or fragments
+    pub fn path(&self) -> crate::request::Path<'_> {
+        crate::request::Path::from_encoded(self.str_at(self.method_length..self.path_end()))
+    }
+
+    /// Return the query section of the request URL, i.e. everything after the "?"
+    pub fn query(&self) -> Option<crate::url_encoded::UrlEncodedString<'_>> {
+        self.query_length.map(|_| {
+            crate::url_encoded::UrlEncodedString(self.str_at(self.path_end()..self.query_end()))
+        })
+    }
+
+    /// Return the request headers
+    pub fn headers(&self) -> crate::request::Headers<'_> {
+        crate::request::Headers(&self.buffer[self.query_end()..])
+    }
+}
+
+/// Rejection used for [AllParts], sent if the request parts don't fit into the buffer.
+#[derive(Debug)]
+pub struct AllPartsTooLarge;
+
+impl IntoResponse for AllPartsTooLarge {
+    async fn write_to<R: Read, W: crate::response::ResponseWriter<Error = R::Error>>(
+        self,
+        connection: crate::response::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        (
+            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+            "Request is too large\n",
+        )
+            .write_to(connection, response_writer)
+            .await
+    }
+}
+
+impl<'r, State, const N: usize> FromRequestParts<'r, State> for AllParts<N> {
+    type Rejection = AllPartsTooLarge;
+
+    async fn from_request_parts(
+        _state: &'r State,
+        request_parts: &RequestParts<'r>,
+    ) -> Result<Self, Self::Rejection> {
+        let method = request_parts.method();
+        let path = request_parts.path().encoded();
+        let query = request_parts.query().map(|query| query.0);
+        let headers = request_parts.headers().0;
+
+        let mut buffer = heapless::Vec::new();
+
+        for part in [
+            method.as_bytes(),
+            path.as_bytes(),
+            query.unwrap_or_default().as_bytes(),
+            headers,
+        ] {
+            buffer
+                .extend_from_slice(part)
+                .map_err(|()| AllPartsTooLarge)?;
+        }
+
+        Ok(Self {
+            buffer,
+            method_length: method.len(),
+            path_length: path.len(),
+            query_length: query.map(str::len),
+        })
+    }
+}
+
+/// Extracts whether the connection will be kept alive after the response has been sent.
+/// The value takes into account the server configuration, the HTTP version, and the "Connection" header sent by the client.
+impl<'r, State> FromRequestParts<'r, State> for crate::KeepAlive {
+    type Rejection = core::convert::Infallible;
+
+    async fn from_request_parts(
+        _state: &'r State,
+        request_parts: &RequestParts<'r>,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(request_parts.keep_alive())
+    }
+}
+
+/// Extracts the pattern of the matched route, such as `/user/{u32}`, rather than the concrete path, such as `/user/42`.
+/// This is useful for labelling metrics or logs without a label for every possible value of each path parameter.
+///
+/// The pattern is the concatenation of the patterns of the path descriptions of the route and any routers it is nested within, where:
+/// + string literals are written verbatim
+/// + [`parse_path_segment::<T>()`](crate::routing::parse_path_segment) and [`parse_path_segment_or_400::<T>()`](crate::routing::parse_path_segment_or_400)
+///   are written as `/{T}`, where `T` is the type name as returned by [core::any::type_name]
+/// + tuples are the concatenation of the patterns of their elements
+///
+/// For routes added using [nest_service](crate::routing::Router::nest_service), the pattern is the path at which the service is nested.
+///
+/// The pattern is copied into a buffer with a capacity of `N` bytes.
+pub struct MatchedPath<const N: usize = 64>(pub heapless::String<N>);
+
+impl<const N: usize> core::ops::Deref for MatchedPath<N> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Rejection used for [MatchedPath].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MatchedPathRejection {
+    /// The request has not been routed by a route, such as if the extractor is used in a service passed to [Router::from_service](crate::routing::Router::from_service).
+    NoMatchedPath,
+    /// The pattern is longer than the capacity of [MatchedPath]
+    MatchedPathTooLong,
+}
+
+impl IntoResponse for MatchedPathRejection {
+    async fn write_to<R: Read, W: crate::response::ResponseWriter<Error = R::Error>>(
+        self,
+        connection: crate::response::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            match self {
+                Self::NoMatchedPath => "No Matched Path\n",
+                Self::MatchedPathTooLong => "Matched Path is too long\n",
+            },
+        )
+            .write_to(connection, response_writer)
+            .await
+    }
+}
+
+impl<'r, State, const N: usize> FromRequestParts<'r, State> for MatchedPath<N> {
+    type Rejection = MatchedPathRejection;
+
+    async fn from_request_parts(
+        _state: &'r State,
+        request_parts: &RequestParts<'r>,
+    ) -> Result<Self, Self::Rejection> {
+        let matched_path = request_parts
+            .matched_path()
+            .ok_or(MatchedPathRejection::NoMatchedPath)?;
+
+        let mut pattern = heapless::String::new();
+
+        core::fmt::Write::write_fmt(&mut pattern, format_args!("{matched_path}"))
+            .map_err(|core::fmt::Error| MatchedPathRejection::MatchedPathTooLong)?;
+
+        Ok(Self(pattern))
+    }
+}
+
+/// Rejection used for [Now] and [RequestStart].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NoClockError;
+
+impl IntoResponse for NoClockError {
+    async fn write_to<R: Read, W: crate::response::ResponseWriter<Error = R::Error>>(
+        self,
+        connection: crate::response::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Server Timer cannot measure time\n",
+        )
+            .write_to(connection, response_writer)
+            .await
+    }
+}
+
+/// Extracts the current time, as measured by the [Timer](crate::Timer) of the server.
+///
+/// The precision depends on the runtime, and extraction is rejected if the timer cannot measure time.
+pub struct Now(pub crate::time::Instant);
+
+impl<'r, State> FromRequestParts<'r, State> for Now {
+    type Rejection = NoClockError;
+
+    async fn from_request_parts(
+        _state: &'r State,
+        request_parts: &RequestParts<'r>,
+    ) -> Result<Self, Self::Rejection> {
+        request_parts.now().map(Self).ok_or(NoClockError)
+    }
+}
+
+/// Extracts the time at which the server started reading the request, which can be used to measure the latency of handling the request.
+///
+/// The precision depends on the runtime, and extraction is rejected if the [Timer](crate::Timer) of the server cannot measure time.
+#[derive(Clone, Copy)]
+pub struct RequestStart {
+    start_time: crate::time::Instant,
+    clock: fn() -> Option<crate::time::Instant>,
+}
+
+impl RequestStart {
+    /// Return the time at which the server started reading the request.
+    pub const fn instant(&self) -> crate::time::Instant {
+        self.start_time
+    }
+
+    /// Return the time elapsed since the server started reading the request.
+    pub fn elapsed(&self) -> core::time::Duration {
+        (self.clock)().map_or(core::time::Duration::ZERO, |now| {
+            now.duration_since(self.start_time)
+        })
+    }
+}
+
+impl<'r, State> FromRequestParts<'r, State> for RequestStart {
+    type Rejection = NoClockError;
+
+    async fn from_request_parts(
+        _state: &'r State,
+        request_parts: &RequestParts<'r>,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self {
+            start_time: request_parts.start_time().ok_or(NoClockError)?,
+            clock: request_parts.clock,
+        })
+    }
+}
+
+/// Rejection used for [ClientIp].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct UnknownClientIpError;
+
+impl IntoResponse for UnknownClientIpError {
+    async fn write_to<R: Read, W: crate::response::ResponseWriter<Error = R::Error>>(
+        self,
+        connection: crate::response::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Client IP address is not known\n",
+        )
+            .write_to(connection, response_writer)
+            .await
+    }
+}
+
+/// Extracts the IP address of the client.
+///
+/// If the server trusts a reverse proxy, as configured by [Config::proxy_trust](crate::Config::proxy_trust),
+/// the address is read from the "Forwarded" header, or if that is not present, the "X-Forwarded-For" header.
+/// Otherwise, or if neither header is present or the selected address is not an IP address, such as "unknown",
+/// the address of the remote peer is used.
+///
+/// Extraction is rejected if the address of the remote peer is needed but not known.
+pub struct ClientIp(pub core::net::IpAddr);
+
+impl ClientIp {
+    /// Parse a node as sent in the "Forwarded" or "X-Forwarded-For" headers, which may be quoted and may include a port.
+    fn parse_node(node: &str) -> Option<core::net::IpAddr> {
+        let node = node.trim().trim_matches('"');
+
+        if let Some(node) = node.strip_prefix('[') {
+            return node
+                .split_once(']')?
+                .0
+                .parse()
+                .ok()
+                .map(core::net::IpAddr::V6);
+        }
+
+        node.parse().ok().or_else(|| {
+            node.parse::<core::net::SocketAddr>()
+                .ok()
+                .map(|address| address.ip())
+        })
+    }
+
+    fn forwarded_address(
+        headers: crate::request::Headers,
+        proxy_trust: crate::ProxyTrust,
+    ) -> Option<core::net::IpAddr> {
+        fn select_hop(
+            mut hops: impl Iterator<Item = Option<core::net::IpAddr>>,
+            proxy_trust: crate::ProxyTrust,
+        ) -> Option<core::net::IpAddr> {
+            match proxy_trust {
+                crate::ProxyTrust::None => None,
+                crate::ProxyTrust::FirstHop => hops.next().flatten(),
+                crate::ProxyTrust::LastHop => hops.last().flatten(),
+            }
+        }
+
+        if let crate::ProxyTrust::None = proxy_trust {
+            return None;
+        }
+
+        if let Some(forwarded) = headers.get("forwarded") {
+            let hops = forwarded.split(b',').map(|element| {
+                element
+                    .split(b';')
+                    .find_map(|pair| {
+                        let (name, value) =
+                            core::str::from_utf8(pair.as_raw()).ok()?.split_once('=')?;
+
+                        name.trim().eq_ignore_ascii_case("for").then_some(value)
+                    })
+                    .and_then(Self::parse_node)
+            });
+
+            return select_hop(hops, proxy_trust);
+        }
+
+        let hops = headers.get("x-forwarded-for")?.split(b',').map(|node| {
+            core::str::from_utf8(node.as_raw())
+                .ok()
+                .and_then(Self::parse_node)
+        });
+
+        select_hop(hops, proxy_trust)
+    }
+}
+
+impl<'r, State> FromRequestParts<'r, State> for ClientIp {
+    type Rejection = UnknownClientIpError;
+
+    async fn from_request_parts(
+        _state: &'r State,
+        request_parts: &RequestParts<'r>,
+    ) -> Result<Self, Self::Rejection> {
+        Self::forwarded_address(request_parts.headers(), request_parts.proxy_trust)
+            .or_else(|| {
+                request_parts
+                    .remote_address()
+                    .map(|remote_address| remote_address.ip())
+            })
+            .map(Self)
+            .ok_or(UnknownClientIpError)
+    }
+}
+
 /// Extractor that deserializes query strings into some type.
 pub struct Query<T: serde::de::DeserializeOwned>(pub T);
 
@@ -398,6 +889,111 @@ impl<'r, State, T: serde::de::DeserializeOwned> FromRequestParts<'r, State> for
     }
 }
 
+/// Extractor which borrows the query string and looks up query parameters by key when they are accessed, without deserializing or copying them.
+///
+/// As the values borrow from the request, [RequestHandlerFunctions](crate::routing::RequestHandlerFunction) can't extract a `QueryMap`,
+/// but [RequestHandlerServices](crate::routing::RequestHandlerService) and [PathRouterServices](crate::routing::PathRouterService) can, using [from_request_parts](crate::from_request_parts).
+///
+/// Each lookup scans the query string, so for a large number of lookups, [Query] may be faster.
+///
+/// Keys and values are decoded on demand, either character by character, into a [heapless::String], or into a caller-provided buffer using [get_decoded_into](Self::get_decoded_into),
+/// so a `QueryMap` can be used without `serde` deserialization.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryMap<'r> {
+    query: crate::url_encoded::UrlEncodedString<'r>,
+}
+
+impl<'r> QueryMap<'r> {
+    /// Create a `QueryMap` from a query string, such as the value of [RequestParts::query].
+    pub const fn new(query: crate::url_encoded::UrlEncodedString<'r>) -> Self {
+        Self { query }
+    }
+
+    /// Iterate over the key-value pairs of the query, in the order they appear in the query string.
+    ///
+    /// A key without an "=" has an empty value.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            crate::url_encoded::UrlEncodedString<'r>,
+            crate::url_encoded::UrlEncodedString<'r>,
+        ),
+    > {
+        self.query
+            .0
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+                (
+                    crate::url_encoded::UrlEncodedString(key),
+                    crate::url_encoded::UrlEncodedString(value),
+                )
+            })
+    }
+
+    /// Returns the value of the first parameter whose decoded key is `key`.
+    ///
+    /// The value points into the request buffer, and is still url-encoded. It can be decoded without copying using [chars](crate::url_encoded::UrlEncodedString::chars),
+    /// or copied using [get_decoded](Self::get_decoded).
+    pub fn get(&self, key: &str) -> Option<crate::url_encoded::UrlEncodedString<'r>> {
+        self.iter()
+            .find_map(|(k, value)| (k == key).then_some(value))
+    }
+
+    /// Returns the decoded value of the first parameter whose decoded key is `key`.
+    ///
+    /// The value is decoded into a string of capacity `N`, so no allocator is required, but `N` bytes are used for the string even if the value is short.
+    pub fn get_decoded<const N: usize>(
+        &self,
+        key: &str,
+    ) -> Option<Result<heapless::String<N>, crate::url_encoded::DecodeError>> {
+        self.get(key)
+            .map(crate::url_encoded::UrlEncodedString::try_into_string)
+    }
+
+    /// Returns the value of the first parameter whose decoded key is `key`, decoded into `buffer`.
+    ///
+    /// Unlike [get_decoded](Self::get_decoded), neither `serde` nor a fixed capacity is required, which suits endpoints which read a single parameter.
+    ///
+    /// ```
+    /// use picoserve::{extract::QueryMap, url_encoded::UrlEncodedString};
+    ///
+    /// let query = QueryMap::new(UrlEncodedString("led=on&name=Hello%2C%20World"));
+    ///
+    /// let mut buffer = [0; 32];
+    ///
+    /// assert_eq!(query.get_decoded_into("name", &mut buffer).unwrap().unwrap(), "Hello, World");
+    /// assert!(query.get_decoded_into("name", &mut [0; 4]).unwrap().is_err());
+    /// assert!(query.get_decoded_into("missing", &mut buffer).is_none());
+    /// ```
+    pub fn get_decoded_into<'b>(
+        &self,
+        key: &str,
+        buffer: &'b mut [u8],
+    ) -> Option<Result<&'b str, crate::url_encoded::DecodeError>> {
+        self.get(key).map(|value| value.decode_into(buffer))
+    }
+
+    /// Returns true if the query contains a parameter whose decoded key is `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+}
+
+impl<'r, State> FromRequestParts<'r, State> for QueryMap<'r> {
+    type Rejection = core::convert::Infallible;
+
+    async fn from_request_parts(
+        _state: &'r State,
+        request_parts: &RequestParts<'r>,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self::new(request_parts.query().unwrap_or_default()))
+    }
+}
+
 /// URL encoded extractor.
 pub struct Form<T: serde::de::DeserializeOwned>(pub T);
 
@@ -463,28 +1059,106 @@ impl<'r, State, T: serde::de::DeserializeOwned> FromRequest<'r, State> for Form<
     }
 }
 
+/// The name of the form field extracted by [FormField].
+pub trait FormFieldName {
+    /// The name of the field, as sent in the form body before the "=".
+    const NAME: &'static str;
+}
+
+/// Extracts the decoded value of a single field of a URL encoded form body into a string with a capacity of `N` bytes,
+/// for simple forms with one important field, such as a PIN, without `serde` deserialization.
+///
+/// The field name is given by `Name`. If the field appears more than once, the first value is extracted.
+/// Values longer than `N` bytes are rejected with "Payload Too Large".
+///
+/// The second field only records the name, so the value can be extracted using the pattern `FormField(value, _)`.
+///
+/// ```
+/// use picoserve::{
+///     extract::{FormField, FormFieldName},
+///     routing::post,
+/// };
+///
+/// struct Pin;
+///
+/// impl FormFieldName for Pin {
+///     const NAME: &'static str = "pin";
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/unlock",
+///     post(|FormField(pin, _): FormField<Pin, 8>| async move {
+///         if pin == "1234" {
+///             "Unlocked\n"
+///         } else {
+///             "Wrong PIN\n"
+///         }
+///     }),
+/// );
+/// ```
+pub struct FormField<Name, const N: usize>(pub heapless::String<N>, pub PhantomData<fn() -> Name>);
+
+impl<Name, const N: usize> core::ops::Deref for FormField<Name, N> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Rejection used for [FormField].
+#[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub enum JsonRejection {
-    IoError,
-    DeserializationError(serde_json_core::de::Error),
+pub enum FormFieldRejection {
+    /// The body could not be read
+    FailedToReadBody,
+    /// The body is not UTF-8
+    BodyIsNotUtf8,
+    /// The form does not contain the field
+    MissingField(&'static str),
+    /// The value of the field is not correctly URL encoded
+    BadlyEncodedValue(&'static str),
+    /// The decoded value of the field is longer than the capacity of the string
+    ValueTooLong { name: &'static str, capacity: usize },
 }
 
-impl IntoResponse for JsonRejection {
+impl IntoResponse for FormFieldRejection {
     async fn write_to<R: Read, W: crate::response::ResponseWriter<Error = R::Error>>(
         self,
         connection: crate::response::Connection<'_, R>,
         response_writer: W,
     ) -> Result<ResponseSent, W::Error> {
         match self {
-            Self::IoError => {
-                (StatusCode::INTERNAL_SERVER_ERROR, "IO Error")
+            Self::FailedToReadBody => {
+                (StatusCode::BAD_REQUEST, "Failed to read body\n")
                     .write_to(connection, response_writer)
                     .await
             }
-            Self::DeserializationError(error) => {
+            Self::BodyIsNotUtf8 => {
+                (StatusCode::BAD_REQUEST, "Body is not UTF-8\n")
+                    .write_to(connection, response_writer)
+                    .await
+            }
+            Self::MissingField(name) => {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format_args!("Missing form field {name:?}\n"),
+                )
+                    .write_to(connection, response_writer)
+                    .await
+            }
+            Self::BadlyEncodedValue(name) => {
                 (
                     StatusCode::BAD_REQUEST,
-                    format_args!("Failed to parse JSON body: {error}"),
+                    format_args!("Form field {name:?} is badly encoded\n"),
+                )
+                    .write_to(connection, response_writer)
+                    .await
+            }
+            Self::ValueTooLong { name, capacity } => {
+                (
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    format_args!("Form field {name:?} is longer than {capacity} bytes\n"),
                 )
                     .write_to(connection, response_writer)
                     .await
@@ -493,6 +1167,92 @@ impl IntoResponse for JsonRejection {
     }
 }
 
+impl<'r, State, Name: FormFieldName, const N: usize> FromRequest<'r, State> for FormField<Name, N> {
+    type Rejection = FormFieldRejection;
+
+    async fn from_request<R: Read>(
+        _state: &'r State,
+        _request_parts: RequestParts<'r>,
+        request_body: RequestBody<'r, R>,
+    ) -> Result<Self, Self::Rejection> {
+        let body = core::str::from_utf8(
+            request_body
+                .read_all()
+                .await
+                .map_err(|_| FormFieldRejection::FailedToReadBody)?,
+        )
+        .map_err(|core::str::Utf8Error { .. }| FormFieldRejection::BodyIsNotUtf8)?;
+
+        match QueryMap::new(crate::url_encoded::UrlEncodedString(body)).get_decoded::<N>(Name::NAME)
+        {
+            Some(Ok(value)) => Ok(Self(value, PhantomData)),
+            Some(Err(crate::url_encoded::DecodeError::NoSpace)) => {
+                Err(FormFieldRejection::ValueTooLong {
+                    name: Name::NAME,
+                    capacity: N,
+                })
+            }
+            Some(Err(crate::url_encoded::DecodeError::BadUrlEncodedCharacter(_))) => {
+                Err(FormFieldRejection::BadlyEncodedValue(Name::NAME))
+            }
+            None => Err(FormFieldRejection::MissingField(Name::NAME)),
+        }
+    }
+}
+
+/// Rejection used for [Json].
+///
+/// The "Bad Request" response describes why the body failed to deserialize, but does not include the body itself.
+/// `serde-json-core` does not report the position of the error within the body.
+/// Enable the `json-error-messages` feature to include messages from the deserializer, such as the name of a missing field,
+/// at the cost of a larger error type.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum JsonRejection {
+    /// Error reading the body
+    IoError,
+    /// Error deserializing the body
+    DeserializationError(serde_json_core::de::Error),
+}
+
+impl core::fmt::Display for JsonRejection {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::IoError => write!(f, "IO Error"),
+            Self::DeserializationError(error) => write!(f, "Failed to parse JSON body: {error}"),
+        }
+    }
+}
+
+impl IntoResponse for JsonRejection {
+    async fn write_to<R: Read, W: crate::response::ResponseWriter<Error = R::Error>>(
+        self,
+        connection: crate::response::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        let status_code = match self {
+            Self::IoError => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::DeserializationError(..) => StatusCode::BAD_REQUEST,
+        };
+
+        (status_code, format_args!("{self}\n"))
+            .write_to(connection, response_writer)
+            .await
+    }
+}
+
+impl<'r, T: serde::Deserialize<'r>, const UNESCAPE_BUFFER_SIZE: usize>
+    Json<T, UNESCAPE_BUFFER_SIZE>
+{
+    /// Deserialize a value from a request body which has already been read, such as by [read_all_and_finalize](crate::request::RequestBodyConnection::read_all_and_finalize).
+    /// The value may borrow from `body`.
+    pub fn from_slice(body: &'r [u8]) -> Result<Self, JsonRejection> {
+        serde_json_core::from_slice_escaped(body, &mut [0; UNESCAPE_BUFFER_SIZE])
+            .map(|(value, _)| Self(value))
+            .map_err(JsonRejection::DeserializationError)
+    }
+}
+
 impl<'r, State, T: serde::Deserialize<'r>, const UNESCAPE_BUFFER_SIZE: usize>
     FromRequest<'r, State, T> for Json<T, UNESCAPE_BUFFER_SIZE>
 {
@@ -503,19 +1263,123 @@ impl<'r, State, T: serde::Deserialize<'r>, const UNESCAPE_BUFFER_SIZE: usize>
         _request_parts: RequestParts<'r>,
         request_body: RequestBody<'r, R>,
     ) -> Result<Self, Self::Rejection> {
-        serde_json_core::from_slice_escaped(
+        Self::from_slice(
             request_body
                 .read_all()
                 .await
                 .map_err(|_| JsonRejection::IoError)?,
-            &mut [0; UNESCAPE_BUFFER_SIZE],
         )
-        .map(|(value, _)| Self(value))
-        .map_err(JsonRejection::DeserializationError)
+    }
+}
+
+/// Rejection used for [Cbor].
+#[cfg(feature = "cbor")]
+#[derive(Debug)]
+pub enum CborRejection {
+    /// Error reading the body
+    IoError,
+    /// Error deserializing the body
+    DeserializationError(minicbor::decode::Error),
+}
+
+#[cfg(feature = "cbor")]
+impl IntoResponse for CborRejection {
+    async fn write_to<R: Read, W: crate::response::ResponseWriter<Error = R::Error>>(
+        self,
+        connection: crate::response::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        match self {
+            Self::IoError => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "IO Error\n")
+                    .write_to(connection, response_writer)
+                    .await
+            }
+            Self::DeserializationError(error) => {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format_args!("Failed to parse CBOR body: {error}\n"),
+                )
+                    .write_to(connection, response_writer)
+                    .await
+            }
+        }
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl<'r, State, T: minicbor::Decode<'r, ()>> FromRequest<'r, State, T> for Cbor<T> {
+    type Rejection = CborRejection;
+
+    async fn from_request<R: Read>(
+        _state: &'r State,
+        _request_parts: RequestParts<'r>,
+        request_body: RequestBody<'r, R>,
+    ) -> Result<Self, Self::Rejection> {
+        let body = request_body
+            .read_all()
+            .await
+            .map_err(|_| CborRejection::IoError)?;
+
+        let mut decoder = minicbor::Decoder::new(body);
+
+        let value = decoder
+            .decode()
+            .map_err(CborRejection::DeserializationError)?;
+
+        if decoder.position() != body.len() {
+            return Err(CborRejection::DeserializationError(
+                minicbor::decode::Error::message("trailing data after CBOR item"),
+            ));
+        }
+
+        Ok(Self(value))
     }
 }
 
 /// Used to do reference to value conversions, mainly used with the [State] extractor to extract parts of the application state.
+///
+/// Rather than implementing [FromRef] for each part of the application state, it can be derived if the `derive` feature is enabled,
+/// which implements [FromRef] for the type of each field by cloning the field.
+/// Fields can be skipped with `#[from_ref(skip)]`, which is required if two fields have the same type,
+/// or if the type of the field is a type parameter of the struct.
+///
+/// ```
+/// # #[cfg(feature = "derive")]
+/// # fn main() {
+/// use picoserve::{extract::{FromRef, State}, routing::get};
+///
+/// struct Config {
+///     name: &'static str,
+/// }
+///
+/// #[derive(Clone, Copy)]
+/// struct Counter(&'static core::cell::Cell<u32>);
+///
+/// #[derive(FromRef)]
+/// struct AppState<T> {
+///     config: &'static Config,
+///     counter: Counter,
+///     #[from_ref(skip)]
+///     extra: T,
+/// }
+///
+/// async fn get_name(State(config): State<&'static Config>) -> &'static str {
+///     config.name
+/// }
+///
+/// async fn increment(State(Counter(counter)): State<Counter>) -> picoserve::response::DebugValue<u32> {
+///     counter.set(counter.get() + 1);
+///     picoserve::response::DebugValue(counter.get())
+/// }
+///
+/// let app: picoserve::Router<_, AppState<u8>> = picoserve::Router::new()
+///     .route("/name", get(get_name))
+///     .route("/increment", get(increment));
+/// # }
+/// # #[cfg(not(feature = "derive"))]
+/// # fn main() {}
+/// ```
 pub trait FromRef<T> {
     /// Perform the reference to value conversion
     fn from_ref(input: &T) -> Self;
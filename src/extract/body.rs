@@ -0,0 +1,185 @@
+//! Extracting request bodies in a chosen format using [Body].
+
+use core::marker::PhantomData;
+
+use crate::{
+    io::Read,
+    request::{RequestBody, RequestParts},
+    response::IntoResponse,
+};
+
+use super::FromRequest;
+
+/// A format which request bodies can be deserialized from, used by [Body].
+pub trait BodyFormat<'r, T>: Sized {
+    /// The rejection if the body can't be read or deserialized.
+    type Rejection: IntoResponse + 'static;
+
+    /// Read the entire request body and deserialize it.
+    async fn deserialize_body<R: Read>(
+        request_parts: RequestParts<'r>,
+        request_body: RequestBody<'r, R>,
+    ) -> Result<T, Self::Rejection>;
+}
+
+/// Extractor that reads the entire request body and deserializes it as `T` using `Format`, such as [Json], [UrlEncoded], or `Cbor` if the `cbor` feature is enabled.
+///
+/// This avoids writing an implementation of [FromRequest] for each type deserialized from the request body.
+/// If the body can't be read or deserialized, the rejection of `Format` is sent, such as [JsonRejection](super::JsonRejection).
+///
+/// The second field only records the format, so the value can be extracted using the pattern `Body(value, _)`.
+pub struct Body<T, Format>(pub T, pub PhantomData<fn() -> Format>);
+
+impl<T, Format> Body<T, Format> {
+    /// Return the deserialized value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, Format> core::ops::Deref for Body<T, Format> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, Format> core::ops::DerefMut for Body<T, Format> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'r, State, T, Format: BodyFormat<'r, T>> FromRequest<'r, State> for Body<T, Format> {
+    type Rejection = Format::Rejection;
+
+    async fn from_request<R: Read>(
+        _state: &'r State,
+        request_parts: RequestParts<'r>,
+        request_body: RequestBody<'r, R>,
+    ) -> Result<Self, Self::Rejection> {
+        Format::deserialize_body(request_parts, request_body)
+            .await
+            .map(|value| Self(value, PhantomData))
+    }
+}
+
+/// The JSON [BodyFormat]. `UNESCAPE_BUFFER_SIZE` is the size of the temporary buffer used for unescaping strings.
+///
+/// ```
+/// use picoserve::{
+///     extract::body::{Body, Json},
+///     response::DebugValue,
+///     routing::post,
+/// };
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Reading {
+///     sensor: u8,
+///     value: f32,
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/reading",
+///     post(|Body(reading, _): Body<Reading, Json>| async move { DebugValue(reading) }),
+/// );
+/// ```
+pub struct Json<const UNESCAPE_BUFFER_SIZE: usize = 32>;
+
+impl<'r, T: serde::Deserialize<'r>, const UNESCAPE_BUFFER_SIZE: usize> BodyFormat<'r, T>
+    for Json<UNESCAPE_BUFFER_SIZE>
+{
+    type Rejection = super::JsonRejection;
+
+    async fn deserialize_body<R: Read>(
+        request_parts: RequestParts<'r>,
+        request_body: RequestBody<'r, R>,
+    ) -> Result<T, Self::Rejection> {
+        <crate::json::Json<T, UNESCAPE_BUFFER_SIZE> as FromRequest<'r, (), T>>::from_request(
+            &(),
+            request_parts,
+            request_body,
+        )
+        .await
+        .map(|crate::json::Json(value)| value)
+    }
+}
+
+/// The URL encoded [BodyFormat], as sent by HTML forms.
+///
+/// ```
+/// use picoserve::{
+///     extract::body::{Body, UrlEncoded},
+///     response::DebugValue,
+///     routing::post,
+/// };
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Login {
+///     username: heapless::String<32>,
+///     remember_me: bool,
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/login",
+///     post(|Body(login, _): Body<Login, UrlEncoded>| async move { DebugValue(login) }),
+/// );
+/// ```
+pub struct UrlEncoded;
+
+impl<'r, T: serde::de::DeserializeOwned> BodyFormat<'r, T> for UrlEncoded {
+    type Rejection = super::FormRejection;
+
+    async fn deserialize_body<R: Read>(
+        request_parts: RequestParts<'r>,
+        request_body: RequestBody<'r, R>,
+    ) -> Result<T, Self::Rejection> {
+        <super::Form<T> as FromRequest<'r, ()>>::from_request(&(), request_parts, request_body)
+            .await
+            .map(|super::Form(value)| value)
+    }
+}
+
+/// The CBOR [BodyFormat].
+///
+/// ```
+/// use picoserve::{
+///     extract::body::{Body, Cbor},
+///     response::DebugValue,
+///     routing::post,
+/// };
+///
+/// #[derive(Debug, minicbor::Decode)]
+/// struct Reading {
+///     #[n(0)]
+///     sensor: u8,
+///     #[n(1)]
+///     value: f32,
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/reading",
+///     post(|Body(reading, _): Body<Reading, Cbor>| async move { DebugValue(reading) }),
+/// );
+/// ```
+#[cfg(feature = "cbor")]
+pub struct Cbor;
+
+#[cfg(feature = "cbor")]
+impl<'r, T: minicbor::Decode<'r, ()>> BodyFormat<'r, T> for Cbor {
+    type Rejection = super::CborRejection;
+
+    async fn deserialize_body<R: Read>(
+        request_parts: RequestParts<'r>,
+        request_body: RequestBody<'r, R>,
+    ) -> Result<T, Self::Rejection> {
+        <crate::cbor::Cbor<T> as FromRequest<'r, (), T>>::from_request(
+            &(),
+            request_parts,
+            request_body,
+        )
+        .await
+        .map(|crate::cbor::Cbor(value)| value)
+    }
+}
@@ -0,0 +1,284 @@
+use core::fmt::{self, Write};
+
+use serde::de::Error;
+
+use crate::{
+    io::Read,
+    response::{IntoResponse, StatusCode},
+    url_encoded::DeserializationError,
+    ResponseSent,
+};
+
+/// Extractor that deserializes the path parameters captured by the route into some type.
+///
+/// Unlike other extractors, [Path] is not extracted from the request, but from the path parameters captured while routing the request.
+/// As the request is routed through the [PathRouter](crate::routing::PathRouter), each [PathDescription](crate::routing::PathDescription) parses its segments of the path,
+/// and the resulting path parameters are passed to the handler function, so [Path] must be the first argument of the handler function, in place of the path parameters.
+///
+/// Each captured path parameter is formatted using its [Display](fmt::Display) implementation and then deserialized,
+/// so `T` may be a single value for routes with a single path parameter, or a tuple or struct for routes with many path parameters,
+/// where struct fields are deserialized in the same order as the path parameters.
+///
+/// ```
+/// use picoserve::{
+///     extract::Path,
+///     routing::{get, parse_path_segment},
+/// };
+///
+/// #[derive(serde::Deserialize)]
+/// struct Post {
+///     user_id: u32,
+///     post_id: u32,
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     ("/user", parse_path_segment::<u32>(), "/post", parse_path_segment::<u32>()),
+///     get(|Path(Post { user_id, post_id }): Path<Post>| async move {
+///         picoserve::response::DebugValue((user_id, post_id))
+///     }),
+/// );
+/// ```
+pub struct Path<T: serde::de::DeserializeOwned>(pub T);
+
+impl<T: serde::de::DeserializeOwned> core::ops::Deref for Path<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: serde::de::DeserializeOwned> core::ops::DerefMut for Path<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: serde::de::DeserializeOwned> Path<T> {
+    pub(crate) fn from_path_parameters(
+        path_parameters: &[&dyn fmt::Display],
+    ) -> Result<Self, PathRejection> {
+        T::deserialize(PathParametersDeserializer(path_parameters))
+            .map(Self)
+            .map_err(|DeserializationError| PathRejection)
+    }
+}
+
+/// Rejection used for [Path].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PathRejection;
+
+impl IntoResponse for PathRejection {
+    async fn write_to<R: Read, W: crate::response::ResponseWriter<Error = R::Error>>(
+        self,
+        connection: crate::response::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        (StatusCode::BAD_REQUEST, "Bad Path Parameters\n")
+            .write_to(connection, response_writer)
+            .await
+    }
+}
+
+struct PathParametersDeserializer<'a>(&'a [&'a dyn fmt::Display]);
+
+impl<'a> PathParametersDeserializer<'a> {
+    fn single_value(self) -> Result<PathParameterDeserializer<'a>, DeserializationError> {
+        match self.0 {
+            [value] => Ok(PathParameterDeserializer(*value)),
+            parameters => Err(DeserializationError::invalid_length(
+                parameters.len(),
+                &"a single path parameter",
+            )),
+        }
+    }
+}
+
+macro_rules! deserialize_single_value {
+    ($($deserialize:ident)*) => {
+        $(
+            fn $deserialize<V: serde::de::Visitor<'de>>(
+                self,
+                visitor: V,
+            ) -> Result<V::Value, Self::Error> {
+                self.single_value()?.$deserialize(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> serde::Deserializer<'de> for PathParametersDeserializer<'a> {
+    type Error = DeserializationError;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(PathParametersSeqAccess(self.0.iter()))
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    deserialize_single_value!(
+        deserialize_bool deserialize_char deserialize_str deserialize_string deserialize_option
+        deserialize_f32 deserialize_f64
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+    );
+
+    fn deserialize_enum<V: serde::de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.single_value()?
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct PathParametersSeqAccess<'a>(core::slice::Iter<'a, &'a dyn fmt::Display>);
+
+impl<'de, 'a> serde::de::SeqAccess<'de> for PathParametersSeqAccess<'a> {
+    type Error = DeserializationError;
+
+    fn next_element_seed<T: serde::de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(PathParameterDeserializer(*value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct PathParameterDeserializer<'a>(&'a dyn fmt::Display);
+
+impl<'a> PathParameterDeserializer<'a> {
+    fn with_formatted<T, F: FnOnce(&str) -> Result<T, DeserializationError>>(
+        self,
+        f: F,
+    ) -> Result<T, DeserializationError> {
+        let mut value = heapless::String::<128>::new();
+
+        write!(value, "{}", self.0)
+            .map_err(|fmt::Error| DeserializationError::custom("path parameter is too long"))?;
+
+        f(&value)
+    }
+}
+
+macro_rules! deserialize_parse_value {
+    ($($deserialize:ident $visit:ident)*) => {
+        $(
+            fn $deserialize<V: serde::de::Visitor<'de>>(
+                self,
+                visitor: V,
+            ) -> Result<V::Value, Self::Error> {
+                self.with_formatted(|value| {
+                    visitor.$visit(value.parse().map_err(|err| {
+                        DeserializationError::custom(format_args!("Failed to parse {:?}: {}", value, err))
+                    })?)
+                })
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> serde::Deserializer<'de> for PathParameterDeserializer<'a> {
+    type Error = DeserializationError;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.with_formatted(|value| visitor.visit_str(value))
+    }
+
+    fn deserialize_option<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.with_formatted(|value| {
+            visitor.visit_enum(serde::de::value::StrDeserializer::new(value))
+        })
+    }
+
+    fn deserialize_seq<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        PathParametersDeserializer(&[self.0]).deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple<V: serde::de::Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    deserialize_parse_value!(
+        deserialize_bool visit_bool deserialize_char visit_char
+        deserialize_f32 visit_f32 deserialize_f64 visit_f64
+        deserialize_i8 visit_i8 deserialize_i16 visit_i16 deserialize_i32 visit_i32 deserialize_i64 visit_i64
+        deserialize_u8 visit_u8 deserialize_u16 visit_u16 deserialize_u32 visit_u32 deserialize_u64 visit_u64
+    );
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct map identifier ignored_any
+    }
+}
@@ -0,0 +1,123 @@
+//! Decompression of gzip ([RFC 1952](https://www.rfc-editor.org/rfc/rfc1952)) and zlib ([RFC 1950](https://www.rfc-editor.org/rfc/rfc1950)) data,
+//! used to decompress request bodies.
+//!
+//! The DEFLATE data is decoded by [miniz_oxide], which also checks the zlib checksum. The gzip wrapper is parsed here.
+//!
+//! The entire decompressed data is written into the output buffer, which also serves as the window for back-references,
+//! so no separate 32KiB window is required.
+
+use miniz_oxide::inflate::{
+    core::{decompress, inflate_flags, DecompressorOxide},
+    TINFLStatus,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InflateError {
+    /// The decompressed data does not fit into the output buffer
+    OutputTooLarge,
+    /// The input is not valid compressed data, or its checksum does not match
+    InvalidData,
+}
+
+/// Decompress all of `input` into `output`, returning the number of bytes of `input` which were read, and the decompressed length.
+fn inflate(input: &[u8], output: &mut [u8], flags: u32) -> Result<(usize, usize), InflateError> {
+    // The decompressor is about 11KiB, but as decompression isn't async, it's only on the stack while decompressing
+    let mut decompressor = DecompressorOxide::new();
+
+    match decompress(
+        &mut decompressor,
+        input,
+        output,
+        0,
+        flags | inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+    ) {
+        (TINFLStatus::Done, read, length) => Ok((read, length)),
+        (TINFLStatus::HasMoreOutput, _, _) => Err(InflateError::OutputTooLarge),
+        _ => Err(InflateError::InvalidData),
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+/// Remove the first `length` bytes from `input`.
+fn take<'a>(input: &mut &'a [u8], length: usize) -> Result<&'a [u8], InflateError> {
+    if input.len() < length {
+        return Err(InflateError::InvalidData);
+    }
+
+    let (bytes, rest) = input.split_at(length);
+
+    *input = rest;
+
+    Ok(bytes)
+}
+
+/// Decompress a gzip member, checking its CRC-32 and length, and returning the decompressed length.
+pub(crate) fn inflate_gzip(mut input: &[u8], output: &mut [u8]) -> Result<usize, InflateError> {
+    const FHCRC: u8 = 0b0000_0010;
+    const FEXTRA: u8 = 0b0000_0100;
+    const FNAME: u8 = 0b0000_1000;
+    const FCOMMENT: u8 = 0b0001_0000;
+    const RESERVED: u8 = 0b1110_0000;
+
+    let &[id1, id2, compression_method, flags, ..] = take(&mut input, 10)? else {
+        return Err(InflateError::InvalidData);
+    };
+
+    if id1 != 0x1f || id2 != 0x8b || compression_method != 8 || flags & RESERVED != 0 {
+        return Err(InflateError::InvalidData);
+    }
+
+    if flags & FEXTRA != 0 {
+        let extra_length = take(&mut input, 2)?;
+        take(
+            &mut input,
+            u16::from_le_bytes([extra_length[0], extra_length[1]]).into(),
+        )?;
+    }
+
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            while take(&mut input, 1)? != [0] {}
+        }
+    }
+
+    if flags & FHCRC != 0 {
+        take(&mut input, 2)?;
+    }
+
+    let (read, length) = inflate(input, output, 0)?;
+
+    let &[c0, c1, c2, c3, l0, l1, l2, l3] = &input[read..] else {
+        return Err(InflateError::InvalidData);
+    };
+
+    if crc32(&output[..length]) != u32::from_le_bytes([c0, c1, c2, c3])
+        || length as u32 != u32::from_le_bytes([l0, l1, l2, l3])
+    {
+        return Err(InflateError::InvalidData);
+    }
+
+    Ok(length)
+}
+
+/// Decompress a zlib stream, checking its Adler-32 checksum, and returning the decompressed length.
+pub(crate) fn inflate_zlib(input: &[u8], output: &mut [u8]) -> Result<usize, InflateError> {
+    let (read, length) = inflate(
+        input,
+        output,
+        inflate_flags::TINFL_FLAG_PARSE_ZLIB_HEADER | inflate_flags::TINFL_FLAG_COMPUTE_ADLER32,
+    )?;
+
+    if read != input.len() {
+        return Err(InflateError::InvalidData);
+    }
+
+    Ok(length)
+}
@@ -1,4 +1,15 @@
 //! IO Utility
+//!
+//! # Flushing
+//!
+//! Data written to a [Write] may be held back by the socket, for example in the transmit buffer of an `embassy-net` socket,
+//! or by Nagle's algorithm, until enough data has been written. Calling [Write::flush] sends any held back data to the client,
+//! and waits until it has been sent.
+//!
+//! The server flushes the writer after writing the response headers and after writing the response body,
+//! so a [Body](crate::response::Body) which writes its data in a single burst need not flush.
+//! A body which is written slowly, such as one which waits for events, should flush after each logical unit of data,
+//! so that the client receives each unit as soon as it has been written, rather than once later data fills the buffer.
 
 use core::fmt;
 
@@ -29,13 +40,27 @@ pub(crate) struct FormatBuffer {
 
 impl fmt::Write for FormatBuffer {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        for &b in s.as_bytes() {
+        self.write_bytes(s.as_bytes()).map_err(|()| fmt::Error)
+    }
+}
+
+impl FormatBuffer {
+    pub fn new(ignore_count: usize) -> Self {
+        Self {
+            data: heapless::Vec::new(),
+            ignore_count,
+            error_state: FormatBufferWriteError::FormatError,
+        }
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        for &b in bytes {
             match self.ignore_count.checked_sub(1) {
                 Some(ignore_count) => self.ignore_count = ignore_count,
                 None => {
                     if self.data.push(b).is_err() {
                         self.error_state = FormatBufferWriteError::OutOfSpace(());
-                        return Err(fmt::Error);
+                        return Err(());
                     }
                 }
             }
@@ -43,16 +68,6 @@ impl fmt::Write for FormatBuffer {
 
         Ok(())
     }
-}
-
-impl FormatBuffer {
-    pub fn new(ignore_count: usize) -> Self {
-        Self {
-            data: heapless::Vec::new(),
-            ignore_count,
-            error_state: FormatBufferWriteError::FormatError,
-        }
-    }
 
     pub fn write(
         &mut self,
@@ -113,6 +128,19 @@ pub trait Socket: Sized {
     /// Split the socket into its "read" and "write" half
     fn split(&mut self) -> (Self::ReadHalf<'_>, Self::WriteHalf<'_>);
 
+    /// Hint that data should be sent as soon as it is flushed rather than waiting to be coalesced with further data,
+    /// i.e. disable Nagle's algorithm, as set by `TCP_NODELAY`. Called before serving requests if [Config::tcp_nodelay](crate::Config::tcp_nodelay) is set.
+    ///
+    /// The default implementation does nothing, for sockets which do not support it.
+    fn set_nodelay(&mut self) {}
+
+    /// The address of the remote peer, used by [Config::connection_filter](crate::Config::connection_filter) and [ClientIp](crate::extract::ClientIp).
+    ///
+    /// The default implementation returns `None`, for sockets which do not know the address of the remote peer.
+    fn remote_address(&self) -> Option<core::net::SocketAddr> {
+        None
+    }
+
     /// Perform a graceful shutdown
     async fn shutdown<Timer: crate::Timer>(
         self,
@@ -165,6 +193,19 @@ pub(crate) mod tokio_support {
             (TokioIo(read_half), TokioIo(write_half))
         }
 
+        fn set_nodelay(&mut self) {
+            if let Err(err) = tokio::net::TcpStream::set_nodelay(self, true) {
+                log_warn!(
+                    "Failed to set TCP_NODELAY: {}",
+                    crate::logging::Debug2Format(&err)
+                );
+            }
+        }
+
+        fn remote_address(&self) -> Option<core::net::SocketAddr> {
+            self.peer_addr().ok()
+        }
+
         async fn shutdown<Timer: crate::Timer>(
             mut self,
             timeouts: &crate::Timeouts<Timer::Duration>,
@@ -199,6 +240,123 @@ pub(crate) mod tokio_support {
     }
 }
 
+#[cfg(feature = "futures-io")]
+mod futures_io_support {
+    use core::{cell::RefCell, pin::Pin};
+
+    use embedded_io_async::{Error, ErrorKind, ErrorType, Read, Write};
+    use futures_io::{AsyncRead, AsyncWrite};
+
+    /// An IO Error from a [FuturesIoSocket].
+    #[derive(Debug)]
+    pub struct FuturesIoError(pub std::io::Error);
+
+    impl Error for FuturesIoError {
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    /// A [Socket](super::Socket) wrapping a connection which implements [AsyncRead] and [AsyncWrite] from `futures-io`,
+    /// such as a `TcpStream` from `smol` or `async-std`.
+    ///
+    /// The read half and the write half share the connection, which is only borrowed while being polled,
+    /// so reading and writing may be done concurrently within the same task, such as by Web Sockets.
+    pub struct FuturesIoSocket<T>(RefCell<T>);
+
+    impl<T> FuturesIoSocket<T> {
+        /// Wrap `connection` so that it can be served.
+        pub const fn new(connection: T) -> Self {
+            Self(RefCell::new(connection))
+        }
+
+        /// Return the wrapped connection.
+        pub fn into_inner(self) -> T {
+            self.0.into_inner()
+        }
+    }
+
+    /// The read half or the write half of a [FuturesIoSocket].
+    pub struct FuturesIoHalf<'a, T>(&'a RefCell<T>);
+
+    impl<'a, T> ErrorType for FuturesIoHalf<'a, T> {
+        type Error = FuturesIoError;
+    }
+
+    impl<'a, T: AsyncRead + Unpin> Read for FuturesIoHalf<'a, T> {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            core::future::poll_fn(|cx| Pin::new(&mut *self.0.borrow_mut()).poll_read(cx, buf))
+                .await
+                .map_err(FuturesIoError)
+        }
+    }
+
+    impl<'a, T: AsyncWrite + Unpin> Write for FuturesIoHalf<'a, T> {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            core::future::poll_fn(|cx| Pin::new(&mut *self.0.borrow_mut()).poll_write(cx, buf))
+                .await
+                .map_err(FuturesIoError)
+        }
+
+        async fn flush(&mut self) -> Result<(), Self::Error> {
+            core::future::poll_fn(|cx| Pin::new(&mut *self.0.borrow_mut()).poll_flush(cx))
+                .await
+                .map_err(FuturesIoError)
+        }
+    }
+
+    impl<T: AsyncRead + AsyncWrite + Unpin> super::Socket for FuturesIoSocket<T> {
+        type Error = FuturesIoError;
+        type ReadHalf<'a> = FuturesIoHalf<'a, T> where T: 'a;
+        type WriteHalf<'a> = FuturesIoHalf<'a, T> where T: 'a;
+
+        fn split(&mut self) -> (Self::ReadHalf<'_>, Self::WriteHalf<'_>) {
+            (FuturesIoHalf(&self.0), FuturesIoHalf(&self.0))
+        }
+
+        async fn shutdown<Timer: crate::Timer>(
+            self,
+            timeouts: &crate::Timeouts<Timer::Duration>,
+            timer: &mut Timer,
+        ) -> Result<(), crate::Error<Self::Error>> {
+            use crate::time::TimerExt;
+
+            let mut connection = self.0.into_inner();
+
+            // Flush any remaining data and close the write half of the connection
+            timer
+                .run_with_maybe_timeout(
+                    timeouts.write.clone(),
+                    core::future::poll_fn(|cx| Pin::new(&mut connection).poll_close(cx)),
+                )
+                .await
+                .map_err(|_err| crate::Error::WriteTimeout)?
+                .map_err(|err| crate::Error::Write(FuturesIoError(err)))?;
+
+            let mut buffer = [0; 128];
+
+            // Discard data until the client closes the connection
+            while timer
+                .run_with_maybe_timeout(
+                    timeouts.read_request.clone(),
+                    core::future::poll_fn(|cx| {
+                        Pin::new(&mut connection).poll_read(cx, &mut buffer)
+                    }),
+                )
+                .await
+                .map_err(|_err| crate::Error::ReadTimeout)?
+                .map_err(|err| crate::Error::Read(FuturesIoError(err)))?
+                > 0
+            {}
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "futures-io")]
+pub use futures_io_support::{FuturesIoError, FuturesIoHalf, FuturesIoSocket};
+
 #[cfg(feature = "embassy")]
 impl<'s> Socket for embassy_net::tcp::TcpSocket<'s> {
     type Error = embassy_net::tcp::Error;
@@ -209,6 +367,11 @@ impl<'s> Socket for embassy_net::tcp::TcpSocket<'s> {
         embassy_net::tcp::TcpSocket::split(self)
     }
 
+    fn remote_address(&self) -> Option<core::net::SocketAddr> {
+        self.remote_endpoint()
+            .map(|endpoint| core::net::SocketAddr::new(endpoint.addr.into(), endpoint.port))
+    }
+
     async fn shutdown<Timer: crate::Timer>(
         mut self,
         timeouts: &crate::Timeouts<Timer::Duration>,
@@ -1,3 +1,60 @@
 /// A JSON encoded value. When serializing, the value might be serialized several times during sending, so the value must be serialized in the same way each time.
 /// When values are deserialized, `UNESCAPE_BUFFER_SIZE` is the size of the temporary buffer used for unescaping strings.
+///
+/// Values are deserialized in-place from the request body, so `T` may borrow from the request, such as fields of type `&'r str`, avoiding copying strings into `heapless::String`.
+/// Borrowed `&str` fields can only be deserialized if the string contains no escape sequences, as unescaping requires a copy.
+/// Use [EscapedStr](crate::extract::json::str::EscapedStr) to borrow strings which might contain escape sequences.
+///
+/// As [RequestHandlerFunctions](crate::routing::RequestHandlerFunction) may not borrow from the request, [Json] extractors in handler functions must own their values.
+/// To borrow from the body, implement [RequestHandlerService](crate::routing::RequestHandlerService),
+/// read the body using [read_all_and_finalize](crate::request::RequestBodyConnection::read_all_and_finalize) and deserialize it using [Json::from_slice],
+/// and the value can be used until the response has been written.
+///
+/// ```
+/// use picoserve::{
+///     extract::Json,
+///     io::Read,
+///     request::Request,
+///     response::{IntoResponse, ResponseWriter, StatusCode},
+///     routing::{post_service, RequestHandlerService},
+///     ResponseSent,
+/// };
+///
+/// #[derive(serde::Deserialize)]
+/// struct Greeting<'a> {
+///     name: &'a str,
+///     repeat: u8,
+/// }
+///
+/// struct Greet;
+///
+/// impl RequestHandlerService<()> for Greet {
+///     async fn call_request_handler_service<R: Read, W: ResponseWriter<Error = R::Error>>(
+///         &self,
+///         _state: &(),
+///         (): (),
+///         request: Request<'_, R>,
+///         response_writer: W,
+///     ) -> Result<ResponseSent, W::Error> {
+///         let (body, connection) = request.body_connection.read_all_and_finalize().await?;
+///
+///         let Some(body) = body else {
+///             return (StatusCode::PAYLOAD_TOO_LARGE, "Body is too large\n")
+///                 .write_to(connection, response_writer)
+///                 .await;
+///         };
+///
+///         match Json::<Greeting>::from_slice(body) {
+///             Ok(Json(Greeting { name, repeat })) => {
+///                 format_args!("Hello {name} ({repeat} times)\n")
+///                     .write_to(connection, response_writer)
+///                     .await
+///             }
+///             Err(rejection) => rejection.write_to(connection, response_writer).await,
+///         }
+///     }
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route("/greet", post_service(Greet));
+/// ```
 pub struct Json<T, const UNESCAPE_BUFFER_SIZE: usize = 32>(pub T);
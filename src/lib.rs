@@ -19,16 +19,26 @@ compile_error!("You cannot enable both tokio and embassy support");
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+// Allows code generated by picoserve_derive to refer to `::picoserve` within this crate.
+extern crate self as picoserve;
+
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "decompression")]
+mod inflate;
 mod json;
 
 #[macro_use]
 mod logging;
 
+pub mod base64;
 pub mod extract;
 pub mod io;
 pub mod request;
 pub mod response;
 pub mod routing;
+#[cfg(any(feature = "test-util", test))]
+pub mod test;
 pub mod time;
 pub mod url_encoded;
 
@@ -75,12 +85,48 @@ pub struct Timeouts<D> {
     /// The duration of time to wait when starting to read a new request before the connection is closed due to inactivity.
     pub start_read_request: Option<D>,
     /// The duration of time to wait when partway reading a request before the connection is aborted and closed.
+    /// This applies to reading the request line and headers. Reads of the request body by handlers are not limited,
+    /// so a handler can read part of the body and then stream a long-lived response, such as for long-polling.
     pub read_request: Option<D>,
     /// The duration of time to wait when writing the response before the connection is aborted and closed.
+    /// This applies to each write to the socket, so long-lived responses such as Server-Sent Events are only aborted if the client stops reading.
     pub write: Option<D>,
+    /// The maximum duration of time that a connection is kept alive for, measured from when the connection is accepted.
+    /// Once exceeded, the request being handled is completed, with a "Connection: close" header, and then the connection is closed,
+    /// so a client making periodic requests can't keep a connection open forever.
+    ///
+    /// This requires a [Timer] which can measure time using [Timer::now] and [Timer::duration_as_micros], such as the timers used by the `tokio` and `embassy` features.
+    /// Otherwise, it has no effect.
+    pub connection_max_lifetime: Option<D>,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl<D> Timeouts<D> {
+    /// Create new timeouts for reading requests and writing responses.
+    /// Connections are kept alive for as long as the client keeps making requests, see [with_connection_max_lifetime](Self::with_connection_max_lifetime).
+    pub const fn new(
+        start_read_request: Option<D>,
+        read_request: Option<D>,
+        write: Option<D>,
+    ) -> Self {
+        Self {
+            start_read_request,
+            read_request,
+            write,
+            connection_max_lifetime: None,
+        }
+    }
+}
+
+impl<D: Copy> Timeouts<D> {
+    /// Set the maximum duration of time that a connection is kept alive for, see [connection_max_lifetime](Self::connection_max_lifetime).
+    pub const fn with_connection_max_lifetime(mut self, connection_max_lifetime: D) -> Self {
+        self.connection_max_lifetime = Some(connection_max_lifetime);
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 /// After the response has been sent, should the connection be kept open to allow the client to make further requests on the same TCP connection?
 pub enum KeepAlive {
@@ -109,20 +155,19 @@ impl KeepAlive {
         }
     }
 
+    /// Requests to upgrade the connection, such as Web Socket handshakes, close the connection once the upgraded connection has finished,
+    /// as the data sent after the request is no longer HTTP, so the server must not attempt to read another request.
+    ///
+    /// The "Connection: close" header is not sent in the response to such requests,
+    /// as the response sets its own "Connection" header of "upgrade", which replaces it.
     fn from_request(http_version: &str, headers: request::Headers) -> Self {
-        match headers.get("connection") {
-            None => Self::default_for_http_version(http_version),
-            Some(close_header) if close_header == "close" => Self::Close,
-            Some(connection_headers) => {
-                if connection_headers
-                    .split(b',')
-                    .any(|connection_header| connection_header == "upgrade")
-                {
-                    Self::Close
-                } else {
-                    Self::default_for_http_version(http_version)
-                }
-            }
+        if headers.connection_has_option("close") || headers.connection_has_option("upgrade") {
+            Self::Close
+        } else if headers.connection_has_option("keep-alive") {
+            // HTTP/1.0 clients opt in to persistent connections using "Connection: keep-alive"
+            Self::KeepAlive
+        } else {
+            Self::default_for_http_version(http_version)
         }
     }
 }
@@ -133,7 +178,32 @@ pub struct Config<D> {
     /// The timeout information
     pub timeouts: Timeouts<D>,
     /// Whether to close the connection after handling a request or keeping it open to allow further requests on the same connection.
+    ///
+    /// Responses to requests which were successfully parsed, including rejections from extractors such as "Bad Request" or "Payload Too Large", keep the connection alive,
+    /// as the rest of the body is read and discarded. The connection is closed if the request could not be parsed, or if the end of the body is not known,
+    /// such as for requests with a "Transfer-Encoding" other than "chunked", as the start of the next request would also not be known.
+    ///
+    /// A handler can close the connection after its response by sending a "Connection: close" header.
     pub connection: KeepAlive,
+    /// How strictly request header lines are validated.
+    pub header_validation: HeaderValidation,
+    /// If set, called with the address of the remote peer of each connection, as given by [Socket::remote_address](io::Socket::remote_address), before any data is read.
+    /// If it returns false, or the address is not known, the connection is closed without reading the request.
+    pub connection_filter: Option<fn(core::net::SocketAddr) -> bool>,
+    /// Whether the "Forwarded" and "X-Forwarded-For" headers are trusted when extracting [ClientIp](extract::ClientIp).
+    pub proxy_trust: ProxyTrust,
+    /// If set, the maximum number of unread request body bytes which are read and discarded after a request has been handled, so that the connection can be kept alive.
+    /// If more of the body is unread, the connection is closed instead. If not set, which is the default, the rest of the body is always read.
+    pub auto_drain_body: Option<usize>,
+    /// If set, the socket is asked to send data as soon as it is flushed, i.e. disable Nagle's algorithm, using [Socket::set_nodelay](io::Socket::set_nodelay).
+    pub tcp_nodelay: bool,
+    /// If set, the maximum number of header lines in a request. Requests with more headers are rejected with "Request Header Fields Too Large",
+    /// and the connection is closed. If not set, which is the default, the number of headers is only limited by the size of the buffer.
+    pub max_headers: Option<usize>,
+    /// If set, the format of the response sent when a request can't be parsed, such as JSON. If not set, which is the default, the response is a plain-text message.
+    pub parse_error_format: Option<ParseErrorFormat>,
+    /// If set, called with the number of bytes read and written once each connection has been closed, such as for bandwidth accounting.
+    pub on_disconnection: Option<fn(&DisconnectionInfo)>,
 }
 
 impl<D> Config<D> {
@@ -143,6 +213,14 @@ impl<D> Config<D> {
         Self {
             timeouts,
             connection: KeepAlive::Close,
+            header_validation: HeaderValidation::Lenient,
+            connection_filter: None,
+            proxy_trust: ProxyTrust::None,
+            auto_drain_body: None,
+            tcp_nodelay: false,
+            max_headers: None,
+            parse_error_format: None,
+            on_disconnection: None,
         }
     }
 
@@ -162,6 +240,229 @@ impl<D> Config<D> {
 
         self
     }
+
+    /// Respond with "Bad Request" to requests with invalid header names, or header values containing control characters or non-ASCII bytes.
+    pub const fn strict_header_validation(mut self) -> Self {
+        self.header_validation = HeaderValidation::Strict;
+
+        self
+    }
+
+    /// Only serve connections from remote peers for which `filter` returns true, such as an IP allowlist.
+    /// Other connections are closed immediately, without reading the request, which is cheaper than rejecting each request.
+    ///
+    /// The filter applies to every connection, including those served by [Server::serve_once].
+    /// If the socket does not know the address of the remote peer, as given by [Socket::remote_address](io::Socket::remote_address),
+    /// the connection is always closed, so that the filter is never bypassed.
+    pub const fn connection_filter(mut self, filter: fn(core::net::SocketAddr) -> bool) -> Self {
+        self.connection_filter = Some(filter);
+
+        self
+    }
+
+    /// Trust the "Forwarded" and "X-Forwarded-For" headers when extracting [ClientIp](extract::ClientIp), such as when the server is behind a reverse proxy.
+    ///
+    /// Only enable this if all connections are made by a trusted proxy, as otherwise clients can set the headers to any address.
+    pub const fn proxy_trust(mut self, proxy_trust: ProxyTrust) -> Self {
+        self.proxy_trust = proxy_trust;
+
+        self
+    }
+
+    /// If a handler does not read the entire request body, read and discard at most `max_bytes` of the rest of the body,
+    /// so that the connection can be kept alive, and otherwise close the connection after the response has been sent.
+    ///
+    /// This avoids spending time reading large bodies which the handler has rejected, while still keeping connections alive for
+    /// handlers which respond without reading small bodies.
+    pub const fn auto_drain_body(mut self, max_bytes: usize) -> Self {
+        self.auto_drain_body = Some(max_bytes);
+
+        self
+    }
+
+    /// Reject requests with more than `count` header lines with "Request Header Fields Too Large", stopping parsing as soon as the limit is exceeded.
+    ///
+    /// This bounds the work spent parsing the headers of each request, which is otherwise only limited by the size of the buffer.
+    pub const fn max_headers(mut self, count: usize) -> Self {
+        self.max_headers = Some(count);
+
+        self
+    }
+
+    /// Format the response sent when a request can't be parsed using `format`, rather than as a plain-text message.
+    ///
+    /// ```
+    /// use picoserve::{Config, ParseErrorFormat, Timeouts};
+    ///
+    /// let config = Config::new(Timeouts::new(None, None, None))
+    /// .parse_error_format(ParseErrorFormat {
+    ///     content_type: "application/json",
+    ///     write_body: |error, f| write!(f, r#"{{"error":"{error}"}}"#),
+    /// });
+    /// # let _: Config<()> = config;
+    /// ```
+    pub const fn parse_error_format(mut self, format: ParseErrorFormat) -> Self {
+        self.parse_error_format = Some(format);
+
+        self
+    }
+
+    /// Call `callback` with the number of bytes read and written once each connection has been closed,
+    /// whether the connection was closed by the client, by the server, or due to an error.
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    ///
+    /// use picoserve::{Config, Timeouts};
+    ///
+    /// static BYTES_WRITTEN: AtomicU64 = AtomicU64::new(0);
+    ///
+    /// let config = Config::new(Timeouts::new(None, None, None))
+    /// .on_disconnection(|info| {
+    ///     BYTES_WRITTEN.fetch_add(info.bytes_written, Ordering::Relaxed);
+    /// });
+    /// # let _: Config<()> = config;
+    /// ```
+    pub const fn on_disconnection(mut self, callback: fn(&DisconnectionInfo)) -> Self {
+        self.on_disconnection = Some(callback);
+
+        self
+    }
+
+    /// Ask the socket to send data as soon as it is flushed rather than coalescing small writes, i.e. disable Nagle's algorithm.
+    ///
+    /// The response headers and the response body are each flushed once written, so with Nagle's algorithm enabled,
+    /// a small response body may be delayed until the client acknowledges the headers.
+    /// This only has an effect for sockets which implement [Socket::set_nodelay](io::Socket::set_nodelay), such as tokio sockets.
+    pub const fn tcp_nodelay(mut self) -> Self {
+        self.tcp_nodelay = true;
+
+        self
+    }
+}
+
+/// The format of the response sent when a request can't be parsed, set using [Config::parse_error_format].
+///
+/// The status code is always [ParseError::status_code](request::ParseError::status_code), and the connection is closed after the response has been sent.
+#[derive(Debug, Clone, Copy)]
+pub struct ParseErrorFormat {
+    /// The value of the "Content-Type" header.
+    pub content_type: &'static str,
+    /// Write the body of the response.
+    pub write_body: fn(request::ParseError, &mut core::fmt::Formatter<'_>) -> core::fmt::Result,
+}
+
+impl ParseErrorFormat {
+    /// Send just the status code, with an empty body.
+    pub const STATUS_ONLY: Self = Self {
+        content_type: "text/plain; charset=utf-8",
+        write_body: |_error, _f| Ok(()),
+    };
+}
+
+/// The body of the response sent when a request can't be parsed, formatted using a [ParseErrorFormat].
+struct ParseErrorBody {
+    format: ParseErrorFormat,
+    error: request::ParseError,
+}
+
+impl core::fmt::Display for ParseErrorBody {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        (self.format.write_body)(self.error, f)
+    }
+}
+
+impl response::Content for ParseErrorBody {
+    fn content_type(&self) -> &'static str {
+        self.format.content_type
+    }
+
+    fn content_length(&self) -> usize {
+        format_args!("{self}").content_length()
+    }
+
+    async fn write_content<W: io::Write>(self, writer: W) -> Result<(), W::Error> {
+        format_args!("{self}").write_content(writer).await
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Which address listed in the "Forwarded" or "X-Forwarded-For" headers is used as the address of the client.
+///
+/// Each proxy appends the address of the peer which connected to it, so the first address is the original client
+/// and the last address is the peer which connected to the proxy which connected to the server.
+pub enum ProxyTrust {
+    /// Ignore the headers, and use the address of the remote peer. This is the default.
+    None,
+    /// Use the first address, which is the original client if all proxies are trusted, but which can be forged by the client.
+    FirstHop,
+    /// Use the last address, which is the peer as seen by the proxy which connected to the server, so cannot be forged by the client.
+    LastHop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// How strictly request header lines are validated.
+///
+/// Header values are never decoded, so non-ASCII bytes and sequences such as `%20` are passed to handlers verbatim.
+pub enum HeaderValidation {
+    /// Only check that each header line contains a ':'. This is the default.
+    Lenient,
+    /// Respond with "Bad Request" if a header name is not a valid token as defined by RFC 7230,
+    /// or a header value contains control characters (other than horizontal tab) or non-ASCII bytes (obs-text).
+    Strict,
+}
+
+/// Information about a connection which has been closed, passed to the callback set by [Config::on_disconnection].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisconnectionInfo {
+    /// The address of the remote peer, or `None` if not known by the socket, as given by [Socket::remote_address](io::Socket::remote_address).
+    pub remote_address: Option<core::net::SocketAddr>,
+    /// The number of bytes read from the socket, including any bytes which were read but not parsed, such as the start of a request which was not handled.
+    pub bytes_read: u64,
+    /// The number of bytes written to the socket, including response headers.
+    pub bytes_written: u64,
+}
+
+/// Counts the bytes read from a socket.
+struct CountingReader<'c, R: embedded_io_async::Read> {
+    reader: R,
+    bytes_read: &'c mut u64,
+}
+
+impl<'c, R: embedded_io_async::Read> embedded_io_async::ErrorType for CountingReader<'c, R> {
+    type Error = R::Error;
+}
+
+impl<'c, R: embedded_io_async::Read> embedded_io_async::Read for CountingReader<'c, R> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let read_size = self.reader.read(buf).await?;
+        *self.bytes_read += read_size as u64;
+        Ok(read_size)
+    }
+}
+
+/// Counts the bytes written, such as to a socket, or as the body of a response.
+pub(crate) struct CountingWriter<'c, W: embedded_io_async::Write> {
+    pub(crate) writer: W,
+    pub(crate) bytes_written: &'c mut u64,
+}
+
+impl<'c, W: embedded_io_async::Write> embedded_io_async::ErrorType for CountingWriter<'c, W> {
+    type Error = W::Error;
+}
+
+impl<'c, W: embedded_io_async::Write> embedded_io_async::Write for CountingWriter<'c, W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let written_size = self.writer.write(buf).await?;
+        *self.bytes_written += written_size as u64;
+        Ok(written_size)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush().await
+    }
 }
 
 /// Maps Read errors to [Error]s
@@ -191,121 +492,559 @@ impl<R: embedded_io_async::Read> embedded_io_async::Read for MapReadErrorReader<
     }
 }
 
-async fn serve_and_shutdown<State, T: Timer, P: routing::PathRouter<State>, S: io::Socket>(
-    Router { router, .. }: &Router<P, State>,
-    mut timer: T,
-    config: &Config<T::Duration>,
-    buffer: &mut [u8],
-    mut socket: S,
-    state: &State,
-) -> Result<u64, Error<S::Error>> {
-    let result = async {
-        let (reader, mut writer) = socket.split();
+/// A HTTP Server, which serves `app` using `config`, with the provided buffers used to read requests.
+///
+/// By default, a single buffer is used to read the request line and headers, and the remainder of the buffer is used to read the request body,
+/// so a request with many headers leaves little space for the body.
+/// [Server::with_buffers] uses separate buffers for the request line and headers and for the request body,
+/// allowing the size of each to be tuned independently.
+///
+/// The `serve` and `serve_with_state` free functions are equivalent to [Server::new] followed by [Server::serve] or [Server::serve_with_state],
+/// so migrating to split buffers only requires replacing the call to the free function, for example
+/// `picoserve::serve(&app, &config, &mut http_buffer, socket)` becomes
+/// `picoserve::Server::with_buffers(&app, &config, &mut header_buffer, &mut body_buffer).serve(socket)`.
+///
+/// The free functions do not support graceful shutdown. To stop serving a connection once a shutdown signal completes,
+/// replace the call to the free function with [Server::new] followed by `serve_with_graceful_shutdown` or `serve_with_state_and_graceful_shutdown`, for example
+/// `picoserve::serve_with_state(&app, &config, &mut http_buffer, socket, &state)` becomes
+/// `picoserve::Server::new(&app, &config, &mut http_buffer).serve_with_state_and_graceful_shutdown(socket, &state, shutdown_signal)`.
+pub struct Server<'a, State, P: routing::PathRouter<State>, D> {
+    app: &'a Router<P, State>,
+    config: &'a Config<D>,
+    buffer: &'a mut [u8],
+    body_buffer: Option<&'a mut [u8]>,
+}
 
-        let mut reader = request::Reader::new(MapReadErrorReader(reader), buffer);
+impl<'a, State, P: routing::PathRouter<State>, D: Clone> Server<'a, State, P, D> {
+    /// Create a new server, where `http_buffer` is used to read the request line and headers, and the remainder is used to read the request body.
+    pub fn new(
+        app: &'a Router<P, State>,
+        config: &'a Config<D>,
+        http_buffer: &'a mut [u8],
+    ) -> Self {
+        Self {
+            app,
+            config,
+            buffer: http_buffer,
+            body_buffer: None,
+        }
+    }
 
-        for request_count in 0.. {
-            match timer
-                .run_with_maybe_timeout(
-                    config.timeouts.start_read_request.clone(),
-                    reader.request_is_pending(),
-                )
-                .await
+    /// Create a new server, where `header_buffer` is used to read the request line and headers, and `body_buffer` is used to read the request body.
+    ///
+    /// Any data read into `header_buffer` after the headers is moved into `body_buffer`,
+    /// so the body is only read into the `header_buffer` if the data already read does not fit into `body_buffer`, in which case the remainder of `header_buffer` is used for that request.
+    pub fn with_buffers(
+        app: &'a Router<P, State>,
+        config: &'a Config<D>,
+        header_buffer: &'a mut [u8],
+        body_buffer: &'a mut [u8],
+    ) -> Self {
+        Self {
+            app,
+            config,
+            buffer: header_buffer,
+            body_buffer: Some(body_buffer),
+        }
+    }
+
+    fn accepts_connection_from(&self, remote_address: Option<core::net::SocketAddr>) -> bool {
+        match self.config.connection_filter {
+            None => true,
+            Some(filter) => remote_address.is_some_and(filter),
+        }
+    }
+
+    async fn serve_and_shutdown<T: Timer<Duration = D>, S: io::Socket>(
+        &mut self,
+        timer: T,
+        socket: S,
+        state: &State,
+        shutdown_signal: impl core::future::Future<Output = ()>,
+    ) -> Result<u64, Error<S::Error>> {
+        self.serve_requests_and_shutdown(timer, socket, state, false, shutdown_signal)
+            .await
+    }
+
+    async fn serve_single_request_and_shutdown<T: Timer<Duration = D>, S: io::Socket>(
+        &mut self,
+        timer: T,
+        socket: S,
+        state: &State,
+    ) -> Result<Option<ResponseSent>, Error<S::Error>> {
+        Ok(
+            match self
+                .serve_requests_and_shutdown(timer, socket, state, true, core::future::pending())
+                .await?
             {
-                Ok(Ok(true)) => (),
-                Ok(Ok(false)) | Err(_) => return Ok(request_count),
-                Ok(Err(err)) => return Err(err),
-            };
+                0 => None,
+                _ => Some(ResponseSent(())),
+            },
+        )
+    }
+
+    /// Serve requests until the connection is closed, or if `single_request` is true, after the first request.
+    ///
+    /// Once `shutdown_signal` completes, no further requests are read, and the request being handled, if any, is the last.
+    async fn serve_requests_and_shutdown<T: Timer<Duration = D>, S: io::Socket>(
+        &mut self,
+        mut timer: T,
+        mut socket: S,
+        state: &State,
+        single_request: bool,
+        shutdown_signal: impl core::future::Future<Output = ()>,
+    ) -> Result<u64, Error<S::Error>> {
+        use futures_util::FutureExt;
+
+        let Router { router, .. } = self.app;
+        let config = self.config;
+
+        let remote_address = socket.remote_address();
 
-            match timer
-                .run_with_maybe_timeout(config.timeouts.read_request.clone(), reader.read())
+        if !self.accepts_connection_from(remote_address) {
+            log_info!(
+                "Rejected connection from {:?}",
+                crate::logging::Debug2Format(&remote_address)
+            );
+
+            return socket
+                .shutdown(&config.timeouts, &mut timer)
                 .await
-            {
-                Ok(Ok(request)) => {
-                    let connection_header = match config.connection {
-                        KeepAlive::Close => KeepAlive::Close,
-                        KeepAlive::KeepAlive => KeepAlive::from_request(
-                            request.parts.http_version(),
-                            request.parts.headers(),
-                        ),
-                    };
-
-                    let mut writer = time::WriteWithTimeout {
-                        inner: &mut writer,
-                        timer: &mut timer,
-                        timeout_duration: config.timeouts.write.clone(),
-                    };
-
-                    let ResponseSent(()) = router
-                        .call_path_router(
-                            state,
-                            routing::NoPathParameters,
-                            request.parts.path(),
-                            request,
-                            response::ResponseStream::new(&mut writer, connection_header),
-                        )
-                        .await?;
+                .map(|()| 0);
+        }
 
-                    if let KeepAlive::Close = connection_header {
-                        return Ok(request_count + 1);
-                    }
-                }
-                Ok(Err(err)) => {
-                    use response::IntoResponse;
+        if config.tcp_nodelay {
+            socket.set_nodelay();
+        }
 
-                    let message = match err {
-                        request::ReadError::BadRequestLine => "Bad Request Line",
-                        request::ReadError::HeaderDoesNotContainColon => {
-                            "Invalid Header line: No ':' character"
-                        }
-                        request::ReadError::UnexpectedEof => "Unexpected EOF while reading request",
-                        request::ReadError::IO(err) => return Err(err),
-                    };
-
-                    let ResponseSent(()) = timer
-                        .run_with_maybe_timeout(
-                            config.timeouts.write.clone(),
-                            (response::StatusCode::BAD_REQUEST, message).write_to(
-                                response::Connection::empty(&mut false),
-                                response::ResponseStream::new(writer, KeepAlive::Close),
-                            ),
+        let connection_deadline =
+            config
+                .timeouts
+                .connection_max_lifetime
+                .as_ref()
+                .and_then(|max_lifetime| {
+                    Some(time::Instant::from_micros(
+                        T::now()?
+                            .as_micros()
+                            .saturating_add(T::duration_as_micros(max_lifetime)?),
+                    ))
+                });
+
+        let (mut bytes_read, mut bytes_written) = (0, 0);
+
+        let result = async {
+            let (reader, writer) = socket.split();
+
+            let mut writer = CountingWriter {
+                writer,
+                bytes_written: &mut bytes_written,
+            };
+
+            let mut reader = request::Reader::new(
+                MapReadErrorReader(CountingReader {
+                    reader,
+                    bytes_read: &mut bytes_read,
+                }),
+                self.buffer,
+                self.body_buffer.as_deref_mut(),
+                config.header_validation,
+                config.auto_drain_body,
+                config.max_headers,
+            );
+
+            let mut shutdown_signal = core::pin::pin!(shutdown_signal);
+
+            for request_count in 0.. {
+                match timer
+                    .run_with_maybe_timeout(config.timeouts.start_read_request.clone(), async {
+                        match futures_util::future::select(
+                            core::pin::pin!(reader.request_is_pending()),
+                            shutdown_signal.as_mut(),
                         )
                         .await
-                        .map_err(|_| Error::WriteTimeout)?
-                        .map_err(Error::Write)?;
+                        {
+                            futures_util::future::Either::Left((request_is_pending, _)) => {
+                                request_is_pending
+                            }
+                            futures_util::future::Either::Right(((), _)) => Ok(false),
+                        }
+                    })
+                    .await
+                {
+                    Ok(Ok(true)) => (),
+                    Ok(Ok(false)) | Err(_) => return Ok(request_count),
+                    Ok(Err(err)) => return Err(err),
+                };
+
+                let start_time = T::now();
+
+                match timer
+                    .run_with_maybe_timeout(config.timeouts.read_request.clone(), reader.read())
+                    .await
+                {
+                    Ok(Ok(mut request)) => {
+                        request.parts.start_time = start_time;
+                        request.parts.clock = T::now;
+                        request.parts.remote_address = remote_address;
+                        request.parts.proxy_trust = config.proxy_trust;
 
-                    return Ok(request_count + 1);
+                        let connection_has_expired = connection_deadline
+                            .zip(T::now())
+                            .is_some_and(|(deadline, now)| now >= deadline);
+
+                        let is_shutting_down = shutdown_signal.as_mut().now_or_never().is_some();
+
+                        let connection_header = match config.connection {
+                            KeepAlive::Close => KeepAlive::Close,
+                            KeepAlive::KeepAlive
+                                if single_request || connection_has_expired || is_shutting_down =>
+                            {
+                                KeepAlive::Close
+                            }
+                            KeepAlive::KeepAlive => request.parts.keep_alive(),
+                        };
+
+                        request.parts.keep_alive = connection_header;
+
+                        let mut writer = time::WriteWithTimeout {
+                            inner: &mut writer,
+                            timer: &mut timer,
+                            timeout_duration: config.timeouts.write.clone(),
+                        };
+
+                        let ResponseSent(()) = router
+                            .call_path_router(
+                                state,
+                                routing::NoPathParameters,
+                                request.parts.path(),
+                                request,
+                                response::ResponseStream::new(&mut writer, connection_header),
+                            )
+                            .await?;
+
+                        if let KeepAlive::Close = connection_header {
+                            return Ok(request_count + 1);
+                        }
+
+                        if shutdown_signal.as_mut().now_or_never().is_some() {
+                            return Ok(request_count + 1);
+                        }
+                    }
+                    Ok(Err(err)) => {
+                        use response::IntoResponse;
+
+                        let error = match err {
+                            request::ReadError::BadRequestLine => {
+                                request::ParseError::BadRequestLine
+                            }
+                            request::ReadError::HeaderDoesNotContainColon => {
+                                request::ParseError::HeaderDoesNotContainColon
+                            }
+                            request::ReadError::InvalidHeader => request::ParseError::InvalidHeader,
+                            request::ReadError::TooManyHeaders => {
+                                request::ParseError::TooManyHeaders
+                            }
+                            request::ReadError::InvalidContentLength => {
+                                request::ParseError::InvalidContentLength
+                            }
+                            request::ReadError::ContentLengthWithTransferEncoding => {
+                                request::ParseError::ContentLengthWithTransferEncoding
+                            }
+                            request::ReadError::InvalidChunkedBody => {
+                                request::ParseError::InvalidChunkedBody
+                            }
+                            request::ReadError::ChunkedBodyTooLarge => {
+                                request::ParseError::ChunkedBodyTooLarge
+                            }
+                            request::ReadError::UnexpectedEof => request::ParseError::UnexpectedEof,
+                            request::ReadError::HeadersTooLarge => {
+                                request::ParseError::HeadersTooLarge
+                            }
+                            request::ReadError::IO(err) => return Err(err),
+                        };
+
+                        let ResponseSent(()) = timer
+                            .run_with_maybe_timeout(config.timeouts.write.clone(), async {
+                                let (mut has_been_upgraded, mut must_close) = (false, false);
+                                let connection = response::Connection::empty(
+                                    &mut has_been_upgraded,
+                                    &mut must_close,
+                                );
+                                let response_writer =
+                                    response::ResponseStream::new(writer, KeepAlive::Close);
+
+                                match config.parse_error_format {
+                                    Some(format) => {
+                                        (error.status_code(), ParseErrorBody { format, error })
+                                            .write_to(connection, response_writer)
+                                            .await
+                                    }
+                                    None => {
+                                        (error.status_code(), error.message())
+                                            .write_to(connection, response_writer)
+                                            .await
+                                    }
+                                }
+                            })
+                            .await
+                            .map_err(|_| Error::WriteTimeout)?
+                            .map_err(Error::Write)?;
+
+                        return Ok(request_count + 1);
+                    }
+                    Err(..) => return Err(Error::ReadTimeout),
                 }
-                Err(..) => return Err(Error::ReadTimeout),
             }
+
+            Ok(0)
+        }
+        .await;
+
+        let shutdown_result = socket.shutdown(&config.timeouts, &mut timer).await;
+
+        if let Some(on_disconnection) = config.on_disconnection {
+            on_disconnection(&DisconnectionInfo {
+                remote_address,
+                bytes_read,
+                bytes_written,
+            });
+        }
+
+        let request_count = result?;
+
+        shutdown_result?;
+
+        Ok(request_count)
+    }
+}
+
+#[cfg(any(feature = "tokio", test))]
+impl<'a, P: routing::PathRouter> Server<'a, (), P, std::time::Duration> {
+    /// Serve incoming requests read from `stream`. App has no state.
+    pub async fn serve(
+        &mut self,
+        stream: tokio::net::TcpStream,
+    ) -> Result<u64, Error<io::tokio_support::TokioIoError>> {
+        self.serve_with_state(stream, &()).await
+    }
+
+    /// Serve incoming requests read from `stream` until `shutdown_signal` completes. App has no state.
+    ///
+    /// See [Server::serve_with_state_and_graceful_shutdown].
+    pub async fn serve_with_graceful_shutdown(
+        &mut self,
+        stream: tokio::net::TcpStream,
+        shutdown_signal: impl core::future::Future<Output = ()>,
+    ) -> Result<u64, Error<io::tokio_support::TokioIoError>> {
+        self.serve_with_state_and_graceful_shutdown(stream, &(), shutdown_signal)
+            .await
+    }
+}
+
+#[cfg(any(feature = "tokio", test))]
+impl<'a, State, P: routing::PathRouter<State>> Server<'a, State, P, std::time::Duration> {
+    /// Serve incoming requests read from `stream`. App has a state of `State`.
+    pub async fn serve_with_state(
+        &mut self,
+        stream: tokio::net::TcpStream,
+        state: &State,
+    ) -> Result<u64, Error<io::tokio_support::TokioIoError>> {
+        self.serve_with_state_and_graceful_shutdown(stream, state, core::future::pending())
+            .await
+    }
+
+    /// Serve incoming requests read from `stream` until `shutdown_signal` completes. App has a state of `State`.
+    ///
+    /// Once `shutdown_signal` completes, the server stops waiting for further requests and closes the connection.
+    /// A request which is being handled is allowed to finish, and is the last request handled on the connection.
+    /// Connections which have been upgraded, such as to a web socket, are not interrupted.
+    pub async fn serve_with_state_and_graceful_shutdown(
+        &mut self,
+        stream: tokio::net::TcpStream,
+        state: &State,
+        shutdown_signal: impl core::future::Future<Output = ()>,
+    ) -> Result<u64, Error<io::tokio_support::TokioIoError>> {
+        self.serve_and_shutdown(time::TokioTimer, stream, state, shutdown_signal)
+            .await
+    }
+
+    /// Read a single request from `socket`, handle it, and then shut down `socket`. App has a state of `State`.
+    ///
+    /// The response always has a "Connection" header of "close", and any further requests sent on the same connection are ignored.
+    /// Returns `None` if the connection was closed before a request was received.
+    /// As `socket` may be any [Socket](io::Socket), this is useful for tests and for transports other than TCP sockets.
+    pub async fn serve_once<S: io::Socket>(
+        &mut self,
+        socket: S,
+        state: &State,
+    ) -> Result<Option<ResponseSent>, Error<S::Error>> {
+        self.serve_single_request_and_shutdown(time::TokioTimer, socket, state)
+            .await
+    }
+}
+
+#[cfg(feature = "embassy")]
+impl<'a, P: routing::PathRouter> Server<'a, (), P, embassy_time::Duration> {
+    /// Serve incoming requests read from `socket`. App has no state.
+    pub async fn serve(
+        &mut self,
+        socket: embassy_net::tcp::TcpSocket<'_>,
+    ) -> Result<u64, Error<embassy_net::tcp::Error>> {
+        self.serve_with_state(socket, &()).await
+    }
+
+    /// Serve incoming requests read from `socket` until `shutdown_signal` completes. App has no state.
+    ///
+    /// See [Server::serve_with_state_and_graceful_shutdown].
+    pub async fn serve_with_graceful_shutdown(
+        &mut self,
+        socket: embassy_net::tcp::TcpSocket<'_>,
+        shutdown_signal: impl core::future::Future<Output = ()>,
+    ) -> Result<u64, Error<embassy_net::tcp::Error>> {
+        self.serve_with_state_and_graceful_shutdown(socket, &(), shutdown_signal)
+            .await
+    }
+}
+
+#[cfg(feature = "embassy")]
+impl<'a, State, P: routing::PathRouter<State>> Server<'a, State, P, embassy_time::Duration> {
+    /// Serve incoming requests read from `socket`. App has a state of `State`.
+    pub async fn serve_with_state(
+        &mut self,
+        socket: embassy_net::tcp::TcpSocket<'_>,
+        state: &State,
+    ) -> Result<u64, Error<embassy_net::tcp::Error>> {
+        self.serve_with_state_and_graceful_shutdown(socket, state, core::future::pending())
+            .await
+    }
+
+    /// Serve incoming requests read from `socket` until `shutdown_signal` completes. App has a state of `State`.
+    ///
+    /// Once `shutdown_signal` completes, the server stops waiting for further requests and closes the connection.
+    /// A request which is being handled is allowed to finish, and is the last request handled on the connection.
+    /// Connections which have been upgraded, such as to a web socket, are not interrupted.
+    pub async fn serve_with_state_and_graceful_shutdown(
+        &mut self,
+        mut socket: embassy_net::tcp::TcpSocket<'_>,
+        state: &State,
+        shutdown_signal: impl core::future::Future<Output = ()>,
+    ) -> Result<u64, Error<embassy_net::tcp::Error>> {
+        // Reset rejected connections rather than waiting for the peer to close them
+        if !self.accepts_connection_from(io::Socket::remote_address(&socket)) {
+            log_info!("Rejected connection from {:?}", socket.remote_endpoint());
+
+            socket.abort();
+            let _ = socket.flush().await;
+
+            return Ok(0);
         }
 
-        Ok(0)
+        self.serve_and_shutdown(time::EmbassyTimer, socket, state, shutdown_signal)
+            .await
     }
-    .await;
 
-    let shutdown_result = socket.shutdown(&config.timeouts, &mut timer).await;
+    /// Read a single request from `socket`, handle it, and then shut down `socket`. App has a state of `State`.
+    ///
+    /// The response always has a "Connection" header of "close", and any further requests sent on the same connection are ignored.
+    /// Returns `None` if the connection was closed before a request was received.
+    /// As `socket` may be any [Socket](io::Socket), this is useful for tests and for transports other than TCP sockets.
+    pub async fn serve_once<S: io::Socket>(
+        &mut self,
+        socket: S,
+        state: &State,
+    ) -> Result<Option<ResponseSent>, Error<S::Error>> {
+        self.serve_single_request_and_shutdown(time::EmbassyTimer, socket, state)
+            .await
+    }
+}
 
-    let request_count = result?;
+#[cfg(not(any(feature = "tokio", feature = "embassy", test)))]
+impl<'a, P: routing::PathRouter, D: Clone> Server<'a, (), P, D> {
+    /// Serve incoming requests read from `socket`. App has no state.
+    pub async fn serve<T: Timer<Duration = D>, S: io::Socket>(
+        &mut self,
+        timer: T,
+        socket: S,
+    ) -> Result<u64, Error<S::Error>> {
+        self.serve_and_shutdown(timer, socket, &(), core::future::pending())
+            .await
+    }
 
-    shutdown_result?;
+    /// Serve incoming requests read from `socket` until `shutdown_signal` completes. App has no state.
+    ///
+    /// See [Server::serve_with_state_and_graceful_shutdown].
+    pub async fn serve_with_graceful_shutdown<T: Timer<Duration = D>, S: io::Socket>(
+        &mut self,
+        timer: T,
+        socket: S,
+        shutdown_signal: impl core::future::Future<Output = ()>,
+    ) -> Result<u64, Error<S::Error>> {
+        self.serve_and_shutdown(timer, socket, &(), shutdown_signal)
+            .await
+    }
+}
 
-    Ok(request_count)
+#[cfg(not(any(feature = "tokio", feature = "embassy", test)))]
+impl<'a, State, P: routing::PathRouter<State>, D: Clone> Server<'a, State, P, D> {
+    /// Serve incoming requests read from `socket`. App has a state of `State`.
+    pub async fn serve_with_state<T: Timer<Duration = D>, S: io::Socket>(
+        &mut self,
+        timer: T,
+        socket: S,
+        state: &State,
+    ) -> Result<u64, Error<S::Error>> {
+        self.serve_and_shutdown(timer, socket, state, core::future::pending())
+            .await
+    }
+
+    /// Serve incoming requests read from `socket` until `shutdown_signal` completes. App has a state of `State`.
+    ///
+    /// Once `shutdown_signal` completes, the server stops waiting for further requests and closes the connection.
+    /// A request which is being handled is allowed to finish, and is the last request handled on the connection.
+    /// Connections which have been upgraded, such as to a web socket, are not interrupted.
+    pub async fn serve_with_state_and_graceful_shutdown<T: Timer<Duration = D>, S: io::Socket>(
+        &mut self,
+        timer: T,
+        socket: S,
+        state: &State,
+        shutdown_signal: impl core::future::Future<Output = ()>,
+    ) -> Result<u64, Error<S::Error>> {
+        self.serve_and_shutdown(timer, socket, state, shutdown_signal)
+            .await
+    }
+
+    /// Read a single request from `socket`, handle it, and then shut down `socket`. App has a state of `State`.
+    ///
+    /// The response always has a "Connection" header of "close", and any further requests sent on the same connection are ignored.
+    /// Returns `None` if the connection was closed before a request was received.
+    pub async fn serve_once<T: Timer<Duration = D>, S: io::Socket>(
+        &mut self,
+        timer: T,
+        socket: S,
+        state: &State,
+    ) -> Result<Option<ResponseSent>, Error<S::Error>> {
+        self.serve_single_request_and_shutdown(timer, socket, state)
+            .await
+    }
 }
 
 #[cfg(any(feature = "tokio", test))]
 /// Serve `app` with incoming requests. App has a no state.
+///
+/// For graceful shutdown, use [Server] instead.
 pub async fn serve<P: routing::PathRouter>(
     app: &Router<P>,
     config: &Config<std::time::Duration>,
     buffer: &mut [u8],
     stream: tokio::net::TcpStream,
 ) -> Result<u64, Error<io::tokio_support::TokioIoError>> {
-    serve_and_shutdown(app, time::TokioTimer, config, buffer, stream, &()).await
+    Server::new(app, config, buffer).serve(stream).await
 }
 
 #[cfg(any(feature = "tokio", test))]
 /// Serve incoming requests read from `reader`, route them to `app`, and write responses to `writer`. App has a state of `State`.
+///
+/// For graceful shutdown, use [Server] instead.
 pub async fn serve_with_state<State, P: routing::PathRouter<State>>(
     app: &Router<P, State>,
     config: &Config<std::time::Duration>,
@@ -313,22 +1052,28 @@ pub async fn serve_with_state<State, P: routing::PathRouter<State>>(
     stream: tokio::net::TcpStream,
     state: &State,
 ) -> Result<u64, Error<io::tokio_support::TokioIoError>> {
-    serve_and_shutdown(app, time::TokioTimer, config, buffer, stream, state).await
+    Server::new(app, config, buffer)
+        .serve_with_state(stream, state)
+        .await
 }
 
 #[cfg(feature = "embassy")]
 /// Serve `app` with incoming requests. App has a no state.
+///
+/// For graceful shutdown, use [Server] instead.
 pub async fn serve<P: routing::PathRouter>(
     app: &Router<P>,
     config: &Config<embassy_time::Duration>,
     buffer: &mut [u8],
     socket: embassy_net::tcp::TcpSocket<'_>,
 ) -> Result<u64, Error<embassy_net::tcp::Error>> {
-    serve_and_shutdown(app, time::EmbassyTimer, config, buffer, socket, &()).await
+    Server::new(app, config, buffer).serve(socket).await
 }
 
 #[cfg(feature = "embassy")]
 /// Serve `app` with incoming requests. App has a state of `State`.
+///
+/// For graceful shutdown, use [Server] instead.
 pub async fn serve_with_state<State, P: routing::PathRouter<State>>(
     app: &Router<P, State>,
     config: &Config<embassy_time::Duration>,
@@ -336,12 +1081,17 @@ pub async fn serve_with_state<State, P: routing::PathRouter<State>>(
     socket: embassy_net::tcp::TcpSocket<'_>,
     state: &State,
 ) -> Result<u64, Error<embassy_net::tcp::Error>> {
-    serve_and_shutdown(app, time::EmbassyTimer, config, buffer, socket, state).await
+    Server::new(app, config, buffer)
+        .serve_with_state(socket, state)
+        .await
 }
 
 #[cfg(feature = "embassy")]
 /// Serve `app` with incoming requests. App has a no state.
 /// `task_id` is printed in log messages.
+/// Connections from peers rejected by [Config::connection_filter] are closed without reading the request.
+///
+/// This never returns, so for graceful shutdown, accept connections in a loop and serve them using [Server::serve_with_graceful_shutdown] instead.
 pub async fn listen_and_serve<P: routing::PathRouter<()>>(
     task_id: impl LogDisplay,
     app: &Router<P, ()>,
@@ -369,6 +1119,9 @@ pub async fn listen_and_serve<P: routing::PathRouter<()>>(
 #[cfg(feature = "embassy")]
 /// Serve `app` with incoming requests. App has a state of `State`.
 /// `task_id` is printed in log messages.
+/// Connections from peers rejected by [Config::connection_filter] are closed without reading the request.
+///
+/// This never returns, so for graceful shutdown, accept connections in a loop and serve them using [Server::serve_with_state_and_graceful_shutdown] instead.
 pub async fn listen_and_serve_with_state<State, P: routing::PathRouter<State>>(
     task_id: impl LogDisplay,
     app: &Router<P, State>,
@@ -413,6 +1166,8 @@ pub async fn listen_and_serve_with_state<State, P: routing::PathRouter<State>>(
 
 #[cfg(not(any(feature = "tokio", feature = "embassy", test)))]
 /// Serve `app` with incoming requests. App has no state.
+///
+/// For graceful shutdown, use [Server] instead.
 pub async fn serve<T: Timer, P: routing::PathRouter, S: io::Socket>(
     app: &Router<P>,
     timer: T,
@@ -420,11 +1175,13 @@ pub async fn serve<T: Timer, P: routing::PathRouter, S: io::Socket>(
     buffer: &mut [u8],
     socket: S,
 ) -> Result<u64, Error<S::Error>> {
-    serve_and_shutdown(app, timer, config, buffer, socket, &()).await
+    Server::new(app, config, buffer).serve(timer, socket).await
 }
 
 #[cfg(not(any(feature = "tokio", feature = "embassy", test)))]
 /// Serve `app` with incoming requests. App has a state of `State`.
+///
+/// For graceful shutdown, use [Server] instead.
 pub async fn serve_with_state<'r, State, T: Timer, P: routing::PathRouter<State>, S: io::Socket>(
     app: &Router<P, State>,
     timer: T,
@@ -433,7 +1190,9 @@ pub async fn serve_with_state<'r, State, T: Timer, P: routing::PathRouter<State>
     socket: S,
     state: &State,
 ) -> Result<u64, Error<S::Error>> {
-    serve_and_shutdown(app, timer, config, buffer, socket, state).await
+    Server::new(app, config, buffer)
+        .serve_with_state(timer, socket, state)
+        .await
 }
 
 /// A helper trait which simplifies creating a static [Router] with no state.
@@ -4,7 +4,7 @@ use core::{fmt, ops::Range};
 
 use embedded_io_async::Read;
 
-use super::url_encoded::UrlEncodedString;
+use super::{url_encoded::UrlEncodedString, HeaderValidation, KeepAlive};
 
 struct Subslice<'a> {
     buffer: &'a [u8],
@@ -106,6 +106,50 @@ fn trim(b: &[u8]) -> &[u8] {
     trim_end(trim_start(b))
 }
 
+/// Split a list of header values separated by `separator`, trimming whitespace and skipping empty elements.
+/// Separators inside quoted strings, such as in entity tags, do not separate elements.
+fn split_list(mut list: &[u8], separator: u8) -> impl Iterator<Item = &[u8]> {
+    core::iter::from_fn(move || loop {
+        if list.is_empty() {
+            return None;
+        }
+
+        let mut is_quoted = false;
+        let mut is_escaped = false;
+
+        let end = list
+            .iter()
+            .position(|&b| {
+                if is_escaped {
+                    is_escaped = false;
+                } else if is_quoted {
+                    match b {
+                        b'\\' => is_escaped = true,
+                        b'"' => is_quoted = false,
+                        _ => (),
+                    }
+                } else {
+                    match b {
+                        b'"' => is_quoted = true,
+                        b if b == separator => return true,
+                        _ => (),
+                    }
+                }
+
+                false
+            })
+            .unwrap_or(list.len());
+
+        let element = trim(&list[..end]);
+
+        list = list.get((end + 1)..).unwrap_or_default();
+
+        if !element.is_empty() {
+            return Some(element);
+        }
+    })
+}
+
 fn eq_ignore_ascii_case(lhs: &[u8], rhs: &[u8]) -> bool {
     if lhs.len() != rhs.len() {
         return false;
@@ -265,7 +309,7 @@ impl<'a> Iterator for HeadersIter<'a> {
 
 #[derive(Clone, Copy)]
 /// The Request Headers.
-pub struct Headers<'a>(&'a [u8]);
+pub struct Headers<'a>(pub(crate) &'a [u8]);
 
 impl<'a> Headers<'a> {
     /// Iterator over all headers.
@@ -278,6 +322,105 @@ impl<'a> Headers<'a> {
         self.iter()
             .find_map(|(header_name, value)| (name == header_name).then_some(value))
     }
+
+    /// Iterate over the elements of comma-separated lists in headers with a name which matches (ignoring ASCII case) the given name,
+    /// such as "Accept-Encoding", "Connection", or "If-None-Match".
+    ///
+    /// Elements are trimmed of whitespace, and empty elements are skipped. If there are several headers with the given name,
+    /// the elements of each header are returned in order. Commas inside quoted strings do not separate elements.
+    /// Elements which are not valid UTF-8 are skipped.
+    ///
+    /// ```
+    /// # use picoserve::request::Headers;
+    /// # fn example(headers: Headers) {
+    /// let accepts_gzip = headers
+    ///     .get_list("accept-encoding")
+    ///     .any(|encoding| encoding.split(';').next().is_some_and(|coding| coding.trim() == "gzip"));
+    /// # }
+    /// ```
+    pub fn get_list<'n>(&self, name: &'n str) -> impl Iterator<Item = &'a str> + 'n
+    where
+        'a: 'n,
+    {
+        self.iter()
+            .filter(move |(header_name, _)| name == *header_name)
+            .flat_map(|(_, value)| split_list(value.value, b','))
+            .filter_map(|element| core::str::from_utf8(element).ok())
+    }
+
+    /// Parse the "Content-Type" header, if present and valid UTF-8.
+    pub fn content_type(&self) -> Option<ContentType<'a>> {
+        ContentType::parse(core::str::from_utf8(self.get("content-type")?.value).ok()?)
+    }
+
+    /// Return whether the "Connection" header contains the given option (ignoring ASCII case), such as "close" or "upgrade".
+    pub(crate) fn connection_has_option(&self, option: &str) -> bool {
+        self.get_list("connection")
+            .any(|connection_option| connection_option.eq_ignore_ascii_case(option))
+    }
+}
+
+/// A parsed "Content-Type" header, such as `multipart/form-data; boundary="abc 123"`, which is a media type followed by parameters.
+///
+/// ```
+/// # use picoserve::request::ContentType;
+/// let content_type = ContentType::parse("multipart/form-data; Boundary=\"abc 123\"").unwrap();
+///
+/// assert!(content_type.is("Multipart/Form-Data"));
+/// assert_eq!(content_type.param("boundary"), Some("abc 123"));
+/// assert_eq!(content_type.param("charset"), None);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ContentType<'a> {
+    media_type: &'a str,
+    params: &'a str,
+}
+
+impl<'a> ContentType<'a> {
+    /// Parse the value of a "Content-Type" header. Returns `None` if the media type is empty.
+    pub fn parse(value: &'a str) -> Option<Self> {
+        let (media_type, params) = value.split_once(';').unwrap_or((value, ""));
+
+        let media_type = media_type.trim();
+
+        (!media_type.is_empty()).then_some(Self { media_type, params })
+    }
+
+    /// The media type, such as "text/plain", without any parameters. Media types are case-insensitive, so compare them using [ContentType::is].
+    pub fn media_type(&self) -> &'a str {
+        self.media_type
+    }
+
+    /// Return whether the media type matches the given media type, ignoring ASCII case.
+    pub fn is(&self, media_type: &str) -> bool {
+        self.media_type.eq_ignore_ascii_case(media_type)
+    }
+
+    /// Iterate over the parameters as name-value pairs, in order.
+    ///
+    /// If a value is a quoted string, the surrounding quotes are removed, but escaped characters within it are not unescaped.
+    pub fn params(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+        split_list(self.params.as_bytes(), b';').filter_map(|param| {
+            let (name, value) = core::str::from_utf8(param).ok()?.split_once('=')?;
+
+            let value = value.trim();
+
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+
+            Some((name.trim(), value))
+        })
+    }
+
+    /// Return the value of the first parameter with a name which matches (ignoring ASCII case) the given name, such as "charset" or "boundary".
+    ///
+    /// If the value is a quoted string, the surrounding quotes are removed, but escaped characters within it are not unescaped.
+    pub fn param(&self, name: &str) -> Option<&'a str> {
+        self.params()
+            .find_map(|(param_name, value)| param_name.eq_ignore_ascii_case(name).then_some(value))
+    }
 }
 
 impl<'a> IntoIterator for Headers<'a> {
@@ -321,11 +464,34 @@ impl<'r> PartialEq<&'r str> for Path<'r> {
 }
 
 impl<'r> Path<'r> {
+    /// Create a path from a URL-encoded string, such as for testing custom routing.
+    pub const fn from_encoded(encoded: &'r str) -> Self {
+        Self(UrlEncodedString(encoded))
+    }
+
     /// Return the encoded string
     pub fn encoded(self) -> &'r str {
         self.0 .0
     }
 
+    /// If the path is empty, such as the remaining path of a request to the prefix of a nested router, return the root path "/".
+    pub(crate) fn or_root(self) -> Self {
+        if self.0 .0.is_empty() {
+            Self(UrlEncodedString("/"))
+        } else {
+            self
+        }
+    }
+
+    /// If the path is just a trailing slash, such as the remaining path of a request for "/items/" after matching "/items", return the empty path.
+    pub(crate) fn strip_trailing_slash(self) -> Self {
+        if self.0 .0 == "/" {
+            Self(UrlEncodedString(""))
+        } else {
+            self
+        }
+    }
+
     pub(crate) fn strip_slash_and_prefix(self, prefix: &str) -> Option<Self> {
         Self(self.0.strip_prefix("/")?).strip_prefix(prefix)
     }
@@ -350,10 +516,44 @@ impl<'r> Path<'r> {
         Some((UrlEncodedString(segment), Path(UrlEncodedString(path))))
     }
 
-    /// Iterate over the segments of the path, more or less split by `/`
+    /// Iterate over the segments of the path, more or less split by `/`.
+    ///
+    /// Each segment is still URL-encoded, so a percent-encoded `/` (`%2F`) does not split a segment.
+    ///
+    /// ```
+    /// let path = picoserve::request::Path::from_encoded("/a/b%20c/d");
+    ///
+    /// let mut segments = path.segments();
+    ///
+    /// assert_eq!(segments.next().unwrap().0, "a");
+    /// assert_eq!(segments.next().unwrap().0, "b%20c");
+    /// assert_eq!(segments.as_path().encoded(), "/d");
+    /// assert_eq!(segments.next().unwrap().0, "d");
+    /// assert!(segments.next().is_none());
+    /// ```
     pub fn segments(self) -> PathSegments<'r> {
         PathSegments(self)
     }
+
+    /// Iterate over the segments of the path, decoding each segment lazily, without allocating.
+    ///
+    /// ```
+    /// let path = picoserve::request::Path::from_encoded("/a/b%20c/d%2Fe");
+    ///
+    /// let segments = path
+    ///     .decoded_segments()
+    ///     .map(|segment| {
+    ///         segment
+    ///             .map(|c| c.unwrap().into_char())
+    ///             .collect::<heapless::String<8>>()
+    ///     })
+    ///     .collect::<heapless::Vec<_, 4>>();
+    ///
+    /// assert_eq!(segments, ["a", "b c", "d/e"]);
+    /// ```
+    pub fn decoded_segments(self) -> DecodedPathSegments<'r> {
+        DecodedPathSegments(self.segments())
+    }
 }
 
 impl<'r> IntoIterator for Path<'r> {
@@ -388,15 +588,44 @@ impl<'r> Iterator for PathSegments<'r> {
 
 impl<'r> core::iter::FusedIterator for PathSegments<'r> {}
 
+#[derive(Clone)]
+/// An iterator over the segments of a path, yielding the [UrlDecodedCharacters](crate::url_encoded::UrlDecodedCharacters) of each segment.
+pub struct DecodedPathSegments<'r>(PathSegments<'r>);
+
+impl<'r> DecodedPathSegments<'r> {
+    /// Represent the remaining path segments as a path
+    pub fn as_path(&self) -> Path<'r> {
+        self.0.as_path()
+    }
+}
+
+impl<'r> Iterator for DecodedPathSegments<'r> {
+    type Item = crate::url_encoded::UrlDecodedCharacters<'r>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(UrlEncodedString::chars)
+    }
+}
+
+impl<'r> core::iter::FusedIterator for DecodedPathSegments<'r> {}
+
 /// Represents an HTTP request.
 #[derive(Debug, Clone, Copy)]
 pub struct RequestParts<'r> {
-    method: &'r str,
+    pub(crate) method: &'r str,
     path: Path<'r>,
     query: Option<UrlEncodedString<'r>>,
     fragments: Option<UrlEncodedString<'r>>,
     http_version: &'r str,
     headers: Headers<'r>,
+    pub(crate) keep_alive: KeepAlive,
+    pub(crate) matched_path: Option<crate::routing::RoutePath<'r>>,
+    pub(crate) allowed_methods: crate::routing::AllowedMethods,
+    pub(crate) bad_path_segment: Option<crate::routing::BadPathSegment<'r>>,
+    pub(crate) start_time: Option<crate::time::Instant>,
+    pub(crate) clock: fn() -> Option<crate::time::Instant>,
+    pub(crate) remote_address: Option<core::net::SocketAddr>,
+    pub(crate) proxy_trust: crate::ProxyTrust,
 }
 
 impl<'r> RequestParts<'r> {
@@ -429,6 +658,41 @@ impl<'r> RequestParts<'r> {
     pub const fn headers(&self) -> Headers<'r> {
         self.headers
     }
+
+    /// Return whether the connection will be kept alive after the response has been sent.
+    /// This takes into account the server configuration, the HTTP version, and the "Connection" header sent by the client.
+    pub const fn keep_alive(&self) -> KeepAlive {
+        self.keep_alive
+    }
+
+    /// Return the pattern of the route which the request has been routed to so far, or `None` if the request has not matched any route.
+    /// See [MatchedPath](crate::extract::MatchedPath) for how the pattern is derived.
+    pub const fn matched_path(&self) -> Option<crate::routing::RoutePath<'r>> {
+        self.matched_path
+    }
+
+    /// Return the methods handled by routes whose path matches the request path but which don't handle the request method.
+    /// This is empty unless the request has been passed on to a fallback, such as [NotFound](crate::routing::NotFound),
+    /// which can use it to respond with "405 Method Not Allowed" and an `Allow` header rather than "404 Not Found".
+    pub const fn allowed_methods(&self) -> crate::routing::AllowedMethods {
+        self.allowed_methods
+    }
+
+    /// Return the time at which the server started reading the request, or `None` if the [Timer](crate::Timer) cannot measure time.
+    pub const fn start_time(&self) -> Option<crate::time::Instant> {
+        self.start_time
+    }
+
+    /// Return the current time as measured by the [Timer](crate::Timer) of the server, or `None` if the timer cannot measure time.
+    pub fn now(&self) -> Option<crate::time::Instant> {
+        (self.clock)()
+    }
+
+    /// Return the address of the remote peer, or `None` if the address is not known, such as when using a socket other than a `tokio` or `embassy` TCP socket.
+    /// If the server is behind a reverse proxy, this is the address of the proxy. See [ClientIp](crate::extract::ClientIp).
+    pub const fn remote_address(&self) -> Option<core::net::SocketAddr> {
+        self.remote_address
+    }
 }
 
 /// Reads the body asynchronously. Implements [Read].
@@ -481,7 +745,7 @@ impl<'r, R: Read> Read for RequestBodyReader<'r, R> {
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 /// Errors arising when reading the entire body
 pub enum ReadAllBodyError<E> {
-    /// The body does not fit into the remaining request buffer.
+    /// The body does not fit into the buffer.
     BufferIsTooSmall,
     /// EndOfFile reached while reading the body before the entire body has been read.
     UnexpectedEof,
@@ -492,8 +756,13 @@ pub enum ReadAllBodyError<E> {
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 /// The body of the request, which may not have yet been buffered.
+///
+/// The length of the body is determined by the "Content-Length" header, unless the request has a "Transfer-Encoding" of "chunked".
+/// Chunked bodies are decoded into the buffer before the request is handled, along with any [trailers](Self::trailers),
+/// so must fit into the buffer, and are read within the [read_request](crate::Timeouts::read_request) timeout.
 pub struct RequestBody<'r, R: Read> {
     content_length: usize,
+    trailers: Headers<'r>,
     reader: &'r mut R,
     buffer: &'r mut [u8],
     read_position: &'r mut usize,
@@ -506,6 +775,12 @@ impl<'r, R: Read> RequestBody<'r, R> {
         self.content_length
     }
 
+    /// The trailers sent after a chunked body, such as the "grpc-status" trailer sent by gRPC-web clients.
+    /// Empty unless the request has a "Transfer-Encoding" of "chunked".
+    pub const fn trailers(&self) -> Headers<'r> {
+        self.trailers
+    }
+
     /// The size of the buffer used to read the body into
     pub const fn buffer_length(&self) -> usize {
         self.buffer.len()
@@ -542,6 +817,29 @@ impl<'r, R: Read> RequestBody<'r, R> {
         Ok(buffer)
     }
 
+    /// Read the entire body into an owned buffer with a capacity of `N` bytes, so that it can be kept after the HTTP buffer has been released.
+    ///
+    /// The body is read directly into the returned buffer, so it does not need to fit into the HTTP buffer.
+    /// Returns [ReadAllBodyError::BufferIsTooSmall] without reading the body if the body is longer than `N` bytes.
+    pub async fn read_all_into<const N: usize>(
+        self,
+    ) -> Result<heapless::Vec<u8, N>, ReadAllBodyError<R::Error>> {
+        let mut body = heapless::Vec::new();
+
+        body.resize_default(self.content_length)
+            .map_err(|()| ReadAllBodyError::BufferIsTooSmall)?;
+
+        self.reader()
+            .read_exact(&mut body)
+            .await
+            .map_err(|err| match err {
+                embedded_io_async::ReadExactError::UnexpectedEof => ReadAllBodyError::UnexpectedEof,
+                embedded_io_async::ReadExactError::Other(err) => ReadAllBodyError::IO(err),
+            })?;
+
+        Ok(body)
+    }
+
     /// Return a reader which can be used to asynchronously read the body, such as decoding it on the fly or streaming into an external buffer.
     pub fn reader(self) -> RequestBodyReader<'r, R> {
         RequestBodyReader {
@@ -557,11 +855,14 @@ impl<'r, R: Read> RequestBody<'r, R> {
 /// such as if the connenction has been upgraded.
 pub struct RequestBodyConnection<'r, R: Read> {
     content_length: usize,
+    trailers: Headers<'r>,
     reader: &'r mut R,
     read_position: usize,
     buffer: &'r mut [u8],
     buffer_usage: usize,
     has_been_upgraded: &'r mut bool,
+    auto_drain_body: Option<usize>,
+    must_close: &'r mut bool,
 }
 
 impl<'r, R: Read> RequestBodyConnection<'r, R> {
@@ -570,10 +871,16 @@ impl<'r, R: Read> RequestBodyConnection<'r, R> {
         self.content_length
     }
 
+    /// Return the trailers sent after a chunked body, see [RequestBody::trailers].
+    pub const fn trailers(&self) -> Headers<'r> {
+        self.trailers
+    }
+
     /// Return the Request Body
     pub fn body(&mut self) -> RequestBody<'_, R> {
         RequestBody {
             content_length: self.content_length,
+            trailers: self.trailers,
             reader: self.reader,
             read_position: &mut self.read_position,
             buffer: self.buffer,
@@ -581,10 +888,71 @@ impl<'r, R: Read> RequestBodyConnection<'r, R> {
         }
     }
 
-    /// "Finalize" the connection, reading and discarding the rest of the body if need be, and returning the underlying connection
+    /// "Finalize" the connection, reading and discarding the rest of the body if need be, and returning the underlying connection.
+    ///
+    /// If [Config::auto_drain_body](crate::Config::auto_drain_body) is set and more of the body remains unread than the limit,
+    /// the rest of the body is not read, and the connection is closed after the response has been sent.
     pub async fn finalize(
         self,
     ) -> Result<crate::response::Connection<'r, impl Read<Error = R::Error> + 'r>, R::Error> {
+        self.finalize_connection().await
+    }
+
+    /// Read the entire body into the HTTP buffer and "finalize" the connection, returning both the body and the underlying connection.
+    ///
+    /// Unlike reading the body using [body](Self::body) and then calling [finalize](Self::finalize), the body remains borrowed while the response is written,
+    /// so values which borrow from the body, such as those deserialized using [Json::from_slice](crate::extract::Json::from_slice), can be used in the response.
+    ///
+    /// The body is `None` if it does not fit into the buffer, in which case it is discarded as by [finalize](Self::finalize),
+    /// or if the connection was closed before the entire body was read.
+    pub async fn read_all_and_finalize(
+        self,
+    ) -> Result<
+        (
+            Option<&'r mut [u8]>,
+            crate::response::Connection<'r, impl Read<Error = R::Error> + 'r>,
+        ),
+        R::Error,
+    > {
+        if self.content_length > self.buffer.len() {
+            return Ok((None, self.finalize_connection().await?));
+        }
+
+        let (body, buffer) = self.buffer.split_at_mut(self.content_length);
+
+        let body = match body.get_mut(self.buffer_usage..) {
+            Some(remaining_body_to_read) => {
+                match self.reader.read_exact(remaining_body_to_read).await {
+                    Ok(()) => Some(body),
+                    Err(embedded_io_async::ReadExactError::UnexpectedEof) => {
+                        *self.must_close = true;
+                        None
+                    }
+                    Err(embedded_io_async::ReadExactError::Other(err)) => return Err(err),
+                }
+            }
+            None => Some(body),
+        };
+
+        Ok((
+            body,
+            crate::response::Connection {
+                reader: crate::response::BufferedReader {
+                    reader: self.reader,
+                    buffer,
+                    read_position: 0,
+                    buffer_usage: self.buffer_usage.saturating_sub(self.content_length),
+                },
+                has_been_upgraded: self.has_been_upgraded,
+                must_close: self.must_close,
+                handler_error: None,
+            },
+        ))
+    }
+
+    async fn finalize_connection(
+        self,
+    ) -> Result<crate::response::Connection<'r, &'r mut R>, R::Error> {
         // If the entire body is already in the buffer
         if self.content_length <= self.buffer_usage {
             return Ok(crate::response::Connection {
@@ -595,6 +963,8 @@ impl<'r, R: Read> RequestBodyConnection<'r, R> {
                     buffer_usage: self.buffer_usage,
                 },
                 has_been_upgraded: self.has_been_upgraded,
+                must_close: self.must_close,
+                handler_error: None,
             });
         }
 
@@ -603,6 +973,25 @@ impl<'r, R: Read> RequestBodyConnection<'r, R> {
         // Skip the section that has already been read into the buffer
         let mut read_position = self.read_position.max(self.buffer_usage);
 
+        if self
+            .auto_drain_body
+            .is_some_and(|auto_drain_body| self.content_length - read_position > auto_drain_body)
+        {
+            *self.must_close = true;
+
+            return Ok(crate::response::Connection {
+                reader: crate::response::BufferedReader {
+                    reader: self.reader,
+                    buffer: self.buffer,
+                    read_position: 0,
+                    buffer_usage: 0,
+                },
+                has_been_upgraded: self.has_been_upgraded,
+                must_close: self.must_close,
+                handler_error: None,
+            });
+        }
+
         while let Some(data_remaining) = self
             .content_length
             .checked_sub(read_position)
@@ -630,10 +1019,51 @@ impl<'r, R: Read> RequestBodyConnection<'r, R> {
                 buffer_usage: 0,
             },
             has_been_upgraded: self.has_been_upgraded,
+            must_close: self.must_close,
+            handler_error: None,
         })
     }
 }
 
+impl<'r, 'c, R: Read> RequestBodyConnection<'r, crate::response::BufferedReader<'c, R>> {
+    /// A connection whose body is held entirely in `body`, such as a decoded copy of the original body, followed by `trailers`,
+    /// which reads any data after the body, such as once the connection has been upgraded, from `connection`.
+    pub(crate) fn from_buffered_body(
+        body: &'r mut [u8],
+        trailers: Headers<'r>,
+        connection: &'r mut crate::response::Connection<'c, R>,
+    ) -> Self {
+        Self {
+            content_length: body.len(),
+            trailers,
+            reader: &mut connection.reader,
+            read_position: 0,
+            buffer_usage: body.len(),
+            buffer: body,
+            has_been_upgraded: connection.has_been_upgraded,
+            auto_drain_body: None,
+            must_close: connection.must_close,
+        }
+    }
+}
+
+/// A header name must be a non-empty token, as defined by RFC 7230
+fn is_valid_header_name(name: &[u8]) -> bool {
+    !name.is_empty()
+        && name
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+/// A header value may contain visible ASCII characters, spaces, and horizontal tabs, and is terminated by an optional carriage return
+fn is_valid_header_value(value: &[u8]) -> bool {
+    let value = value.strip_suffix(b"\r").unwrap_or(value);
+
+    value
+        .iter()
+        .all(|&b| b == b'\t' || b == b' ' || b.is_ascii_graphic())
+}
+
 /// A HTTP Request
 pub struct Request<'r, R: Read> {
     /// The method, path, query, fragments, and headers.
@@ -642,38 +1072,352 @@ pub struct Request<'r, R: Read> {
     pub body_connection: RequestBodyConnection<'r, R>,
 }
 
+impl<'r, R: Read> Request<'r, R> {
+    /// Split the request into the method, path, query, fragments, and headers, and the request body and underlying connection.
+    ///
+    /// The parts borrow from the request buffer rather than the returned [RequestBodyConnection], so they can be used while reading the body and writing the response.
+    pub fn into_parts(self) -> (RequestParts<'r>, RequestBodyConnection<'r, R>) {
+        (self.parts, self.body_connection)
+    }
+}
+
+/// Why a request could not be parsed. The server responds with an error, which can be customized using [Config::parse_error_format](crate::Config::parse_error_format),
+/// and then closes the connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParseError {
+    /// The request line is invalid
+    BadRequestLine,
+    /// A Header line does not contain a ':'
+    HeaderDoesNotContainColon,
+    /// A Header line has an invalid name or value, and strict header validation is enabled
+    InvalidHeader,
+    /// The request has more headers than the limit set by [Config::max_headers](crate::Config::max_headers)
+    TooManyHeaders,
+    /// The "Content-Length" header is not a valid length, or there are several "Content-Length" headers, so the end of the body is not known
+    InvalidContentLength,
+    /// The request has both a "Content-Length" and a "Transfer-Encoding" header, which might be interpreted differently by a proxy in front of the server
+    ContentLengthWithTransferEncoding,
+    /// A chunked request body has an invalid chunk size line or trailer
+    InvalidChunkedBody,
+    /// A chunked request body, along with its trailers, does not fit in the buffer used to read the body
+    ChunkedBodyTooLarge,
+    /// EndOfFile before the end of the request line or headers, or a chunked request body
+    UnexpectedEof,
+    /// The request line and headers do not fit in the buffer passed to [serve](crate::serve)
+    HeadersTooLarge,
+}
+
+impl ParseError {
+    /// The status code of the response, which is "Request Header Fields Too Large" for [ParseError::TooManyHeaders] and [ParseError::HeadersTooLarge],
+    /// "Payload Too Large" for [ParseError::ChunkedBodyTooLarge], and "Bad Request" otherwise.
+    pub const fn status_code(self) -> crate::response::StatusCode {
+        match self {
+            Self::TooManyHeaders | Self::HeadersTooLarge => {
+                crate::response::StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+            }
+            Self::ChunkedBodyTooLarge => crate::response::StatusCode::PAYLOAD_TOO_LARGE,
+            _ => crate::response::StatusCode::BAD_REQUEST,
+        }
+    }
+
+    /// The message sent in the body of the response by default.
+    pub const fn message(self) -> &'static str {
+        match self {
+            Self::BadRequestLine => "Bad Request Line",
+            Self::HeaderDoesNotContainColon => "Invalid Header line: No ':' character",
+            Self::InvalidHeader => "Invalid Header line",
+            Self::TooManyHeaders => "Too many headers",
+            Self::InvalidContentLength => "Invalid Content-Length",
+            Self::ContentLengthWithTransferEncoding => {
+                "Both Content-Length and Transfer-Encoding are present"
+            }
+            Self::InvalidChunkedBody => "Invalid chunked body",
+            Self::ChunkedBodyTooLarge => "Chunked body is too large",
+            Self::UnexpectedEof => "Unexpected EOF while reading request",
+            Self::HeadersTooLarge => "Request headers are too large",
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.message().fmt(f)
+    }
+}
+
 /// Errors arising while reading a HTTP Request
 pub(crate) enum ReadError<E> {
     /// The request line is invalid
     BadRequestLine,
     /// A Header line does not contain a ':'
     HeaderDoesNotContainColon,
-    /// EndOfFile before the end of the request line or headers
+    /// A Header line has an invalid name or value, and strict header validation is enabled
+    InvalidHeader,
+    /// The request has more headers than the limit set by [Config::max_headers](crate::Config::max_headers)
+    TooManyHeaders,
+    /// The "Content-Length" header is not a valid length, or there are several "Content-Length" headers, so the end of the body is not known
+    InvalidContentLength,
+    /// The request has both a "Content-Length" and a "Transfer-Encoding" header, which might be interpreted differently by a proxy in front of the server
+    ContentLengthWithTransferEncoding,
+    /// A chunked request body has an invalid chunk size line or trailer
+    InvalidChunkedBody,
+    /// A chunked request body, along with its trailers, does not fit in the buffer
+    ChunkedBodyTooLarge,
+    /// EndOfFile before the end of the request line or headers, or a chunked request body
     UnexpectedEof,
+    /// The request line and headers do not fit in the buffer
+    HeadersTooLarge,
     /// IO Error
     IO(E),
 }
 
+/// Parse the size of a chunk from a chunk size line, ignoring any chunk extensions.
+fn parse_chunk_size(line: &[u8]) -> Option<usize> {
+    let size = trim(line.split(|&b| b == b';').next()?);
+
+    if size.is_empty() || !size.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+
+    usize::from_str_radix(core::str::from_utf8(size).ok()?, 16).ok()
+}
+
+fn is_empty_line(line: &[u8]) -> bool {
+    matches!(line, b"\n" | b"\r\n")
+}
+
+/// Decodes a chunked body in place, copying the chunk data and trailers to the start of the buffer.
+struct ChunkedBodyDecoder<'a, R: Read> {
+    reader: &'a mut R,
+    buffer: &'a mut [u8],
+    /// The end of the data which has been read from the reader
+    buffer_usage: usize,
+    /// The start of the data which has been read but not yet decoded
+    read_position: usize,
+    /// The end of the decoded chunk data and trailers, which is never after `read_position`
+    decoded_length: usize,
+    /// The maximum length of the data which has been read but not yet decoded
+    max_pending: usize,
+}
+
+impl<'a, R: Read> ChunkedBodyDecoder<'a, R> {
+    /// Read more data, first moving the data which has not yet been decoded to the end of the decoded data if the buffer is full.
+    async fn fill(&mut self) -> Result<(), ReadError<R::Error>> {
+        if self.buffer_usage == self.buffer.len() {
+            self.buffer
+                .copy_within(self.read_position..self.buffer_usage, self.decoded_length);
+
+            self.buffer_usage -= self.read_position - self.decoded_length;
+            self.read_position = self.decoded_length;
+        }
+
+        let read_end = self
+            .buffer
+            .len()
+            .min(self.read_position.saturating_add(self.max_pending));
+
+        let read_buffer = self
+            .buffer
+            .get_mut(self.buffer_usage..read_end)
+            .filter(|read_buffer| !read_buffer.is_empty())
+            .ok_or(ReadError::ChunkedBodyTooLarge)?;
+
+        let read_size = self.reader.read(read_buffer).await.map_err(ReadError::IO)?;
+
+        if read_size == 0 {
+            return Err(ReadError::UnexpectedEof);
+        }
+
+        self.buffer_usage += read_size;
+
+        Ok(())
+    }
+
+    /// Read a line, returning the range of the buffer containing it, including the line ending.
+    async fn read_line(&mut self) -> Result<Range<usize>, ReadError<R::Error>> {
+        loop {
+            if let Some(line_ending) = self.buffer[self.read_position..self.buffer_usage]
+                .iter()
+                .position(|&b| b == b'\n')
+            {
+                let line = self.read_position..(self.read_position + line_ending + 1);
+
+                self.read_position = line.end;
+
+                return Ok(line);
+            }
+
+            self.fill().await?;
+        }
+    }
+
+    /// Copy data which has already been read to the end of the decoded data.
+    fn keep(&mut self, data: Range<usize>) {
+        let length = data.len();
+
+        self.buffer.copy_within(data, self.decoded_length);
+
+        self.decoded_length += length;
+    }
+
+    async fn read_chunk_data(&mut self, mut chunk_size: usize) -> Result<(), ReadError<R::Error>> {
+        while chunk_size > 0 {
+            if self.read_position == self.buffer_usage {
+                self.fill().await?;
+            }
+
+            let length = chunk_size.min(self.buffer_usage - self.read_position);
+
+            self.keep(self.read_position..(self.read_position + length));
+
+            self.read_position += length;
+            chunk_size -= length;
+        }
+
+        Ok(())
+    }
+}
+
+/// The layout of the buffer once a chunked body has been decoded by [decode_chunked_body].
+struct ChunkedBody {
+    trailers_length: usize,
+    body_length: usize,
+    buffer_usage: usize,
+}
+
+/// Decode the chunked body at the start of `buffer`, of which `buffer_usage` bytes have already been read, reading the rest from `reader`.
+///
+/// Once decoded, the buffer contains the trailers, then the body, and then any data read after the body, such as the next request.
+/// No more than `max_pending` bytes which have not yet been decoded are held in the buffer, so the data read after the body fits into `max_pending` bytes.
+async fn decode_chunked_body<R: Read>(
+    reader: &mut R,
+    buffer: &mut [u8],
+    buffer_usage: usize,
+    max_pending: usize,
+) -> Result<ChunkedBody, ReadError<R::Error>> {
+    let mut decoder = ChunkedBodyDecoder {
+        reader,
+        buffer,
+        buffer_usage,
+        read_position: 0,
+        decoded_length: 0,
+        max_pending,
+    };
+
+    loop {
+        let line = decoder.read_line().await?;
+
+        let chunk_size =
+            parse_chunk_size(&decoder.buffer[line]).ok_or(ReadError::InvalidChunkedBody)?;
+
+        if chunk_size == 0 {
+            break;
+        }
+
+        decoder.read_chunk_data(chunk_size).await?;
+
+        let line = decoder.read_line().await?;
+
+        if !is_empty_line(&decoder.buffer[line]) {
+            return Err(ReadError::InvalidChunkedBody);
+        }
+    }
+
+    let body_length = decoder.decoded_length;
+
+    loop {
+        let line = decoder.read_line().await?;
+
+        let trailer = &decoder.buffer[line.clone()];
+
+        if is_empty_line(trailer) {
+            break;
+        }
+
+        if !trailer.contains(&b':') {
+            return Err(ReadError::InvalidChunkedBody);
+        }
+
+        decoder.keep(line);
+    }
+
+    let ChunkedBodyDecoder {
+        buffer,
+        buffer_usage,
+        read_position,
+        decoded_length,
+        ..
+    } = decoder;
+
+    // Move the trailers before the body, and the data read after the body to directly after it
+    buffer[..decoded_length].rotate_left(body_length);
+    buffer.copy_within(read_position..buffer_usage, decoded_length);
+
+    Ok(ChunkedBody {
+        trailers_length: decoded_length - body_length,
+        body_length,
+        buffer_usage: decoded_length + (buffer_usage - read_position),
+    })
+}
+
 pub(crate) struct Reader<'b, R: Read> {
     reader: R,
     read_position: usize,
     buffer: &'b mut [u8],
     buffer_usage: usize,
+    body_buffer: Option<&'b mut [u8]>,
+    /// The section of the body buffer which contains data sent after the body of the previous request
+    body_buffer_excess: Range<usize>,
+    header_validation: HeaderValidation,
     has_been_upgraded: bool,
+    auto_drain_body: Option<usize>,
+    max_headers: Option<usize>,
+    /// Set if the connection must be closed after the response, such as if the rest of a request body was not read,
+    /// so further requests cannot be read from the connection, or if the response has a "Connection: close" header
+    must_close: bool,
 }
 
 impl<'b, R: Read> Reader<'b, R> {
-    pub fn new(reader: R, buffer: &'b mut [u8]) -> Self {
+    pub fn new(
+        reader: R,
+        buffer: &'b mut [u8],
+        body_buffer: Option<&'b mut [u8]>,
+        header_validation: HeaderValidation,
+        auto_drain_body: Option<usize>,
+        max_headers: Option<usize>,
+    ) -> Self {
         Self {
             reader,
             read_position: 0,
             buffer,
             buffer_usage: 0,
+            body_buffer,
+            body_buffer_excess: 0..0,
+            header_validation,
             has_been_upgraded: false,
+            auto_drain_body,
+            max_headers,
+            must_close: false,
         }
     }
 
     fn wind_buffer_to_start(&mut self) {
+        if let Some(body_buffer) = self.body_buffer.as_deref() {
+            let excess = core::mem::replace(&mut self.body_buffer_excess, 0..0);
+
+            if !excess.is_empty() {
+                // The excess data was originally read into the request buffer, so it will fit back into it
+                let excess = &body_buffer[excess];
+
+                self.buffer[..excess.len()].copy_from_slice(excess);
+                self.buffer_usage = excess.len();
+                self.read_position = 0;
+
+                return;
+            }
+        }
+
         if let Some(used_buffer) = self.buffer.get_mut(..self.buffer_usage) {
             used_buffer.rotate_left(self.read_position);
 
@@ -686,7 +1430,7 @@ impl<'b, R: Read> Reader<'b, R> {
     }
 
     pub async fn request_is_pending(&mut self) -> Result<bool, R::Error> {
-        if self.has_been_upgraded {
+        if self.has_been_upgraded || self.must_close {
             Ok(false)
         } else {
             self.wind_buffer_to_start();
@@ -706,6 +1450,10 @@ impl<'b, R: Read> Reader<'b, R> {
 
     async fn next_byte(&mut self) -> Result<u8, ReadError<R::Error>> {
         if self.read_position == self.buffer_usage {
+            if self.buffer_usage == self.buffer.len() {
+                return Err(ReadError::HeadersTooLarge);
+            }
+
             let read_size = self
                 .reader
                 .read(&mut self.buffer[self.buffer_usage..])
@@ -758,18 +1506,48 @@ impl<'b, R: Read> Reader<'b, R> {
             }
         }
 
-        let line = self.read_line().await?;
+        // Empty lines before the request line, such as those sent by some clients after the body of the previous request, are ignored, as per RFC 9112
+        let line_range = loop {
+            let line = self.read_line().await?;
+
+            if !matches!(line.as_ref(), b"" | b"\r") {
+                break line.range;
+            }
+        };
 
-        let mut words = core::str::from_utf8(line.as_ref())
+        let line = Subslice {
+            buffer: self.used_buffer(),
+            range: line_range,
+        };
+
+        let request_line = line.as_ref();
+        let request_line = request_line.strip_suffix(b"\r").unwrap_or(request_line);
+
+        // The method, request target, and HTTP version must be separated by single spaces, and none may be empty
+        let mut words = core::str::from_utf8(request_line)
             .map_err(|_| ReadError::BadRequestLine)?
-            .split_whitespace()
-            .map(str::trim);
+            .split(' ');
+
+        let mut next_word = || {
+            words
+                .next()
+                .filter(|word| {
+                    !word.is_empty()
+                        && !word
+                            .bytes()
+                            .any(|b| b.is_ascii_whitespace() || b.is_ascii_control())
+                })
+                .ok_or(ReadError::BadRequestLine)
+        };
 
-        let method = words.next().ok_or(ReadError::BadRequestLine)?;
-        let path = words.next().ok_or(ReadError::BadRequestLine)?;
-        let http_version = words.next().ok_or(ReadError::BadRequestLine)?;
+        let method = next_word()?;
+        let path = next_word()?;
+        let http_version = next_word()?;
 
-        if words.next().is_some() {
+        if words.next().is_some()
+            || !is_valid_header_name(method.as_bytes())
+            || !http_version.starts_with("HTTP/")
+        {
             return Err(ReadError::BadRequestLine);
         }
 
@@ -782,6 +1560,10 @@ impl<'b, R: Read> Reader<'b, R> {
 
     async fn read_headers(&mut self) -> Result<Subslice, ReadError<R::Error>> {
         let start_index = self.read_position;
+        let header_validation = self.header_validation;
+        let max_headers = self.max_headers;
+
+        let mut header_count = 0;
 
         let mut end_index = loop {
             // First read the line
@@ -792,10 +1574,24 @@ impl<'b, R: Read> Reader<'b, R> {
                 break line.range.start;
             }
 
+            // Then check that there are not too many headers
+            header_count += 1;
+
+            if max_headers.is_some_and(|max_headers| header_count > max_headers) {
+                return Err(ReadError::TooManyHeaders);
+            }
+
             // Then verify that the header is valid
-            // TODO - more thorough verification
-            if !line.as_ref().contains(&b':') {
+            let Some(colon_position) = line.as_ref().iter().position(|&b| b == b':') else {
                 return Err(ReadError::HeaderDoesNotContainColon);
+            };
+
+            if let HeaderValidation::Strict = header_validation {
+                let (name, value) = line.as_ref().split_at(colon_position);
+
+                if !is_valid_header_name(name) || !is_valid_header_value(&value[1..]) {
+                    return Err(ReadError::InvalidHeader);
+                }
             }
         };
 
@@ -828,16 +1624,102 @@ impl<'b, R: Read> Reader<'b, R> {
 
         let headers = self.read_headers().await?;
 
-        let content_length = Headers(headers.as_ref())
-            .get("content-length")
-            .and_then(|value| value.as_str().ok()?.parse::<usize>().ok())
-            .unwrap_or(0);
+        let content_length = {
+            let mut content_lengths = Headers(headers.as_ref())
+                .iter()
+                .filter(|(name, _)| "content-length" == *name);
+
+            match (content_lengths.next(), content_lengths.next()) {
+                (None, _) => None,
+                (Some((_, value)), None) => Some(
+                    value
+                        .as_str()
+                        .ok()
+                        .and_then(|value| value.parse::<usize>().ok())
+                        .ok_or(ReadError::InvalidContentLength)?,
+                ),
+                // A proxy in front of the server might use a different "Content-Length" header
+                _ => return Err(ReadError::InvalidContentLength),
+            }
+        };
+
+        let (is_chunked, framing_is_uncertain) = {
+            let mut transfer_codings = Headers(headers.as_ref()).get_list("transfer-encoding");
+
+            match (transfer_codings.next(), transfer_codings.next()) {
+                (None, _) => (false, false),
+                (Some(coding), None) if coding.eq_ignore_ascii_case("chunked") => (true, false),
+                // Other transfer codings are not supported, so the end of the body, and thus the start of the next request, is not known
+                _ => (false, true),
+            }
+        };
+
+        // A proxy in front of the server might use the "Content-Length" header rather than the "Transfer-Encoding" header,
+        // and so disagree with the server over where the next request starts
+        if content_length.is_some() && (is_chunked || framing_is_uncertain) {
+            return Err(ReadError::ContentLengthWithTransferEncoding);
+        }
+
+        let content_length = content_length.unwrap_or(0);
 
         let headers = headers.range;
 
         let parts_length = self.read_position;
 
-        let (parts_buffer, body_buffer) = self.buffer.split_at_mut(parts_length);
+        let body_buffer_usage = self.buffer_usage - parts_length;
+
+        // Data read after a chunked body must fit back into the request buffer
+        let max_pending = self.buffer.len();
+
+        let (parts_buffer, body_buffer, uses_separate_body_buffer): (&[u8], &mut [u8], bool) =
+            match self.body_buffer.as_deref_mut() {
+                // Move any of the body which has already been read into the separate body buffer, if it fits
+                Some(body_buffer) if body_buffer_usage <= body_buffer.len() => {
+                    body_buffer[..body_buffer_usage]
+                        .copy_from_slice(&self.buffer[parts_length..self.buffer_usage]);
+
+                    (&self.buffer[..parts_length], body_buffer, true)
+                }
+                // Otherwise fall back to using the rest of the request buffer as the body buffer
+                _ => {
+                    let (parts_buffer, body_buffer) = self.buffer.split_at_mut(parts_length);
+
+                    (parts_buffer, body_buffer, false)
+                }
+            };
+
+        let (trailers, body_buffer, body_offset, content_length, body_buffer_usage) = if is_chunked
+        {
+            let ChunkedBody {
+                trailers_length,
+                body_length,
+                buffer_usage,
+            } = decode_chunked_body(
+                &mut self.reader,
+                body_buffer,
+                body_buffer_usage,
+                max_pending,
+            )
+            .await?;
+
+            let (trailers, body_buffer) = body_buffer.split_at_mut(trailers_length);
+
+            (
+                Headers(trailers),
+                body_buffer,
+                trailers_length,
+                body_length,
+                buffer_usage - trailers_length,
+            )
+        } else {
+            (
+                Headers(&[]),
+                body_buffer,
+                0,
+                content_length,
+                body_buffer_usage,
+            )
+        };
 
         let RequestLine {
             method,
@@ -868,20 +1750,43 @@ impl<'b, R: Read> Reader<'b, R> {
                 fragments,
                 http_version,
                 headers,
+                keep_alive: if framing_is_uncertain {
+                    KeepAlive::Close
+                } else {
+                    KeepAlive::from_request(http_version, headers)
+                },
+                matched_path: None,
+                allowed_methods: crate::routing::AllowedMethods::NONE,
+                bad_path_segment: None,
+                start_time: None,
+                clock: || None,
+                remote_address: None,
+                proxy_trust: crate::ProxyTrust::None,
             },
             body_connection: RequestBodyConnection {
                 content_length,
+                trailers,
                 reader: &mut self.reader,
                 read_position: 0,
                 buffer: body_buffer,
-                buffer_usage: self.buffer_usage - parts_length,
+                buffer_usage: body_buffer_usage,
                 has_been_upgraded: &mut self.has_been_upgraded,
+                auto_drain_body: self.auto_drain_body,
+                must_close: &mut self.must_close,
             },
         };
 
         // This will be true once the RequestBodyConnection has been finalized, which happens no matter how the request is handled
-        self.read_position += content_length;
-        self.buffer_usage = self.buffer_usage.max(self.read_position);
+        if uses_separate_body_buffer {
+            self.read_position = 0;
+            self.buffer_usage = 0;
+            self.body_buffer_excess = (body_offset + content_length.min(body_buffer_usage))
+                ..(body_offset + body_buffer_usage);
+        } else {
+            self.read_position += body_offset + content_length;
+            self.buffer_usage =
+                (parts_length + body_offset + body_buffer_usage).max(self.read_position);
+        }
 
         Ok(request)
     }
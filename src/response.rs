@@ -16,6 +16,7 @@
 //!         + `(&str, impl Display)`
 //!         + `Option<impl HeadersIter>`
 //!         + `[impl HeadersIter; N]`
+//!         + [`Headers`], which may contain several headers with the same name
 //!     3. A value which implements [Content]
 //!
 //! For a complete list, see [IntoResponse].
@@ -27,17 +28,24 @@ use crate::{
     KeepAlive, ResponseSent,
 };
 
+#[cfg(feature = "cbor")]
+pub mod cbor;
 pub mod chunked;
 pub mod custom;
 pub mod fs;
 pub mod json;
+pub mod multipart;
 pub mod sse;
 pub mod status;
 pub mod ws;
 
+#[cfg(feature = "cbor")]
+pub use cbor::Cbor;
+pub use chunked::Streaming;
 pub use fs::{Directory, File};
 pub use json::Json;
-pub use sse::EventStream;
+pub use multipart::MultipartStream;
+pub use sse::{EventStream, EventStreamWithShutdownSignal};
 pub use status::StatusCode;
 pub use ws::WebSocketUpgrade;
 
@@ -75,6 +83,16 @@ impl<'r, R: Read> BufferedReader<'r, R> {
     }
 }
 
+impl<'r, R: Read> crate::io::ErrorType for BufferedReader<'r, R> {
+    type Error = R::Error;
+}
+
+impl<'r, R: Read> Read for BufferedReader<'r, R> {
+    async fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        self.read_into(buffer).await
+    }
+}
+
 /// A connection which has been upgraded, and is thus allowed to read arbitary data from the socket.
 pub struct UpgradedConnection<'r, R: Read> {
     reader: BufferedReader<'r, R>,
@@ -94,6 +112,10 @@ impl<'r, R: Read> Read for UpgradedConnection<'r, R> {
 pub struct Connection<'r, R: Read> {
     pub(crate) reader: BufferedReader<'r, R>,
     pub(crate) has_been_upgraded: &'r mut bool,
+    /// Set if the connection must be closed after the response, such as if the rest of the request body was not read
+    pub(crate) must_close: &'r mut bool,
+    /// The error of a [HandlerError] response, as an `Option<E>`, until it is taken by a [CatchErrorLayer](crate::routing::CatchErrorLayer)
+    pub(crate) handler_error: Option<&'r mut (dyn core::any::Any + 'static)>,
 }
 
 impl<'r, R: Read> Connection<'r, R> {
@@ -109,6 +131,27 @@ impl<'r, R: Read> Connection<'r, R> {
         }
     }
 
+    /// Attach the error of a [HandlerError] to the connection, so that it is passed along with the response to a [CatchErrorLayer](crate::routing::CatchErrorLayer).
+    fn with_handler_error<'c, E: 'static>(self, error: &'c mut Option<E>) -> Connection<'c, R>
+    where
+        'r: 'c,
+    {
+        Connection {
+            reader: self.reader,
+            has_been_upgraded: self.has_been_upgraded,
+            must_close: self.must_close,
+            handler_error: Some(error),
+        }
+    }
+
+    /// If the response is a [HandlerError] with an error of type `E`, take the error.
+    pub(crate) fn take_handler_error<E: 'static>(&mut self) -> Option<E> {
+        self.handler_error
+            .as_mut()?
+            .downcast_mut::<Option<E>>()?
+            .take()
+    }
+
     /// Wait for the client to disconnect. This will discard any additional data sent by the client.
     pub async fn wait_for_disconnection(self) -> Result<(), R::Error> {
         crate::extract::UpgradeToken::discard_all_data(self).await
@@ -145,7 +188,7 @@ impl<E: crate::io::Error> crate::io::Read for EmptyReader<E> {
 }
 
 impl<'r, E: crate::io::Error> Connection<'r, EmptyReader<E>> {
-    pub(crate) fn empty(has_been_upgraded: &'r mut bool) -> Self {
+    pub(crate) fn empty(has_been_upgraded: &'r mut bool, must_close: &'r mut bool) -> Self {
         Self {
             reader: BufferedReader {
                 reader: EmptyReader(core::marker::PhantomData),
@@ -154,6 +197,8 @@ impl<'r, E: crate::io::Error> Connection<'r, EmptyReader<E>> {
                 buffer_usage: 0,
             },
             has_been_upgraded,
+            must_close,
+            handler_error: None,
         }
     }
 }
@@ -192,6 +237,11 @@ impl<'a, F: ForEachHeader> ForEachHeader for BorrowedForEachHeader<'a, F> {
 }
 
 /// The HTTP response headers.
+///
+/// Headers are not buffered, so there is no limit on their number or size, and they do not need to fit into the HTTP buffer.
+/// Each header line is formatted into a small fixed-size buffer and written to the socket in chunks,
+/// formatting the value again for each chunk, so a value which is much larger than the chunk size is slower to write,
+/// and its [Display](fmt::Display) implementation must produce the same output each time it is formatted.
 pub trait HeadersIter {
     /// Perform the following action for each header.
     async fn for_each_header<F: ForEachHeader>(self, f: F) -> Result<F::Output, F::Error>;
@@ -235,6 +285,200 @@ impl<T: HeadersIter> HeadersIter for Option<T> {
     }
 }
 
+/// A collection of up to `N` headers, which may contain several headers with the same name, such as multiple "Set-Cookie" headers.
+///
+/// Headers are written in the order in which they were added, and headers with the same name are not merged.
+///
+/// ```
+/// use picoserve::response::Headers;
+///
+/// let mut headers = Headers::<&str, 4>::new();
+///
+/// for cookie in ["a=1", "b=2", "c=3"] {
+///     headers.push("Set-Cookie", cookie).unwrap();
+/// }
+///
+/// assert_eq!(headers.len(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Headers<'a, V: fmt::Display, const N: usize> {
+    headers: heapless::Vec<(&'a str, V), N>,
+}
+
+impl<'a, V: fmt::Display, const N: usize> Default for Headers<'a, V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, V: fmt::Display, const N: usize> Headers<'a, V, N> {
+    /// Create an empty collection of headers.
+    pub const fn new() -> Self {
+        Self {
+            headers: heapless::Vec::new(),
+        }
+    }
+
+    /// Add a header, even if a header with the same name has already been added.
+    /// If the collection is full, the header is returned as an error.
+    pub fn push(&mut self, name: &'a str, value: V) -> Result<(), (&'a str, V)> {
+        self.headers.push((name, value))
+    }
+
+    /// The number of headers in the collection.
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Returns true if the collection contains no headers.
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+}
+
+impl<'a, V: fmt::Display, const N: usize> HeadersIter for Headers<'a, V, N> {
+    async fn for_each_header<F: ForEachHeader>(self, mut f: F) -> Result<F::Output, F::Error> {
+        for (name, value) in self.headers {
+            f.call(name, value).await?;
+        }
+        f.finalize().await
+    }
+}
+
+/// A "Content-Disposition" header of "attachment", which asks the client to download the response as a file with the given name,
+/// rather than displaying it.
+///
+/// The filename is sent as a quoted string, with non-ASCII characters replaced by "_".
+/// If the filename contains non-ASCII characters, the percent-encoded UTF-8 filename is also sent using the `filename*=UTF-8''` form defined by RFC 6266,
+/// which is preferred by clients which support it.
+///
+/// ```
+/// use picoserve::{response::Attachment, routing::get};
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/report.csv",
+///     get(|| async { (Attachment::new("Report – March.csv"), "sensor,value\n0,42\n") }),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Attachment<'a> {
+    filename: &'a str,
+}
+
+impl<'a> Attachment<'a> {
+    /// Download the response as a file named `filename`.
+    pub const fn new(filename: &'a str) -> Self {
+        Self { filename }
+    }
+}
+
+impl<'a> fmt::Display for Attachment<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use fmt::Write;
+
+        f.write_str("attachment; filename=\"")?;
+
+        for c in self.filename.chars() {
+            match c {
+                '"' | '\\' => write!(f, "\\{c}")?,
+                ' '..='~' => f.write_char(c)?,
+                _ => f.write_char('_')?,
+            }
+        }
+
+        f.write_char('"')?;
+
+        if self.filename.chars().all(|c| matches!(c, ' '..='~')) {
+            return Ok(());
+        }
+
+        f.write_str("; filename*=UTF-8''")?;
+
+        for &b in self.filename.as_bytes() {
+            if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+                f.write_char(b.into())?;
+            } else {
+                write!(f, "%{b:02X}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> HeadersIter for Attachment<'a> {
+    async fn for_each_header<F: ForEachHeader>(self, mut f: F) -> Result<F::Output, F::Error> {
+        f.call("Content-Disposition", self).await?;
+        f.finalize().await
+    }
+}
+
+/// A "Vary" header, listing the request headers which were used to select the response, such as "Accept-Encoding",
+/// so that caches only reuse the response for requests with the same values of those headers.
+///
+/// If the list is empty, no header is sent. Responses from [File::with_gzip] include a "Vary: Accept-Encoding" header automatically.
+///
+/// ```
+/// use picoserve::{extract::FromRequestParts, request::RequestParts, response::Vary, routing::get};
+///
+/// struct PrefersFrench(bool);
+///
+/// impl<'r, State> FromRequestParts<'r, State> for PrefersFrench {
+///     type Rejection = core::convert::Infallible;
+///
+///     async fn from_request_parts(
+///         _state: &'r State,
+///         request_parts: &RequestParts<'r>,
+///     ) -> Result<Self, Self::Rejection> {
+///         Ok(Self(
+///             request_parts
+///                 .headers()
+///                 .get_list("Accept-Language")
+///                 .next()
+///                 .is_some_and(|language| language.starts_with("fr")),
+///         ))
+///     }
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/greeting",
+///     get(|PrefersFrench(prefers_french)| async move {
+///         (
+///             Vary(&["Accept-Language"]),
+///             if prefers_french { "Bonjour\n" } else { "Hello\n" },
+///         )
+///     }),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Vary<'a>(pub &'a [&'a str]);
+
+impl<'a> fmt::Display for Vary<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut header_names = self.0.iter();
+
+        if let Some(header_name) = header_names.next() {
+            f.write_str(header_name)?;
+        }
+
+        for header_name in header_names {
+            write!(f, ", {header_name}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> HeadersIter for Vary<'a> {
+    async fn for_each_header<F: ForEachHeader>(self, mut f: F) -> Result<F::Output, F::Error> {
+        if !self.0.is_empty() {
+            f.call("Vary", self).await?;
+        }
+
+        f.finalize().await
+    }
+}
+
 struct HeadersChain<A: HeadersIter, B: HeadersIter>(A, B);
 
 impl<A: HeadersIter, B: HeadersIter> HeadersIter for HeadersChain<A, B> {
@@ -247,8 +491,14 @@ impl<A: HeadersIter, B: HeadersIter> HeadersIter for HeadersChain<A, B> {
 }
 
 /// The HTTP response body.
+///
+/// If the response headers don't include a "Content-Length" or "Transfer-Encoding" header, the client can only tell where the body ends
+/// when the connection is closed, so the connection is closed after the response is sent, unless the status code never has a body, such as "204 No Content".
 pub trait Body {
     /// Write the response body to the socket.
+    ///
+    /// `writer` is flushed by the server once this returns. Bodies which are written slowly should call [Write::flush] after each
+    /// logical unit of data, such as each event, so that it is sent immediately. See [Flushing](crate::io#flushing).
     async fn write_response_body<R: Read, W: Write<Error = R::Error>>(
         self,
         connection: Connection<'_, R>,
@@ -256,6 +506,81 @@ pub trait Body {
     ) -> Result<(), W::Error>;
 }
 
+/// An error returned by a handler, which is mapped to a response by a [CatchErrorLayer](crate::routing::CatchErrorLayer),
+/// so that application errors can be converted into responses in a single place rather than in each handler.
+///
+/// Handlers return `Result<T, HandlerError<E>>`, and as `HandlerError<E>` implements `From<E>`, errors can be returned using `?`.
+/// If no [CatchErrorLayer] for errors of type `E` handles the response, the response is "500 Internal Server Error".
+///
+/// [CatchErrorLayer]: crate::routing::CatchErrorLayer
+pub struct HandlerError<E>(pub E);
+
+impl<E> From<E> for HandlerError<E> {
+    fn from(error: E) -> Self {
+        Self(error)
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for HandlerError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("HandlerError").field(&self.0).finish()
+    }
+}
+
+/// The error is passed along with the "Internal Server Error" response in the [Connection],
+/// which every [ResponseWriter] passes on, so that a [CatchErrorLayer](crate::routing::CatchErrorLayer) can replace the response.
+impl<E: 'static> IntoResponse for HandlerError<E> {
+    async fn write_to<R: Read, W: ResponseWriter<Error = R::Error>>(
+        self,
+        connection: Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        let mut error = Some(self.0);
+
+        (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error\n")
+            .write_to(connection.with_handler_error(&mut error), response_writer)
+            .await
+    }
+}
+
+/// A [Body] which is sent as is, without any "Content-Type" or "Content-Length" headers, so that it can be returned from a handler in a tuple,
+/// after an optional status code and any number of headers.
+///
+/// The headers must describe how the client can tell where the body ends, such as with a "Content-Length" or "Transfer-Encoding" header,
+/// otherwise the client reads the body until the connection is closed, and the connection is closed after the response has been sent.
+///
+/// ```
+/// use picoserve::{
+///     io::{Read, Write},
+///     response::{Body, Connection, RawBody, StatusCode},
+///     routing::get,
+/// };
+///
+/// struct Countdown;
+///
+/// impl Body for Countdown {
+///     async fn write_response_body<R: Read, W: Write<Error = R::Error>>(
+///         self,
+///         _connection: Connection<'_, R>,
+///         mut writer: W,
+///     ) -> Result<(), W::Error> {
+///         writer.write_all(b"3 2 1").await
+///     }
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/countdown",
+///     get(|| async {
+///         (
+///             StatusCode::CREATED,
+///             [("Content-Type", "text/plain"), ("Content-Length", "5")],
+///             RawBody(Countdown),
+///         )
+///     }),
+/// );
+/// ```
+pub struct RawBody<B: Body>(pub B);
+
 struct NoBody;
 
 impl Body for NoBody {
@@ -298,21 +623,57 @@ macro_rules! content_methods {
 
 #[doc(hidden)]
 pub struct ContentBody<C: Content> {
+    content_length: usize,
     content: C,
 }
 
 impl<C: Content> Body for ContentBody<C> {
+    /// Writes the content, checking that the number of bytes written matches the declared "Content-Length",
+    /// as otherwise the client either waits forever for the rest of the body, or reads the excess as the start of the next response.
+    /// A mismatch is logged as an error and closes the connection after the response, and also causes a panic if debug assertions are enabled.
     async fn write_response_body<R: Read, W: Write<Error = R::Error>>(
         self,
-        _connection: Connection<'_, R>,
-        mut writer: W,
+        connection: Connection<'_, R>,
+        writer: W,
     ) -> Result<(), W::Error> {
+        let mut written_length = 0;
+
+        let mut writer = crate::CountingWriter {
+            writer,
+            bytes_written: &mut written_length,
+        };
+
         self.content.write_content(&mut writer).await?;
         writer.flush().await?;
+
+        if written_length != self.content_length as u64 {
+            log_error!(
+                "Content wrote {} bytes, but declared a Content-Length of {} bytes",
+                written_length,
+                self.content_length
+            );
+
+            // The client can't tell where the next response starts
+            *connection.must_close = true;
+
+            debug_assert_eq!(
+                written_length, self.content_length as u64,
+                "Content wrote a different number of bytes than its declared Content-Length"
+            );
+        }
+
         Ok(())
     }
 }
 
+/// Byte slices are sent with a "Content-Type" of "application/octet-stream".
+///
+/// ```
+/// use picoserve::routing::get;
+///
+/// let app: picoserve::Router<_> =
+///     picoserve::Router::new().route("/bytes", get(|| async { &b"\x00\x01"[..] }));
+/// ```
 impl<'a> Content for &'a [u8] {
     fn content_type(&self) -> &'static str {
         "application/octet-stream"
@@ -327,6 +688,16 @@ impl<'a> Content for &'a [u8] {
     }
 }
 
+/// Byte vectors are sent with a "Content-Type" of "application/octet-stream".
+///
+/// ```
+/// use picoserve::routing::get;
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/bytes",
+///     get(|| async { heapless::Vec::<u8, 2>::from_slice(b"\x00\x01").unwrap() }),
+/// );
+/// ```
 impl<const N: usize> Content for heapless::Vec<u8, N> {
     content_methods!(as_slice);
 }
@@ -336,6 +707,9 @@ impl Content for alloc::vec::Vec<u8> {
     content_methods!(as_slice);
 }
 
+/// String slices are sent with a "Content-Type" of "text/plain; charset=utf-8".
+///
+/// Use [Response::with_content_type] to send a different "Content-Type".
 impl<'a> Content for &'a str {
     fn content_type(&self) -> &'static str {
         "text/plain; charset=utf-8"
@@ -359,6 +733,43 @@ impl Content for alloc::string::String {
     content_methods!(as_str);
 }
 
+/// Formatted arguments are sent with a "Content-Type" of "text/plain; charset=utf-8".
+///
+/// The "Content-Length" is calculated by formatting the arguments once before sending the response, and they are formatted again while writing the body,
+/// so the [Display](fmt::Display) implementations of the arguments must produce the same output each time.
+///
+/// As formatted arguments borrow the values being formatted, they can only be returned directly from a handler if they don't borrow any local values,
+/// otherwise they should be written from within [IntoResponse::write_to].
+///
+/// ```
+/// use picoserve::{
+///     response::{Connection, IntoResponse, ResponseWriter},
+///     routing::get,
+///     ResponseSent,
+/// };
+///
+/// struct Greeting {
+///     name: &'static str,
+/// }
+///
+/// impl IntoResponse for Greeting {
+///     async fn write_to<R: picoserve::io::Read, W: ResponseWriter<Error = R::Error>>(
+///         self,
+///         connection: Connection<'_, R>,
+///         response_writer: W,
+///     ) -> Result<ResponseSent, W::Error> {
+///         let Self { name } = self;
+///
+///         format_args!("Hello {name}")
+///             .write_to(connection, response_writer)
+///             .await
+///     }
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new()
+///     .route("/", get(|| async { format_args!("Hello World") }))
+///     .route("/greeting", get(|| async { Greeting { name: "World" } }));
+/// ```
 impl<'a> Content for fmt::Arguments<'a> {
     fn content_type(&self) -> &'static str {
         "".content_type()
@@ -376,6 +787,213 @@ impl<'a> Content for fmt::Arguments<'a> {
     }
 }
 
+/// [Content] assembled from a sequence of fragments, such as the static parts of a templated page and the values between them,
+/// which are written in order without first being copied into a single buffer.
+///
+/// The "Content-Length" is the sum of the lengths of the fragments. The fragments are iterated over once to calculate it,
+/// and again while writing the body, so `fragments` must be cheap to clone and must produce the same fragments each time,
+/// such as an array or a slice of `&str` or `&[u8]`.
+///
+/// ```
+/// use picoserve::{response::Fragments, routing::get};
+///
+/// const HEADER: &str = "<!DOCTYPE html><html><head><title>Status</title></head><body>";
+/// const FOOTER: &str = "</body></html>";
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/",
+///     get(|| async { Fragments::html([HEADER, "<p>All systems nominal</p>", FOOTER]) }),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fragments<I> {
+    content_type: &'static str,
+    fragments: I,
+}
+
+impl<I: IntoIterator<Item = T> + Clone, T: AsRef<[u8]>> Fragments<I> {
+    /// Create content with the given "Content-Type" from `fragments`.
+    pub const fn new(content_type: &'static str, fragments: I) -> Self {
+        Self {
+            content_type,
+            fragments,
+        }
+    }
+
+    /// Create content with a "Content-Type" of "text/html; charset=utf-8" from `fragments`.
+    pub const fn html(fragments: I) -> Self {
+        Self::new("text/html; charset=utf-8", fragments)
+    }
+}
+
+impl<I: IntoIterator<Item = T> + Clone, T: AsRef<[u8]>> Content for Fragments<I> {
+    fn content_type(&self) -> &'static str {
+        self.content_type
+    }
+
+    fn content_length(&self) -> usize {
+        self.fragments
+            .clone()
+            .into_iter()
+            .map(|fragment| fragment.as_ref().len())
+            .sum()
+    }
+
+    async fn write_content<W: Write>(self, mut writer: W) -> Result<(), W::Error> {
+        for fragment in self.fragments {
+            writer.write_all(fragment.as_ref()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Sends [Content] as a binary frame, prefixed by the length of the content as a 4-byte big-endian integer,
+/// for clients which expect a simple framed protocol rather than reading the "Content-Length" header.
+///
+/// The "Content-Length" header includes the 4 bytes of the prefix. The content must be shorter than 4 GiB.
+///
+/// ```
+/// use picoserve::{response::LengthPrefixed, routing::get};
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/frame",
+///     get(|| async { LengthPrefixed::new("application/x-sensor-frame", &b"\x01\x02\x03"[..]) }),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LengthPrefixed<C> {
+    content_type: &'static str,
+    payload: C,
+}
+
+impl<C: Content> LengthPrefixed<C> {
+    /// Send `payload` with the given "Content-Type", prefixed by its length.
+    pub const fn new(content_type: &'static str, payload: C) -> Self {
+        Self {
+            content_type,
+            payload,
+        }
+    }
+
+    /// Send `payload` with a "Content-Type" of "application/octet-stream", prefixed by its length.
+    pub const fn octet_stream(payload: C) -> Self {
+        Self::new("application/octet-stream", payload)
+    }
+}
+
+impl<C: Content> Content for LengthPrefixed<C> {
+    fn content_type(&self) -> &'static str {
+        self.content_type
+    }
+
+    fn content_length(&self) -> usize {
+        4 + self.payload.content_length()
+    }
+
+    async fn write_content<W: Write>(self, mut writer: W) -> Result<(), W::Error> {
+        let payload_length = self.payload.content_length() as u32;
+
+        writer.write_all(&payload_length.to_be_bytes()).await?;
+
+        self.payload.write_content(writer).await
+    }
+}
+
+macro_rules! content_type_wrappers {
+    ($($(#[$meta:meta])* $name:ident => $content_type:literal,)*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name<T>(pub T);
+
+            impl<T: Content> Content for $name<T> {
+                fn content_type(&self) -> &'static str {
+                    $content_type
+                }
+
+                fn content_length(&self) -> usize {
+                    self.0.content_length()
+                }
+
+                async fn write_content<W: Write>(self, writer: W) -> Result<(), W::Error> {
+                    self.0.write_content(writer).await
+                }
+            }
+        )*
+    };
+}
+
+content_type_wrappers! {
+    /// Sends [Content], such as dynamically formatted text, with a "Content-Type" of "text/html; charset=utf-8".
+    ///
+    /// ```
+    /// use picoserve::{
+    ///     response::{Connection, Html, IntoResponse, ResponseWriter},
+    ///     routing::get,
+    ///     ResponseSent,
+    /// };
+    ///
+    /// struct Greeting {
+    ///     name: &'static str,
+    /// }
+    ///
+    /// impl IntoResponse for Greeting {
+    ///     async fn write_to<R: picoserve::io::Read, W: ResponseWriter<Error = R::Error>>(
+    ///         self,
+    ///         connection: Connection<'_, R>,
+    ///         response_writer: W,
+    ///     ) -> Result<ResponseSent, W::Error> {
+    ///         let Self { name } = self;
+    ///
+    ///         Html(format_args!("<h1>Hello {name}</h1>"))
+    ///             .write_to(connection, response_writer)
+    ///             .await
+    ///     }
+    /// }
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::new()
+    ///     .route("/", get(|| async { Html("<h1>Hello World</h1>") }))
+    ///     .route("/greeting", get(|| async { Greeting { name: "Ferris" } }))
+    ///     .route(
+    ///         "/count",
+    ///         get(|| async {
+    ///             let mut body = heapless::String::<32>::new();
+    ///             core::fmt::Write::write_fmt(&mut body, format_args!("<p>{}</p>", 42)).unwrap();
+    ///             Html(body)
+    ///         }),
+    ///     );
+    /// ```
+    Html => "text/html; charset=utf-8",
+    /// Sends [Content] with a "Content-Type" of "text/plain; charset=utf-8", such as for byte slices which contain text.
+    ///
+    /// ```
+    /// use picoserve::{response::Text, routing::get};
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::new()
+    ///     .route("/", get(|| async { Text(&b"Hello World"[..]) }));
+    /// ```
+    Text => "text/plain; charset=utf-8",
+    /// Sends [Content] with a "Content-Type" of "text/css".
+    ///
+    /// ```
+    /// use picoserve::{response::Css, routing::get};
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::new()
+    ///     .route("/theme.css", get(|| async { Css(format_args!("body {{ color: #333; }}")) }));
+    /// ```
+    Css => "text/css",
+    /// Sends [Content] with a "Content-Type" of "application/javascript; charset=utf-8".
+    ///
+    /// ```
+    /// use picoserve::{response::JavaScript, routing::get};
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::new()
+    ///     .route("/config.js", get(|| async { JavaScript("const DEBUG = false;") }));
+    /// ```
+    JavaScript => "application/javascript; charset=utf-8",
+}
+
 #[doc(hidden)]
 pub struct ContentHeaders {
     content_type: &'static str,
@@ -393,6 +1011,7 @@ impl HeadersIter for ContentHeaders {
 /// Represents a HTTP response.
 pub struct Response<H: HeadersIter, B: Body> {
     pub(crate) status_code: StatusCode,
+    pub(crate) reason_phrase: Option<&'static str>,
     pub(crate) headers: H,
     pub(crate) body: B,
 }
@@ -400,13 +1019,19 @@ pub struct Response<H: HeadersIter, B: Body> {
 impl<C: Content> Response<ContentHeaders, ContentBody<C>> {
     /// Creates a response from a HTTP status code and body with content. The Content-Type and Content-Length headers are generated from the values returned by the Body.
     pub fn new(status_code: StatusCode, content: C) -> Self {
+        let content_length = content.content_length();
+
         Self {
             status_code,
+            reason_phrase: None,
             headers: ContentHeaders {
                 content_type: content.content_type(),
-                content_length: content.content_length(),
+                content_length,
+            },
+            body: ContentBody {
+                content_length,
+                content,
             },
-            body: ContentBody { content },
         }
     }
 
@@ -414,6 +1039,26 @@ impl<C: Content> Response<ContentHeaders, ContentBody<C>> {
     pub fn ok(body: C) -> Self {
         Self::new(StatusCode::OK, body)
     }
+
+    /// Return a new response with the given "Content-Type", in place of the one declared by the content.
+    ///
+    /// ```
+    /// use picoserve::{response::Response, routing::get};
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::new().route(
+    ///     "/",
+    ///     get(|| async { Response::ok("Hello World").with_content_type("text/plain; charset=us-ascii") }),
+    /// );
+    /// ```
+    pub fn with_content_type(self, content_type: &'static str) -> Self {
+        Self {
+            headers: ContentHeaders {
+                content_type,
+                ..self.headers
+            },
+            ..self
+        }
+    }
 }
 
 impl<H: HeadersIter, B: Body> Response<H, B> {
@@ -426,27 +1071,48 @@ impl<H: HeadersIter, B: Body> Response<H, B> {
     pub fn with_status_code(self, status_code: StatusCode) -> Self {
         let Self {
             status_code: _,
+            reason_phrase,
             headers,
             body,
         } = self;
 
         Self {
             status_code,
+            reason_phrase,
             headers,
             body,
         }
     }
 
+    /// Return a new response with the given reason phrase, which is written after the status code in the status line instead of the canonical reason phrase.
+    /// An empty reason phrase is allowed.
+    ///
+    /// As the reason phrase must not contain a carriage return or line feed, such reason phrases are ignored and the canonical reason phrase is used instead.
+    pub fn with_reason(self, reason_phrase: &'static str) -> Self {
+        if reason_phrase.contains(['\r', '\n']) {
+            log_warn!("Ignoring reason phrase containing CR or LF");
+
+            return self;
+        }
+
+        Self {
+            reason_phrase: Some(reason_phrase),
+            ..self
+        }
+    }
+
     /// Add additional headers to a response.
     pub fn with_headers<HH: HeadersIter>(self, headers: HH) -> Response<impl HeadersIter, B> {
         let Response {
             status_code,
+            reason_phrase,
             headers: current_headers,
             body,
         } = self;
 
         Response {
             status_code,
+            reason_phrase,
             headers: HeadersChain(current_headers, headers),
             body,
         }
@@ -489,6 +1155,70 @@ impl<W: Write> ResponseStream<W> {
     }
 }
 
+/// Returns whether the comma-separated list of options in `value`, such as the value of a "Connection" header, includes `option` (ignoring ASCII case).
+pub(crate) fn header_value_has_option(value: impl fmt::Display, option: &str) -> bool {
+    struct OptionFinder<'a> {
+        option: &'a str,
+        /// The part of the current option which has been matched, or None if the current option does not match
+        matched_length: Option<usize>,
+        has_option: bool,
+    }
+
+    impl<'a> OptionFinder<'a> {
+        fn end_option(&mut self) {
+            if self.matched_length == Some(self.option.len()) {
+                self.has_option = true;
+            }
+
+            self.matched_length = Some(0);
+        }
+    }
+
+    impl<'a> fmt::Write for OptionFinder<'a> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            for b in s.bytes() {
+                match b {
+                    b',' => self.end_option(),
+                    b' ' | b'\t' => {
+                        // Whitespace is only allowed around an option
+                        if self
+                            .matched_length
+                            .is_some_and(|matched_length| matched_length > 0)
+                        {
+                            self.matched_length = self
+                                .matched_length
+                                .filter(|&matched_length| matched_length == self.option.len());
+                        }
+                    }
+                    b => {
+                        self.matched_length = self.matched_length.and_then(|matched_length| {
+                            self.option
+                                .as_bytes()
+                                .get(matched_length)
+                                .filter(|expected| expected.eq_ignore_ascii_case(&b))
+                                .map(|_| matched_length + 1)
+                        });
+                    }
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    let mut finder = OptionFinder {
+        option,
+        matched_length: Some(0),
+        has_option: false,
+    };
+
+    let _ = fmt::write(&mut finder, format_args!("{value}"));
+
+    finder.end_option();
+
+    finder.has_option
+}
+
 impl<W: Write> ResponseWriter for ResponseStream<W> {
     type Error = W::Error;
 
@@ -497,16 +1227,20 @@ impl<W: Write> ResponseWriter for ResponseStream<W> {
         connection: Connection<'_, R>,
         Response {
             status_code,
+            reason_phrase,
             headers,
             body,
         }: Response<H, B>,
     ) -> Result<ResponseSent, Self::Error> {
-        struct HeadersWriter<WW: Write> {
+        struct HeadersWriter<'a, WW: Write> {
             writer: WW,
             connection_header: Option<KeepAlive>,
+            must_close: &'a mut bool,
+            /// Whether the client can tell where the body ends without the connection being closed
+            body_is_delimited: bool,
         }
 
-        impl<WW: Write> ForEachHeader for HeadersWriter<WW> {
+        impl<'a, WW: Write> ForEachHeader for HeadersWriter<'a, WW> {
             type Output = ();
             type Error = WW::Error;
 
@@ -515,13 +1249,33 @@ impl<W: Write> ResponseWriter for ResponseStream<W> {
                 name: &str,
                 value: Value,
             ) -> Result<(), Self::Error> {
+                if name.eq_ignore_ascii_case("content-length")
+                    || name.eq_ignore_ascii_case("transfer-encoding")
+                {
+                    self.body_is_delimited = true;
+                }
+
                 if name.eq_ignore_ascii_case("connection") {
                     self.connection_header = None;
+
+                    // If the handler closes the connection, the server must not read further requests
+                    if header_value_has_option(&value, "close") {
+                        *self.must_close = true;
+                    }
                 }
                 write!(self.writer, "{name}: {value}\r\n").await
             }
 
             async fn finalize(mut self) -> Result<(), Self::Error> {
+                // Without a "Content-Length" or "Transfer-Encoding" header, the end of the body is marked by closing the connection
+                if !self.body_is_delimited {
+                    *self.must_close = true;
+
+                    if self.connection_header.is_some() {
+                        self.connection_header = Some(KeepAlive::Close);
+                    }
+                }
+
                 if let Some(connection_header) = self.connection_header {
                     self.call("Connection", connection_header).await?;
                 }
@@ -531,12 +1285,25 @@ impl<W: Write> ResponseWriter for ResponseStream<W> {
         }
 
         use crate::io::WriteExt;
-        write!(self.writer, "HTTP/1.1 {status_code}\r\n").await?;
+        write!(
+            self.writer,
+            "HTTP/1.1 {status_code} {}\r\n",
+            reason_phrase
+                .or(status_code.canonical_reason())
+                .unwrap_or_default()
+        )
+        .await?;
 
         headers
             .for_each_header(HeadersWriter {
                 writer: &mut self.writer,
-                connection_header: Some(self.connection_header),
+                connection_header: Some(if *connection.must_close {
+                    KeepAlive::Close
+                } else {
+                    self.connection_header
+                }),
+                must_close: connection.must_close,
+                body_is_delimited: !status_code.allows_body(),
             })
             .await?;
 
@@ -544,14 +1311,30 @@ impl<W: Write> ResponseWriter for ResponseStream<W> {
         self.writer.flush().await?;
 
         body.write_response_body(connection, &mut self.writer)
-            .await
-            .map(super::ResponseSent)
+            .await?;
+
+        // Flush the body, so that the client receives the entire response before the next request is read
+        self.writer.flush().await?;
+
+        Ok(super::ResponseSent(()))
     }
 }
 
 /// Trait for generating responses.
 ///
 /// Types that implement IntoResponse can be returned from handlers.
+///
+/// As well as [Content], such as `&str`, and [Response], tuples of an optional [StatusCode], followed by up to 16 [HeadersIter]s,
+/// followed by either [Content] or a [RawBody], implement IntoResponse. If the status code is omitted, it is "200 OK".
+///
+/// ```
+/// use picoserve::{response::StatusCode, routing::post};
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/x",
+///     post(|| async { (StatusCode::CREATED, ("Location", "/x"), "done") }),
+/// );
+/// ```
 pub trait IntoResponse: Sized {
     /// Write the generated response into the given [ResponseWriter].
     async fn write_to<R: Read, W: ResponseWriter<Error = R::Error>>(
@@ -632,6 +1415,33 @@ macro_rules! declare_tuple_into_response {
                 }
             }
 
+            impl<$($name: HeadersIter,)* B: Body> IntoResponse for (StatusCode, $($name,)* RawBody<B>,) {
+                #[allow(non_snake_case)]
+                async fn write_to<R: Read, W: ResponseWriter<Error = R::Error>>(self, connection: Connection<'_, R>, response_writer: W) -> Result<ResponseSent, W::Error> {
+                    let (status_code, $($name,)* RawBody(body),) = self;
+
+                    response_writer.write_response(
+                        connection,
+                        Response {
+                            status_code,
+                            reason_phrase: None,
+                            headers: [("", ""); 0],
+                            body,
+                        }
+                        $(.with_headers($name,))*
+                    ).await
+                }
+            }
+
+            impl<$($name: HeadersIter,)* B: Body> IntoResponse for ($($name,)* RawBody<B>,) {
+                #[allow(non_snake_case)]
+                async fn write_to<R: Read, W: ResponseWriter<Error = R::Error>>(self, connection: Connection<'_, R>, response_writer: W) -> Result<ResponseSent, W::Error> {
+                    let ($($name,)* body,) = self;
+
+                    (StatusCode::OK, $($name,)* body,).write_to(connection, response_writer).await
+                }
+            }
+
             impl<$($name: HeadersIter,)* C: Content> IntoResponse for ($($name,)* C,) {
                 #[allow(non_snake_case)]
                 async fn write_to<R: Read, W: ResponseWriter<Error = R::Error>>(self, connection: Connection<'_, R>, response_writer: W) -> Result<ResponseSent, W::Error> {
@@ -669,16 +1479,51 @@ declare_tuple_into_response!(
 );
 
 /// Returns a value in [core::fmt::Debug] form as text.
+///
+/// The value is written in compact form with a "Content-Type" of "text/plain; charset=utf-8".
+/// Use [pretty](Self::pretty) and [with_content_type](Self::with_content_type) to change the formatting and the content type.
+///
+/// ```
+/// use picoserve::{response::DebugValue, routing::get};
+///
+/// #[derive(Debug)]
+/// struct Status {
+///     uptime: u32,
+///     connections: u8,
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new()
+///     .route(
+///         "/status",
+///         get(|| async { DebugValue(Status { uptime: 42, connections: 1 }) }),
+///     )
+///     .route(
+///         "/status/pretty",
+///         get(|| async { DebugValue(Status { uptime: 42, connections: 1 }).pretty() }),
+///     );
+/// ```
 pub struct DebugValue<D>(pub D);
 
+impl<D: fmt::Debug> DebugValue<D> {
+    /// Write the value using the "alternate" form, i.e. `{:#?}`, which places each field on its own line.
+    pub fn pretty(self) -> DebugContent<D> {
+        DebugContent::new(self.0).pretty()
+    }
+
+    /// Send the value with the given "Content-Type".
+    pub fn with_content_type(self, content_type: &'static str) -> DebugContent<D> {
+        DebugContent::new(self.0).with_content_type(content_type)
+    }
+}
+
 impl<D: fmt::Debug> IntoResponse for DebugValue<D> {
     async fn write_to<R: Read, W: ResponseWriter<Error = R::Error>>(
         self,
         connection: Connection<'_, R>,
         response_writer: W,
     ) -> Result<ResponseSent, W::Error> {
-        response_writer
-            .write_response(connection, Response::ok(format_args!("{:?}\r\n", self.0)))
+        DebugContent::new(self.0)
+            .write_to(connection, response_writer)
             .await
     }
 }
@@ -692,23 +1537,162 @@ impl<D: fmt::Debug> core::future::IntoFuture for DebugValue<D> {
     }
 }
 
+/// [Content] which writes a value in [core::fmt::Debug] form, created by [DebugValue::pretty] or [DebugValue::with_content_type].
+///
+/// The value is formatted twice, once to calculate the "Content-Length" and once while writing the body,
+/// so its [Debug](core::fmt::Debug) implementation must produce the same output each time.
+#[derive(Debug, Clone, Copy)]
+pub struct DebugContent<D> {
+    value: D,
+    content_type: &'static str,
+    pretty: bool,
+}
+
+impl<D: fmt::Debug> DebugContent<D> {
+    const fn new(value: D) -> Self {
+        Self {
+            value,
+            content_type: "text/plain; charset=utf-8",
+            pretty: false,
+        }
+    }
+
+    /// Write the value using the "alternate" form, i.e. `{:#?}`, which places each field on its own line.
+    pub fn pretty(self) -> Self {
+        Self {
+            pretty: true,
+            ..self
+        }
+    }
+
+    /// Write the value in compact form, i.e. `{:?}`.
+    pub fn compact(self) -> Self {
+        Self {
+            pretty: false,
+            ..self
+        }
+    }
+
+    /// Send the value with the given "Content-Type".
+    pub fn with_content_type(self, content_type: &'static str) -> Self {
+        Self {
+            content_type,
+            ..self
+        }
+    }
+}
+
+impl<D: fmt::Debug> fmt::Display for DebugContent<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pretty {
+            write!(f, "{:#?}\r\n", self.value)
+        } else {
+            write!(f, "{:?}\r\n", self.value)
+        }
+    }
+}
+
+impl<D: fmt::Debug> Content for DebugContent<D> {
+    fn content_type(&self) -> &'static str {
+        self.content_type
+    }
+
+    fn content_length(&self) -> usize {
+        format_args!("{self}").content_length()
+    }
+
+    async fn write_content<W: Write>(self, writer: W) -> Result<(), W::Error> {
+        format_args!("{self}").write_content(writer).await
+    }
+}
+
+/// An absolute URL, written as `{scheme}://{host}{path}`, for use as the location of a [Redirect].
+///
+/// `path` should start with a `/`, and is written verbatim, so must already be percent-encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbsoluteUrl<S: fmt::Display, H: fmt::Display, P: fmt::Display> {
+    pub scheme: S,
+    pub host: H,
+    pub path: P,
+}
+
+impl<S: fmt::Display, H: fmt::Display, P: fmt::Display> fmt::Display for AbsoluteUrl<S, H, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { scheme, host, path } = self;
+
+        write!(f, "{scheme}://{host}{path}")
+    }
+}
+
 /// Response that redirects the request to another location.
-pub struct Redirect {
+///
+/// The location is written verbatim into the "Location" header, so may be relative to the request path or an absolute URL.
+pub struct Redirect<L: fmt::Display = &'static str> {
     status_code: StatusCode,
-    location: &'static str,
+    location: L,
 }
 
-impl Redirect {
+impl<L: fmt::Display> Redirect<L> {
     /// Create a new [Redirect] that uses a 303 "See Other" status code.
-    pub fn to(location: &'static str) -> Self {
+    ///
+    /// Equivalent to [Redirect::see_other].
+    pub fn to(location: L) -> Self {
+        Self::see_other(location)
+    }
+
+    /// Create a new [Redirect] that uses a 303 "See Other" status code.
+    ///
+    /// The client follows the redirect with a `GET` request, whatever the original method was,
+    /// so this is the status to use for the Post/Redirect/Get pattern after handling a form submission.
+    pub fn see_other(location: L) -> Self {
         Self {
             status_code: StatusCode::SEE_OTHER,
             location,
         }
     }
+
+    /// Create a new [Redirect] that uses a 302 "Found" status code.
+    ///
+    /// For historical reasons, clients may change the method of a `POST` request to `GET` when following the redirect.
+    /// Prefer [Redirect::temporary] or [Redirect::see_other], which make the intent explicit.
+    pub fn found(location: L) -> Self {
+        Self {
+            status_code: StatusCode::FOUND,
+            location,
+        }
+    }
+
+    /// Create a new [Redirect] that uses a 307 "Temporary Redirect" status code.
+    ///
+    /// The client follows the redirect with the same method and body as the original request, and should keep using the original location for future requests.
+    pub fn temporary(location: L) -> Self {
+        Self {
+            status_code: StatusCode::TEMPORARY_REDIRECT,
+            location,
+        }
+    }
+
+    /// Create a new [Redirect] that uses a 308 "Permanent Redirect" status code.
+    ///
+    /// The client follows the redirect with the same method and body as the original request, and may use the new location for future requests.
+    pub fn permanent(location: L) -> Self {
+        Self {
+            status_code: StatusCode::PERMANENT_REDIRECT,
+            location,
+        }
+    }
+}
+
+impl<S: fmt::Display, H: fmt::Display, P: fmt::Display> Redirect<AbsoluteUrl<S, H, P>> {
+    /// Create a new [Redirect] to an [AbsoluteUrl] that uses a 303 "See Other" status code, such as for redirecting to another host.
+    ///
+    /// `host` may be taken from the "Host" header of the request, so that the redirect is correct when the server is behind a proxy.
+    pub fn to_absolute(scheme: S, host: H, path: P) -> Self {
+        Self::to(AbsoluteUrl { scheme, host, path })
+    }
 }
 
-impl IntoResponse for Redirect {
+impl<L: fmt::Display> IntoResponse for Redirect<L> {
     async fn write_to<R: Read, W: ResponseWriter<Error = R::Error>>(
         self,
         connection: Connection<'_, R>,
@@ -716,7 +1700,7 @@ impl IntoResponse for Redirect {
     ) -> Result<ResponseSent, W::Error> {
         (
             self.status_code,
-            ("Location", self.location),
+            ("Location", &self.location),
             format_args!("{}\n", self.location),
         )
             .write_to(connection, response_writer)
@@ -724,7 +1708,7 @@ impl IntoResponse for Redirect {
     }
 }
 
-impl core::future::IntoFuture for Redirect {
+impl<L: fmt::Display> core::future::IntoFuture for Redirect<L> {
     type Output = Self;
     type IntoFuture = core::future::Ready<Self>;
 
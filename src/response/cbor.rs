@@ -0,0 +1,139 @@
+//! Support for serializing CBOR structures
+
+use crate::io::{FormatBuffer, FormatBufferWriteError, Write};
+
+pub use crate::cbor::Cbor;
+
+struct BufferIsFull;
+
+struct FormatBufferWriter<'a>(&'a mut FormatBuffer);
+
+impl<'a> minicbor::encode::Write for FormatBufferWriter<'a> {
+    type Error = BufferIsFull;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0.write_bytes(buf).map_err(|()| BufferIsFull)
+    }
+}
+
+struct MeasureSize<'a>(&'a mut usize);
+
+impl<'a> minicbor::encode::Write for MeasureSize<'a> {
+    type Error = core::convert::Infallible;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        *self.0 += buf.len();
+
+        Ok(())
+    }
+}
+
+fn serialize_into_buffer<T: minicbor::Encode<()>>(
+    value: &T,
+    buffer: &mut FormatBuffer,
+) -> Result<(), minicbor::encode::Error<BufferIsFull>> {
+    minicbor::encode(value, FormatBufferWriter(buffer))
+}
+
+enum CborStream<T> {
+    Short { buffer: FormatBuffer },
+    Long { buffer: FormatBuffer, value: T },
+}
+
+impl<T: minicbor::Encode<()>> CborStream<T> {
+    fn new(value: T) -> Self {
+        let mut buffer = FormatBuffer::new(0);
+        match serialize_into_buffer(&value, &mut buffer) {
+            Ok(()) => CborStream::Short { buffer },
+            Err(_) => match buffer.error_state {
+                FormatBufferWriteError::FormatError => CborStream::Long {
+                    buffer: FormatBuffer::new(0),
+                    value,
+                },
+                FormatBufferWriteError::OutOfSpace(()) => CborStream::Long { buffer, value },
+            },
+        }
+    }
+
+    async fn write_cbor_value<W: Write>(self, mut writer: W) -> Result<(), W::Error> {
+        match self {
+            CborStream::Short { buffer } => writer.write_all(&buffer.data).await,
+            CborStream::Long { mut buffer, value } => {
+                writer.write_all(&buffer.data).await?;
+
+                let mut ignore_count = buffer.data.len();
+
+                loop {
+                    buffer.data.clear();
+                    buffer.ignore_count = ignore_count;
+                    buffer.error_state = FormatBufferWriteError::FormatError;
+
+                    match serialize_into_buffer(&value, &mut buffer) {
+                        Ok(()) => return writer.write_all(&buffer.data).await,
+                        Err(_) => match buffer.error_state {
+                            FormatBufferWriteError::FormatError => {
+                                log_warn!("Failed to serialize CBOR");
+                                return Ok(());
+                            }
+                            FormatBufferWriteError::OutOfSpace(()) => {
+                                writer.write_all(&buffer.data).await?;
+                                ignore_count += buffer.data.len();
+                            }
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct CborBody<T>(CborStream<T>);
+
+impl<T: minicbor::Encode<()>> super::Content for CborBody<T> {
+    fn content_type(&self) -> &'static str {
+        "application/cbor"
+    }
+
+    fn content_length(&self) -> usize {
+        match &self.0 {
+            CborStream::Short { buffer } => buffer.data.len(),
+            CborStream::Long { buffer: _, value } => {
+                let mut content_length = 0;
+                minicbor::encode(value, MeasureSize(&mut content_length))
+                    .map_or(0, |()| content_length)
+            }
+        }
+    }
+
+    async fn write_content<W: Write>(self, writer: W) -> Result<(), W::Error> {
+        self.0.write_cbor_value(writer).await
+    }
+}
+
+impl<T: minicbor::Encode<()>> Cbor<T> {
+    /// Convert CBOR payload into a [super::Response] with a status code of "OK"
+    pub fn into_response(self) -> super::Response<impl super::HeadersIter, impl super::Body> {
+        super::Response::ok(CborBody(CborStream::new(self.0)))
+    }
+}
+
+impl<T: minicbor::Encode<()>> super::IntoResponse for Cbor<T> {
+    async fn write_to<R: embedded_io_async::Read, W: super::ResponseWriter<Error = R::Error>>(
+        self,
+        connection: super::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<crate::ResponseSent, W::Error> {
+        response_writer
+            .write_response(connection, self.into_response())
+            .await
+    }
+}
+
+impl<T: minicbor::Encode<()>> core::future::IntoFuture for Cbor<T> {
+    type Output = Self;
+    type IntoFuture = core::future::Ready<Self>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        core::future::ready(self)
+    }
+}
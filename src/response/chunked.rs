@@ -3,9 +3,12 @@
 /// A marker showing that all of the chunks have been written.
 pub struct ChunksWritten(());
 
-/// Writing chunks to a [ChunkWriter] will send them to the client and flush the stream
+/// Writes chunks to the client.
+///
+/// Chunks may be held back by the socket until more data has been written, so call [ChunkWriter::flush] after writing a chunk
+/// which should be sent immediately, such as if the next chunk will not be ready for a while. [ChunkWriter::finalize] flushes the stream.
 pub struct ChunkWriter<W: crate::io::Write> {
-    writer: W,
+    pub(crate) writer: W,
 }
 
 impl<W: crate::io::Write> ChunkWriter<W> {
@@ -58,7 +61,7 @@ impl<W: crate::io::Write> ChunkWriter<W> {
         Ok(())
     }
 
-    /// Flush the underlying connection.
+    /// Flush the underlying connection, sending all chunks written so far to the client.
     pub async fn flush(&mut self) -> Result<(), W::Error> {
         self.writer.flush().await
     }
@@ -113,6 +116,7 @@ impl<C: Chunks> ChunkedResponse<C> {
 
         super::Response {
             status_code: super::StatusCode::OK,
+            reason_phrase: None,
             headers: [
                 ("Content-Type", content_type),
                 ("Transfer-Encoding", "chunked"),
@@ -133,3 +137,109 @@ impl<C: Chunks> super::IntoResponse for ChunkedResponse<C> {
             .await
     }
 }
+
+/// A chunked response whose chunks are produced by an async closure, for responses which don't need a dedicated [Chunks] type.
+///
+/// The closure is called with the current state, and returns either the next chunk and the next state, or `None` once all chunks have been produced,
+/// at which point the terminating chunk is written. Each chunk is written using its [Display](core::fmt::Display) implementation and the stream is then flushed.
+/// If writing a chunk fails, no further chunks are produced and the error is returned.
+///
+/// As the state is passed by value, the closure doesn't borrow the writer, so it works with closures returning `async move` blocks.
+///
+/// ```
+/// use picoserve::{response::Streaming, routing::get};
+///
+/// struct Line(u32);
+///
+/// impl core::fmt::Display for Line {
+///     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+///         writeln!(f, "Line {}", self.0)
+///     }
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/lines",
+///     get(|| async {
+///         Streaming::new("text/plain", 0, |n| async move {
+///             (n < 10).then(|| (Line(n), n + 1))
+///         })
+///     }),
+/// );
+/// ```
+pub struct Streaming<S, F> {
+    content_type: &'static str,
+    state: S,
+    next_chunk: F,
+}
+
+impl<
+        S,
+        T: core::fmt::Display,
+        Fut: core::future::Future<Output = Option<(T, S)>>,
+        F: FnMut(S) -> Fut,
+    > Streaming<S, F>
+{
+    /// Create a response with the given Content Type, which writes chunks produced by `next_chunk`, starting with `initial_state`.
+    pub fn new(content_type: &'static str, initial_state: S, next_chunk: F) -> Self {
+        Self {
+            content_type,
+            state: initial_state,
+            next_chunk,
+        }
+    }
+
+    /// Convert the response into a [Response](super::Response), which can then have its status code changed or headers added.
+    pub fn into_response(self) -> super::Response<impl super::HeadersIter, impl super::Body> {
+        ChunkedResponse::new(self).into_response()
+    }
+}
+
+impl<
+        S,
+        T: core::fmt::Display,
+        Fut: core::future::Future<Output = Option<(T, S)>>,
+        F: FnMut(S) -> Fut,
+    > Chunks for Streaming<S, F>
+{
+    fn content_type(&self) -> &'static str {
+        self.content_type
+    }
+
+    async fn write_chunks<W: crate::io::Write>(
+        self,
+        mut chunk_writer: ChunkWriter<W>,
+    ) -> Result<ChunksWritten, W::Error> {
+        let Self {
+            content_type: _,
+            mut state,
+            mut next_chunk,
+        } = self;
+
+        while let Some((chunk, next_state)) = next_chunk(state).await {
+            write!(chunk_writer, "{chunk}").await?;
+            chunk_writer.flush().await?;
+
+            state = next_state;
+        }
+
+        chunk_writer.finalize().await
+    }
+}
+
+impl<
+        S,
+        T: core::fmt::Display,
+        Fut: core::future::Future<Output = Option<(T, S)>>,
+        F: FnMut(S) -> Fut,
+    > super::IntoResponse for Streaming<S, F>
+{
+    async fn write_to<R: embedded_io_async::Read, W: super::ResponseWriter<Error = R::Error>>(
+        self,
+        connection: super::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<crate::ResponseSent, W::Error> {
+        response_writer
+            .write_response(connection, self.into_response())
+            .await
+    }
+}
@@ -87,6 +87,7 @@ impl<H: HeadersIter, B: CustomBody> super::IntoResponse for CustomResponse<H, B>
                 connection,
                 super::Response {
                     status_code,
+                    reason_phrase: None,
                     headers,
                     body: Body { body },
                 },
@@ -86,15 +86,171 @@ impl super::HeadersIter for ETag {
     }
 }
 
+/// A HTTP date in the IMF-fixdate format, e.g. "Sun, 06 Nov 1994 08:49:37 GMT".
+///
+/// Fields are declared from most to least significant, so the derived ordering is chronological.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct HttpDate {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl HttpDate {
+    fn parse(date: &[u8]) -> Option<Self> {
+        const MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+
+        let (_day_name, date) = core::str::from_utf8(date).ok()?.trim().split_once(", ")?;
+
+        let mut parts = date.split(' ');
+
+        let day = parts.next()?.parse().ok()?;
+        let month_name = parts.next()?;
+        let month = MONTHS.iter().position(|&month| month == month_name)? as u8 + 1;
+        let year = parts.next()?.parse().ok()?;
+
+        let mut time = parts.next()?.split(':');
+        let hour = time.next()?.parse().ok()?;
+        let minute = time.next()?.parse().ok()?;
+        let second = time.next()?.parse().ok()?;
+
+        (time.next().is_none() && parts.next()? == "GMT" && parts.next().is_none()).then_some(
+            Self {
+                year,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+            },
+        )
+    }
+}
+
+/// The part of a [File] requested using a "Range" header.
+#[derive(Debug, PartialEq, Eq)]
+enum RequestedRange {
+    /// The entire file, as the request has no supported "Range" header, or it is not applicable.
+    Full,
+    /// A single range of bytes, from `start` to `end` inclusive.
+    Partial { start: usize, end: usize },
+    /// The requested range does not overlap with the file.
+    NotSatisfiable,
+}
+
+impl RequestedRange {
+    /// Parse the value of a "Range" header for a file of `length` bytes.
+    ///
+    /// Only a single byte range is supported, so requests for multiple ranges, as well as invalid values, result in the entire file.
+    fn parse(range: &[u8], length: usize) -> Self {
+        let Some((unit, range)) = core::str::from_utf8(range)
+            .ok()
+            .and_then(|range| range.trim().split_once('='))
+        else {
+            return Self::Full;
+        };
+
+        if !unit.trim().eq_ignore_ascii_case("bytes") || range.contains(',') {
+            return Self::Full;
+        }
+
+        let Some((start, end)) = range.trim().split_once('-') else {
+            return Self::Full;
+        };
+
+        if start.is_empty() {
+            let Ok(suffix_length) = end.parse::<usize>() else {
+                return Self::Full;
+            };
+
+            return if suffix_length == 0 || length == 0 {
+                Self::NotSatisfiable
+            } else {
+                Self::Partial {
+                    start: length.saturating_sub(suffix_length),
+                    end: length - 1,
+                }
+            };
+        }
+
+        let Ok(start) = start.parse::<usize>() else {
+            return Self::Full;
+        };
+
+        let end = if end.is_empty() {
+            usize::MAX
+        } else {
+            match end.parse::<usize>() {
+                Ok(end) if end >= start => end,
+                _ => return Self::Full,
+            }
+        };
+
+        if start >= length {
+            Self::NotSatisfiable
+        } else {
+            Self::Partial {
+                start,
+                end: end.min(length - 1),
+            }
+        }
+    }
+}
+
+/// The value of a "Content-Range" header.
+struct ContentRange {
+    range: Option<(usize, usize)>,
+    length: usize,
+}
+
+impl fmt::Display for ContentRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { range, length } = self;
+
+        match range {
+            Some((start, end)) => write!(f, "bytes {start}-{end}/{length}"),
+            None => write!(f, "bytes */{length}"),
+        }
+    }
+}
+
 /// [RequestHandlerService] that serves a single file.
+///
+/// `GET` requests with a "Range" header for a single range of bytes are sent just that range with a status of "Partial Content".
+/// If the request also has an "If-Range" header which does not match the "ETag" or "Last-Modified" header of the file,
+/// the entire file is sent instead, so that the client does not combine parts of different versions of the file.
+///
+/// A gzip-compressed copy of the file can be added using [with_gzip](Self::with_gzip), which is sent to clients which accept it.
 #[derive(Debug, Clone)]
 pub struct File {
     content_type: &'static str,
     body: &'static [u8],
     etag: ETag,
+    last_modified: Option<&'static str>,
+    attachment: Option<&'static str>,
+    gzip: Option<GzipBody>,
     headers: &'static [(&'static str, &'static str)],
 }
 
+/// The gzip-compressed copy of a [File], which has its own "ETag" as it is a different representation of the file.
+#[derive(Debug, Clone)]
+struct GzipBody {
+    body: &'static [u8],
+    etag: ETag,
+}
+
+/// The representation of a [File] which is sent in response to a request.
+struct Representation<'a> {
+    body: &'static [u8],
+    etag: &'a ETag,
+    content_encoding: Option<&'static str>,
+}
+
 impl File {
     /// Create a file with the given content type but no additional headers.
     pub const fn with_content_type(content_type: &'static str, body: &'static [u8]) -> Self {
@@ -102,6 +258,9 @@ impl File {
             content_type,
             body,
             etag: ETag(const_sha1::sha1(body).as_bytes()),
+            last_modified: None,
+            attachment: None,
+            gzip: None,
             headers: &[],
         }
     }
@@ -116,10 +275,155 @@ impl File {
             content_type,
             body,
             etag: ETag(const_sha1::sha1(body).as_bytes()),
+            last_modified: None,
+            attachment: None,
+            gzip: None,
             headers,
         }
     }
 
+    /// Return a file which is sent with a "Last-Modified" header, and which responds with "Not Modified" to requests
+    /// with an "If-Modified-Since" header which is not earlier than `last_modified`, if the request has no "If-None-Match" header.
+    ///
+    /// As the server might not have access to the current time, the timestamp must be provided, for example as the build time,
+    /// and should be in the IMF-fixdate format, e.g. "Sun, 06 Nov 1994 08:49:37 GMT".
+    pub const fn with_last_modified(self, last_modified: &'static str) -> Self {
+        Self {
+            last_modified: Some(last_modified),
+            ..self
+        }
+    }
+
+    /// Return a file which is sent with a "Content-Disposition" header, so that it is downloaded as a file named `filename`. See [Attachment](super::Attachment).
+    pub const fn as_attachment(self, filename: &'static str) -> Self {
+        Self {
+            attachment: Some(filename),
+            ..self
+        }
+    }
+
+    /// Return a file which is sent as `gzip_body`, with a "Content-Encoding" of "gzip", to clients which accept gzip-compressed responses.
+    /// `gzip_body` must be the gzip-compressed file, for example compressed at build time. Other clients are sent the uncompressed file.
+    ///
+    /// Responses include a "Vary: Accept-Encoding" header, so that caches store each representation separately.
+    pub const fn with_gzip(self, gzip_body: &'static [u8]) -> Self {
+        Self {
+            gzip: Some(GzipBody {
+                body: gzip_body,
+                etag: ETag(const_sha1::sha1(gzip_body).as_bytes()),
+            }),
+            ..self
+        }
+    }
+
+    /// Returns true if the "Accept-Encoding" header lists "gzip", or "*" if "gzip" is not listed, without a quality of zero.
+    fn accepts_gzip(request_parts: &crate::request::RequestParts) -> bool {
+        let mut accepts_any = false;
+
+        for encoding in request_parts.headers().get_list("Accept-Encoding") {
+            let (coding, parameters) = encoding.split_once(';').unwrap_or((encoding, ""));
+
+            let is_accepted = !parameters.split(';').any(|parameter| {
+                parameter.split_once('=').is_some_and(|(name, value)| {
+                    name.trim().eq_ignore_ascii_case("q")
+                        && value.trim().parse::<f32>().is_ok_and(|q| q <= 0.0)
+                })
+            });
+
+            match coding.trim() {
+                "*" => accepts_any = is_accepted,
+                coding if coding.eq_ignore_ascii_case("gzip") => return is_accepted,
+                _ => (),
+            }
+        }
+
+        accepts_any
+    }
+
+    fn representation(&self, request_parts: &crate::request::RequestParts) -> Representation<'_> {
+        match &self.gzip {
+            Some(gzip) if Self::accepts_gzip(request_parts) => Representation {
+                body: gzip.body,
+                etag: &gzip.etag,
+                content_encoding: Some("gzip"),
+            },
+            _ => Representation {
+                body: self.body,
+                etag: &self.etag,
+                content_encoding: None,
+            },
+        }
+    }
+
+    fn is_not_modified(&self, etag: &ETag, request_parts: &crate::request::RequestParts) -> bool {
+        if request_parts.headers().get("If-None-Match").is_some() {
+            return request_parts
+                .headers()
+                .get_list("If-None-Match")
+                .any(|if_none_match| *etag == if_none_match.as_bytes());
+        }
+
+        if !(request_parts.method().eq_ignore_ascii_case("get")
+            || request_parts.method().eq_ignore_ascii_case("head"))
+        {
+            return false;
+        }
+
+        let Some(if_modified_since) = request_parts.headers().get("If-Modified-Since") else {
+            return false;
+        };
+
+        let Some(last_modified) = self.last_modified else {
+            return false;
+        };
+
+        match (
+            HttpDate::parse(if_modified_since.as_raw()),
+            HttpDate::parse(last_modified.as_bytes()),
+        ) {
+            (Some(if_modified_since), Some(last_modified)) => last_modified <= if_modified_since,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the "If-Range" header matches the current version of the file.
+    ///
+    /// An entity tag must match the "ETag" of the file, and a date must exactly match the "Last-Modified" header of the file.
+    fn if_range_matches(&self, etag: &ETag, if_range: &[u8]) -> bool {
+        if if_range.starts_with(b"\"") {
+            return *etag == if_range;
+        }
+
+        match (self.last_modified, HttpDate::parse(if_range)) {
+            (Some(last_modified), Some(if_range)) => {
+                HttpDate::parse(last_modified.as_bytes()) == Some(if_range)
+            }
+            _ => false,
+        }
+    }
+
+    fn requested_range(
+        &self,
+        representation: &Representation,
+        request_parts: &crate::request::RequestParts,
+    ) -> RequestedRange {
+        if !request_parts.method().eq_ignore_ascii_case("get") {
+            return RequestedRange::Full;
+        }
+
+        let Some(range) = request_parts.headers().get("Range") else {
+            return RequestedRange::Full;
+        };
+
+        if let Some(if_range) = request_parts.headers().get("If-Range") {
+            if !self.if_range_matches(representation.etag, if_range.as_raw()) {
+                return RequestedRange::Full;
+            }
+        }
+
+        RequestedRange::parse(range.as_raw(), representation.body.len())
+    }
+
     /// A HyperText Markup Language file with a MIME type of "text/html; charset=utf-8"
     pub const fn html(body: &'static str) -> Self {
         Self::with_content_type("text/html; charset=utf-8", body.as_bytes())
@@ -144,49 +448,107 @@ impl<State, PathParameters> crate::routing::RequestHandlerService<State, PathPar
         request: crate::request::Request<'_, R>,
         response_writer: W,
     ) -> Result<ResponseSent, W::Error> {
-        if let Some(if_none_match) = request.parts.headers().get("If-None-Match") {
-            if if_none_match
-                .split(b',')
-                .any(|etag| self.etag == etag.as_raw())
-            {
-                return response_writer
-                    .write_response(
-                        request.body_connection.finalize().await?,
-                        super::Response {
-                            status_code: StatusCode::NOT_MODIFIED,
-                            headers: self.etag.clone(),
-                            body: super::NoBody,
-                        },
-                    )
-                    .await;
-            }
+        let last_modified = self
+            .last_modified
+            .map(|last_modified| ("Last-Modified", last_modified));
+
+        let representation = self.representation(&request.parts);
+
+        let vary = self.gzip.is_some().then_some(("Vary", "Accept-Encoding"));
+
+        if self.is_not_modified(representation.etag, &request.parts) {
+            return response_writer
+                .write_response(
+                    request.body_connection.finalize().await?,
+                    super::Response {
+                        status_code: StatusCode::NOT_MODIFIED,
+                        reason_phrase: None,
+                        headers: representation.etag.clone(),
+                        body: super::NoBody,
+                    }
+                    .with_headers([last_modified, vary]),
+                )
+                .await;
         }
 
-        struct FileContent<'a>(&'a File);
+        struct FileContent {
+            content_type: &'static str,
+            body: &'static [u8],
+        }
 
-        impl<'a> super::Content for FileContent<'a> {
+        impl super::Content for FileContent {
             fn content_type(&self) -> &'static str {
-                self.0.content_type
+                self.content_type
             }
 
             fn content_length(&self) -> usize {
-                self.0.body.len()
+                self.body.len()
             }
 
             async fn write_content<W: Write>(self, mut writer: W) -> Result<(), W::Error> {
-                writer.write_all(self.0.body).await
+                writer.write_all(self.body).await
             }
         }
 
-        super::Response::ok(FileContent(self))
-            .with_headers(self.headers)
-            .with_headers(self.etag.clone())
-            .write_to(request.body_connection.finalize().await?, response_writer)
-            .await
+        let length = representation.body.len();
+
+        let (status_code, body, content_range) =
+            match self.requested_range(&representation, &request.parts) {
+                RequestedRange::Full => (StatusCode::OK, representation.body, None),
+                RequestedRange::Partial { start, end } => (
+                    StatusCode::PARTIAL_CONTENT,
+                    &representation.body[start..=end],
+                    Some(ContentRange {
+                        range: Some((start, end)),
+                        length,
+                    }),
+                ),
+                RequestedRange::NotSatisfiable => {
+                    return super::Response::new(
+                        StatusCode::RANGE_NOT_SATISFIABLE,
+                        "Requested Range Not Satisfiable\r\n",
+                    )
+                    .with_header(
+                        "Content-Range",
+                        ContentRange {
+                            range: None,
+                            length,
+                        },
+                    )
+                    .with_headers(vary)
+                    .write_to(request.body_connection.finalize().await?, response_writer)
+                    .await;
+                }
+            };
+
+        super::Response::new(
+            status_code,
+            FileContent {
+                content_type: self.content_type,
+                body,
+            },
+        )
+        .with_headers(self.headers)
+        .with_headers(representation.etag.clone())
+        .with_headers([
+            last_modified,
+            representation
+                .content_encoding
+                .map(|content_encoding| ("Content-Encoding", content_encoding)),
+            vary,
+        ])
+        .with_headers(self.attachment.map(super::Attachment::new))
+        .with_header("Accept-Ranges", "bytes")
+        .with_headers(content_range.map(|content_range| ("Content-Range", content_range)))
+        .write_to(request.body_connection.finalize().await?, response_writer)
+        .await
     }
 }
 
 /// [PathRouter] that serves a single file based on the request path.
+///
+/// To serve an entire asset bundle at the root of the server, create the router using [Router::from_service](crate::Router::from_service),
+/// and set `index_file` to serve a file such as "index.html" at "/". Routes added to the router are matched before the directory.
 #[derive(Debug, Default)]
 pub struct Directory {
     /// The files in the directory.
@@ -194,15 +556,38 @@ pub struct Directory {
 
     /// Subdirectories inside this directory.
     pub sub_directories: &'static [(&'static str, Directory)],
+
+    /// The name of a file in `files` which is served if the request path is the path of the directory itself, for example "index.html".
+    /// If not set, such requests are treated as not matching any file.
+    pub index_file: Option<&'static str>,
+
+    /// The name of a file in `files` which is served with a status code of "OK" if no file matches the request path,
+    /// for example "index.html" for a Single-Page Application which uses client-side routing.
+    /// If not set, the "Not Found" response is sent.
+    pub not_found_file: Option<&'static str>,
 }
 
 impl Directory {
     pub const DEFAULT: Self = Self {
         files: &[],
         sub_directories: &[],
+        index_file: None,
+        not_found_file: None,
     };
 
+    fn file_named(&self, file_name: Option<&str>) -> Option<&File> {
+        let file_name = file_name?;
+
+        self.files
+            .iter()
+            .find_map(|(name, file)| (*name == file_name).then_some(file))
+    }
+
     fn matching_file(&self, path: crate::request::Path) -> Option<&File> {
+        if let "" | "/" = path.encoded() {
+            return self.file_named(self.index_file);
+        }
+
         for (name, file) in self.files.iter() {
             if let Some(crate::request::Path(crate::url_encoded::UrlEncodedString(""))) =
                 path.strip_slash_and_prefix(name)
@@ -225,6 +610,45 @@ impl Directory {
     }
 }
 
+impl Directory {
+    async fn serve_matching_file<
+        State,
+        CurrentPathParameters,
+        R: Read,
+        W: super::ResponseWriter<Error = R::Error>,
+    >(
+        &self,
+        state: &State,
+        current_path_parameters: CurrentPathParameters,
+        path: Path<'_>,
+        request: crate::request::Request<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        if let Some(file) = self
+            .matching_file(path)
+            .or_else(|| self.file_named(self.not_found_file))
+        {
+            file.call_request_handler_service(
+                state,
+                current_path_parameters,
+                request,
+                response_writer,
+            )
+            .await
+        } else {
+            crate::routing::NotFound
+                .call_path_router(
+                    state,
+                    current_path_parameters,
+                    path,
+                    request,
+                    response_writer,
+                )
+                .await
+        }
+    }
+}
+
 impl<State, CurrentPathParameters> PathRouterService<State, CurrentPathParameters> for Directory {
     async fn call_request_handler_service<R: Read, W: super::ResponseWriter<Error = R::Error>>(
         &self,
@@ -234,21 +658,324 @@ impl<State, CurrentPathParameters> PathRouterService<State, CurrentPathParameter
         request: crate::request::Request<'_, R>,
         response_writer: W,
     ) -> Result<ResponseSent, W::Error> {
-        if !request.parts.method().eq_ignore_ascii_case("get") {
-            return crate::routing::MethodNotAllowed
-                .call_request_handler(state, current_path_parameters, request, response_writer)
-                .await;
-        }
+        let method = request.parts.method();
 
-        if let Some(file) = self.matching_file(path) {
-            file.call_request_handler_service(
+        if !request.parts.allowed_methods().is_empty() {
+            // A route matched the path but not the method, so don't serve a file in its place
+            crate::routing::PathRouter::call_path_router(
+                &crate::routing::NotFound,
                 state,
                 current_path_parameters,
+                path,
                 request,
                 response_writer,
             )
             .await
+        } else if method.eq_ignore_ascii_case("get") {
+            self.serve_matching_file(
+                state,
+                current_path_parameters,
+                path,
+                request,
+                response_writer,
+            )
+            .await
+        } else if method.eq_ignore_ascii_case("head") {
+            // The headers, including "Content-Length", are the same as for GET, but the body is not sent
+            self.serve_matching_file(
+                state,
+                current_path_parameters,
+                path,
+                request,
+                crate::routing::head_method_util::ignore_body(response_writer),
+            )
+            .await
         } else {
+            crate::routing::MethodNotAllowed
+                .call_request_handler(state, current_path_parameters, request, response_writer)
+                .await
+        }
+    }
+}
+
+/// Information about a file in a [FileSource], as returned by [FileSource::open].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileInfo<Handle> {
+    /// Identifies the file when it is read, such as its address in flash memory.
+    pub handle: Handle,
+    /// The length of the file in bytes, which is sent as the "Content-Length".
+    pub length: usize,
+    /// The value of the "Content-Type" header.
+    pub content_type: &'static str,
+}
+
+/// A source of files which are read lazily while the response is sent, such as a filesystem stored in flash memory
+/// using `embedded-storage` or `sequential-storage`, so that assets don't need to be included in the binary as a [Directory].
+///
+/// Files are served using a [FileSystemService].
+pub trait FileSource {
+    /// Identifies an open file.
+    type Handle;
+
+    /// The error returned if the source cannot be read.
+    type Error: fmt::Debug;
+
+    /// Look up the file at `path`, which is the decoded request path relative to the service, without a leading "/", such as "assets/app.js".
+    ///
+    /// [FileSystemService] never passes a path with an empty, "." or ".." segment, even if the segments were percent-encoded in the request,
+    /// but the path is otherwise chosen by the client, so implementors must ensure that only the files which are meant to be served can be opened,
+    /// for example by not following links, and by rejecting paths containing characters other than "/" which are special to the underlying storage, such as "\\".
+    ///
+    /// Returns `None` if there is no such file.
+    async fn open(&self, path: &str) -> Result<Option<FileInfo<Self::Handle>>, Self::Error>;
+
+    /// Read the bytes of the file starting at `offset` into `buffer`, returning the number of bytes read.
+    ///
+    /// `offset` is always less than the length of the file, so returning 0 is treated as an error.
+    async fn read(
+        &self,
+        handle: &Self::Handle,
+        offset: usize,
+        buffer: &mut [u8],
+    ) -> Result<usize, Self::Error>;
+}
+
+/// [PathRouterService] which serves files from a [FileSource], reading each file in chunks of `CHUNK_SIZE` bytes while the response is sent,
+/// so that files do not need to fit in memory.
+///
+/// The request path is decoded into a buffer of `MAX_PATH_LENGTH` bytes. Requests with longer paths are treated as not matching any file,
+/// as are paths with an empty, "." or ".." segment once decoded, such as "/assets/%2e%2e/secret", so that the path passed to [FileSource::open] can't escape the source.
+///
+/// Unlike [File], "ETag" and "Range" headers are not supported, as the content of the file is not known before it is sent.
+///
+/// If the source fails to open the file, the response is "500 Internal Server Error".
+/// If reading fails once the response headers have been sent, the error is logged and the body is cut short,
+/// and the connection is closed once the response has been sent, so that the client can tell that the body is incomplete.
+///
+/// ```
+/// use picoserve::response::fs::{FileInfo, FileSource, FileSystemService};
+///
+/// /// Files stored one after another in a single block of memory, such as a flash partition.
+/// struct Flash {
+///     data: &'static [u8],
+///     files: &'static [(&'static str, &'static str, core::ops::Range<usize>)],
+/// }
+///
+/// impl FileSource for Flash {
+///     type Handle = core::ops::Range<usize>;
+///     type Error = core::convert::Infallible;
+///
+///     async fn open(&self, path: &str) -> Result<Option<FileInfo<Self::Handle>>, Self::Error> {
+///         Ok(self.files.iter().find(|(name, ..)| *name == path).map(
+///             |(_, content_type, range)| FileInfo {
+///                 handle: range.clone(),
+///                 length: range.len(),
+///                 content_type,
+///             },
+///         ))
+///     }
+///
+///     async fn read(
+///         &self,
+///         handle: &Self::Handle,
+///         offset: usize,
+///         buffer: &mut [u8],
+///     ) -> Result<usize, Self::Error> {
+///         let data = &self.data[(handle.start + offset)..handle.end];
+///         let length = data.len().min(buffer.len());
+///         buffer[..length].copy_from_slice(&data[..length]);
+///         Ok(length)
+///     }
+/// }
+///
+/// let flash = Flash {
+///     data: b"<h1>Hello World</h1>h1 { color: red; }",
+///     files: &[
+///         ("index.html", "text/html; charset=utf-8", 0..20),
+///         ("style.css", "text/css", 20..38),
+///     ],
+/// };
+///
+/// let app: picoserve::Router<_> = picoserve::Router::from_service(
+///     FileSystemService::new(flash).with_index_file("index.html"),
+/// );
+/// ```
+pub struct FileSystemService<S, const MAX_PATH_LENGTH: usize = 64, const CHUNK_SIZE: usize = 256> {
+    /// The source of the files.
+    pub source: S,
+
+    /// The path of a file which is served if the request path is the path of the service itself, for example "index.html".
+    /// If not set, such requests are treated as not matching any file.
+    pub index_file: Option<&'static str>,
+}
+
+impl<S: FileSource> FileSystemService<S> {
+    /// Serve files from `source`, with no index file.
+    pub const fn new(source: S) -> Self {
+        Self {
+            source,
+            index_file: None,
+        }
+    }
+}
+
+impl<S: FileSource, const MAX_PATH_LENGTH: usize, const CHUNK_SIZE: usize>
+    FileSystemService<S, MAX_PATH_LENGTH, CHUNK_SIZE>
+{
+    /// Serve `index_file` if the request path is the path of the service itself.
+    pub fn with_index_file(self, index_file: &'static str) -> Self {
+        Self {
+            index_file: Some(index_file),
+            ..self
+        }
+    }
+
+    async fn serve_matching_file<
+        State,
+        CurrentPathParameters,
+        R: Read,
+        W: super::ResponseWriter<Error = R::Error>,
+    >(
+        &self,
+        state: &State,
+        current_path_parameters: CurrentPathParameters,
+        path: Path<'_>,
+        request: crate::request::Request<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        let file_path = match path.encoded().trim_start_matches('/') {
+            "" => self.index_file.and_then(|index_file| {
+                heapless::String::<MAX_PATH_LENGTH>::try_from(index_file).ok()
+            }),
+            file_path => crate::url_encoded::UrlEncodedString(file_path)
+                .try_into_string::<MAX_PATH_LENGTH>()
+                .ok()
+                // Segments are checked once decoded, as "%2e%2e%2f" decodes to "../"
+                .filter(|file_path| {
+                    !file_path
+                        .split('/')
+                        .any(|segment| matches!(segment, "" | "." | ".."))
+                }),
+        };
+
+        let file_info = match file_path {
+            Some(file_path) => match self.source.open(&file_path).await {
+                Ok(file_info) => file_info,
+                Err(err) => {
+                    log_error!(
+                        "Failed to open {}: {}",
+                        file_path.as_str(),
+                        crate::logging::Debug2Format(&err)
+                    );
+
+                    return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to read file\r\n")
+                        .write_to(request.body_connection.finalize().await?, response_writer)
+                        .await;
+                }
+            },
+            None => None,
+        };
+
+        let Some(file_info) = file_info else {
+            return crate::routing::NotFound
+                .call_path_router(
+                    state,
+                    current_path_parameters,
+                    path,
+                    request,
+                    response_writer,
+                )
+                .await;
+        };
+
+        response_writer
+            .write_response(
+                request.body_connection.finalize().await?,
+                super::Response {
+                    status_code: StatusCode::OK,
+                    reason_phrase: None,
+                    headers: ("Content-Type", file_info.content_type),
+                    body: FileSourceBody::<S, CHUNK_SIZE> {
+                        source: &self.source,
+                        handle: file_info.handle,
+                        length: file_info.length,
+                    },
+                }
+                .with_header("Content-Length", file_info.length),
+            )
+            .await
+    }
+}
+
+struct FileSourceBody<'s, S: FileSource, const CHUNK_SIZE: usize> {
+    source: &'s S,
+    handle: S::Handle,
+    length: usize,
+}
+
+impl<'s, S: FileSource, const CHUNK_SIZE: usize> super::Body for FileSourceBody<'s, S, CHUNK_SIZE> {
+    async fn write_response_body<R: Read, W: Write<Error = R::Error>>(
+        self,
+        connection: super::Connection<'_, R>,
+        mut writer: W,
+    ) -> Result<(), W::Error> {
+        let mut buffer = [0; CHUNK_SIZE];
+        let mut offset = 0;
+
+        while offset < self.length {
+            let chunk = &mut buffer[..CHUNK_SIZE.min(self.length - offset)];
+
+            let read_length = match self.source.read(&self.handle, offset, chunk).await {
+                Ok(0) => {
+                    log_error!("File ended after {} of {} bytes", offset, self.length);
+                    None
+                }
+                Ok(read_length) => Some(read_length.min(chunk.len())),
+                Err(err) => {
+                    log_error!(
+                        "Failed to read file: {}",
+                        crate::logging::Debug2Format(&err)
+                    );
+                    None
+                }
+            };
+
+            let Some(read_length) = read_length else {
+                // The "Content-Length" has already been sent, so the only way to tell the client that the body is incomplete is to close the connection
+                *connection.must_close = true;
+                return Ok(());
+            };
+
+            writer.write_all(&chunk[..read_length]).await?;
+
+            offset += read_length;
+        }
+
+        Ok(())
+    }
+}
+
+impl<
+        State,
+        CurrentPathParameters,
+        S: FileSource,
+        const MAX_PATH_LENGTH: usize,
+        const CHUNK_SIZE: usize,
+    > PathRouterService<State, CurrentPathParameters>
+    for FileSystemService<S, MAX_PATH_LENGTH, CHUNK_SIZE>
+{
+    async fn call_request_handler_service<R: Read, W: super::ResponseWriter<Error = R::Error>>(
+        &self,
+        state: &State,
+        current_path_parameters: CurrentPathParameters,
+        path: Path<'_>,
+        request: crate::request::Request<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        let method = request.parts.method();
+
+        if !request.parts.allowed_methods().is_empty() {
+            // A route matched the path but not the method, so don't serve a file in its place
             crate::routing::NotFound
                 .call_path_router(
                     state,
@@ -258,6 +985,28 @@ impl<State, CurrentPathParameters> PathRouterService<State, CurrentPathParameter
                     response_writer,
                 )
                 .await
+        } else if method.eq_ignore_ascii_case("get") {
+            self.serve_matching_file(
+                state,
+                current_path_parameters,
+                path,
+                request,
+                response_writer,
+            )
+            .await
+        } else if method.eq_ignore_ascii_case("head") {
+            self.serve_matching_file(
+                state,
+                current_path_parameters,
+                path,
+                request,
+                crate::routing::head_method_util::ignore_body(response_writer),
+            )
+            .await
+        } else {
+            crate::routing::MethodNotAllowed
+                .call_request_handler(state, current_path_parameters, request, response_writer)
+                .await
         }
     }
 }
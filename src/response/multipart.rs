@@ -0,0 +1,151 @@
+//! A streaming `multipart/x-mixed-replace` response, in which each part replaces the previous part, such as the frames of an MJPEG camera stream.
+
+use core::{fmt, future::Future};
+
+use super::chunked::{ChunkWriter, ChunksWritten};
+
+struct MultipartContentType {
+    boundary: &'static str,
+}
+
+impl fmt::Display for MultipartContentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "multipart/x-mixed-replace; boundary={}", self.boundary)
+    }
+}
+
+/// A `multipart/x-mixed-replace` response whose parts are produced by an async closure, such as the frames of an MJPEG camera stream.
+///
+/// The closure is called with the current state, and returns either the next part and the next state, or `None` once all parts have been produced.
+/// Each part is a Content Type and the bytes of the part, which are written after the boundary and the part headers, and the stream is then flushed.
+/// The closure may wait before returning the next part, and may keep returning parts indefinitely.
+///
+/// The response is sent using chunked encoding. If writing a part fails, for example because the client has disconnected, no further parts are produced and the error is returned.
+///
+/// The boundary must not appear in the bytes of any part.
+///
+/// ```
+/// use picoserve::{response::MultipartStream, routing::get};
+///
+/// static FRAMES: [&[u8]; 2] = [b"frame 0", b"frame 1"];
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/stream",
+///     get(|| async {
+///         MultipartStream::new("frame", 0, |n| async move {
+///             // Wait for the next frame here
+///             Some((("text/plain", FRAMES[n % FRAMES.len()]), n + 1))
+///         })
+///     }),
+/// );
+/// ```
+pub struct MultipartStream<S, F> {
+    boundary: &'static str,
+    state: S,
+    next_part: F,
+}
+
+impl<
+        S,
+        B: AsRef<[u8]>,
+        Fut: Future<Output = Option<((&'static str, B), S)>>,
+        F: FnMut(S) -> Fut,
+    > MultipartStream<S, F>
+{
+    /// Create a response with the given boundary, which writes parts produced by `next_part`, starting with `initial_state`.
+    pub fn new(boundary: &'static str, initial_state: S, next_part: F) -> Self {
+        Self {
+            boundary,
+            state: initial_state,
+            next_part,
+        }
+    }
+
+    async fn write_parts<W: crate::io::Write>(
+        self,
+        mut chunk_writer: ChunkWriter<W>,
+    ) -> Result<ChunksWritten, W::Error> {
+        let Self {
+            boundary,
+            mut state,
+            mut next_part,
+        } = self;
+
+        while let Some(((content_type, part), next_state)) = next_part(state).await {
+            let part = part.as_ref();
+
+            write!(
+                chunk_writer,
+                "--{boundary}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\r\n",
+                part.len()
+            )
+            .await?;
+            chunk_writer.write_chunk(part).await?;
+            chunk_writer.write_chunk(b"\r\n").await?;
+            chunk_writer.flush().await?;
+
+            state = next_state;
+        }
+
+        write!(chunk_writer, "--{boundary}--\r\n").await?;
+
+        chunk_writer.finalize().await
+    }
+
+    /// Convert the response into a [Response](super::Response), which can then have its status code changed or headers added.
+    pub fn into_response(self) -> super::Response<impl super::HeadersIter, impl super::Body> {
+        struct Body<S, F>(MultipartStream<S, F>);
+
+        impl<
+                S,
+                B: AsRef<[u8]>,
+                Fut: Future<Output = Option<((&'static str, B), S)>>,
+                F: FnMut(S) -> Fut,
+            > super::Body for Body<S, F>
+        {
+            async fn write_response_body<
+                R: crate::io::Read,
+                W: crate::io::Write<Error = R::Error>,
+            >(
+                self,
+                _connection: super::Connection<'_, R>,
+                writer: W,
+            ) -> Result<(), W::Error> {
+                self.0.write_parts(ChunkWriter { writer }).await.map(|_| ())
+            }
+        }
+
+        super::Response {
+            status_code: super::StatusCode::OK,
+            reason_phrase: None,
+            headers: super::HeadersChain(
+                (
+                    "Content-Type",
+                    MultipartContentType {
+                        boundary: self.boundary,
+                    },
+                ),
+                ("Transfer-Encoding", "chunked"),
+            ),
+            body: Body(self),
+        }
+    }
+}
+
+impl<
+        S,
+        B: AsRef<[u8]>,
+        Fut: Future<Output = Option<((&'static str, B), S)>>,
+        F: FnMut(S) -> Fut,
+    > super::IntoResponse for MultipartStream<S, F>
+{
+    async fn write_to<R: embedded_io_async::Read, W: super::ResponseWriter<Error = R::Error>>(
+        self,
+        connection: super::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<crate::ResponseSent, W::Error> {
+        response_writer
+            .write_response(connection, self.into_response())
+            .await
+    }
+}
@@ -30,7 +30,7 @@ impl<T: serde::Serialize> EventData for super::json::Json<T> {
 
 /// Writing events to an [EventWriter] will send the events to the client.
 pub struct EventWriter<W: Write> {
-    writer: W,
+    pub(crate) writer: W,
 }
 
 impl<W: Write> EventWriter<W> {
@@ -41,11 +41,140 @@ impl<W: Write> EventWriter<W> {
         self.writer.flush().await
     }
 
+    /// Send a comment, which is ignored by the client, followed by `padding` spaces.
+    ///
+    /// Some proxies buffer the response until a certain amount of data has been received, so a padding comment of around 2KB sent
+    /// before the first event ensures that events are passed on to the client as soon as they are sent.
+    pub async fn write_comment(&mut self, comment: &str, padding: usize) -> Result<(), W::Error> {
+        for line in comment.split('\n') {
+            self.writer.write_all(b":").await?;
+            self.writer.write_all(line.as_bytes()).await?;
+            self.writer.write_all(b"\n").await?;
+        }
+
+        if padding > 0 {
+            const SPACES: &[u8] = &[b' '; 64];
+
+            self.writer.write_all(b":").await?;
+
+            let mut remaining = padding;
+
+            while remaining > 0 {
+                let chunk_size = remaining.min(SPACES.len());
+                self.writer.write_all(&SPACES[..chunk_size]).await?;
+                remaining -= chunk_size;
+            }
+
+            self.writer.write_all(b"\n").await?;
+        }
+
+        self.writer.write_all(b"\n").await?;
+
+        self.writer.flush().await
+    }
+
     /// Send an event with a given name and data.
+    ///
+    /// If the client stops reading events, this will wait until the write timeout set in [Config](crate::Config) expires,
+    /// at which point an error with a kind of [ErrorKind::TimedOut](embedded_io_async::ErrorKind::TimedOut) is returned and the connection must be closed,
+    /// as part of the event might have been sent. To drop events sent to slow clients instead, use [EventWriter::try_write_event].
     pub async fn write_event<T: EventData>(
         &mut self,
         event: &str,
         data: T,
+    ) -> Result<(), W::Error> {
+        self.writer.write_all(EVENT_PREFIX).await?;
+
+        self.write_event_body(event, data).await
+    }
+
+    /// Send an event with a given name and data, unless the client is not reading events quickly enough.
+    ///
+    /// The write timeout set in [Config](crate::Config) applies to each write to the socket. If the first write of the event times out,
+    /// none of the event has been sent, so the event is dropped, [TryWriteEventError::Stalled] is returned, and further events may be sent.
+    /// Once part of the event has been sent, the rest of the event is written as per [EventWriter::write_event],
+    /// and any error, including a timeout, is returned as [TryWriteEventError::Io], after which the connection must be closed.
+    pub async fn try_write_event<T: EventData>(
+        &mut self,
+        event: &str,
+        data: T,
+    ) -> Result<(), TryWriteEventError<W::Error>> {
+        let written_size = self.writer.write(EVENT_PREFIX).await.map_err(|err| {
+            if let embedded_io_async::ErrorKind::TimedOut = embedded_io_async::Error::kind(&err) {
+                TryWriteEventError::Stalled(err)
+            } else {
+                TryWriteEventError::Io(err)
+            }
+        })?;
+
+        self.writer
+            .write_all(&EVENT_PREFIX[written_size..])
+            .await
+            .map_err(TryWriteEventError::Io)?;
+
+        self.write_event_body(event, data)
+            .await
+            .map_err(TryWriteEventError::Io)
+    }
+
+    /// Send an event with a given name, and the given value serialized as JSON as its data.
+    ///
+    /// The JSON is written as a single "data" field, even if the value is serialized in several chunks.
+    /// If the value is long, it will be repeatedly serialized, so it must serialize to the same value each time.
+    pub async fn write_json_event<T: serde::Serialize>(
+        &mut self,
+        event: &str,
+        value: T,
+    ) -> Result<(), W::Error> {
+        struct JsonDataWriter<W: Write> {
+            writer: W,
+        }
+
+        impl<W: Write> embedded_io_async::ErrorType for JsonDataWriter<W> {
+            type Error = W::Error;
+        }
+
+        impl<W: Write> Write for JsonDataWriter<W> {
+            async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+                // Serialized JSON does not usually contain newlines, but if it does, each line must be sent as a separate "data" field
+                let mut lines = buf.split(|&b| b == b'\n');
+
+                if let Some(line) = lines.next() {
+                    self.writer.write_all(line).await?;
+                }
+
+                for line in lines {
+                    self.writer.write_all(b"\ndata:").await?;
+                    self.writer.write_all(line).await?;
+                }
+
+                Ok(buf.len())
+            }
+
+            async fn flush(&mut self) -> Result<(), Self::Error> {
+                self.writer.flush().await
+            }
+        }
+
+        self.writer.write_all(EVENT_PREFIX).await?;
+        self.writer.write_all(event.as_bytes()).await?;
+        self.writer.write_all(b"\ndata:").await?;
+
+        super::json::Json(value)
+            .do_write_to(&mut JsonDataWriter {
+                writer: &mut self.writer,
+            })
+            .await?;
+
+        self.writer.write_all(b"\n\n").await?;
+
+        self.writer.flush().await
+    }
+
+    async fn write_event_body<T: EventData>(
+        &mut self,
+        event: &str,
+        data: T,
     ) -> Result<(), W::Error> {
         pub struct DataWriter<W: Write> {
             writer: W,
@@ -72,7 +201,6 @@ impl<W: Write> EventWriter<W> {
             }
         }
 
-        self.writer.write_all(b"event:").await?;
         self.writer.write_all(event.as_bytes()).await?;
         self.writer.write_all(b"\n").await?;
 
@@ -87,26 +215,138 @@ impl<W: Write> EventWriter<W> {
     }
 }
 
+const EVENT_PREFIX: &[u8] = b"event:";
+
+/// Errors returned by [EventWriter::try_write_event].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TryWriteEventError<E> {
+    /// The client is not reading events quickly enough, and none of the event was sent. Further events may be sent.
+    Stalled(E),
+    /// The event failed to send. The connection must be closed.
+    Io(E),
+}
+
 /// Implement this trait to generate events to send to the client.
 pub trait EventSource {
     /// Produce a stream of events and write them to `writer`
     async fn write_events<W: Write>(self, writer: EventWriter<W>) -> Result<(), W::Error>;
 }
 
+/// Implement this trait to generate events to send to the client, until a shutdown signal is received.
+pub trait EventSourceWithShutdownSignal {
+    /// Produce a stream of events and write them to `writer`.
+    ///
+    /// Once `shutdown_signal` completes, the server is shutting down, so the implementation should send any final events and return.
+    async fn write_events<W: Write>(
+        self,
+        writer: EventWriter<W>,
+        shutdown_signal: impl core::future::Future<Output = ()>,
+    ) -> Result<(), W::Error>;
+}
+
+fn event_stream_response<B: super::Body>(
+    body: B,
+) -> super::Response<impl super::HeadersIter, impl super::Body> {
+    super::Response {
+        status_code: StatusCode::OK,
+        reason_phrase: None,
+        headers: [
+            ("Cache-Control", "no-cache"),
+            ("Content-Type", "text/event-stream"),
+        ],
+        body,
+    }
+}
+
+/// An [EventSource] which sends a comment before the events of `source`, created by [EventStream::with_initial_comment].
+pub struct InitialComment<S> {
+    /// The comment, which is ignored by the client.
+    pub comment: &'static str,
+    /// The number of spaces sent after the comment.
+    pub padding: usize,
+    /// The source of events, which are sent after the comment.
+    pub source: S,
+}
+
+impl<S: EventSource> EventSource for InitialComment<S> {
+    async fn write_events<W: Write>(self, mut writer: EventWriter<W>) -> Result<(), W::Error> {
+        writer.write_comment(self.comment, self.padding).await?;
+
+        self.source.write_events(writer).await
+    }
+}
+
+impl<S: EventSourceWithShutdownSignal> EventSourceWithShutdownSignal for InitialComment<S> {
+    async fn write_events<W: Write>(
+        self,
+        mut writer: EventWriter<W>,
+        shutdown_signal: impl core::future::Future<Output = ()>,
+    ) -> Result<(), W::Error> {
+        writer.write_comment(self.comment, self.padding).await?;
+
+        self.source.write_events(writer, shutdown_signal).await
+    }
+}
+
 /// A stream of Events sent by the server. Return an instance of this from the handler function.
+///
+/// Some proxies buffer responses, which delays events. To work around them, send an initial padding comment using [EventStream::with_initial_comment],
+/// and add headers such as "X-Accel-Buffering: no" or "Cache-Control: no-transform" using [EventStream::with_headers].
+///
+/// ```
+/// use picoserve::{
+///     io::Write,
+///     response::sse::{EventSource, EventStream, EventWriter},
+///     routing::get,
+/// };
+///
+/// struct Events;
+///
+/// impl EventSource for Events {
+///     async fn write_events<W: Write>(self, mut writer: EventWriter<W>) -> Result<(), W::Error> {
+///         writer.write_event("message", "Hello").await
+///     }
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     "/events",
+///     get(|| async {
+///         EventStream(Events)
+///             .with_initial_comment("padding", 2048)
+///             .with_headers([("X-Accel-Buffering", "no"), ("Cache-Control", "no-transform")])
+///     }),
+/// );
+/// ```
 pub struct EventStream<S: EventSource>(pub S);
 
 impl<S: EventSource> EventStream<S> {
     /// Convert SSE stream into a [super::Response] with a status code of "OK"
     pub fn into_response(self) -> super::Response<impl super::HeadersIter, impl super::Body> {
-        super::Response {
-            status_code: StatusCode::OK,
-            headers: [
-                ("Cache-Control", "no-cache"),
-                ("Content-Type", "text/event-stream"),
-            ],
-            body: self,
-        }
+        event_stream_response(self)
+    }
+
+    /// Send a comment, followed by `padding` spaces, before the first event. See [EventWriter::write_comment].
+    pub fn with_initial_comment(
+        self,
+        comment: &'static str,
+        padding: usize,
+    ) -> EventStream<InitialComment<S>> {
+        EventStream(InitialComment {
+            comment,
+            padding,
+            source: self.0,
+        })
+    }
+
+    /// Convert SSE stream into a [super::Response] with a status code of "OK" and additional headers.
+    ///
+    /// The headers are sent in addition to the default "Cache-Control: no-cache" header, so a "Cache-Control: no-transform" header is combined with it by the client.
+    pub fn with_headers<H: super::HeadersIter>(
+        self,
+        headers: H,
+    ) -> super::Response<impl super::HeadersIter, impl super::Body> {
+        self.into_response().with_headers(headers)
     }
 }
 
@@ -144,3 +384,73 @@ impl<S: EventSource> core::future::IntoFuture for EventStream<S> {
         core::future::ready(self)
     }
 }
+
+/// A stream of Events sent by the server, which is notified when the server is shutting down. Return an instance of this from the handler function.
+///
+/// picoserve does not provide a shutdown signal, so `shutdown_signal` must be provided by the application,
+/// for example by waiting for a change to a value shared with the task which decides when to shut down the server.
+///
+/// If the client disconnects before `shutdown_signal` completes, the event source is dropped.
+pub struct EventStreamWithShutdownSignal<
+    S: EventSourceWithShutdownSignal,
+    F: core::future::Future<Output = ()>,
+> {
+    /// The source of events.
+    pub source: S,
+    /// Completes when the server is shutting down.
+    pub shutdown_signal: F,
+}
+
+impl<S: EventSourceWithShutdownSignal, F: core::future::Future<Output = ()>>
+    EventStreamWithShutdownSignal<S, F>
+{
+    /// Convert SSE stream into a [super::Response] with a status code of "OK"
+    pub fn into_response(self) -> super::Response<impl super::HeadersIter, impl super::Body> {
+        event_stream_response(self)
+    }
+}
+
+impl<S: EventSourceWithShutdownSignal, F: core::future::Future<Output = ()>> super::Body
+    for EventStreamWithShutdownSignal<S, F>
+{
+    async fn write_response_body<R: Read, W: Write<Error = R::Error>>(
+        self,
+        connection: super::Connection<'_, R>,
+        mut writer: W,
+    ) -> Result<(), W::Error> {
+        writer.flush().await?;
+
+        connection
+            .run_until_disconnection(
+                (),
+                self.source
+                    .write_events(EventWriter { writer }, self.shutdown_signal),
+            )
+            .await
+    }
+}
+
+impl<S: EventSourceWithShutdownSignal, F: core::future::Future<Output = ()>> super::IntoResponse
+    for EventStreamWithShutdownSignal<S, F>
+{
+    async fn write_to<R: Read, W: super::ResponseWriter<Error = R::Error>>(
+        self,
+        connection: super::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<crate::ResponseSent, W::Error> {
+        response_writer
+            .write_response(connection, self.into_response())
+            .await
+    }
+}
+
+impl<S: EventSourceWithShutdownSignal, F: core::future::Future<Output = ()>>
+    core::future::IntoFuture for EventStreamWithShutdownSignal<S, F>
+{
+    type Output = Self;
+    type IntoFuture = core::future::Ready<Self>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        core::future::ready(self)
+    }
+}
@@ -6,39 +6,55 @@ pub struct StatusCode(u16);
 
 impl StatusCode {
     /// Create a status code with the given numerical value.
+    ///
+    /// The status code is not validated, so this should only be used with known-valid status codes. Use [StatusCode::from_u16] to validate the status code.
     pub const fn new(status_code: u16) -> Self {
         Self(status_code)
     }
 
+    /// Create a status code with the given numerical value, returning None if the value is not within the range 100-599.
+    pub const fn from_u16(status_code: u16) -> Option<Self> {
+        if 100 <= status_code && status_code < 600 {
+            Some(Self(status_code))
+        } else {
+            None
+        }
+    }
+
     /// Convert a status code into the underlying numerical value.
     pub const fn as_u16(self) -> u16 {
         self.0
     }
 
-    /// Is the status code with the 1xx range
+    /// Is the status code within the 1xx range
     pub const fn is_informational(&self) -> bool {
         200 > self.0 && self.0 >= 100
     }
 
-    /// Is the status code with the 2xx range
+    /// Is the status code within the 2xx range
     pub const fn is_success(&self) -> bool {
         300 > self.0 && self.0 >= 200
     }
 
-    /// Is the status code with the 3xx range
+    /// Is the status code within the 3xx range
     pub const fn is_redirection(&self) -> bool {
         400 > self.0 && self.0 >= 300
     }
 
-    /// Is the status code with the 4xx range
+    /// Is the status code within the 4xx range
     pub const fn is_client_error(&self) -> bool {
         500 > self.0 && self.0 >= 400
     }
 
-    /// Is the status code with the 5xx range
+    /// Is the status code within the 5xx range
     pub const fn is_server_error(&self) -> bool {
         600 > self.0 && self.0 >= 500
     }
+
+    /// Returns false if responses with the status code never have a body, i.e. "1xx", "204 No Content", and "304 Not Modified" responses.
+    pub(crate) const fn allows_body(&self) -> bool {
+        !(self.is_informational() || self.0 == 204 || self.0 == 304)
+    }
 }
 
 impl core::fmt::Display for StatusCode {
@@ -53,71 +69,115 @@ impl super::IntoResponse for StatusCode {
         connection: super::Connection<'_, R>,
         response_writer: W,
     ) -> Result<crate::ResponseSent, W::Error> {
+        if !self.allows_body() {
+            return response_writer
+                .write_response(
+                    connection,
+                    super::Response {
+                        status_code: self,
+                        reason_phrase: None,
+                        headers: [("", ""); 0],
+                        body: super::NoBody,
+                    },
+                )
+                .await;
+        }
+
+        if !(self.is_client_error() || self.is_server_error()) {
+            return super::Response::new(self, "")
+                .write_to(connection, response_writer)
+                .await;
+        }
+
         super::Response::new(self, format_args!("Error {}", self.0))
             .write_to(connection, response_writer)
             .await
     }
 }
 
-impl StatusCode {
-    pub const CONTINUE: StatusCode = StatusCode(100);
-    pub const SWITCHING_PROTOCOLS: StatusCode = StatusCode(101);
-    pub const PROCESSING: StatusCode = StatusCode(102);
-    pub const OK: StatusCode = StatusCode(200);
-    pub const CREATED: StatusCode = StatusCode(201);
-    pub const ACCEPTED: StatusCode = StatusCode(202);
-    pub const NON_AUTHORITATIVE_INFORMATION: StatusCode = StatusCode(203);
-    pub const NO_CONTENT: StatusCode = StatusCode(204);
-    pub const RESET_CONTENT: StatusCode = StatusCode(205);
-    pub const PARTIAL_CONTENT: StatusCode = StatusCode(206);
-    pub const MULTI_STATUS: StatusCode = StatusCode(207);
-    pub const ALREADY_REPORTED: StatusCode = StatusCode(208);
-    pub const IM_USED: StatusCode = StatusCode(226);
-    pub const MULTIPLE_CHOICES: StatusCode = StatusCode(300);
-    pub const MOVED_PERMANENTLY: StatusCode = StatusCode(301);
-    pub const FOUND: StatusCode = StatusCode(302);
-    pub const SEE_OTHER: StatusCode = StatusCode(303);
-    pub const NOT_MODIFIED: StatusCode = StatusCode(304);
-    pub const USE_PROXY: StatusCode = StatusCode(305);
-    pub const TEMPORARY_REDIRECT: StatusCode = StatusCode(307);
-    pub const PERMANENT_REDIRECT: StatusCode = StatusCode(308);
-    pub const BAD_REQUEST: StatusCode = StatusCode(400);
-    pub const UNAUTHORIZED: StatusCode = StatusCode(401);
-    pub const PAYMENT_REQUIRED: StatusCode = StatusCode(402);
-    pub const FORBIDDEN: StatusCode = StatusCode(403);
-    pub const NOT_FOUND: StatusCode = StatusCode(404);
-    pub const METHOD_NOT_ALLOWED: StatusCode = StatusCode(405);
-    pub const NOT_ACCEPTABLE: StatusCode = StatusCode(406);
-    pub const PROXY_AUTHENTICATION_REQUIRED: StatusCode = StatusCode(407);
-    pub const REQUEST_TIMEOUT: StatusCode = StatusCode(408);
-    pub const CONFLICT: StatusCode = StatusCode(409);
-    pub const GONE: StatusCode = StatusCode(410);
-    pub const LENGTH_REQUIRED: StatusCode = StatusCode(411);
-    pub const PRECONDITION_FAILED: StatusCode = StatusCode(412);
-    pub const PAYLOAD_TOO_LARGE: StatusCode = StatusCode(413);
-    pub const URI_TOO_LONG: StatusCode = StatusCode(414);
-    pub const UNSUPPORTED_MEDIA_TYPE: StatusCode = StatusCode(415);
-    pub const RANGE_NOT_SATISFIABLE: StatusCode = StatusCode(416);
-    pub const EXPECTATION_FAILED: StatusCode = StatusCode(417);
-    pub const IM_A_TEAPOT: StatusCode = StatusCode(418);
-    pub const MISDIRECTED_REQUEST: StatusCode = StatusCode(421);
-    pub const UNPROCESSABLE_ENTITY: StatusCode = StatusCode(422);
-    pub const LOCKED: StatusCode = StatusCode(423);
-    pub const FAILED_DEPENDENCY: StatusCode = StatusCode(424);
-    pub const UPGRADE_REQUIRED: StatusCode = StatusCode(426);
-    pub const PRECONDITION_REQUIRED: StatusCode = StatusCode(428);
-    pub const TOO_MANY_REQUESTS: StatusCode = StatusCode(429);
-    pub const REQUEST_HEADER_FIELDS_TOO_LARGE: StatusCode = StatusCode(431);
-    pub const UNAVAILABLE_FOR_LEGAL_REASONS: StatusCode = StatusCode(451);
-    pub const INTERNAL_SERVER_ERROR: StatusCode = StatusCode(500);
-    pub const NOT_IMPLEMENTED: StatusCode = StatusCode(501);
-    pub const BAD_GATEWAY: StatusCode = StatusCode(502);
-    pub const SERVICE_UNAVAILABLE: StatusCode = StatusCode(503);
-    pub const GATEWAY_TIMEOUT: StatusCode = StatusCode(504);
-    pub const HTTP_VERSION_NOT_SUPPORTED: StatusCode = StatusCode(505);
-    pub const VARIANT_ALSO_NEGOTIATES: StatusCode = StatusCode(506);
-    pub const INSUFFICIENT_STORAGE: StatusCode = StatusCode(507);
-    pub const LOOP_DETECTED: StatusCode = StatusCode(508);
-    pub const NOT_EXTENDED: StatusCode = StatusCode(510);
-    pub const NETWORK_AUTHENTICATION_REQUIRED: StatusCode = StatusCode(511);
+macro_rules! status_codes {
+    ($($name:ident = $code:literal, $reason_phrase:literal;)*) => {
+        impl StatusCode {
+            $(
+                pub const $name: StatusCode = StatusCode($code);
+            )*
+
+            /// All status codes with a constant, in ascending order.
+            #[cfg(test)]
+            pub(crate) const ALL: &'static [StatusCode] = &[$(StatusCode::$name,)*];
+
+            /// Return the canonical reason phrase of the status code, such as "Not Found", or None if the status code is not known.
+            pub const fn canonical_reason(&self) -> Option<&'static str> {
+                match self.0 {
+                    $($code => Some($reason_phrase),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+status_codes! {
+    CONTINUE = 100, "Continue";
+    SWITCHING_PROTOCOLS = 101, "Switching Protocols";
+    PROCESSING = 102, "Processing";
+    EARLY_HINTS = 103, "Early Hints";
+    OK = 200, "OK";
+    CREATED = 201, "Created";
+    ACCEPTED = 202, "Accepted";
+    NON_AUTHORITATIVE_INFORMATION = 203, "Non-Authoritative Information";
+    NO_CONTENT = 204, "No Content";
+    RESET_CONTENT = 205, "Reset Content";
+    PARTIAL_CONTENT = 206, "Partial Content";
+    MULTI_STATUS = 207, "Multi-Status";
+    ALREADY_REPORTED = 208, "Already Reported";
+    IM_USED = 226, "IM Used";
+    MULTIPLE_CHOICES = 300, "Multiple Choices";
+    MOVED_PERMANENTLY = 301, "Moved Permanently";
+    FOUND = 302, "Found";
+    SEE_OTHER = 303, "See Other";
+    NOT_MODIFIED = 304, "Not Modified";
+    USE_PROXY = 305, "Use Proxy";
+    TEMPORARY_REDIRECT = 307, "Temporary Redirect";
+    PERMANENT_REDIRECT = 308, "Permanent Redirect";
+    BAD_REQUEST = 400, "Bad Request";
+    UNAUTHORIZED = 401, "Unauthorized";
+    PAYMENT_REQUIRED = 402, "Payment Required";
+    FORBIDDEN = 403, "Forbidden";
+    NOT_FOUND = 404, "Not Found";
+    METHOD_NOT_ALLOWED = 405, "Method Not Allowed";
+    NOT_ACCEPTABLE = 406, "Not Acceptable";
+    PROXY_AUTHENTICATION_REQUIRED = 407, "Proxy Authentication Required";
+    REQUEST_TIMEOUT = 408, "Request Timeout";
+    CONFLICT = 409, "Conflict";
+    GONE = 410, "Gone";
+    LENGTH_REQUIRED = 411, "Length Required";
+    PRECONDITION_FAILED = 412, "Precondition Failed";
+    PAYLOAD_TOO_LARGE = 413, "Payload Too Large";
+    URI_TOO_LONG = 414, "URI Too Long";
+    UNSUPPORTED_MEDIA_TYPE = 415, "Unsupported Media Type";
+    RANGE_NOT_SATISFIABLE = 416, "Range Not Satisfiable";
+    EXPECTATION_FAILED = 417, "Expectation Failed";
+    IM_A_TEAPOT = 418, "I'm a teapot";
+    MISDIRECTED_REQUEST = 421, "Misdirected Request";
+    UNPROCESSABLE_ENTITY = 422, "Unprocessable Entity";
+    LOCKED = 423, "Locked";
+    FAILED_DEPENDENCY = 424, "Failed Dependency";
+    TOO_EARLY = 425, "Too Early";
+    UPGRADE_REQUIRED = 426, "Upgrade Required";
+    PRECONDITION_REQUIRED = 428, "Precondition Required";
+    TOO_MANY_REQUESTS = 429, "Too Many Requests";
+    REQUEST_HEADER_FIELDS_TOO_LARGE = 431, "Request Header Fields Too Large";
+    UNAVAILABLE_FOR_LEGAL_REASONS = 451, "Unavailable For Legal Reasons";
+    INTERNAL_SERVER_ERROR = 500, "Internal Server Error";
+    NOT_IMPLEMENTED = 501, "Not Implemented";
+    BAD_GATEWAY = 502, "Bad Gateway";
+    SERVICE_UNAVAILABLE = 503, "Service Unavailable";
+    GATEWAY_TIMEOUT = 504, "Gateway Timeout";
+    HTTP_VERSION_NOT_SUPPORTED = 505, "HTTP Version Not Supported";
+    VARIANT_ALSO_NEGOTIATES = 506, "Variant Also Negotiates";
+    INSUFFICIENT_STORAGE = 507, "Insufficient Storage";
+    LOOP_DETECTED = 508, "Loop Detected";
+    NOT_EXTENDED = 510, "Not Extended";
+    NETWORK_AUTHENTICATION_REQUIRED = 511, "Network Authentication Required";
 }
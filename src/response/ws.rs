@@ -19,6 +19,8 @@ pub enum WebSocketUpgradeRejection {
     InvalidWebSocketVersionHeader,
     /// Websocket upgrade header "sec-websocket-key" is missing.
     WebSocketKeyHeaderMissing,
+    /// Websocket upgrade header "sec-websocket-protocol" is longer than the `PROTOCOLS_SIZE` of [WebSocketUpgrade].
+    ProtocolsTooLong,
 }
 
 impl super::IntoResponse for WebSocketUpgradeRejection {
@@ -50,6 +52,14 @@ impl super::IntoResponse for WebSocketUpgradeRejection {
                 WebSocketUpgradeRejection::WebSocketKeyHeaderMissing => {
                     "Websocket upgrades must have a `Sec-WebSocket-Key` header\n"
                 }
+                WebSocketUpgradeRejection::ProtocolsTooLong => {
+                    return (
+                        StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+                        "Websocket upgrade `Sec-WebSocket-Protocol` header is too long\n",
+                    )
+                        .write_to(connection, response_writer)
+                        .await
+                }
             },
         )
             .write_to(connection, response_writer)
@@ -82,13 +92,51 @@ impl<P: AsRef<str>> WebSocketProtocol for SpecifiedProtocol<P> {
 }
 
 /// A HTTP upgrade request.
-pub struct WebSocketUpgrade {
-    key: [u8; 28],
-    protocols: Option<heapless::String<32>>,
+///
+/// The handshake is parsed as a regular HTTP request, so all of its headers, including "Sec-WebSocket-Protocol" and "Sec-WebSocket-Extensions",
+/// must fit in the buffer passed to [serve](crate::serve), otherwise the request is rejected with "Request Header Fields Too Large".
+/// The requested protocols are copied into a buffer of `PROTOCOLS_SIZE` bytes, and if they don't fit the upgrade is rejected with
+/// [WebSocketUpgradeRejection::ProtocolsTooLong]. Clients which send long protocol lists can be supported by increasing `PROTOCOLS_SIZE`,
+/// e.g. `WebSocketUpgrade<256>`.
+///
+/// As with other extractors, the path parameters captured by the route are passed to the handler before the extractors,
+/// and can be moved into the [WebSocketCallback] so that they are available once the connection has been upgraded.
+///
+/// ```
+/// use picoserve::{
+///     io::{Read, Write},
+///     response::ws::{SocketRx, SocketTx, WebSocketCallback, WebSocketUpgrade},
+///     routing::{get, parse_path_segment},
+/// };
+///
+/// struct JoinRoom {
+///     room_id: u32,
+/// }
+///
+/// impl WebSocketCallback for JoinRoom {
+///     async fn run<R: Read, W: Write<Error = R::Error>>(
+///         self,
+///         _rx: SocketRx<R>,
+///         mut tx: SocketTx<W>,
+///     ) -> Result<(), W::Error> {
+///         tx.send_display(format_args!("Joined room {}", self.room_id)).await
+///     }
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route(
+///     ("/rooms", parse_path_segment::<u32>(), "/ws"),
+///     get(|room_id: u32, upgrade: WebSocketUpgrade| async move {
+///         upgrade.on_upgrade(JoinRoom { room_id })
+///     }),
+/// );
+/// ```
+pub struct WebSocketUpgrade<const PROTOCOLS_SIZE: usize = 32> {
+    key: [u8; 20],
+    protocols: Option<heapless::String<PROTOCOLS_SIZE>>,
     upgrade_token: crate::extract::UpgradeToken,
 }
 
-impl WebSocketUpgrade {
+impl<const PROTOCOLS_SIZE: usize> WebSocketUpgrade<PROTOCOLS_SIZE> {
     /// If protocols are specified by the client, return an iterator of them.
     /// If not, return None.
     pub fn protocols(&self) -> Option<impl Iterator<Item = &str>> {
@@ -98,7 +146,9 @@ impl WebSocketUpgrade {
     }
 }
 
-impl<'r, State> crate::extract::FromRequest<'r, State> for WebSocketUpgrade {
+impl<'r, State, const PROTOCOLS_SIZE: usize> crate::extract::FromRequest<'r, State>
+    for WebSocketUpgrade<PROTOCOLS_SIZE>
+{
     type Rejection = WebSocketUpgradeRejection;
 
     async fn from_request<R: Read>(
@@ -116,6 +166,10 @@ impl<'r, State> crate::extract::FromRequest<'r, State> for WebSocketUpgrade {
                 WebSocketUpgradeRejection::InvalidUpgradeHeader
             })?;
 
+        if !request_parts.headers().connection_has_option("upgrade") {
+            return Err(WebSocketUpgradeRejection::InvalidConnectionHeader);
+        }
+
         if request_parts
             .headers()
             .get("upgrade")
@@ -136,27 +190,26 @@ impl<'r, State> crate::extract::FromRequest<'r, State> for WebSocketUpgrade {
             .headers()
             .get("sec-websocket-key")
             .map(|key| {
-                let hash = lhash::Sha1::new()
+                lhash::Sha1::new()
                     .const_update(key.value)
                     .const_update(b"258EAFA5-E914-47DA-95CA-C5AB0DC85B11")
-                    .const_result();
-
-                let mut buffer = [0; 28];
-
-                data_encoding::BASE64.encode_mut(&hash, &mut buffer);
-
-                buffer
+                    .const_result()
             })
             .ok_or(WebSocketUpgradeRejection::WebSocketKeyHeaderMissing)?;
 
-        let protocols = request_parts
-            .headers()
-            .get("sec-websocket-protocol")
-            .and_then(|protocol| {
-                let mut buffer = heapless::String::new();
-                buffer.push_str(protocol.as_str().ok()?).ok()?;
-                Some(buffer)
-            });
+        let protocols = match request_parts.headers().get("sec-websocket-protocol") {
+            Some(protocol) => match protocol.as_str() {
+                Ok(protocol) => {
+                    let mut buffer = heapless::String::new();
+                    buffer
+                        .push_str(protocol)
+                        .map_err(|_| WebSocketUpgradeRejection::ProtocolsTooLong)?;
+                    Some(buffer)
+                }
+                Err(_) => None,
+            },
+            None => None,
+        };
 
         Ok(Self {
             key,
@@ -278,14 +331,111 @@ impl<E> From<core::str::Utf8Error> for ReadMessageError<E> {
     }
 }
 
-enum MessageOpcode {
+macro_rules! close_codes {
+    ($($(#[$meta:meta])* $name:ident = $code:literal,)*) => {
+        /// The status code of a Close message, indicating why the connection is being closed.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        pub enum CloseCode {
+            $($(#[$meta])* $name,)*
+            /// Any other status code, such as an application-specific status code in the range 4000-4999.
+            Other(u16),
+        }
+
+        impl From<u16> for CloseCode {
+            fn from(code: u16) -> Self {
+                match code {
+                    $($code => Self::$name,)*
+                    code => Self::Other(code),
+                }
+            }
+        }
+
+        impl From<CloseCode> for u16 {
+            fn from(code: CloseCode) -> Self {
+                match code {
+                    $(CloseCode::$name => $code,)*
+                    CloseCode::Other(code) => code,
+                }
+            }
+        }
+    };
+}
+
+close_codes!(
+    /// The connection has been closed normally.
+    Normal = 1000,
+    /// The endpoint is going away, such as the server shutting down or the browser navigating away from the page.
+    GoingAway = 1001,
+    /// The endpoint received a message which violates the Web Socket protocol.
+    ProtocolError = 1002,
+    /// The endpoint received a type of data which it cannot accept, such as a binary message if it only accepts text messages.
+    UnsupportedData = 1003,
+    /// The Close message contained no status code. Must not be sent in a Close message.
+    NoStatusReceived = 1005,
+    /// The connection was closed without sending a Close message. Must not be sent in a Close message.
+    Abnormal = 1006,
+    /// The endpoint received a message containing data inconsistent with the type of the message, such as a text message which is not UTF-8.
+    InvalidPayload = 1007,
+    /// The endpoint received a message which violates its policy.
+    PolicyViolation = 1008,
+    /// The endpoint received a message which is too large to process.
+    MessageTooBig = 1009,
+    /// The client expected the server to negotiate one or more extensions, but the server didn't.
+    MandatoryExtension = 1010,
+    /// The server encountered an unexpected condition which prevented it from fulfilling the request.
+    InternalError = 1011,
+    /// The TLS handshake failed. Must not be sent in a Close message.
+    TlsHandshake = 1015,
+);
+
+/// The type of a message, as read by [SocketRx::next_chunk].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MessageOpcode {
+    /// A UTF-8 encoded string.
     Text,
+    /// A blob of (possibly structured) binary data.
     Binary,
+    /// A request to close the connection.
     Close,
+    /// A ping message, which should be replied with a "pong" message containing the same data.
     Ping,
+    /// The response to a "ping" message
     Pong,
 }
 
+/// A chunk of the payload of a message, as read by [SocketRx::next_chunk].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MessageChunk<'a> {
+    /// The type of the message which this chunk is part of.
+    pub opcode: MessageOpcode,
+    /// If true, this is the first chunk of the message.
+    pub is_start: bool,
+    /// If true, this is the final chunk of the message.
+    pub is_end: bool,
+    /// The payload data of this chunk. Chunks of Text messages may split UTF-8 sequences.
+    pub data: &'a [u8],
+}
+
+struct FrameHeader {
+    is_final: bool,
+    opcode: Opcode,
+    length: usize,
+    mask: Option<[u8; 4]>,
+}
+
+/// The state of the frame currently being read by [SocketRx::next_chunk].
+struct PartialFrame {
+    opcode: MessageOpcode,
+    is_start: bool,
+    is_final: bool,
+    remaining: usize,
+    mask: Option<[u8; 4]>,
+    mask_offset: usize,
+}
+
 /// Message Types.
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -295,7 +445,7 @@ pub enum Message<'a> {
     /// A blob of (possibly structured) binary data.
     Binary(&'a [u8]),
     /// A request to close the connection.
-    Close(Option<(u16, &'a str)>),
+    Close(Option<(CloseCode, &'a str)>),
     /// A ping message, which should be replied with a "pong" message containing the same data.
     Ping(&'a [u8]),
     /// The response to a "ping" message
@@ -305,15 +455,22 @@ pub enum Message<'a> {
 /// A source of Web Socket Frames.
 pub struct SocketRx<R: Read> {
     reader: R,
+    /// The frame currently being read by [SocketRx::next_chunk], if it has not been read entirely.
+    partial_frame: Option<PartialFrame>,
+    /// The type of the fragmented data message currently being read by [SocketRx::next_chunk].
+    fragmented_message: Option<MessageOpcode>,
 }
 
 impl<R: Read> SocketRx<R> {
-    /// Read the next frame. If the frame is not final, then before calling next_message,
-    /// next_frame must be repeatedly called until a final frame is received.
-    pub async fn next_frame(
-        &mut self,
-        buffer: &mut [u8],
-    ) -> Result<Frame, ReadFrameError<R::Error>> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            reader,
+            partial_frame: None,
+            fragmented_message: None,
+        }
+    }
+
+    async fn read_frame_header(&mut self) -> Result<FrameHeader, ReadFrameError<R::Error>> {
         let [first, second] = {
             let mut header = [0; 2];
             self.reader.read_exact(&mut header).await?;
@@ -349,17 +506,40 @@ impl<R: Read> SocketRx<R> {
             length => length.into(),
         };
 
-        let mut mask = [0; 4];
-
-        if is_masked {
+        let mask = if is_masked {
+            let mut mask = [0; 4];
             self.reader.read_exact(&mut mask).await?;
-        }
+            Some(mask)
+        } else {
+            None
+        };
+
+        Ok(FrameHeader {
+            is_final,
+            opcode,
+            length,
+            mask,
+        })
+    }
+
+    /// Read the next frame. If the frame is not final, then before calling next_message,
+    /// next_frame must be repeatedly called until a final frame is received.
+    pub async fn next_frame(
+        &mut self,
+        buffer: &mut [u8],
+    ) -> Result<Frame, ReadFrameError<R::Error>> {
+        let FrameHeader {
+            is_final,
+            opcode,
+            length,
+            mask,
+        } = self.read_frame_header().await?;
 
         let data = buffer.get_mut(..length).ok_or(ReadFrameError::OutOfSpace)?;
 
         self.reader.read_exact(data).await?;
 
-        if is_masked {
+        if let Some(mask) = mask {
             for (data, mask) in data.iter_mut().zip(mask.iter().cycle()) {
                 *data ^= mask;
             }
@@ -372,6 +552,105 @@ impl<R: Read> SocketRx<R> {
         })
     }
 
+    /// Read the next chunk of message data, which is at most the size of `buffer`, so that messages larger than any buffer can be processed incrementally.
+    ///
+    /// The chunks of a fragmented message are read across frames, with [MessageChunk::is_start] and [MessageChunk::is_end] marking the boundaries of the message.
+    /// Control messages (Close, Ping, and Pong) may arrive between the chunks of a fragmented Text or Binary message, so check [MessageChunk::opcode] of each chunk.
+    ///
+    /// Do not call [next_frame](Self::next_frame) or [next_message](Self::next_message) until the final chunk of a message has been read.
+    pub async fn next_chunk<'a>(
+        &mut self,
+        buffer: &'a mut [u8],
+    ) -> Result<MessageChunk<'a>, ReadMessageError<R::Error>> {
+        let mut frame = match self.partial_frame.take() {
+            Some(frame) => frame,
+            None => {
+                let FrameHeader {
+                    is_final,
+                    opcode,
+                    length,
+                    mask,
+                } = self
+                    .read_frame_header()
+                    .await
+                    .map_err(ReadMessageError::ReadFrameError)?;
+
+                let (opcode, is_start) = match opcode {
+                    Opcode::Data(Data::Continue) => (
+                        self.fragmented_message
+                            .ok_or(ReadMessageError::MessageStartsWithContinuation)?,
+                        false,
+                    ),
+                    Opcode::Data(Data::Text | Data::Binary)
+                        if self.fragmented_message.is_some() =>
+                    {
+                        return Err(ReadMessageError::UnexpectedMessageStart)
+                    }
+                    Opcode::Data(Data::Text) => (MessageOpcode::Text, true),
+                    Opcode::Data(Data::Binary) => (MessageOpcode::Binary, true),
+                    Opcode::Control(Control::Close) => (MessageOpcode::Close, true),
+                    Opcode::Control(Control::Ping) => (MessageOpcode::Ping, true),
+                    Opcode::Control(Control::Pong) => (MessageOpcode::Pong, true),
+                    Opcode::Data(Data::Reserved(opcode))
+                    | Opcode::Control(Control::Reserved(opcode)) => {
+                        return Err(ReadMessageError::ReservedOpcode(opcode))
+                    }
+                };
+
+                if let MessageOpcode::Text | MessageOpcode::Binary = opcode {
+                    self.fragmented_message = (!is_final).then_some(opcode);
+                }
+
+                PartialFrame {
+                    opcode,
+                    is_start,
+                    is_final,
+                    remaining: length,
+                    mask,
+                    mask_offset: 0,
+                }
+            }
+        };
+
+        let read_size = frame.remaining.min(buffer.len());
+
+        if read_size == 0 && frame.remaining > 0 {
+            return Err(ReadMessageError::ReadFrameError(ReadFrameError::OutOfSpace));
+        }
+
+        let data = &mut buffer[..read_size];
+
+        self.reader
+            .read_exact(data)
+            .await
+            .map_err(|err| ReadMessageError::ReadFrameError(err.into()))?;
+
+        if let Some(mask) = frame.mask {
+            for (index, data) in data.iter_mut().enumerate() {
+                *data ^= mask[(frame.mask_offset + index) % 4];
+            }
+        }
+
+        frame.remaining -= read_size;
+        frame.mask_offset += read_size;
+
+        let chunk = MessageChunk {
+            opcode: frame.opcode,
+            is_start: frame.is_start,
+            is_end: frame.is_final && frame.remaining == 0,
+            data,
+        };
+
+        if frame.remaining > 0 {
+            self.partial_frame = Some(PartialFrame {
+                is_start: false,
+                ..frame
+            });
+        }
+
+        Ok(chunk)
+    }
+
     /// Read the next message. Frame data is concatenated together.
     pub async fn next_message<'a>(
         &mut self,
@@ -444,10 +723,11 @@ impl<R: Read> SocketRx<R> {
             MessageOpcode::Binary => Message::Binary(data),
             MessageOpcode::Close => Message::Close(match data {
                 [] => None,
-                &[code] => Some((code.into(), "")),
-                [c1, c0, text @ ..] => {
-                    Some((u16::from_be_bytes([*c1, *c0]), core::str::from_utf8(text)?))
-                }
+                &[code] => Some((u16::from(code).into(), "")),
+                [c1, c0, text @ ..] => Some((
+                    u16::from_be_bytes([*c1, *c0]).into(),
+                    core::str::from_utf8(text)?,
+                )),
             }),
             MessageOpcode::Ping => Message::Ping(data),
             MessageOpcode::Pong => Message::Pong(data),
@@ -492,18 +772,69 @@ impl<W: Write> SocketTx<W> {
         self.writer.write_all(data).await
     }
 
+    /// Send a final frame, unless the first write to the socket fails, in which case none of the frame has been sent.
+    async fn try_write_frame(
+        &mut self,
+        opcode: u8,
+        data: &[u8],
+    ) -> Result<(), TrySendError<W::Error>> {
+        let first_byte = [0b10000000 | opcode];
+
+        let written_size = self.writer.write(&first_byte).await.map_err(|err| {
+            if let embedded_io_async::ErrorKind::TimedOut = embedded_io_async::Error::kind(&err) {
+                TrySendError::Stalled(err)
+            } else {
+                TrySendError::Io(err)
+            }
+        })?;
+
+        async {
+            self.writer.write_all(&first_byte[written_size..]).await?;
+            self.write_length(data.len()).await?;
+            self.writer.write_all(data).await?;
+            self.flush().await
+        }
+        .await
+        .map_err(TrySendError::Io)
+    }
+
     /// Send a text message.
+    ///
+    /// If the client stops reading messages, this will wait until the write timeout set in [Config](crate::Config) expires,
+    /// at which point an error with a kind of [ErrorKind::TimedOut](embedded_io_async::ErrorKind::TimedOut) is returned and the connection must be closed,
+    /// as part of the message might have been sent. To drop messages sent to slow clients instead, use [SocketTx::try_send_text].
     pub async fn send_text(&mut self, data: &str) -> Result<(), W::Error> {
         self.write_frame(true, 1, data.as_bytes()).await?;
         self.flush().await
     }
 
     /// Send a binary message.
+    ///
+    /// As with [SocketTx::send_text], this waits for the client to read the message. To drop messages sent to slow clients instead, use [SocketTx::try_send_binary].
     pub async fn send_binary(&mut self, data: &[u8]) -> Result<(), W::Error> {
         self.write_frame(true, 2, data).await?;
         self.flush().await
     }
 
+    /// Send a text message, unless the client is not reading messages quickly enough, such as when broadcasting to many clients.
+    ///
+    /// The write timeout set in [Config](crate::Config) applies to each write to the socket, so it sets how long to wait for a slow client.
+    /// If it is not set, this waits for the client as per [SocketTx::send_text]. If the first write of the message times out,
+    /// none of the message has been sent, so the message is dropped, [TrySendError::Stalled] is returned, and further messages may be sent.
+    /// Once part of the message has been sent, the rest of the message is written as per [SocketTx::send_text],
+    /// and any error, including a timeout, is returned as [TrySendError::Io], after which the connection must be closed.
+    ///
+    /// Whether the first write stalls depends on the socket. For example, tokio and embassy sockets accept writes while their send buffer has space,
+    /// so a message is only dropped once the send buffer is full.
+    pub async fn try_send_text(&mut self, data: &str) -> Result<(), TrySendError<W::Error>> {
+        self.try_write_frame(1, data.as_bytes()).await
+    }
+
+    /// Send a binary message, unless the client is not reading messages quickly enough. See [SocketTx::try_send_text] for more details.
+    pub async fn try_send_binary(&mut self, data: &[u8]) -> Result<(), TrySendError<W::Error>> {
+        self.try_write_frame(2, data).await
+    }
+
     /// Send the given value as UTF-8 text using its [Display](core::fmt::Display) implementation.
     /// If the message is long, the message will be sent as several frames, [Display::fmt](core::fmt::Display::fmt) will be repeatedly called
     /// so must produce the same output each time.
@@ -527,12 +858,15 @@ impl<W: Write> SocketTx<W> {
     }
 
     /// Close the connection with the given reason.
-    pub async fn close(mut self, reason: impl Into<Option<(u16, &str)>>) -> Result<(), W::Error> {
+    pub async fn close(
+        mut self,
+        reason: impl Into<Option<(CloseCode, &str)>>,
+    ) -> Result<(), W::Error> {
         self.writer.write_all(&[0b10000000 | 8]).await?; // Final Close frame
 
         match reason.into() {
             Some((code, message)) => {
-                let code_bytes = code.to_be_bytes();
+                let code_bytes = u16::from(code).to_be_bytes();
                 self.write_length(code_bytes.len() + message.len()).await?;
                 self.writer.write_all(&code_bytes).await?;
                 self.writer.write_all(message.as_bytes()).await
@@ -554,6 +888,16 @@ impl<W: Write> SocketTx<W> {
     }
 }
 
+/// Errors returned by [SocketTx::try_send_text] and [SocketTx::try_send_binary].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TrySendError<E> {
+    /// The client is not reading messages quickly enough, and none of the message was sent. Further messages may be sent.
+    Stalled(E),
+    /// The message failed to send. The connection must be closed.
+    Io(E),
+}
+
 struct FrameWriter<'w, W: Write> {
     opcode: &'w mut u8,
     tx: &'w mut SocketTx<W>,
@@ -588,7 +932,8 @@ pub trait WebSocketCallback {
 
 /// The HTTP response sent to the client, notifying it that the connection can been upgraded to a web socket connection.
 pub struct UpgradedWebSocket<P: WebSocketProtocol, C: WebSocketCallback> {
-    sec_websocket_accept: [u8; 28],
+    /// The SHA-1 hash of the key, which is sent encoded as base64
+    sec_websocket_accept: [u8; 20],
     sec_websocket_protocol: P,
     upgrade_token: crate::extract::UpgradeToken,
     callback: C,
@@ -616,7 +961,7 @@ impl<C: WebSocketCallback> UpgradedWebSocket<UnspecifiedProtocol, C> {
     }
 }
 
-impl WebSocketUpgrade {
+impl<const PROTOCOLS_SIZE: usize> WebSocketUpgrade<PROTOCOLS_SIZE> {
     /// Handle the websocket upgrade. The returned [UpgradedWebSocket] should be returned by the request handler,
     /// and thus returned to the client.
     pub fn on_upgrade<C: WebSocketCallback>(
@@ -652,9 +997,7 @@ impl<C: WebSocketCallback> super::Body for UpgradedWebSocketBody<C> {
     ) -> Result<(), W::Error> {
         self.callback
             .run(
-                SocketRx {
-                    reader: connection.upgrade(self.upgrade_token),
-                },
+                SocketRx::new(connection.upgrade(self.upgrade_token)),
                 SocketTx { writer },
             )
             .await
@@ -679,27 +1022,20 @@ impl<P: WebSocketProtocol, C: WebSocketCallback> super::IntoResponse for Upgrade
                 connection,
                 super::Response {
                     status_code: StatusCode::SWITCHING_PROTOCOLS,
-                    headers: [
-                        ("Upgrade", "websocket"),
-                        ("Connection", "upgrade"),
-                        (
-                            "Sec-WebSocket-Accept",
-                            // Safety:
-                            // sec_websocket_accept was created by data_encoding::BASE64.encode_mut, which creates a UTF-8 string
-                            #[allow(unsafe_code)]
-                            unsafe {
-                                core::str::from_utf8_unchecked(&sec_websocket_accept)
-                            },
-                        ),
-                    ],
+                    reason_phrase: None,
+                    headers: [("Upgrade", "websocket"), ("Connection", "upgrade")],
                     body: UpgradedWebSocketBody {
                         upgrade_token,
                         callback,
                     },
                 }
-                .with_headers(sec_websocket_protocol.name().map(
-                    |sec_websocket_protocol| ("Sec-WebSocket-Protocol", sec_websocket_protocol),
-                )),
+                .with_header(
+                    "Sec-WebSocket-Accept",
+                    crate::base64::Encoded(&sec_websocket_accept),
+                )
+                .with_headers(sec_websocket_protocol.name().map(|sec_websocket_protocol| {
+                    ("Sec-WebSocket-Protocol", sec_websocket_protocol)
+                })),
             )
             .await
     }
@@ -8,16 +8,30 @@
 use core::{fmt, future::IntoFuture, marker::PhantomData, str::FromStr};
 
 use crate::{
-    extract::{FromRequest, FromRequestParts},
+    extract::{self, FromRequest, FromRequestParts},
     io::Read,
     request::{Path, Request},
     response::{IntoResponse, ResponseWriter, StatusCode},
     ResponseSent,
 };
 
+mod catch_error;
+#[cfg(feature = "decompression")]
+mod decompress;
 mod layer;
-
+mod method_not_allowed;
+mod method_override;
+mod set_response_header;
+mod trace;
+
+pub use catch_error::CatchErrorLayer;
+#[cfg(feature = "decompression")]
+pub use decompress::DecompressRequestBodyLayer;
 pub use layer::{Layer, Next};
+pub use method_not_allowed::MethodNotAllowedService;
+pub use method_override::MethodOverrideLayer;
+pub use set_response_header::SetResponseHeaderLayer;
+pub use trace::TraceLayer;
 
 mod sealed {
     pub trait Sealed {}
@@ -69,7 +83,58 @@ impl<P> IntoPathParameterList for ManyPathParameters<P> {
     }
 }
 
+#[doc(hidden)]
+pub trait DisplayPathParameters: Sealed {
+    fn with_display_list<T>(&self, f: impl FnOnce(&[&dyn fmt::Display]) -> T) -> T;
+}
+
+impl<P: fmt::Display> DisplayPathParameters for OnePathParameter<P> {
+    fn with_display_list<T>(&self, f: impl FnOnce(&[&dyn fmt::Display]) -> T) -> T {
+        f(&[&self.0])
+    }
+}
+
+macro_rules! declare_display_path_parameters {
+    ($($($name:ident $index:tt)*;)*) => {
+        $(
+            impl<$($name: fmt::Display,)*> DisplayPathParameters for ManyPathParameters<($($name,)*)> {
+                fn with_display_list<T>(&self, f: impl FnOnce(&[&dyn fmt::Display]) -> T) -> T {
+                    f(&[$(&self.0.$index,)*])
+                }
+            }
+        )*
+    };
+}
+
+declare_display_path_parameters!(
+    P0 0 P1 1;
+    P0 0 P1 1 P2 2;
+    P0 0 P1 1 P2 2 P3 3;
+    P0 0 P1 1 P2 2 P3 3 P4 4;
+    P0 0 P1 1 P2 2 P3 3 P4 4 P5 5;
+    P0 0 P1 1 P2 2 P3 3 P4 4 P5 5 P6 6;
+    P0 0 P1 1 P2 2 P3 3 P4 4 P5 5 P6 6 P7 7;
+    P0 0 P1 1 P2 2 P3 3 P4 4 P5 5 P6 6 P7 7 P8 8;
+    P0 0 P1 1 P2 2 P3 3 P4 4 P5 5 P6 6 P7 7 P8 8 P9 9;
+    P0 0 P1 1 P2 2 P3 3 P4 4 P5 5 P6 6 P7 7 P8 8 P9 9 P10 10;
+    P0 0 P1 1 P2 2 P3 3 P4 4 P5 5 P6 6 P7 7 P8 8 P9 9 P10 10 P11 11;
+    P0 0 P1 1 P2 2 P3 3 P4 4 P5 5 P6 6 P7 7 P8 8 P9 9 P10 10 P11 11 P12 12;
+    P0 0 P1 1 P2 2 P3 3 P4 4 P5 5 P6 6 P7 7 P8 8 P9 9 P10 10 P11 11 P12 12 P13 13;
+    P0 0 P1 1 P2 2 P3 3 P4 4 P5 5 P6 6 P7 7 P8 8 P9 9 P10 10 P11 11 P12 12 P13 13 P14 14;
+    P0 0 P1 1 P2 2 P3 3 P4 4 P5 5 P6 6 P7 7 P8 8 P9 9 P10 10 P11 11 P12 12 P13 13 P14 14 P15 15;
+);
+
 /// Functions which can be used as a [RequestHandler].
+///
+/// The arguments which implement [FromRequestParts] are extracted one at a time, from left to right, followed by the [FromRequest] argument, if any.
+/// If an extractor fails, its rejection is sent as the response, and the extractors to its right are not run.
+/// This order is guaranteed, so extractors with side effects, such as incrementing a counter in the state, behave predictably.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a handler function for a route with path parameters of `{PathParameters}` and a state of `{State}`",
+    note = "if the route has path parameters, the first argument must be the single path parameter, or a tuple of all of them, or `extract::Path<T>`",
+    note = "the other arguments must implement `FromRequestParts`, except the last, which may implement `FromRequest`, and the function must return a future whose output implements `IntoResponse`",
+    note = "routers nested under a path with parameters inherit those parameters, see `Router::nest_parameterized`"
+)]
 pub trait RequestHandlerFunction<State, PathParameters, T> {
     /// Call the handler function and write the response to the [ResponseWriter].
     async fn call_handler_func<R: Read, W: ResponseWriter<Error = R::Error>>(
@@ -142,6 +207,39 @@ where
     }
 }
 
+impl<
+        State,
+        PathParameters: DisplayPathParameters,
+        T: serde::de::DeserializeOwned,
+        FunctionReturn: IntoFuture,
+        H: Fn(extract::Path<T>) -> FunctionReturn,
+    > RequestHandlerFunction<State, PathParameters, (extract::Path<T>, (FunctionReturn,))> for H
+where
+    FunctionReturn::Output: IntoResponse,
+{
+    async fn call_handler_func<R: Read, W: ResponseWriter<Error = R::Error>>(
+        &self,
+        _state: &State,
+        path_parameters: PathParameters,
+        request: Request<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        (self)(
+            match path_parameters.with_display_list(extract::Path::from_path_parameters) {
+                Ok(value) => value,
+                Err(err) => {
+                    return err
+                        .write_to(request.body_connection.finalize().await?, response_writer)
+                        .await
+                }
+            },
+        )
+        .await
+        .write_to(request.body_connection.finalize().await?, response_writer)
+        .await
+    }
+}
+
 macro_rules! declare_handler_func {
     ($($($name:ident)*;)*) => {
         $(
@@ -232,6 +330,39 @@ macro_rules! declare_handler_func {
                     .await
                 }
             }
+
+            impl<State, PathParameters: DisplayPathParameters, T: serde::de::DeserializeOwned, FunctionReturn: IntoFuture, $($name: for<'a> FromRequestParts<'a, State>,)* M, E: for<'a> FromRequest<'a, State, M>, H: Fn(extract::Path<T>, $($name,)* E,) -> FunctionReturn>
+                RequestHandlerFunction<State, PathParameters, (extract::Path<T>, (M, $($name,)* E, FunctionReturn))> for H
+            where
+                FunctionReturn::Output: IntoResponse,
+            {
+                #[allow(unused_variables)]
+                async fn call_handler_func<R: Read, W: ResponseWriter<Error = R::Error>>(
+                    &self,
+                    state: &State,
+                    path_parameters: PathParameters,
+                    mut request: Request<'_, R>,
+                    response_writer: W,
+                ) -> Result<ResponseSent, W::Error> {
+                    (self)(
+                        match path_parameters.with_display_list(extract::Path::from_path_parameters) {
+                            Ok(value) => value,
+                            Err(err) => return err.write_to(request.body_connection.finalize().await?, response_writer).await,
+                        },
+                        $(match <$name>::from_request_parts(state, &request.parts).await {
+                            Ok(value) => value,
+                            Err(err) => return err.write_to(request.body_connection.finalize().await?, response_writer).await,
+                        },)*
+                        match E::from_request(state, request.parts, request.body_connection.body()).await {
+                            Ok(value) => value,
+                            Err(err) => return err.write_to(request.body_connection.finalize().await?, response_writer).await,
+                        }
+                    )
+                    .await
+                    .write_to(request.body_connection.finalize().await?, response_writer)
+                    .await
+                }
+            }
         )*
     };
 }
@@ -266,6 +397,11 @@ pub trait RequestHandler<State, PathParameters>: Sealed {
         request: Request<'_, R>,
         response_writer: W,
     ) -> Result<ResponseSent, W::Error>;
+
+    /// Returns false if all requests are rejected with "Method Not Allowed", i.e. the method has no handler.
+    fn is_method_allowed(&self) -> bool {
+        true
+    }
 }
 
 struct HandlerFunctionRequestHandler<T, Handler> {
@@ -301,6 +437,52 @@ impl<State, PathParameters, T, H: RequestHandlerFunction<State, PathParameters,
 }
 
 /// A service which handles [Request]s and writes the response to the provided [ResponseWriter].
+///
+/// Unlike [RequestHandlerFunctions](RequestHandlerFunction), services may borrow from the request.
+/// Use [Request::into_parts] to read the headers and the body separately.
+///
+/// ```
+/// use picoserve::{
+///     io::Read,
+///     request::Request,
+///     response::{IntoResponse, ResponseWriter, StatusCode},
+///     routing::{post_service, RequestHandlerService},
+///     ResponseSent,
+/// };
+///
+/// struct Upload;
+///
+/// impl RequestHandlerService<()> for Upload {
+///     async fn call_request_handler_service<R: Read, W: ResponseWriter<Error = R::Error>>(
+///         &self,
+///         _state: &(),
+///         (): (),
+///         request: Request<'_, R>,
+///         response_writer: W,
+///     ) -> Result<ResponseSent, W::Error> {
+///         let (parts, mut body_connection) = request.into_parts();
+///
+///         let media_type = parts
+///             .headers()
+///             .content_type()
+///             .map_or("unknown", |content_type| content_type.media_type());
+///
+///         let Ok(body) = body_connection.body().read_all().await else {
+///             return (StatusCode::PAYLOAD_TOO_LARGE, "Body is too large\n")
+///                 .write_to(body_connection.finalize().await?, response_writer)
+///                 .await;
+///         };
+///
+///         let body_length = body.len();
+///
+///         format_args!("Received {body_length} bytes of {media_type}\n")
+///             .write_to(body_connection.finalize().await?, response_writer)
+///             .await
+///     }
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new().route("/upload", post_service(Upload));
+/// ```
 pub trait RequestHandlerService<State, PathParameters = ()> {
     /// Handle the request and write the response to the provided  [ResponseWriter].
     async fn call_request_handler_service<R: Read, W: ResponseWriter<Error = R::Error>>(
@@ -366,9 +548,13 @@ impl<State, PathParameters> RequestHandler<State, PathParameters> for MethodNotA
             .write_to(request.body_connection.finalize().await?, response_writer)
             .await
     }
+
+    fn is_method_allowed(&self) -> bool {
+        false
+    }
 }
 
-mod head_method_util {
+pub(crate) mod head_method_util {
     use embedded_io_async::Write;
 
     use crate::response::{Body, Connection, HeadersIter, Response, ResponseWriter};
@@ -399,6 +585,7 @@ mod head_method_util {
             connection: Connection<'_, R>,
             Response {
                 status_code,
+                reason_phrase,
                 headers,
                 body: _,
             }: Response<H, B>,
@@ -408,6 +595,7 @@ mod head_method_util {
                     connection,
                     Response {
                         status_code,
+                        reason_phrase,
                         headers,
                         body: EmptyBody,
                     },
@@ -433,19 +621,155 @@ pub trait MethodHandler<State, PathParameters>: Sealed {
         request: Request<'_, R>,
         response_writer: W,
     ) -> Result<ResponseSent, W::Error>;
+
+    /// Call `f` with each method which is handled.
+    fn for_each_allowed_method(&self, f: &mut dyn FnMut(&'static str));
+}
+
+/// The set of methods handled by a route, as sent in the `Allow` header of a `405 Method Not Allowed` response.
+///
+/// Displays as a comma-separated list, such as `GET, HEAD, POST`.
+///
+/// Sets of methods are built by combining constants using [or](Self::or), such as `AllowedMethods::GET.or(AllowedMethods::POST)`, and passed to [on].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AllowedMethods {
+    methods: u8,
+}
+
+impl AllowedMethods {
+    pub(crate) const NONE: Self = Self { methods: 0 };
+
+    /// The `GET` method.
+    pub const GET: Self = Self { methods: 1 << 0 };
+    /// The `HEAD` method.
+    pub const HEAD: Self = Self { methods: 1 << 1 };
+    /// The `POST` method.
+    pub const POST: Self = Self { methods: 1 << 2 };
+    /// The `PUT` method.
+    pub const PUT: Self = Self { methods: 1 << 3 };
+    /// The `DELETE` method.
+    pub const DELETE: Self = Self { methods: 1 << 4 };
+    /// The `PATCH` method.
+    pub const PATCH: Self = Self { methods: 1 << 5 };
+    /// The `OPTIONS` method.
+    pub const OPTIONS: Self = Self { methods: 1 << 6 };
+    /// The `TRACE` method.
+    pub const TRACE: Self = Self { methods: 1 << 7 };
+
+    const METHODS: [&'static str; 8] = [
+        "GET", "HEAD", "POST", "PUT", "DELETE", "PATCH", "OPTIONS", "TRACE",
+    ];
+
+    fn of<State, PathParameters>(handler: &impl MethodHandler<State, PathParameters>) -> Self {
+        let mut allowed_methods = Self::default();
+
+        handler.for_each_allowed_method(&mut |method| allowed_methods.insert(method));
+
+        allowed_methods
+    }
+
+    /// Parse the value of an `Allow` header, ignoring unknown methods.
+    pub(crate) fn from_header_value(value: impl fmt::Display) -> Self {
+        let mut allowed_methods = Self::default();
+
+        for method in Self::METHODS {
+            if crate::response::header_value_has_option(&value, method) {
+                allowed_methods.insert(method);
+            }
+        }
+
+        allowed_methods
+    }
+
+    fn insert(&mut self, method: &str) {
+        if let Some(index) = Self::METHODS.iter().position(|&m| m == method) {
+            self.methods |= 1 << index;
+        }
+    }
+
+    /// The set of all methods in either `self` or `other`.
+    pub const fn or(self, other: Self) -> Self {
+        Self {
+            methods: self.methods | other.methods,
+        }
+    }
+
+    const fn contains_get(&self) -> bool {
+        self.methods & Self::GET.methods != 0
+    }
+
+    /// Returns true if `method` is allowed.
+    pub fn contains(&self, method: &str) -> bool {
+        self.iter().any(|m| m == method)
+    }
+
+    /// Returns true if no methods are allowed.
+    pub fn is_empty(&self) -> bool {
+        self.methods == 0
+    }
+
+    /// Iterate over the allowed methods.
+    pub fn iter(&self) -> impl Iterator<Item = &'static str> {
+        let methods = self.methods;
+
+        Self::METHODS
+            .into_iter()
+            .enumerate()
+            .filter(move |(index, _)| methods & (1 << index) != 0)
+            .map(|(_, method)| method)
+    }
+}
+
+impl fmt::Display for AllowedMethods {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, method) in self.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+
+            f.write_str(method)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Write the `405 Method Not Allowed` response, including the `Allow` header.
+async fn method_not_allowed<R: Read, W: ResponseWriter<Error = R::Error>>(
+    allowed_methods: AllowedMethods,
+    request: Request<'_, R>,
+    response_writer: W,
+) -> Result<ResponseSent, W::Error> {
+    (
+        StatusCode::METHOD_NOT_ALLOWED,
+        ("Allow", allowed_methods),
+        format_args!(
+            "Method {} not allowed for {}\r\n",
+            request.parts.method(),
+            request.parts.path()
+        ),
+    )
+        .write_to(request.body_connection.finalize().await?, response_writer)
+        .await
 }
 
 /// A [MethodHandler] which routes requests to the appropriate [RequestHandler] based on the method.
 ///
 /// Automatically handled the `HEAD` method by calling the `GET` handler and returning an empty body.
-pub struct MethodRouter<GET, POST, PUT, DELETE> {
+pub struct MethodRouter<GET, POST, PUT, DELETE, PATCH, OPTIONS, TRACE> {
     get: GET,
     post: POST,
     put: PUT,
     delete: DELETE,
+    patch: PATCH,
+    options: OPTIONS,
+    trace: TRACE,
 }
 
-impl<GET, POST, PUT, DELETE> Sealed for MethodRouter<GET, POST, PUT, DELETE> {}
+impl<GET, POST, PUT, DELETE, PATCH, OPTIONS, TRACE> Sealed
+    for MethodRouter<GET, POST, PUT, DELETE, PATCH, OPTIONS, TRACE>
+{
+}
 
 /// Route `GET` requests to the given [handler](RequestHandlerFunction).
 pub fn get<State, PathParameters, T, Handler: RequestHandlerFunction<State, PathParameters, T>>(
@@ -455,12 +779,18 @@ pub fn get<State, PathParameters, T, Handler: RequestHandlerFunction<State, Path
     MethodNotAllowed,
     MethodNotAllowed,
     MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
 > {
     MethodRouter {
         get: HandlerFunctionRequestHandler::new(handler),
         post: MethodNotAllowed,
         put: MethodNotAllowed,
         delete: MethodNotAllowed,
+        patch: MethodNotAllowed,
+        options: MethodNotAllowed,
+        trace: MethodNotAllowed,
     }
 }
 
@@ -472,12 +802,18 @@ pub fn get_service<State, PathParameters: IntoPathParameterList>(
     MethodNotAllowed,
     MethodNotAllowed,
     MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
 > {
     MethodRouter {
         get: RequestHandlerServiceRequestHandler { service },
         post: MethodNotAllowed,
         put: MethodNotAllowed,
         delete: MethodNotAllowed,
+        patch: MethodNotAllowed,
+        options: MethodNotAllowed,
+        trace: MethodNotAllowed,
     }
 }
 
@@ -489,12 +825,18 @@ pub fn post<State, PathParameters, T, Handler: RequestHandlerFunction<State, Pat
     impl RequestHandler<State, PathParameters>,
     MethodNotAllowed,
     MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
 > {
     MethodRouter {
         get: MethodNotAllowed,
         post: HandlerFunctionRequestHandler::new(handler),
         put: MethodNotAllowed,
         delete: MethodNotAllowed,
+        patch: MethodNotAllowed,
+        options: MethodNotAllowed,
+        trace: MethodNotAllowed,
     }
 }
 
@@ -506,12 +848,18 @@ pub fn post_service<State, PathParameters: IntoPathParameterList>(
     impl RequestHandler<State, PathParameters>,
     MethodNotAllowed,
     MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
 > {
     MethodRouter {
         get: MethodNotAllowed,
         post: RequestHandlerServiceRequestHandler { service },
         put: MethodNotAllowed,
         delete: MethodNotAllowed,
+        patch: MethodNotAllowed,
+        options: MethodNotAllowed,
+        trace: MethodNotAllowed,
     }
 }
 
@@ -523,12 +871,18 @@ pub fn put<State, PathParameters, T, Handler: RequestHandlerFunction<State, Path
     MethodNotAllowed,
     impl RequestHandler<State, PathParameters>,
     MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
 > {
     MethodRouter {
         get: MethodNotAllowed,
         post: MethodNotAllowed,
         put: HandlerFunctionRequestHandler::new(handler),
         delete: MethodNotAllowed,
+        patch: MethodNotAllowed,
+        options: MethodNotAllowed,
+        trace: MethodNotAllowed,
     }
 }
 
@@ -540,12 +894,18 @@ pub fn put_service<State, PathParameters: IntoPathParameterList>(
     MethodNotAllowed,
     impl RequestHandler<State, PathParameters>,
     MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
 > {
     MethodRouter {
         get: MethodNotAllowed,
         post: MethodNotAllowed,
         put: RequestHandlerServiceRequestHandler { service },
         delete: MethodNotAllowed,
+        patch: MethodNotAllowed,
+        options: MethodNotAllowed,
+        trace: MethodNotAllowed,
     }
 }
 
@@ -562,12 +922,18 @@ pub fn delete<
     MethodNotAllowed,
     MethodNotAllowed,
     impl RequestHandler<State, PathParameters>,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
 > {
     MethodRouter {
         get: MethodNotAllowed,
         post: MethodNotAllowed,
         put: MethodNotAllowed,
         delete: HandlerFunctionRequestHandler::new(handler),
+        patch: MethodNotAllowed,
+        options: MethodNotAllowed,
+        trace: MethodNotAllowed,
     }
 }
 
@@ -579,16 +945,376 @@ pub fn delete_service<State, PathParameters: IntoPathParameterList>(
     MethodNotAllowed,
     MethodNotAllowed,
     impl RequestHandler<State, PathParameters>,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
 > {
     MethodRouter {
         get: MethodNotAllowed,
         post: MethodNotAllowed,
         put: MethodNotAllowed,
         delete: RequestHandlerServiceRequestHandler { service },
+        patch: MethodNotAllowed,
+        options: MethodNotAllowed,
+        trace: MethodNotAllowed,
+    }
+}
+
+/// Route `PATCH` requests to the given [handler](RequestHandlerFunction).
+pub fn patch<
+    State,
+    PathParameters,
+    T,
+    Handler: RequestHandlerFunction<State, PathParameters, T>,
+>(
+    handler: Handler,
+) -> MethodRouter<
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    impl RequestHandler<State, PathParameters>,
+    MethodNotAllowed,
+    MethodNotAllowed,
+> {
+    MethodRouter {
+        get: MethodNotAllowed,
+        post: MethodNotAllowed,
+        put: MethodNotAllowed,
+        delete: MethodNotAllowed,
+        patch: HandlerFunctionRequestHandler::new(handler),
+        options: MethodNotAllowed,
+        trace: MethodNotAllowed,
+    }
+}
+
+/// Route `PATCH` requests to the given [service](RequestHandlerService).
+pub fn patch_service<State, PathParameters: IntoPathParameterList>(
+    service: impl RequestHandlerService<State, PathParameters::ParameterList>,
+) -> MethodRouter<
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    impl RequestHandler<State, PathParameters>,
+    MethodNotAllowed,
+    MethodNotAllowed,
+> {
+    MethodRouter {
+        get: MethodNotAllowed,
+        post: MethodNotAllowed,
+        put: MethodNotAllowed,
+        delete: MethodNotAllowed,
+        patch: RequestHandlerServiceRequestHandler { service },
+        options: MethodNotAllowed,
+        trace: MethodNotAllowed,
+    }
+}
+
+/// Route `OPTIONS` requests to the given [handler](RequestHandlerFunction).
+pub fn options<
+    State,
+    PathParameters,
+    T,
+    Handler: RequestHandlerFunction<State, PathParameters, T>,
+>(
+    handler: Handler,
+) -> MethodRouter<
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    impl RequestHandler<State, PathParameters>,
+    MethodNotAllowed,
+> {
+    MethodRouter {
+        get: MethodNotAllowed,
+        post: MethodNotAllowed,
+        put: MethodNotAllowed,
+        delete: MethodNotAllowed,
+        patch: MethodNotAllowed,
+        options: HandlerFunctionRequestHandler::new(handler),
+        trace: MethodNotAllowed,
+    }
+}
+
+/// Route `OPTIONS` requests to the given [service](RequestHandlerService).
+pub fn options_service<State, PathParameters: IntoPathParameterList>(
+    service: impl RequestHandlerService<State, PathParameters::ParameterList>,
+) -> MethodRouter<
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    impl RequestHandler<State, PathParameters>,
+    MethodNotAllowed,
+> {
+    MethodRouter {
+        get: MethodNotAllowed,
+        post: MethodNotAllowed,
+        put: MethodNotAllowed,
+        delete: MethodNotAllowed,
+        patch: MethodNotAllowed,
+        options: RequestHandlerServiceRequestHandler { service },
+        trace: MethodNotAllowed,
+    }
+}
+
+/// Route `TRACE` requests to the given [handler](RequestHandlerFunction).
+pub fn trace<
+    State,
+    PathParameters,
+    T,
+    Handler: RequestHandlerFunction<State, PathParameters, T>,
+>(
+    handler: Handler,
+) -> MethodRouter<
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    impl RequestHandler<State, PathParameters>,
+> {
+    MethodRouter {
+        get: MethodNotAllowed,
+        post: MethodNotAllowed,
+        put: MethodNotAllowed,
+        delete: MethodNotAllowed,
+        patch: MethodNotAllowed,
+        options: MethodNotAllowed,
+        trace: HandlerFunctionRequestHandler::new(handler),
     }
 }
 
-impl<POST, PUT, DELETE> MethodRouter<MethodNotAllowed, POST, PUT, DELETE> {
+/// Route `TRACE` requests to the given [service](RequestHandlerService).
+pub fn trace_service<State, PathParameters: IntoPathParameterList>(
+    service: impl RequestHandlerService<State, PathParameters::ParameterList>,
+) -> MethodRouter<
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    MethodNotAllowed,
+    impl RequestHandler<State, PathParameters>,
+> {
+    MethodRouter {
+        get: MethodNotAllowed,
+        post: MethodNotAllowed,
+        put: MethodNotAllowed,
+        delete: MethodNotAllowed,
+        patch: MethodNotAllowed,
+        options: MethodNotAllowed,
+        trace: RequestHandlerServiceRequestHandler { service },
+    }
+}
+
+/// A [MethodHandler] which only accepts `HEAD` requests, responding to all other methods with "Method Not Allowed".
+///
+/// The response body is discarded, so the handler only needs to produce the status code and headers.
+/// Prefer [get] for resources which can also be fetched, as it handles `HEAD` requests automatically.
+/// Use [head] for routes which only make sense without a body, such as a cheap existence check,
+/// where generating the full body would be wasteful.
+pub struct HeadMethodRouter<HEAD> {
+    head: HEAD,
+}
+
+impl<HEAD> Sealed for HeadMethodRouter<HEAD> {}
+
+/// Route only `HEAD` requests to the given [handler](RequestHandlerFunction). See [HeadMethodRouter] for more details.
+pub fn head<State, PathParameters, T, Handler: RequestHandlerFunction<State, PathParameters, T>>(
+    handler: Handler,
+) -> HeadMethodRouter<impl RequestHandler<State, PathParameters>> {
+    HeadMethodRouter {
+        head: HandlerFunctionRequestHandler::new(handler),
+    }
+}
+
+/// Route only `HEAD` requests to the given [service](RequestHandlerService). See [HeadMethodRouter] for more details.
+pub fn head_service<State, PathParameters: IntoPathParameterList>(
+    service: impl RequestHandlerService<State, PathParameters::ParameterList>,
+) -> HeadMethodRouter<impl RequestHandler<State, PathParameters>> {
+    HeadMethodRouter {
+        head: RequestHandlerServiceRequestHandler { service },
+    }
+}
+
+impl<HEAD> HeadMethodRouter<HEAD> {
+    /// Add a [Layer] to the route
+    pub fn layer<State, PathParameters, L: Layer<State, PathParameters>>(
+        self,
+        layer: L,
+    ) -> impl MethodHandler<State, PathParameters>
+    where
+        HEAD: RequestHandler<L::NextState, L::NextPathParameters>,
+    {
+        layer::MethodRouterLayer { layer, inner: self }
+    }
+}
+
+impl<State, PathParameters, HEAD: RequestHandler<State, PathParameters>>
+    MethodHandler<State, PathParameters> for HeadMethodRouter<HEAD>
+{
+    async fn call_method_handler<R: Read, W: ResponseWriter<Error = R::Error>>(
+        &self,
+        state: &State,
+        path_parameters: PathParameters,
+        request: Request<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        let allowed_methods = AllowedMethods::of(self);
+
+        if request.parts.method() != "HEAD" {
+            method_not_allowed(allowed_methods, request, response_writer).await
+        } else if !allowed_methods.contains("HEAD") {
+            method_not_allowed(
+                allowed_methods,
+                request,
+                head_method_util::ignore_body(response_writer),
+            )
+            .await
+        } else {
+            self.head
+                .call_request_handler(
+                    state,
+                    path_parameters,
+                    request,
+                    head_method_util::ignore_body(response_writer),
+                )
+                .await
+        }
+    }
+
+    fn for_each_allowed_method(&self, f: &mut dyn FnMut(&'static str)) {
+        if RequestHandler::<State, PathParameters>::is_method_allowed(&self.head) {
+            f("HEAD");
+        }
+    }
+}
+
+/// A [MethodHandler] which routes requests with any of a set of methods to a single handler, responding to all other methods with "Method Not Allowed".
+///
+/// If the set contains `GET`, `HEAD` requests are also handled. The response body is discarded for `HEAD` requests.
+/// Use [on] when the same handler handles several methods, or the set of methods is chosen at runtime,
+/// and [MethodRouter] when each method has its own handler.
+pub struct MethodSetRouter<H> {
+    methods: AllowedMethods,
+    handler: H,
+}
+
+impl<H> Sealed for MethodSetRouter<H> {}
+
+impl<H> MethodSetRouter<H> {
+    const fn new(methods: AllowedMethods, handler: H) -> Self {
+        Self {
+            methods: if methods.contains_get() {
+                methods.or(AllowedMethods::HEAD)
+            } else {
+                methods
+            },
+            handler,
+        }
+    }
+}
+
+/// Route requests with any of `methods` to the given [handler](RequestHandlerFunction). See [MethodSetRouter] for more details.
+///
+/// ```
+/// use picoserve::routing::{on, AllowedMethods};
+///
+/// const WRITE_METHODS: AllowedMethods = AllowedMethods::POST
+///     .or(AllowedMethods::PUT)
+///     .or(AllowedMethods::PATCH);
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new()
+///     .route("/status", on(AllowedMethods::GET, || async { "OK" }))
+///     .route("/config", on(WRITE_METHODS, || async { "Updated" }));
+/// ```
+pub fn on<State, PathParameters, T, Handler: RequestHandlerFunction<State, PathParameters, T>>(
+    methods: AllowedMethods,
+    handler: Handler,
+) -> MethodSetRouter<impl RequestHandler<State, PathParameters>> {
+    MethodSetRouter::new(methods, HandlerFunctionRequestHandler::new(handler))
+}
+
+/// Route requests with any of `methods` to the given [service](RequestHandlerService). See [MethodSetRouter] for more details.
+pub fn on_service<State, PathParameters: IntoPathParameterList>(
+    methods: AllowedMethods,
+    service: impl RequestHandlerService<State, PathParameters::ParameterList>,
+) -> MethodSetRouter<impl RequestHandler<State, PathParameters>> {
+    MethodSetRouter::new(methods, RequestHandlerServiceRequestHandler { service })
+}
+
+impl<H> MethodSetRouter<H> {
+    /// Add a [Layer] to the route
+    pub fn layer<State, PathParameters, L: Layer<State, PathParameters>>(
+        self,
+        layer: L,
+    ) -> impl MethodHandler<State, PathParameters>
+    where
+        H: RequestHandler<L::NextState, L::NextPathParameters>,
+    {
+        layer::MethodRouterLayer { layer, inner: self }
+    }
+}
+
+impl<State, PathParameters, H: RequestHandler<State, PathParameters>>
+    MethodHandler<State, PathParameters> for MethodSetRouter<H>
+{
+    async fn call_method_handler<R: Read, W: ResponseWriter<Error = R::Error>>(
+        &self,
+        state: &State,
+        path_parameters: PathParameters,
+        request: Request<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        let allowed_methods = AllowedMethods::of(self);
+
+        match (
+            allowed_methods.contains(request.parts.method()),
+            request.parts.method() == "HEAD",
+        ) {
+            (true, false) => {
+                self.handler
+                    .call_request_handler(state, path_parameters, request, response_writer)
+                    .await
+            }
+            (true, true) => {
+                self.handler
+                    .call_request_handler(
+                        state,
+                        path_parameters,
+                        request,
+                        head_method_util::ignore_body(response_writer),
+                    )
+                    .await
+            }
+            (false, false) => method_not_allowed(allowed_methods, request, response_writer).await,
+            (false, true) => {
+                method_not_allowed(
+                    allowed_methods,
+                    request,
+                    head_method_util::ignore_body(response_writer),
+                )
+                .await
+            }
+        }
+    }
+
+    fn for_each_allowed_method(&self, f: &mut dyn FnMut(&'static str)) {
+        if RequestHandler::<State, PathParameters>::is_method_allowed(&self.handler) {
+            self.methods.iter().for_each(f);
+        }
+    }
+}
+
+impl<POST, PUT, DELETE, PATCH, OPTIONS, TRACE>
+    MethodRouter<MethodNotAllowed, POST, PUT, DELETE, PATCH, OPTIONS, TRACE>
+{
     /// Chain an additional [handler](RequestHandlerFunction) that will only accept `GET` requests.
     pub fn get<
         State,
@@ -598,12 +1324,23 @@ impl<POST, PUT, DELETE> MethodRouter<MethodNotAllowed, POST, PUT, DELETE> {
     >(
         self,
         handler: Handler,
-    ) -> MethodRouter<impl RequestHandler<State, PathParameters>, POST, PUT, DELETE> {
+    ) -> MethodRouter<
+        impl RequestHandler<State, PathParameters>,
+        POST,
+        PUT,
+        DELETE,
+        PATCH,
+        OPTIONS,
+        TRACE,
+    > {
         let MethodRouter {
             get: MethodNotAllowed,
             post,
             put,
             delete,
+            patch,
+            options,
+            trace,
         } = self;
 
         MethodRouter {
@@ -611,6 +1348,9 @@ impl<POST, PUT, DELETE> MethodRouter<MethodNotAllowed, POST, PUT, DELETE> {
             post,
             put,
             delete,
+            patch,
+            options,
+            trace,
         }
     }
 
@@ -618,12 +1358,23 @@ impl<POST, PUT, DELETE> MethodRouter<MethodNotAllowed, POST, PUT, DELETE> {
     pub fn get_service<State, PathParameters: IntoPathParameterList>(
         self,
         service: impl RequestHandlerService<State, PathParameters::ParameterList>,
-    ) -> MethodRouter<impl RequestHandler<State, PathParameters>, POST, PUT, DELETE> {
+    ) -> MethodRouter<
+        impl RequestHandler<State, PathParameters>,
+        POST,
+        PUT,
+        DELETE,
+        PATCH,
+        OPTIONS,
+        TRACE,
+    > {
         let MethodRouter {
             get: MethodNotAllowed,
             post,
             put,
             delete,
+            patch,
+            options,
+            trace,
         } = self;
 
         MethodRouter {
@@ -631,11 +1382,16 @@ impl<POST, PUT, DELETE> MethodRouter<MethodNotAllowed, POST, PUT, DELETE> {
             post,
             put,
             delete,
+            patch,
+            options,
+            trace,
         }
     }
 }
 
-impl<GET, PUT, DELETE> MethodRouter<GET, MethodNotAllowed, PUT, DELETE> {
+impl<GET, PUT, DELETE, PATCH, OPTIONS, TRACE>
+    MethodRouter<GET, MethodNotAllowed, PUT, DELETE, PATCH, OPTIONS, TRACE>
+{
     /// Chain an additional [handler](RequestHandlerFunction) that will only accept `POST` requests.
     pub fn post<
         State,
@@ -645,12 +1401,23 @@ impl<GET, PUT, DELETE> MethodRouter<GET, MethodNotAllowed, PUT, DELETE> {
     >(
         self,
         handler: Handler,
-    ) -> MethodRouter<GET, impl RequestHandler<State, PathParameters>, PUT, DELETE> {
+    ) -> MethodRouter<
+        GET,
+        impl RequestHandler<State, PathParameters>,
+        PUT,
+        DELETE,
+        PATCH,
+        OPTIONS,
+        TRACE,
+    > {
         let MethodRouter {
             get,
             post: MethodNotAllowed,
             put,
             delete,
+            patch,
+            options,
+            trace,
         } = self;
 
         MethodRouter {
@@ -658,6 +1425,9 @@ impl<GET, PUT, DELETE> MethodRouter<GET, MethodNotAllowed, PUT, DELETE> {
             post: HandlerFunctionRequestHandler::new(handler),
             put,
             delete,
+            patch,
+            options,
+            trace,
         }
     }
 
@@ -665,12 +1435,23 @@ impl<GET, PUT, DELETE> MethodRouter<GET, MethodNotAllowed, PUT, DELETE> {
     pub fn post_service<State, PathParameters: IntoPathParameterList>(
         self,
         service: impl RequestHandlerService<State, PathParameters::ParameterList>,
-    ) -> MethodRouter<GET, impl RequestHandler<State, PathParameters>, PUT, DELETE> {
+    ) -> MethodRouter<
+        GET,
+        impl RequestHandler<State, PathParameters>,
+        PUT,
+        DELETE,
+        PATCH,
+        OPTIONS,
+        TRACE,
+    > {
         let MethodRouter {
             get,
             post: MethodNotAllowed,
             put,
             delete,
+            patch,
+            options,
+            trace,
         } = self;
 
         MethodRouter {
@@ -678,11 +1459,16 @@ impl<GET, PUT, DELETE> MethodRouter<GET, MethodNotAllowed, PUT, DELETE> {
             post: RequestHandlerServiceRequestHandler { service },
             put,
             delete,
+            patch,
+            options,
+            trace,
         }
     }
 }
 
-impl<GET, POST, DELETE> MethodRouter<GET, POST, MethodNotAllowed, DELETE> {
+impl<GET, POST, DELETE, PATCH, OPTIONS, TRACE>
+    MethodRouter<GET, POST, MethodNotAllowed, DELETE, PATCH, OPTIONS, TRACE>
+{
     /// Chain an additional [handler](RequestHandlerFunction) that will only accept `PUT` requests.
     pub fn put<
         State,
@@ -692,12 +1478,23 @@ impl<GET, POST, DELETE> MethodRouter<GET, POST, MethodNotAllowed, DELETE> {
     >(
         self,
         handler: Handler,
-    ) -> MethodRouter<GET, POST, impl RequestHandler<State, PathParameters>, DELETE> {
+    ) -> MethodRouter<
+        GET,
+        POST,
+        impl RequestHandler<State, PathParameters>,
+        DELETE,
+        PATCH,
+        OPTIONS,
+        TRACE,
+    > {
         let MethodRouter {
             get,
             post,
             put: MethodNotAllowed,
             delete,
+            patch,
+            options,
+            trace,
         } = self;
 
         MethodRouter {
@@ -705,6 +1502,9 @@ impl<GET, POST, DELETE> MethodRouter<GET, POST, MethodNotAllowed, DELETE> {
             post,
             put: HandlerFunctionRequestHandler::new(handler),
             delete,
+            patch,
+            options,
+            trace,
         }
     }
 
@@ -712,12 +1512,23 @@ impl<GET, POST, DELETE> MethodRouter<GET, POST, MethodNotAllowed, DELETE> {
     pub fn put_service<State, PathParameters: IntoPathParameterList>(
         self,
         service: impl RequestHandlerService<State, PathParameters::ParameterList>,
-    ) -> MethodRouter<GET, POST, impl RequestHandler<State, PathParameters>, DELETE> {
+    ) -> MethodRouter<
+        GET,
+        POST,
+        impl RequestHandler<State, PathParameters>,
+        DELETE,
+        PATCH,
+        OPTIONS,
+        TRACE,
+    > {
         let MethodRouter {
             get,
             post,
             put: MethodNotAllowed,
             delete,
+            patch,
+            options,
+            trace,
         } = self;
 
         MethodRouter {
@@ -725,11 +1536,16 @@ impl<GET, POST, DELETE> MethodRouter<GET, POST, MethodNotAllowed, DELETE> {
             post,
             put: RequestHandlerServiceRequestHandler { service },
             delete,
+            patch,
+            options,
+            trace,
         }
     }
 }
 
-impl<GET, POST, PUT> MethodRouter<GET, POST, PUT, MethodNotAllowed> {
+impl<GET, POST, PUT, PATCH, OPTIONS, TRACE>
+    MethodRouter<GET, POST, PUT, MethodNotAllowed, PATCH, OPTIONS, TRACE>
+{
     /// Chain an additional [handler](RequestHandlerFunction) that will only accept `DELETE` requests.
     pub fn delete<
         State,
@@ -739,12 +1555,23 @@ impl<GET, POST, PUT> MethodRouter<GET, POST, PUT, MethodNotAllowed> {
     >(
         self,
         handler: Handler,
-    ) -> MethodRouter<GET, POST, PUT, impl RequestHandler<State, PathParameters>> {
+    ) -> MethodRouter<
+        GET,
+        POST,
+        PUT,
+        impl RequestHandler<State, PathParameters>,
+        PATCH,
+        OPTIONS,
+        TRACE,
+    > {
         let MethodRouter {
             get,
             post,
             put,
             delete: MethodNotAllowed,
+            patch,
+            options,
+            trace,
         } = self;
 
         MethodRouter {
@@ -752,6 +1579,9 @@ impl<GET, POST, PUT> MethodRouter<GET, POST, PUT, MethodNotAllowed> {
             post,
             put,
             delete: HandlerFunctionRequestHandler::new(handler),
+            patch,
+            options,
+            trace,
         }
     }
 
@@ -759,12 +1589,23 @@ impl<GET, POST, PUT> MethodRouter<GET, POST, PUT, MethodNotAllowed> {
     pub fn delete_service<State, PathParameters: IntoPathParameterList>(
         self,
         service: impl RequestHandlerService<State, PathParameters::ParameterList>,
-    ) -> MethodRouter<GET, POST, PUT, impl RequestHandler<State, PathParameters>> {
+    ) -> MethodRouter<
+        GET,
+        POST,
+        PUT,
+        impl RequestHandler<State, PathParameters>,
+        PATCH,
+        OPTIONS,
+        TRACE,
+    > {
         let MethodRouter {
             get,
             post,
             put,
             delete: MethodNotAllowed,
+            patch,
+            options,
+            trace,
         } = self;
 
         MethodRouter {
@@ -772,11 +1613,247 @@ impl<GET, POST, PUT> MethodRouter<GET, POST, PUT, MethodNotAllowed> {
             post,
             put,
             delete: RequestHandlerServiceRequestHandler { service },
+            patch,
+            options,
+            trace,
         }
     }
 }
 
-impl<GET, POST, PUT, DELETE> MethodRouter<GET, POST, PUT, DELETE> {
+impl<GET, POST, PUT, DELETE, OPTIONS, TRACE>
+    MethodRouter<GET, POST, PUT, DELETE, MethodNotAllowed, OPTIONS, TRACE>
+{
+    /// Chain an additional [handler](RequestHandlerFunction) that will only accept `PATCH` requests.
+    pub fn patch<
+        State,
+        PathParameters,
+        T,
+        Handler: RequestHandlerFunction<State, PathParameters, T>,
+    >(
+        self,
+        handler: Handler,
+    ) -> MethodRouter<
+        GET,
+        POST,
+        PUT,
+        DELETE,
+        impl RequestHandler<State, PathParameters>,
+        OPTIONS,
+        TRACE,
+    > {
+        let MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch: MethodNotAllowed,
+            options,
+            trace,
+        } = self;
+
+        MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch: HandlerFunctionRequestHandler::new(handler),
+            options,
+            trace,
+        }
+    }
+
+    /// Chain an additional [service](RequestHandlerService) that will only accept `PATCH` requests.
+    pub fn patch_service<State, PathParameters: IntoPathParameterList>(
+        self,
+        service: impl RequestHandlerService<State, PathParameters::ParameterList>,
+    ) -> MethodRouter<
+        GET,
+        POST,
+        PUT,
+        DELETE,
+        impl RequestHandler<State, PathParameters>,
+        OPTIONS,
+        TRACE,
+    > {
+        let MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch: MethodNotAllowed,
+            options,
+            trace,
+        } = self;
+
+        MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch: RequestHandlerServiceRequestHandler { service },
+            options,
+            trace,
+        }
+    }
+}
+
+impl<GET, POST, PUT, DELETE, PATCH, TRACE>
+    MethodRouter<GET, POST, PUT, DELETE, PATCH, MethodNotAllowed, TRACE>
+{
+    /// Chain an additional [handler](RequestHandlerFunction) that will only accept `OPTIONS` requests.
+    pub fn options<
+        State,
+        PathParameters,
+        T,
+        Handler: RequestHandlerFunction<State, PathParameters, T>,
+    >(
+        self,
+        handler: Handler,
+    ) -> MethodRouter<
+        GET,
+        POST,
+        PUT,
+        DELETE,
+        PATCH,
+        impl RequestHandler<State, PathParameters>,
+        TRACE,
+    > {
+        let MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch,
+            options: MethodNotAllowed,
+            trace,
+        } = self;
+
+        MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch,
+            options: HandlerFunctionRequestHandler::new(handler),
+            trace,
+        }
+    }
+
+    /// Chain an additional [service](RequestHandlerService) that will only accept `OPTIONS` requests.
+    pub fn options_service<State, PathParameters: IntoPathParameterList>(
+        self,
+        service: impl RequestHandlerService<State, PathParameters::ParameterList>,
+    ) -> MethodRouter<
+        GET,
+        POST,
+        PUT,
+        DELETE,
+        PATCH,
+        impl RequestHandler<State, PathParameters>,
+        TRACE,
+    > {
+        let MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch,
+            options: MethodNotAllowed,
+            trace,
+        } = self;
+
+        MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch,
+            options: RequestHandlerServiceRequestHandler { service },
+            trace,
+        }
+    }
+}
+
+impl<GET, POST, PUT, DELETE, PATCH, OPTIONS>
+    MethodRouter<GET, POST, PUT, DELETE, PATCH, OPTIONS, MethodNotAllowed>
+{
+    /// Chain an additional [handler](RequestHandlerFunction) that will only accept `TRACE` requests.
+    pub fn trace<
+        State,
+        PathParameters,
+        T,
+        Handler: RequestHandlerFunction<State, PathParameters, T>,
+    >(
+        self,
+        handler: Handler,
+    ) -> MethodRouter<
+        GET,
+        POST,
+        PUT,
+        DELETE,
+        PATCH,
+        OPTIONS,
+        impl RequestHandler<State, PathParameters>,
+    > {
+        let MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch,
+            options,
+            trace: MethodNotAllowed,
+        } = self;
+
+        MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch,
+            options,
+            trace: HandlerFunctionRequestHandler::new(handler),
+        }
+    }
+
+    /// Chain an additional [service](RequestHandlerService) that will only accept `TRACE` requests.
+    pub fn trace_service<State, PathParameters: IntoPathParameterList>(
+        self,
+        service: impl RequestHandlerService<State, PathParameters::ParameterList>,
+    ) -> MethodRouter<
+        GET,
+        POST,
+        PUT,
+        DELETE,
+        PATCH,
+        OPTIONS,
+        impl RequestHandler<State, PathParameters>,
+    > {
+        let MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch,
+            options,
+            trace: MethodNotAllowed,
+        } = self;
+
+        MethodRouter {
+            get,
+            post,
+            put,
+            delete,
+            patch,
+            options,
+            trace: RequestHandlerServiceRequestHandler { service },
+        }
+    }
+}
+
+impl<GET, POST, PUT, DELETE, PATCH, OPTIONS, TRACE>
+    MethodRouter<GET, POST, PUT, DELETE, PATCH, OPTIONS, TRACE>
+{
     /// Add a [Layer] to all routes in the router
     pub fn layer<State, PathParameters, L: Layer<State, PathParameters>>(
         self,
@@ -787,6 +1864,9 @@ impl<GET, POST, PUT, DELETE> MethodRouter<GET, POST, PUT, DELETE> {
         POST: RequestHandler<L::NextState, L::NextPathParameters>,
         PUT: RequestHandler<L::NextState, L::NextPathParameters>,
         DELETE: RequestHandler<L::NextState, L::NextPathParameters>,
+        PATCH: RequestHandler<L::NextState, L::NextPathParameters>,
+        OPTIONS: RequestHandler<L::NextState, L::NextPathParameters>,
+        TRACE: RequestHandler<L::NextState, L::NextPathParameters>,
     {
         layer::MethodRouterLayer { layer, inner: self }
     }
@@ -799,7 +1879,11 @@ impl<
         POST: RequestHandler<State, PathParameters>,
         PUT: RequestHandler<State, PathParameters>,
         DELETE: RequestHandler<State, PathParameters>,
-    > MethodHandler<State, PathParameters> for MethodRouter<GET, POST, PUT, DELETE>
+        PATCH: RequestHandler<State, PathParameters>,
+        OPTIONS: RequestHandler<State, PathParameters>,
+        TRACE: RequestHandler<State, PathParameters>,
+    > MethodHandler<State, PathParameters>
+    for MethodRouter<GET, POST, PUT, DELETE, PATCH, OPTIONS, TRACE>
 {
     async fn call_method_handler<R: Read, W: ResponseWriter<Error = R::Error>>(
         &self,
@@ -808,6 +1892,21 @@ impl<
         request: Request<'_, R>,
         response_writer: W,
     ) -> Result<ResponseSent, W::Error> {
+        let allowed_methods = AllowedMethods::of(self);
+
+        if !allowed_methods.contains(request.parts.method()) {
+            return if request.parts.method() == "HEAD" {
+                method_not_allowed(
+                    allowed_methods,
+                    request,
+                    head_method_util::ignore_body(response_writer),
+                )
+                .await
+            } else {
+                method_not_allowed(allowed_methods, request, response_writer).await
+            };
+        }
+
         match request.parts.method() {
             "GET" => {
                 self.get
@@ -834,21 +1933,68 @@ impl<
                     .call_request_handler(state, path_parameters, request, response_writer)
                     .await
             }
-            "DELETE" => {
-                self.delete
+            "DELETE" => {
+                self.delete
+                    .call_request_handler(state, path_parameters, request, response_writer)
+                    .await
+            }
+            "PATCH" => {
+                self.patch
+                    .call_request_handler(state, path_parameters, request, response_writer)
+                    .await
+            }
+            "OPTIONS" => {
+                self.options
                     .call_request_handler(state, path_parameters, request, response_writer)
                     .await
             }
-            _ => {
-                MethodNotAllowed
+            "TRACE" => {
+                self.trace
                     .call_request_handler(state, path_parameters, request, response_writer)
                     .await
             }
+            _ => method_not_allowed(allowed_methods, request, response_writer).await,
+        }
+    }
+
+    fn for_each_allowed_method(&self, f: &mut dyn FnMut(&'static str)) {
+        if RequestHandler::<State, PathParameters>::is_method_allowed(&self.get) {
+            f("GET");
+            f("HEAD");
+        }
+
+        if RequestHandler::<State, PathParameters>::is_method_allowed(&self.post) {
+            f("POST");
+        }
+
+        if RequestHandler::<State, PathParameters>::is_method_allowed(&self.put) {
+            f("PUT");
+        }
+
+        if RequestHandler::<State, PathParameters>::is_method_allowed(&self.delete) {
+            f("DELETE");
+        }
+
+        if RequestHandler::<State, PathParameters>::is_method_allowed(&self.patch) {
+            f("PATCH");
+        }
+
+        if RequestHandler::<State, PathParameters>::is_method_allowed(&self.options) {
+            f("OPTIONS");
+        }
+
+        if RequestHandler::<State, PathParameters>::is_method_allowed(&self.trace) {
+            f("TRACE");
         }
     }
 }
 
 /// Routes a request based on its path.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not route requests with path parameters of `{CurrentPathParameters}` and a state of `{State}`",
+    note = "a nested router must have the same state as the router it is nested in, and the path parameters captured by the path at which it is nested",
+    note = "use `Router::nest_parameterized` to create a nested router with the inferred path parameters"
+)]
 pub trait PathRouter<State = (), CurrentPathParameters = NoPathParameters>: Sealed {
     /// Handle the request and write the response to the provided  [ResponseWriter].
     async fn call_path_router<R: Read, W: ResponseWriter<Error = R::Error>>(
@@ -859,9 +2005,90 @@ pub trait PathRouter<State = (), CurrentPathParameters = NoPathParameters>: Seal
         request: Request<'_, R>,
         response_writer: W,
     ) -> Result<ResponseSent, W::Error>;
+
+    /// Call `f` with a description of each route, in the order that the routes were added, with paths prefixed by `prefix`.
+    fn describe_routes(&self, prefix: &RoutePath<'_>, f: &mut dyn FnMut(RouteDescription<'_>));
+}
+
+/// The path pattern of a route, as a chain of [PathDescription]s. Displays as the concatenated pattern, such as `/user/{u32}/posts`.
+#[derive(Clone, Copy)]
+pub struct RoutePath<'a> {
+    parent: Option<&'a RoutePath<'a>>,
+    segment: &'a dyn fmt::Display,
+}
+
+impl<'a> RoutePath<'a> {
+    /// The empty path, which all routes are prefixed by.
+    pub const ROOT: RoutePath<'static> = RoutePath {
+        parent: None,
+        segment: &"",
+    };
+
+    /// Append a segment to the path.
+    pub fn join(&'a self, segment: &'a dyn fmt::Display) -> Self {
+        Self {
+            parent: Some(self),
+            segment,
+        }
+    }
+}
+
+impl<'a> fmt::Display for RoutePath<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(parent) = self.parent {
+            parent.fmt(f)?;
+        }
+
+        self.segment.fmt(f)
+    }
+}
+
+impl<'a> fmt::Debug for RoutePath<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", format_args!("{self}"))
+    }
+}
+
+/// A description of a single route, as passed to the callback of [Router::describe].
+#[derive(Debug, Clone, Copy)]
+pub struct RouteDescription<'a> {
+    /// The path pattern of the route.
+    pub path: &'a RoutePath<'a>,
+    /// The methods handled by the route, or `None` if the route is a service, such as a [Directory](crate::response::fs::Directory),
+    /// which handles all paths starting with the path pattern, and might handle any method.
+    pub methods: Option<&'a [&'static str]>,
+}
+
+struct DescribePath<'a, PD, CurrentPathParameters> {
+    path_description: &'a PD,
+    _data: PhantomData<fn(CurrentPathParameters)>,
+}
+
+impl<'a, PD: PathDescription<CurrentPathParameters>, CurrentPathParameters>
+    DescribePath<'a, PD, CurrentPathParameters>
+{
+    fn new(path_description: &'a PD) -> Self {
+        Self {
+            path_description,
+            _data: PhantomData,
+        }
+    }
+}
+
+impl<'a, PD: PathDescription<CurrentPathParameters>, CurrentPathParameters> fmt::Display
+    for DescribePath<'a, PD, CurrentPathParameters>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.path_description.describe(f)
+    }
 }
 
 /// [RequestHandler] for unhandled paths.
+///
+/// If a route matched the path but not the method, as recorded by [RequestParts::allowed_methods](crate::request::RequestParts::allowed_methods),
+/// responds with "405 Method Not Allowed" and an `Allow` header listing the methods of those routes.
+/// Otherwise, if the path matched the shape of a route using [parse_path_segment_or_400] but a segment failed to parse,
+/// responds with "400 Bad Request" and the parse error. Otherwise responds with "404 Not Found".
 pub struct NotFound;
 
 impl Sealed for NotFound {}
@@ -875,13 +2102,34 @@ impl<State, CurrentPathParameters> PathRouter<State, CurrentPathParameters> for
         request: Request<'_, R>,
         response_writer: W,
     ) -> Result<ResponseSent, W::Error> {
-        (
-            StatusCode::NOT_FOUND,
-            format_args!("{} not found\r\n", request.parts.path()),
-        )
-            .write_to(request.body_connection.finalize().await?, response_writer)
+        let allowed_methods = request.parts.allowed_methods();
+
+        if let (true, Some(bad_path_segment)) =
+            (allowed_methods.is_empty(), request.parts.bad_path_segment)
+        {
+            bad_path_segment
+                .write_to(request.body_connection.finalize().await?, response_writer)
+                .await
+        } else if allowed_methods.is_empty() {
+            (
+                StatusCode::NOT_FOUND,
+                format_args!("{} not found\r\n", request.parts.path()),
+            )
+                .write_to(request.body_connection.finalize().await?, response_writer)
+                .await
+        } else if request.parts.method() == "HEAD" {
+            method_not_allowed(
+                allowed_methods,
+                request,
+                head_method_util::ignore_body(response_writer),
+            )
             .await
+        } else {
+            method_not_allowed(allowed_methods, request, response_writer).await
+        }
     }
+
+    fn describe_routes(&self, _prefix: &RoutePath<'_>, _f: &mut dyn FnMut(RouteDescription<'_>)) {}
 }
 
 #[doc(hidden)]
@@ -897,9 +2145,11 @@ impl<T: Copy + fmt::Debug> PathDescriptionBase for T {}
 ///     + `/foo`
 ///     + `/foo/bar`
 /// + `parse_path_segment::<T>()`, which captures a single segment and tries to parse it using the `core::str::FromStr` implementation of `T`
+/// + `parse_path_segment_or_400::<T>()`, which is like `parse_path_segment::<T>()`, but responds with "Bad Request" if the segment fails to parse and no other route matches
 /// + A tuple of types implementing PathDescription, thus allowing paths consisting of both static segments and captured segments, e.g.:
 ///     + `("/add", parse_path_segment::<i32>(), parse_path_segment::<i32>())`
 ///     + `("/user", parse_path_segment::<UserId>(), "/set_name", parse_path_segment::<UserName>())`
+/// + `optional_trailing_slash(path_description)`, which matches the path matched by `path_description`, with or without a trailing slash
 pub trait PathDescription<CurrentPathParameters>: PathDescriptionBase {
     /// The output of the parsed path description. Must implement [PushPathSegmentParameter] if not the final path description.
     type Output;
@@ -922,6 +2172,119 @@ pub trait PathDescription<CurrentPathParameters>: PathDescriptionBase {
         path: Path<'r>,
         validate: F,
     ) -> Result<T, CurrentPathParameters>;
+
+    /// Match the shape of the path without parsing path parameters, and check for any segments which should result in a "Bad Request" response if they fail to parse.
+    ///
+    /// Used to distinguish between a path which has the wrong shape, and a path which has the right shape but a bad value.
+    /// The default implementation assumes that the path does not match.
+    fn match_shape<'r>(&self, path: Path<'r>) -> PathShape<'r> {
+        let _ = path;
+
+        PathShape::Mismatch
+    }
+
+    /// Write the path pattern, as shown by [Router::describe]. The default implementation writes the [Debug](fmt::Debug) representation.
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// The result of [PathDescription::match_shape].
+pub enum PathShape<'r> {
+    /// The path does not match the shape of the path description.
+    Mismatch,
+    /// The path matches the shape of the path description.
+    Match {
+        /// The rest of the path after the matched segments.
+        remaining: Path<'r>,
+        /// The first segment which failed to parse and should result in a "Bad Request" response.
+        bad_segment: Option<BadPathSegment<'r>>,
+    },
+}
+
+/// A path segment which failed to parse. Responds with "Bad Request" and the error. Displays as the parse error.
+#[derive(Clone, Copy)]
+pub struct BadPathSegment<'r> {
+    /// The segment which failed to parse.
+    pub segment: crate::url_encoded::UrlEncodedString<'r>,
+    write_error:
+        fn(crate::url_encoded::UrlEncodedString<'_>, &mut fmt::Formatter<'_>) -> fmt::Result,
+}
+
+impl<'r> BadPathSegment<'r> {
+    /// Parse `segment` as a `T`, returning the segment if it fails to parse.
+    fn parse<T: FromStr>(segment: crate::url_encoded::UrlEncodedString<'r>) -> Option<Self>
+    where
+        T::Err: fmt::Display,
+    {
+        fn write_error<T: FromStr>(
+            segment: crate::url_encoded::UrlEncodedString<'_>,
+            f: &mut fmt::Formatter<'_>,
+        ) -> fmt::Result
+        where
+            T::Err: fmt::Display,
+        {
+            match segment.try_into_string::<128>() {
+                Ok(decoded_segment) => match decoded_segment.parse::<T>() {
+                    Ok(_) => Ok(()),
+                    Err(err) => fmt::Display::fmt(&err, f),
+                },
+                Err(err) => fmt::Display::fmt(&err, f),
+            }
+        }
+
+        let is_valid = segment
+            .try_into_string::<128>()
+            .is_ok_and(|decoded_segment| decoded_segment.parse::<T>().is_ok());
+
+        (!is_valid).then_some(Self {
+            segment,
+            write_error: write_error::<T>,
+        })
+    }
+
+    /// The same segment, but borrowing from `path`, if the segment is part of `path`.
+    pub(crate) fn within(self, path: Path<'r>) -> Option<BadPathSegment<'r>> {
+        let path = path.encoded();
+        let segment = self.segment.0;
+
+        let start = (segment.as_ptr() as usize).checked_sub(path.as_ptr() as usize)?;
+
+        Some(BadPathSegment {
+            segment: crate::url_encoded::UrlEncodedString(path.get(start..)?.get(..segment.len())?),
+            write_error: self.write_error,
+        })
+    }
+}
+
+impl<'r> fmt::Display for BadPathSegment<'r> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.write_error)(self.segment, f)
+    }
+}
+
+impl<'r> fmt::Debug for BadPathSegment<'r> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BadPathSegment")
+            .field("segment", &self.segment)
+            .field("error", &format_args!("{self}"))
+            .finish()
+    }
+}
+
+impl<'r> IntoResponse for BadPathSegment<'r> {
+    async fn write_to<R: Read, W: ResponseWriter<Error = R::Error>>(
+        self,
+        connection: crate::response::Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        (
+            StatusCode::BAD_REQUEST,
+            format_args!("Failed to parse path segment {:?}: {self}\n", self.segment),
+        )
+            .write_to(connection, response_writer)
+            .await
+    }
 }
 
 impl<'a, CurrentPathParameters> PathDescription<CurrentPathParameters> for &'a str {
@@ -938,6 +2301,20 @@ impl<'a, CurrentPathParameters> PathDescription<CurrentPathParameters> for &'a s
             None => Err(current_path_parameters),
         }
     }
+
+    fn match_shape<'r>(&self, path: Path<'r>) -> PathShape<'r> {
+        match path.strip_prefix(self) {
+            Some(remaining) => PathShape::Match {
+                remaining,
+                bad_segment: None,
+            },
+            None => PathShape::Mismatch,
+        }
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self)
+    }
 }
 
 /// The trait which powers concatinating several path parameters into a tuple of path parameters.
@@ -1077,6 +2454,130 @@ impl<CurrentPathParameters: PushPathSegmentParameter<P>, P: FromStr>
             None => Err(current_path_parameters),
         }
     }
+
+    fn match_shape<'r>(&self, path: Path<'r>) -> PathShape<'r> {
+        match path.split_first_segment() {
+            Some((_segment, remaining)) => PathShape::Match {
+                remaining,
+                bad_segment: None,
+            },
+            None => PathShape::Mismatch,
+        }
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/{{{}}}", core::any::type_name::<P>())
+    }
+}
+
+/// A [PathDescription] which parses a single segment using the implementation of `core::str::FromStr` of `T`,
+/// and responds with "Bad Request" if the path matches the route but the segment fails to parse.
+pub struct ParsePathSegmentOr400<T>(PhantomData<T>);
+
+impl<T> Clone for ParsePathSegmentOr400<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ParsePathSegmentOr400<T> {}
+
+impl<T> fmt::Debug for ParsePathSegmentOr400<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ParsePathSegmentOr400")
+    }
+}
+
+/// Parse a single segment using the implementation of `core::str::FromStr` of `T`.
+///
+/// Unlike [parse_path_segment], if the rest of the path matches the route but the segment fails to parse,
+/// and no other route handles the request, the server responds with "Bad Request" and the parse error, instead of "Not Found".
+///
+/// Other routes are tried as usual, so a route with the same shape which accepts the segment still handles the request,
+/// whether it was added before or after this route.
+pub fn parse_path_segment_or_400<T: FromStr>() -> ParsePathSegmentOr400<T>
+where
+    T::Err: fmt::Display,
+{
+    ParsePathSegmentOr400(PhantomData)
+}
+
+impl<CurrentPathParameters: PushPathSegmentParameter<P>, P: FromStr>
+    PathDescription<CurrentPathParameters> for ParsePathSegmentOr400<P>
+where
+    P::Err: fmt::Display,
+{
+    type Output = CurrentPathParameters::Output;
+
+    fn parse_and_validate<'r, T, F: FnOnce(Self::Output, Path<'r>) -> Result<T, Self::Output>>(
+        &self,
+        current_path_parameters: CurrentPathParameters,
+        path: Path<'r>,
+        f: F,
+    ) -> Result<T, CurrentPathParameters> {
+        ParsePathSegment::<P>(PhantomData).parse_and_validate(current_path_parameters, path, f)
+    }
+
+    fn match_shape<'r>(&self, path: Path<'r>) -> PathShape<'r> {
+        let Some((segment, remaining)) = path.split_first_segment() else {
+            return PathShape::Mismatch;
+        };
+
+        PathShape::Match {
+            remaining,
+            bad_segment: BadPathSegment::parse::<P>(segment),
+        }
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        PathDescription::<CurrentPathParameters>::describe(&ParsePathSegment::<P>(PhantomData), f)
+    }
+}
+
+/// A [PathDescription] which matches the path matched by `PD`, with or without a trailing slash, created by [optional_trailing_slash].
+#[derive(Clone, Copy, Debug)]
+pub struct OptionalTrailingSlash<PD>(PD);
+
+/// Match the path matched by `path_description`, with or without a trailing slash, so for example `optional_trailing_slash("/items")` matches both "/items" and "/items/".
+///
+/// See [Router::route_with_tsr].
+pub fn optional_trailing_slash<PD>(path_description: PD) -> OptionalTrailingSlash<PD> {
+    OptionalTrailingSlash(path_description)
+}
+
+impl<CurrentPathParameters, PD: PathDescription<CurrentPathParameters>>
+    PathDescription<CurrentPathParameters> for OptionalTrailingSlash<PD>
+{
+    type Output = PD::Output;
+
+    fn parse_and_validate<'r, T, F: FnOnce(Self::Output, Path<'r>) -> Result<T, Self::Output>>(
+        &self,
+        current_path_parameters: CurrentPathParameters,
+        path: Path<'r>,
+        f: F,
+    ) -> Result<T, CurrentPathParameters> {
+        self.0
+            .parse_and_validate(current_path_parameters, path, |path_parameters, path| {
+                f(path_parameters, path.strip_trailing_slash())
+            })
+    }
+
+    fn match_shape<'r>(&self, path: Path<'r>) -> PathShape<'r> {
+        match self.0.match_shape(path) {
+            PathShape::Mismatch => PathShape::Mismatch,
+            PathShape::Match {
+                remaining,
+                bad_segment,
+            } => PathShape::Match {
+                remaining: remaining.strip_trailing_slash(),
+                bad_segment,
+            },
+        }
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.describe(f)
+    }
 }
 
 impl<CurrentPathParameters> PathDescription<CurrentPathParameters> for () {
@@ -1090,6 +2591,17 @@ impl<CurrentPathParameters> PathDescription<CurrentPathParameters> for () {
     ) -> Result<T, CurrentPathParameters> {
         f(current_path_parameters, path)
     }
+
+    fn match_shape<'r>(&self, path: Path<'r>) -> PathShape<'r> {
+        PathShape::Match {
+            remaining: path,
+            bad_segment: None,
+        }
+    }
+
+    fn describe(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(())
+    }
 }
 
 macro_rules! impl_tuple_path_description {
@@ -1121,6 +2633,31 @@ macro_rules! impl_tuple_path_description {
                         |current_path_parameters, path| ($($name,)*).parse_and_validate(current_path_parameters, path, f),
                     )
                 }
+
+                #[allow(non_snake_case)]
+                fn match_shape<'r>(&self, path: Path<'r>) -> PathShape<'r> {
+                    let &(P, $($name,)*) = self;
+
+                    let PathShape::Match { remaining, bad_segment } = PathDescription::<CurrentPathParameters>::match_shape(&P, path) else {
+                        return PathShape::Mismatch;
+                    };
+
+                    match PathDescription::<P::Output>::match_shape(&($($name,)*), remaining) {
+                        PathShape::Mismatch => PathShape::Mismatch,
+                        PathShape::Match { remaining, bad_segment: rest_bad_segment } => PathShape::Match {
+                            remaining,
+                            bad_segment: bad_segment.or(rest_bad_segment),
+                        },
+                    }
+                }
+
+                #[allow(non_snake_case)]
+                fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    let &(P, $($name,)*) = self;
+
+                    PathDescription::<CurrentPathParameters>::describe(&P, f)?;
+                    PathDescription::<P::Output>::describe(&($($name,)*), f)
+                }
             }
         )*
     };
@@ -1162,23 +2699,58 @@ impl<
         request: Request<'_, R>,
         response_writer: W,
     ) -> Result<ResponseSent, W::Error> {
-        match self.path_description.parse_and_validate(
+        let method = request.parts.method();
+        let mut near_miss = AllowedMethods::NONE;
+
+        let path_parameters = self.path_description.parse_and_validate(
             current_path_parameters,
             path,
             |path_parameters, path| {
-                if path.0.is_empty() {
+                if !path.0.is_empty() {
+                    return Err(path_parameters);
+                }
+
+                let allowed_methods = AllowedMethods::of(&self.handler);
+
+                if allowed_methods.contains(method) {
                     Ok(path_parameters)
                 } else {
+                    // The path matches but the method doesn't, so give later routes for the same path a chance to handle the request
+                    near_miss = allowed_methods;
                     Err(path_parameters)
                 }
             },
-        ) {
+        );
+
+        match path_parameters {
             Ok(path_parameters) => {
+                let mut request = request;
+                let prefix = request.parts.matched_path.unwrap_or(RoutePath::ROOT);
+                let path_description = DescribePath::new(&self.path_description);
+                request.parts.matched_path = Some(prefix.join(&path_description));
+
                 self.handler
                     .call_method_handler(state, path_parameters, request, response_writer)
                     .await
             }
             Err(current_path_parameters) => {
+                let mut request = request;
+                request.parts.allowed_methods = request.parts.allowed_methods.or(near_miss);
+
+                // The "Bad Request" response is only sent if no other route handles the request
+                if request.parts.bad_path_segment.is_none() {
+                    if let PathShape::Match {
+                        remaining,
+                        bad_segment: Some(bad_segment),
+                    } = self.path_description.match_shape(path)
+                    {
+                        if remaining.0.is_empty() {
+                            request.parts.bad_path_segment =
+                                bad_segment.within(request.parts.path());
+                        }
+                    }
+                }
+
                 self.fallback
                     .call_path_router(
                         state,
@@ -1191,6 +2763,21 @@ impl<
             }
         }
     }
+
+    fn describe_routes(&self, prefix: &RoutePath<'_>, f: &mut dyn FnMut(RouteDescription<'_>)) {
+        self.fallback.describe_routes(prefix, f);
+
+        let mut methods = heapless::Vec::<&'static str, 8>::new();
+
+        self.handler.for_each_allowed_method(&mut |method| {
+            let _ = methods.push(method);
+        });
+
+        f(RouteDescription {
+            path: &prefix.join(&DescribePath::new(&self.path_description)),
+            methods: Some(&methods),
+        });
+    }
 }
 
 struct NestedService<PD, Service, Fallback> {
@@ -1219,11 +2806,16 @@ impl<
     ) -> Result<ResponseSent, W::Error> {
         match self.path_description.parse(current_path_parameters, path) {
             Ok((current_path_parameters, path)) => {
+                let mut request = request;
+                let prefix = request.parts.matched_path.unwrap_or(RoutePath::ROOT);
+                let path_description = DescribePath::new(&self.path_description);
+                request.parts.matched_path = Some(prefix.join(&path_description));
+
                 self.service
                     .call_path_router(
                         state,
                         current_path_parameters,
-                        path,
+                        path.or_root(),
                         request,
                         response_writer,
                     )
@@ -1242,6 +2834,13 @@ impl<
             }
         }
     }
+
+    fn describe_routes(&self, prefix: &RoutePath<'_>, f: &mut dyn FnMut(RouteDescription<'_>)) {
+        self.fallback.describe_routes(prefix, f);
+
+        self.service
+            .describe_routes(&prefix.join(&DescribePath::new(&self.path_description)), f);
+    }
 }
 
 /// A service which handles both path routing and subsequent request handling.
@@ -1289,6 +2888,11 @@ where
             path,
         ) {
             Ok((path_parameters, path)) => {
+                let mut request = request;
+                let prefix = request.parts.matched_path.unwrap_or(RoutePath::ROOT);
+                let path_description = DescribePath::new(&self.path_description);
+                request.parts.matched_path = Some(prefix.join(&path_description));
+
                 self.service
                     .call_request_handler_service(
                         state,
@@ -1312,6 +2916,15 @@ where
             }
         }
     }
+
+    fn describe_routes(&self, prefix: &RoutePath<'_>, f: &mut dyn FnMut(RouteDescription<'_>)) {
+        self.fallback.describe_routes(prefix, f);
+
+        f(RouteDescription {
+            path: &prefix.join(&DescribePath::new(&self.path_description)),
+            methods: None,
+        });
+    }
 }
 
 /// A [PathRouter] which forwards all requests to the provided [PathRouterService]
@@ -1345,6 +2958,13 @@ impl<
             )
             .await
     }
+
+    fn describe_routes(&self, prefix: &RoutePath<'_>, f: &mut dyn FnMut(RouteDescription<'_>)) {
+        f(RouteDescription {
+            path: prefix,
+            methods: None,
+        });
+    }
 }
 
 /// A [PathRouter] which routes requests to a [MethodHandler].
@@ -1384,10 +3004,69 @@ impl<
     }
 }
 
+impl<State, CurrentPathParameters: IntoPathParameterList>
+    Router<ServicePathRouter<crate::response::Directory>, State, CurrentPathParameters>
+{
+    /// Create a [Router] which serves a Single-Page Application from `directory`.
+    ///
+    /// Files are served as by [Directory](crate::response::Directory), including their "ETag", caching headers, and gzip-compressed copies
+    /// added using [File::with_gzip](crate::response::File::with_gzip).
+    /// The file in `directory` named `index_file` is served at the root of the directory, and to all requests which don't match a file,
+    /// so that paths handled by client-side routing can be loaded directly.
+    ///
+    /// Routes added to the router, such as an API, are matched before the directory, so are not replaced by the application.
+    /// Requests which match a route but not its method are sent "Method Not Allowed" rather than the application.
+    ///
+    /// ```
+    /// use picoserve::{
+    ///     response::{Directory, File},
+    ///     routing::get,
+    /// };
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::spa(
+    ///     const {
+    ///         Directory {
+    ///             files: &[
+    ///                 ("index.html", File::html("<script src=\"/app.js\"></script>")),
+    ///                 ("app.js", File::javascript("console.log(location.pathname)")),
+    ///             ],
+    ///             ..Directory::DEFAULT
+    ///         }
+    ///     },
+    ///     "index.html",
+    /// )
+    /// .route("/api/status", get(|| async { "OK" }));
+    /// ```
+    pub fn spa(directory: crate::response::Directory, index_file: &'static str) -> Self {
+        Self::from_service(crate::response::Directory {
+            index_file: Some(index_file),
+            not_found_file: Some(index_file),
+            ..directory
+        })
+    }
+}
+
 impl<State, CurrentPathParameters, RouterInner: PathRouter<State, CurrentPathParameters>>
     Router<RouterInner, State, CurrentPathParameters>
 {
     /// Add another route to the router
+    ///
+    /// `handler` may handle several methods, either by chaining handlers for each method, such as `get(..).post(..)`,
+    /// or by handling a set of methods with a single handler using [on].
+    ///
+    /// ```
+    /// use picoserve::routing::{get, on, AllowedMethods};
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::new()
+    ///     .route(
+    ///         "/item",
+    ///         get(|| async { "Item" }).post(|| async { "Created" }),
+    ///     )
+    ///     .route(
+    ///         "/ping",
+    ///         on(AllowedMethods::GET.or(AllowedMethods::POST), || async { "Pong" }),
+    ///     );
+    /// ```
     pub fn route<PD: PathDescription<CurrentPathParameters>>(
         self,
         path_description: PD,
@@ -1408,11 +3087,47 @@ impl<State, CurrentPathParameters, RouterInner: PathRouter<State, CurrentPathPar
         }
     }
 
-    /// Nest a [Router] at some path
+    /// Add another route to the router, which matches the path with or without a trailing slash, such as both "/items" and "/items/".
+    ///
+    /// Both forms of the path are handled directly by `handler`, rather than one being redirected to the other,
+    /// and the matched path is described without the trailing slash. This is equivalent to `self.route(optional_trailing_slash(path_description), handler)`.
+    ///
+    /// ```
+    /// use picoserve::routing::get;
+    ///
+    /// let app: picoserve::Router<_> =
+    ///     picoserve::Router::new().route_with_tsr("/items", get(|| async { "Items" }));
+    /// ```
+    pub fn route_with_tsr<PD: PathDescription<CurrentPathParameters>>(
+        self,
+        path_description: PD,
+        handler: impl MethodHandler<State, PD::Output>,
+    ) -> Router<impl PathRouter<State, CurrentPathParameters>, State, CurrentPathParameters> {
+        self.route(optional_trailing_slash(path_description), handler)
+    }
+
+    /// Nest a [Router] at some path.
+    ///
+    /// The nested router inherits the path parameters captured by `path_description`, and any captured by this router,
+    /// which are passed to its handlers before any parameters captured by the routes of the nested router.
+    /// As those types can be awkward to name, consider using [nest_parameterized](Self::nest_parameterized) if `path_description` captures parameters.
+    ///
+    /// The nested router sees the rest of the path after the prefix. A request for the prefix itself, with or without a trailing slash,
+    /// is seen as "/" by the nested router, so for example if a router is nested at "/api", requests to both "/api" and "/api/" are handled by its "/" route.
+    ///
+    /// ```
+    /// use picoserve::routing::get;
+    ///
+    /// let api = picoserve::Router::new()
+    ///     .route("/", get(|| async { "API Index" }))
+    ///     .route("/value", get(|| async { "42" }));
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::new().nest("/api", api);
+    /// ```
     pub fn nest<PD: PathDescription<CurrentPathParameters>>(
         self,
         path_description: PD,
-        router: Router<impl PathRouter<State, PD::Output>, State>,
+        router: Router<impl PathRouter<State, PD::Output>, State, PD::Output>,
     ) -> Router<impl PathRouter<State, CurrentPathParameters>, State, CurrentPathParameters> {
         let Router {
             router: fallback,
@@ -1429,6 +3144,50 @@ impl<State, CurrentPathParameters, RouterInner: PathRouter<State, CurrentPathPar
         }
     }
 
+    /// Nest a [Router] at some path, like [nest](Self::nest), but the nested router is built by `build_router`,
+    /// which is passed an empty router whose state and path parameters are inferred from this router and `path_description`.
+    ///
+    /// ```
+    /// use picoserve::routing::{get, parse_path_segment};
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::new().nest_parameterized(
+    ///     ("/user", parse_path_segment::<u32>()),
+    ///     |router| {
+    ///         router
+    ///             .route("/name", get(|user_id: u32| async move { picoserve::response::DebugValue(user_id) }))
+    ///             .route(
+    ///                 ("/post", parse_path_segment::<u16>()),
+    ///                 get(|(user_id, post_id): (u32, u16)| async move {
+    ///                     picoserve::response::DebugValue((user_id, post_id))
+    ///                 }),
+    ///             )
+    ///     },
+    /// );
+    /// ```
+    ///
+    /// Handlers of the nested routes must accept the path parameters captured by `path_description`:
+    ///
+    /// ```compile_fail
+    /// use picoserve::routing::{get, parse_path_segment};
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::new().nest_parameterized(
+    ///     ("/user", parse_path_segment::<u32>()),
+    ///     |router| router.route("/name", get(|| async { "Missing user ID" })),
+    /// );
+    /// ```
+    pub fn nest_parameterized<
+        PD: PathDescription<CurrentPathParameters>,
+        NestedRouter: PathRouter<State, PD::Output>,
+    >(
+        self,
+        path_description: PD,
+        build_router: impl FnOnce(
+            Router<NotFound, State, PD::Output>,
+        ) -> Router<NestedRouter, State, PD::Output>,
+    ) -> Router<impl PathRouter<State, CurrentPathParameters>, State, CurrentPathParameters> {
+        self.nest(path_description, build_router(Router::new()))
+    }
+
     /// Nest a [PathRouterService] at some path, like [nest](Self::nest) but accepts an arbitary service
     pub fn nest_service<PD: PathDescription<CurrentPathParameters>>(
         self,
@@ -1479,6 +3238,133 @@ impl<State, CurrentPathParameters, RouterInner: PathRouter<State, CurrentPathPar
         }
     }
 
+    /// Convert a router with a state of `State` into a router with a state of `OuterState`, such as the state of a router it will be nested in.
+    ///
+    /// For each request, the state passed to the routes is created from the outer state using [FromRef](extract::FromRef),
+    /// in the same way as the [State](extract::State) extractor, so `State` is typically a cheap-to-clone part of the outer state, such as a reference or a field.
+    /// `OuterState` is usually inferred from where the router is used.
+    ///
+    /// ```
+    /// use picoserve::{
+    ///     extract::{FromRef, State},
+    ///     routing::get,
+    /// };
+    ///
+    /// #[derive(Clone, Copy)]
+    /// struct ApiState {
+    ///     version: u32,
+    /// }
+    ///
+    /// struct AppState {
+    ///     api: ApiState,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// impl FromRef<AppState> for ApiState {
+    ///     fn from_ref(state: &AppState) -> Self {
+    ///         state.api
+    ///     }
+    /// }
+    ///
+    /// impl FromRef<AppState> for &'static str {
+    ///     fn from_ref(state: &AppState) -> Self {
+    ///         state.name
+    ///     }
+    /// }
+    ///
+    /// // The API router only knows about its own state
+    /// fn api_router() -> picoserve::Router<impl picoserve::routing::PathRouter<ApiState>, ApiState> {
+    ///     picoserve::Router::new().route(
+    ///         "/version",
+    ///         get(|State(api): State<ApiState>| async move { picoserve::response::DebugValue(api.version) }),
+    ///     )
+    /// }
+    ///
+    /// let app: picoserve::Router<_, AppState> = picoserve::Router::new()
+    ///     .route("/", get(|State(name): State<&'static str>| async move { name }))
+    ///     .nest("/api", api_router().with_state_from());
+    /// ```
+    pub fn with_state_from<OuterState>(
+        self,
+    ) -> Router<impl PathRouter<OuterState, CurrentPathParameters>, OuterState, CurrentPathParameters>
+    where
+        State: extract::FromRef<OuterState>,
+    {
+        self.layer(layer::StateFromRefLayer {
+            _inner_state: PhantomData,
+        })
+    }
+
+    /// Use `service` to write the `405 Method Not Allowed` response sent when a route matches the request path but not the request method,
+    /// for example to add headers or to change the body.
+    ///
+    /// Like [layer](Self::layer), this applies to all routes which have already been added, so should be called after adding routes.
+    /// Any `405 Method Not Allowed` response from those routes is replaced, including responses returned by handlers.
+    ///
+    /// ```
+    /// use picoserve::{
+    ///     io::Read,
+    ///     request::RequestParts,
+    ///     response::{Connection, IntoResponse, ResponseWriter, StatusCode},
+    ///     routing::{get, AllowedMethods, MethodNotAllowedService},
+    ///     ResponseSent,
+    /// };
+    ///
+    /// struct CustomMethodNotAllowed;
+    ///
+    /// impl<State> MethodNotAllowedService<State> for CustomMethodNotAllowed {
+    ///     async fn call_method_not_allowed_service<R: Read, W: ResponseWriter<Error = R::Error>>(
+    ///         &self,
+    ///         _state: &State,
+    ///         request_parts: RequestParts<'_>,
+    ///         allowed_methods: AllowedMethods,
+    ///         connection: Connection<'_, R>,
+    ///         response_writer: W,
+    ///     ) -> Result<ResponseSent, W::Error> {
+    ///         (
+    ///             StatusCode::METHOD_NOT_ALLOWED,
+    ///             ("Allow", allowed_methods),
+    ///             format_args!("{} only supports {allowed_methods}\n", request_parts.path()),
+    ///         )
+    ///             .write_to(connection, response_writer)
+    ///             .await
+    ///     }
+    /// }
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::new()
+    ///     .route("/", get(|| async { "Hello World" }))
+    ///     .method_not_allowed(CustomMethodNotAllowed);
+    /// ```
+    pub fn method_not_allowed(
+        self,
+        service: impl MethodNotAllowedService<State>,
+    ) -> Router<impl PathRouter<State, CurrentPathParameters>, State, CurrentPathParameters> {
+        self.layer(method_not_allowed::MethodNotAllowedLayer { service })
+    }
+
+    /// Call `f` with a description of each route, in the order that the routes were added.
+    ///
+    /// Routes in nested routers are described with the full path pattern, and within the enclosing router,
+    /// are described at the point that the nested router was added.
+    ///
+    /// ```
+    /// use picoserve::routing::{get, parse_path_segment};
+    ///
+    /// let app: picoserve::Router<_> = picoserve::Router::new()
+    ///     .route("/", get(|| async { "Hello World" }))
+    ///     .route(
+    ///         ("/user", parse_path_segment::<u32>()),
+    ///         get(|id: u32| async move { picoserve::response::DebugValue(id) }),
+    ///     );
+    ///
+    /// app.describe(|route| {
+    ///     println!("{} {:?}", route.path, route.methods);
+    /// });
+    /// ```
+    pub fn describe(&self, mut f: impl FnMut(RouteDescription<'_>)) {
+        self.router.describe_routes(&RoutePath::ROOT, &mut f)
+    }
+
     pub async fn handle_request<R: Read<Error = W::Error>, W: ResponseWriter>(
         &self,
         state: &State,
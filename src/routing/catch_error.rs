@@ -0,0 +1,136 @@
+use core::marker::PhantomData;
+
+use crate::{
+    io::Read,
+    request::RequestParts,
+    response::{Body, Connection, HeadersIter, IntoResponse, Response, ResponseWriter},
+    ResponseSent,
+};
+
+use super::{head_method_util, Layer, Next};
+
+/// A [Layer] which maps errors of type `E` returned by handlers as a [HandlerError](crate::response::HandlerError) to responses,
+/// so that application errors are converted into responses in a single place.
+///
+/// Only the response is observed by the layer, so handlers must return `Result<T, HandlerError<E>>`, or another response which writes a `HandlerError<E>`.
+/// Other responses, including errors of other types, are sent unchanged.
+///
+/// ```
+/// use picoserve::{
+///     response::{HandlerError, StatusCode},
+///     routing::{get, parse_path_segment, CatchErrorLayer},
+/// };
+///
+/// enum AppError {
+///     NotFound,
+///     SensorOffline(u8),
+/// }
+///
+/// fn read_sensor(id: u8) -> Result<u16, AppError> {
+///     match id {
+///         0 => Ok(42),
+///         1 => Err(AppError::SensorOffline(id)),
+///         _ => Err(AppError::NotFound),
+///     }
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new()
+///     .route(
+///         ("/sensor", parse_path_segment::<u8>()),
+///         get(|id: u8| async move {
+///             let value = read_sensor(id)?;
+///
+///             Ok::<_, HandlerError<AppError>>(picoserve::response::DebugValue(value))
+///         }),
+///     )
+///     .layer(CatchErrorLayer::new(|error: AppError| match error {
+///         AppError::NotFound => (StatusCode::NOT_FOUND, "No such sensor\n"),
+///         AppError::SensorOffline(..) => (StatusCode::SERVICE_UNAVAILABLE, "Sensor is offline\n"),
+///     }));
+/// ```
+pub struct CatchErrorLayer<E, F> {
+    map_error: F,
+    _error: PhantomData<fn(E)>,
+}
+
+impl<E: 'static, F: Fn(E) -> T, T: IntoResponse> CatchErrorLayer<E, F> {
+    /// Map errors of type `E` to responses using `map_error`.
+    pub const fn new(map_error: F) -> Self {
+        Self {
+            map_error,
+            _error: PhantomData,
+        }
+    }
+}
+
+struct CatchErrorResponseWriter<'a, E, F, W> {
+    map_error: &'a F,
+    request_parts: RequestParts<'a>,
+    response_writer: W,
+    _error: PhantomData<fn(E)>,
+}
+
+impl<'a, E: 'static, F: Fn(E) -> T, T: IntoResponse, W: ResponseWriter> ResponseWriter
+    for CatchErrorResponseWriter<'a, E, F, W>
+{
+    type Error = W::Error;
+
+    async fn write_response<R: Read<Error = Self::Error>, H: HeadersIter, B: Body>(
+        self,
+        mut connection: Connection<'_, R>,
+        response: Response<H, B>,
+    ) -> Result<ResponseSent, Self::Error> {
+        let Some(error) = connection.take_handler_error::<E>() else {
+            return self
+                .response_writer
+                .write_response(connection, response)
+                .await;
+        };
+
+        let response = (self.map_error)(error);
+
+        if self.request_parts.method() == "HEAD" {
+            response
+                .write_to(
+                    connection,
+                    head_method_util::ignore_body(self.response_writer),
+                )
+                .await
+        } else {
+            response.write_to(connection, self.response_writer).await
+        }
+    }
+}
+
+impl<State, PathParameters, E: 'static, F: Fn(E) -> T, T: IntoResponse> Layer<State, PathParameters>
+    for CatchErrorLayer<E, F>
+{
+    type NextState = State;
+    type NextPathParameters = PathParameters;
+
+    async fn call_layer<
+        'a,
+        R: Read + 'a,
+        NextLayer: Next<'a, R, Self::NextState, Self::NextPathParameters>,
+        W: ResponseWriter<Error = R::Error>,
+    >(
+        &self,
+        next: NextLayer,
+        state: &State,
+        path_parameters: PathParameters,
+        request_parts: RequestParts<'_>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        next.run(
+            state,
+            path_parameters,
+            CatchErrorResponseWriter {
+                map_error: &self.map_error,
+                request_parts,
+                response_writer,
+                _error: PhantomData,
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{
+    inflate::{inflate_gzip, inflate_zlib, InflateError},
+    io::Read,
+    request::{ReadAllBodyError, RequestParts},
+    response::{IntoResponse, StatusCode},
+    ResponseSent,
+};
+
+use super::{Layer, Next, ResponseWriter};
+
+#[derive(Clone, Copy)]
+enum ContentCoding {
+    Gzip,
+    Deflate,
+}
+
+/// A [Layer] which decompresses request bodies sent with a "Content-Encoding" of "gzip" or "deflate", so that handlers read the decompressed body.
+/// Requires the `decompression` feature.
+///
+/// Requests whose decompressed body is larger than `MAX_BODY_SIZE` are rejected with "413 Payload Too Large" once the limit is reached,
+/// so a small compressed body can't expand without limit (a "zip bomb").
+///
+/// Requests without a "Content-Encoding" header, or with a "Content-Encoding" of "identity", are passed on unchanged.
+/// Requests with any other content coding are rejected with "415 Unsupported Media Type".
+///
+/// The headers seen by the handler are unchanged, so "Content-Encoding" and "Content-Length" describe the compressed body.
+/// Use [RequestBody::content_length](crate::request::RequestBody::content_length) for the length of the decompressed body.
+///
+/// # Memory Usage
+///
+/// The compressed body is read into the HTTP buffer, and then decompressed into a `[u8; MAX_BODY_SIZE]` array,
+/// which is part of the request handling future, not the HTTP buffer, and is held while the request is handled.
+/// So every request handling future of a router with this layer is at least `MAX_BODY_SIZE` bytes larger, including for requests which aren't compressed,
+/// which on embedded targets is usually memory reserved for each socket task. Keep `MAX_BODY_SIZE` small, or only add this layer to the routes which need it.
+///
+/// While decompressing, a further 10KiB or so of stack is used by the decompressor.
+///
+/// ```
+/// use picoserve::{
+///     extract::body::{Body, Json},
+///     response::DebugValue,
+///     routing::{post, DecompressRequestBodyLayer},
+/// };
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Reading {
+///     sensor: u8,
+///     value: f32,
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new()
+///     .route(
+///         "/reading",
+///         post(|Body(reading, _): Body<Reading, Json>| async move { DebugValue(reading) }),
+///     )
+///     .layer(DecompressRequestBodyLayer::<1024>);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DecompressRequestBodyLayer<const MAX_BODY_SIZE: usize>;
+
+impl<const MAX_BODY_SIZE: usize> DecompressRequestBodyLayer<MAX_BODY_SIZE> {
+    /// Returns the content coding of the body, `Ok(None)` if the body is not encoded, or `Err(())` if the content coding is not supported.
+    fn content_coding(request_parts: &RequestParts) -> Result<Option<ContentCoding>, ()> {
+        let mut content_coding = None;
+
+        for coding in request_parts.headers().get_list("Content-Encoding") {
+            let coding = coding.trim();
+
+            if coding.eq_ignore_ascii_case("identity") {
+                continue;
+            }
+
+            if content_coding.is_some() {
+                return Err(());
+            }
+
+            content_coding = Some(
+                if coding.eq_ignore_ascii_case("gzip") || coding.eq_ignore_ascii_case("x-gzip") {
+                    ContentCoding::Gzip
+                } else if coding.eq_ignore_ascii_case("deflate") {
+                    ContentCoding::Deflate
+                } else {
+                    return Err(());
+                },
+            );
+        }
+
+        Ok(content_coding)
+    }
+}
+
+impl<State, PathParameters, const MAX_BODY_SIZE: usize> Layer<State, PathParameters>
+    for DecompressRequestBodyLayer<MAX_BODY_SIZE>
+{
+    type NextState = State;
+    type NextPathParameters = PathParameters;
+
+    async fn call_layer<
+        'a,
+        R: Read + 'a,
+        NextLayer: Next<'a, R, Self::NextState, Self::NextPathParameters>,
+        W: ResponseWriter<Error = R::Error>,
+    >(
+        &self,
+        mut next: NextLayer,
+        state: &State,
+        path_parameters: PathParameters,
+        request_parts: RequestParts<'_>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        let content_coding = match Self::content_coding(&request_parts) {
+            Ok(Some(content_coding)) => content_coding,
+            Ok(None) => return next.run(state, path_parameters, response_writer).await,
+            Err(()) => {
+                return (
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    ("Accept-Encoding", "gzip, deflate"),
+                    "Unsupported Content-Encoding\n",
+                )
+                    .write_to(next.into_connection().await?, response_writer)
+                    .await
+            }
+        };
+
+        let mut body = [0; MAX_BODY_SIZE];
+
+        let decompressed = match next.body_connection().body().read_all().await {
+            Ok(compressed_body) => match content_coding {
+                ContentCoding::Gzip => inflate_gzip(compressed_body, &mut body),
+                ContentCoding::Deflate => inflate_zlib(compressed_body, &mut body),
+            },
+            Err(ReadAllBodyError::BufferIsTooSmall) => Err(InflateError::OutputTooLarge),
+            Err(ReadAllBodyError::UnexpectedEof) => Err(InflateError::InvalidData),
+            Err(ReadAllBodyError::IO(err)) => return Err(err),
+        };
+
+        match decompressed {
+            Ok(length) => {
+                next.run_with_body(&mut body[..length], state, path_parameters, response_writer)
+                    .await
+            }
+            Err(InflateError::OutputTooLarge) => {
+                (StatusCode::PAYLOAD_TOO_LARGE, "Body is too large\n")
+                    .write_to(next.into_connection().await?, response_writer)
+                    .await
+            }
+            Err(InflateError::InvalidData) => {
+                (
+                    StatusCode::BAD_REQUEST,
+                    "Body is not correctly compressed\n",
+                )
+                    .write_to(next.into_connection().await?, response_writer)
+                    .await
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     io::Read,
-    request::{Path, Request, RequestParts},
+    request::{Path, Request, RequestBodyConnection, RequestParts},
     ResponseSent,
 };
 
@@ -16,8 +16,25 @@ pub trait Next<'a, R: Read + 'a, State, PathParameters>: Sealed + Sized {
         response_writer: W,
     ) -> Result<ResponseSent, W::Error>;
 
+    /// Override the method of the request as seen by the next layer, such as when routing the request.
+    fn with_method(self, method: &'static str) -> Self;
+
+    /// Take the request without running the next layer, such as when the layer sends a response itself.
     fn into_request(self) -> Request<'a, R>;
 
+    /// Access the request body before running the next layer, such as to decode it and pass the decoded body to the next layer using [Next::run_with_body].
+    fn body_connection(&mut self) -> &mut RequestBodyConnection<'a, R>;
+
+    /// Discard the rest of the request body and run the next layer with `body` as the request body, such as a decompressed copy of the original body.
+    async fn run_with_body<W: ResponseWriter<Error = R::Error>>(
+        self,
+        body: &mut [u8],
+        state: &State,
+        path_parameters: PathParameters,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error>;
+
+    /// Take the connection without running the next layer, discarding the request body, so that the layer can send a response itself.
     async fn into_connection(
         self,
     ) -> Result<crate::response::Connection<'a, impl Read<Error = R::Error>>, R::Error> {
@@ -31,12 +48,60 @@ pub trait Next<'a, R: Read + 'a, State, PathParameters>: Sealed + Sized {
 /// + inspect the request before it is passed to the inner handler
 /// + send a different state to the inner handler than the state passed to the layer
 /// + send different path parameters to the inner handler than the path parameters passed to the layer
+/// + send a different request body to the inner handler, such as a decompressed copy of the request body
 /// + send a response instead of passing the request to the inner handler
 /// + send a different response than the one returned by the inner handler
 /// + and more...
 ///
 /// To modify the response, create a struct that implements [ResponseWriter] and wraps `response_writer`,
 /// and pass an instance of that struct to `next`
+///
+/// To send a response instead of passing the request to the inner handler, call [Next::into_connection] and write the response to `response_writer`.
+///
+/// ```
+/// use picoserve::{
+///     io::Read,
+///     request::RequestParts,
+///     response::{IntoResponse, ResponseWriter, StatusCode},
+///     routing::{get, Layer, Next},
+///     ResponseSent,
+/// };
+///
+/// struct RequireAuthorization;
+///
+/// impl<State, PathParameters> Layer<State, PathParameters> for RequireAuthorization {
+///     type NextState = State;
+///     type NextPathParameters = PathParameters;
+///
+///     async fn call_layer<
+///         'a,
+///         R: Read + 'a,
+///         NextLayer: Next<'a, R, Self::NextState, Self::NextPathParameters>,
+///         W: ResponseWriter<Error = R::Error>,
+///     >(
+///         &self,
+///         next: NextLayer,
+///         state: &State,
+///         path_parameters: PathParameters,
+///         request_parts: RequestParts<'_>,
+///         response_writer: W,
+///     ) -> Result<ResponseSent, W::Error> {
+///         let authorization = request_parts.headers().get("Authorization");
+///
+///         if authorization.is_some_and(|authorization| authorization.as_raw() == b"Bearer secret") {
+///             next.run(state, path_parameters, response_writer).await
+///         } else {
+///             (StatusCode::UNAUTHORIZED, "Unauthorized\n")
+///                 .write_to(next.into_connection().await?, response_writer)
+///                 .await
+///         }
+///     }
+/// }
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new()
+///     .route("/secret", get(|| async { "Secret" }))
+///     .layer(RequireAuthorization);
+/// ```
 pub trait Layer<State, PathParameters> {
     /// The state passed to the next layer
     type NextState;
@@ -80,9 +145,48 @@ impl<'a, R: Read, State, PathParameters, N: MethodHandler<State, PathParameters>
             .await
     }
 
+    fn with_method(mut self, method: &'static str) -> Self {
+        self.request.parts.method = method;
+        self
+    }
+
     fn into_request(self) -> Request<'a, R> {
         self.request
     }
+
+    fn body_connection(&mut self) -> &mut RequestBodyConnection<'a, R> {
+        &mut self.request.body_connection
+    }
+
+    async fn run_with_body<W: ResponseWriter<Error = R::Error>>(
+        self,
+        body: &mut [u8],
+        state: &State,
+        path_parameters: PathParameters,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        let (parts, body_connection) = self.request.into_parts();
+
+        let trailers = body_connection.trailers();
+
+        let mut connection = body_connection.finalize().await?;
+
+        self.next
+            .call_method_handler(
+                state,
+                path_parameters,
+                Request {
+                    parts,
+                    body_connection: RequestBodyConnection::from_buffered_body(
+                        body,
+                        trailers,
+                        &mut connection,
+                    ),
+                },
+                response_writer,
+            )
+            .await
+    }
 }
 
 pub(crate) struct MethodRouterLayer<L, I> {
@@ -121,6 +225,10 @@ impl<
             )
             .await
     }
+
+    fn for_each_allowed_method(&self, f: &mut dyn FnMut(&'static str)) {
+        self.inner.for_each_allowed_method(f)
+    }
 }
 
 struct NextPathRouterLayer<'a, R: Read, N> {
@@ -151,9 +259,49 @@ impl<'a, R: Read, State, CurrentPathParameters, N: PathRouter<State, CurrentPath
             .await
     }
 
+    fn with_method(mut self, method: &'static str) -> Self {
+        self.request.parts.method = method;
+        self
+    }
+
     fn into_request(self) -> Request<'a, R> {
         self.request
     }
+
+    fn body_connection(&mut self) -> &mut RequestBodyConnection<'a, R> {
+        &mut self.request.body_connection
+    }
+
+    async fn run_with_body<W: ResponseWriter<Error = R::Error>>(
+        self,
+        body: &mut [u8],
+        state: &State,
+        current_path_parameters: CurrentPathParameters,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        let (parts, body_connection) = self.request.into_parts();
+
+        let trailers = body_connection.trailers();
+
+        let mut connection = body_connection.finalize().await?;
+
+        self.next
+            .call_path_router(
+                state,
+                current_path_parameters,
+                self.path,
+                Request {
+                    parts,
+                    body_connection: RequestBodyConnection::from_buffered_body(
+                        body,
+                        trailers,
+                        &mut connection,
+                    ),
+                },
+                response_writer,
+            )
+            .await
+    }
 }
 
 pub(crate) struct PathRouterLayer<L, I> {
@@ -194,4 +342,45 @@ impl<
             )
             .await
     }
+
+    fn describe_routes(
+        &self,
+        prefix: &super::RoutePath<'_>,
+        f: &mut dyn FnMut(super::RouteDescription<'_>),
+    ) {
+        self.inner.describe_routes(prefix, f)
+    }
+}
+
+/// A [Layer] which passes the part of the outer state extracted using [FromRef](crate::extract::FromRef) to the next layer.
+pub(crate) struct StateFromRefLayer<InnerState> {
+    pub(crate) _inner_state: core::marker::PhantomData<fn() -> InnerState>,
+}
+
+impl<OuterState, PathParameters, InnerState: crate::extract::FromRef<OuterState>>
+    Layer<OuterState, PathParameters> for StateFromRefLayer<InnerState>
+{
+    type NextState = InnerState;
+    type NextPathParameters = PathParameters;
+
+    async fn call_layer<
+        'a,
+        R: Read + 'a,
+        NextLayer: Next<'a, R, Self::NextState, Self::NextPathParameters>,
+        W: ResponseWriter<Error = R::Error>,
+    >(
+        &self,
+        next: NextLayer,
+        state: &OuterState,
+        path_parameters: PathParameters,
+        _request_parts: RequestParts<'_>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        next.run(
+            &InnerState::from_ref(state),
+            path_parameters,
+            response_writer,
+        )
+        .await
+    }
 }
@@ -0,0 +1,154 @@
+use core::{convert::Infallible, fmt};
+
+use crate::{
+    io::Read,
+    request::RequestParts,
+    response::{
+        Body, Connection, ForEachHeader, HeadersIter, Response, ResponseWriter, StatusCode,
+    },
+    ResponseSent,
+};
+
+use super::{head_method_util, AllowedMethods, Layer, Next};
+
+/// A service which writes the `405 Method Not Allowed` response when a route matches the request path, but does not handle the request method.
+///
+/// Set using [Router::method_not_allowed](super::Router::method_not_allowed).
+///
+/// The request body has already been read and discarded when the service is called.
+pub trait MethodNotAllowedService<State = ()> {
+    /// Write the response to the provided [ResponseWriter].
+    ///
+    /// `allowed_methods` are the methods handled by the matched route, and should be sent in the `Allow` header.
+    async fn call_method_not_allowed_service<R: Read, W: ResponseWriter<Error = R::Error>>(
+        &self,
+        state: &State,
+        request_parts: RequestParts<'_>,
+        allowed_methods: AllowedMethods,
+        connection: Connection<'_, R>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error>;
+}
+
+/// Finds the value of the "Allow" header.
+struct FindAllowHeader {
+    allowed_methods: AllowedMethods,
+}
+
+impl ForEachHeader for FindAllowHeader {
+    type Output = AllowedMethods;
+    type Error = Infallible;
+
+    async fn call<Value: fmt::Display>(
+        &mut self,
+        name: &str,
+        value: Value,
+    ) -> Result<(), Self::Error> {
+        if name.eq_ignore_ascii_case("Allow") {
+            self.allowed_methods = AllowedMethods::from_header_value(value);
+        }
+
+        Ok(())
+    }
+
+    async fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.allowed_methods)
+    }
+}
+
+struct MethodNotAllowedResponseWriter<'a, State, Service, W> {
+    service: &'a Service,
+    state: &'a State,
+    request_parts: RequestParts<'a>,
+    response_writer: W,
+}
+
+impl<'a, State, Service: MethodNotAllowedService<State>, W: ResponseWriter> ResponseWriter
+    for MethodNotAllowedResponseWriter<'a, State, Service, W>
+{
+    type Error = W::Error;
+
+    async fn write_response<R: Read<Error = Self::Error>, H: HeadersIter, B: Body>(
+        self,
+        connection: Connection<'_, R>,
+        response: Response<H, B>,
+    ) -> Result<ResponseSent, Self::Error> {
+        if response.status_code != StatusCode::METHOD_NOT_ALLOWED {
+            return self
+                .response_writer
+                .write_response(connection, response)
+                .await;
+        }
+
+        let allowed_methods = match response
+            .headers
+            .for_each_header(FindAllowHeader {
+                allowed_methods: AllowedMethods::default(),
+            })
+            .await
+        {
+            Ok(allowed_methods) => allowed_methods,
+            Err(infallible) => match infallible {},
+        };
+
+        if self.request_parts.method() == "HEAD" {
+            self.service
+                .call_method_not_allowed_service(
+                    self.state,
+                    self.request_parts,
+                    allowed_methods,
+                    connection,
+                    head_method_util::ignore_body(self.response_writer),
+                )
+                .await
+        } else {
+            self.service
+                .call_method_not_allowed_service(
+                    self.state,
+                    self.request_parts,
+                    allowed_methods,
+                    connection,
+                    self.response_writer,
+                )
+                .await
+        }
+    }
+}
+
+/// Replaces `405 Method Not Allowed` responses with the response written by a [MethodNotAllowedService].
+pub(crate) struct MethodNotAllowedLayer<Service> {
+    pub(crate) service: Service,
+}
+
+impl<State, PathParameters, Service: MethodNotAllowedService<State>> Layer<State, PathParameters>
+    for MethodNotAllowedLayer<Service>
+{
+    type NextState = State;
+    type NextPathParameters = PathParameters;
+
+    async fn call_layer<
+        'a,
+        R: Read + 'a,
+        NextLayer: Next<'a, R, Self::NextState, Self::NextPathParameters>,
+        W: ResponseWriter<Error = R::Error>,
+    >(
+        &self,
+        next: NextLayer,
+        state: &State,
+        path_parameters: PathParameters,
+        request_parts: RequestParts<'_>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        next.run(
+            state,
+            path_parameters,
+            MethodNotAllowedResponseWriter {
+                service: &self.service,
+                state,
+                request_parts,
+                response_writer,
+            },
+        )
+        .await
+    }
+}
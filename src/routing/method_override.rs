@@ -0,0 +1,71 @@
+use crate::{io::Read, request::RequestParts, ResponseSent};
+
+use super::{Layer, Next, ResponseWriter};
+
+/// A [Layer] which allows HTML forms, which can only send GET and POST requests, to send PUT, PATCH, and DELETE requests.
+///
+/// For POST requests, the method used when routing the request is overridden by either:
+/// + the "X-HTTP-Method-Override" header, or
+/// + the `_method` query parameter, e.g. `<form method="post" action="/item/1?_method=DELETE">`.
+///
+/// A `_method` field in the request body is not used, as reading it would consume the body before it reaches the handler.
+///
+/// Only PUT, PATCH, and DELETE are accepted as overrides, and other requests are passed on unchanged.
+///
+/// # Security
+///
+/// Any client, including a cross-site form submission, can use this to send PUT, PATCH, and DELETE requests,
+/// which would otherwise be blocked by the browser without a CORS preflight request.
+/// Handlers of overridable methods should have the same CSRF protection as POST handlers.
+/// Proxies and firewalls in front of the server see the original POST method, so cannot filter by the overridden method.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MethodOverrideLayer;
+
+impl MethodOverrideLayer {
+    fn overridden_method(request_parts: &RequestParts) -> Option<&'static str> {
+        if !request_parts.method().eq_ignore_ascii_case("post") {
+            return None;
+        }
+
+        let requested_method =
+            if let Some(method) = request_parts.headers().get("X-HTTP-Method-Override") {
+                core::str::from_utf8(method.as_raw()).ok()?
+            } else {
+                request_parts
+                    .query()?
+                    .0
+                    .split('&')
+                    .find_map(|parameter| parameter.strip_prefix("_method="))?
+            };
+
+        ["PUT", "PATCH", "DELETE"]
+            .into_iter()
+            .find(|method| method.eq_ignore_ascii_case(requested_method.trim()))
+    }
+}
+
+impl<State, PathParameters> Layer<State, PathParameters> for MethodOverrideLayer {
+    type NextState = State;
+    type NextPathParameters = PathParameters;
+
+    async fn call_layer<
+        'a,
+        R: Read + 'a,
+        NextLayer: Next<'a, R, Self::NextState, Self::NextPathParameters>,
+        W: ResponseWriter<Error = R::Error>,
+    >(
+        &self,
+        next: NextLayer,
+        state: &State,
+        path_parameters: PathParameters,
+        request_parts: RequestParts<'_>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        match Self::overridden_method(&request_parts) {
+            Some(method) => next.with_method(method),
+            None => next,
+        }
+        .run(state, path_parameters, response_writer)
+        .await
+    }
+}
@@ -0,0 +1,165 @@
+use core::fmt;
+
+use crate::{
+    io::Read,
+    request::RequestParts,
+    response::{Body, Connection, ForEachHeader, HeadersIter, Response, ResponseWriter},
+    ResponseSent,
+};
+
+use super::{Layer, Next};
+
+/// A [Layer] which adds a fixed set of headers to every response, such as security headers.
+///
+/// The headers are written after the headers set by the handler, and a header is skipped if the handler has already set a header with the same name (ignoring ASCII case),
+/// so handlers can override the defaults.
+///
+/// ```
+/// use picoserve::routing::{get, SetResponseHeaderLayer};
+///
+/// let app: picoserve::Router<_> = picoserve::Router::new()
+///     .route("/", get(|| async { "Hello World" }))
+///     .layer(SetResponseHeaderLayer::new([
+///         ("X-Content-Type-Options", "nosniff"),
+///         ("X-Frame-Options", "DENY"),
+///         ("Referrer-Policy", "no-referrer"),
+///     ]));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SetResponseHeaderLayer<const N: usize> {
+    headers: [(&'static str, &'static str); N],
+}
+
+impl<const N: usize> SetResponseHeaderLayer<N> {
+    /// Add the given headers to every response, unless the handler has set a header with the same name.
+    pub const fn new(headers: [(&'static str, &'static str); N]) -> Self {
+        Self { headers }
+    }
+}
+
+/// Forwards headers to the inner [ForEachHeader], recording which of the default headers have been set.
+struct RecordSetHeaders<'a, F: ForEachHeader, const N: usize> {
+    f: &'a mut F,
+    default_headers: &'a [(&'static str, &'static str); N],
+    is_set: &'a mut [bool; N],
+}
+
+impl<'a, F: ForEachHeader, const N: usize> ForEachHeader for RecordSetHeaders<'a, F, N> {
+    type Output = ();
+    type Error = F::Error;
+
+    async fn call<Value: fmt::Display>(
+        &mut self,
+        name: &str,
+        value: Value,
+    ) -> Result<(), Self::Error> {
+        for ((default_name, _), is_set) in self.default_headers.iter().zip(self.is_set.iter_mut()) {
+            if default_name.eq_ignore_ascii_case(name) {
+                *is_set = true;
+            }
+        }
+
+        self.f.call(name, value).await
+    }
+
+    async fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(())
+    }
+}
+
+struct WithDefaultHeaders<'a, H: HeadersIter, const N: usize> {
+    headers: H,
+    default_headers: &'a [(&'static str, &'static str); N],
+}
+
+impl<'a, H: HeadersIter, const N: usize> HeadersIter for WithDefaultHeaders<'a, H, N> {
+    async fn for_each_header<F: ForEachHeader>(self, mut f: F) -> Result<F::Output, F::Error> {
+        let mut is_set = [false; N];
+
+        self.headers
+            .for_each_header(RecordSetHeaders {
+                f: &mut f,
+                default_headers: self.default_headers,
+                is_set: &mut is_set,
+            })
+            .await?;
+
+        for (&(name, value), is_set) in self.default_headers.iter().zip(is_set) {
+            if !is_set {
+                f.call(name, value).await?;
+            }
+        }
+
+        f.finalize().await
+    }
+}
+
+struct SetResponseHeaderResponseWriter<'a, W, const N: usize> {
+    default_headers: &'a [(&'static str, &'static str); N],
+    response_writer: W,
+}
+
+impl<'a, W: ResponseWriter, const N: usize> ResponseWriter
+    for SetResponseHeaderResponseWriter<'a, W, N>
+{
+    type Error = W::Error;
+
+    async fn write_response<R: Read<Error = Self::Error>, H: HeadersIter, B: Body>(
+        self,
+        connection: Connection<'_, R>,
+        response: Response<H, B>,
+    ) -> Result<ResponseSent, Self::Error> {
+        let Response {
+            status_code,
+            reason_phrase,
+            headers,
+            body,
+        } = response;
+
+        self.response_writer
+            .write_response(
+                connection,
+                Response {
+                    status_code,
+                    reason_phrase,
+                    headers: WithDefaultHeaders {
+                        headers,
+                        default_headers: self.default_headers,
+                    },
+                    body,
+                },
+            )
+            .await
+    }
+}
+
+impl<State, PathParameters, const N: usize> Layer<State, PathParameters>
+    for SetResponseHeaderLayer<N>
+{
+    type NextState = State;
+    type NextPathParameters = PathParameters;
+
+    async fn call_layer<
+        'a,
+        R: Read + 'a,
+        NextLayer: Next<'a, R, Self::NextState, Self::NextPathParameters>,
+        W: ResponseWriter<Error = R::Error>,
+    >(
+        &self,
+        next: NextLayer,
+        state: &State,
+        path_parameters: PathParameters,
+        _request_parts: RequestParts<'_>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        next.run(
+            state,
+            path_parameters,
+            SetResponseHeaderResponseWriter {
+                default_headers: &self.headers,
+                response_writer,
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    io::Read,
+    request::RequestParts,
+    response::{Body, Connection, HeadersIter, Response, ResponseWriter},
+    ResponseSent,
+};
+
+use super::{Layer, Next};
+
+/// A [Layer] which traces each request.
+///
+/// If the `tracing` feature is enabled, a [`tracing::Span`](https://docs.rs/tracing/latest/tracing/struct.Span.html) named "request" is opened for each request, with `method` and `path` fields,
+/// and once the response has been sent, the `status` and `latency_ms` fields are recorded and an event is emitted within the span.
+/// The latency is measured using the [Timer](crate::Timer) of the server, so is not recorded if the timer cannot measure time.
+/// This span is compatible with `tracing-subscriber`.
+///
+/// Otherwise, the method, path, and status code of each request are logged using `log` or `defmt` if either feature is enabled, or ignored if not.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceLayer;
+
+struct TraceResponseWriter<'r, W> {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "tracing")]
+    start_time: Option<crate::time::Instant>,
+    request_parts: RequestParts<'r>,
+    response_writer: W,
+}
+
+impl<'r, W: ResponseWriter> ResponseWriter for TraceResponseWriter<'r, W> {
+    type Error = W::Error;
+
+    async fn write_response<R: Read<Error = Self::Error>, H: HeadersIter, B: Body>(
+        self,
+        connection: Connection<'_, R>,
+        response: Response<H, B>,
+    ) -> Result<ResponseSent, Self::Error> {
+        let Self {
+            #[cfg(feature = "tracing")]
+            span,
+            #[cfg(feature = "tracing")]
+            start_time,
+            request_parts,
+            response_writer,
+        } = self;
+
+        let status_code = response.status_code();
+
+        let result = response_writer.write_response(connection, response).await;
+
+        #[cfg(feature = "tracing")]
+        {
+            let latency_ms = start_time
+                .zip(request_parts.now())
+                .map(|(start_time, now)| now.duration_since(start_time).as_secs_f64() * 1000.0);
+
+            span.record("status", status_code.as_u16());
+            span.record("latency_ms", latency_ms);
+
+            tracing::info!(
+                parent: &span,
+                status = status_code.as_u16(),
+                latency_ms,
+                "response sent"
+            );
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        log_info!(
+            "{} {}: {}",
+            request_parts.method(),
+            request_parts.path().encoded(),
+            status_code.as_u16(),
+        );
+
+        result
+    }
+}
+
+impl<State, PathParameters> Layer<State, PathParameters> for TraceLayer {
+    type NextState = State;
+    type NextPathParameters = PathParameters;
+
+    async fn call_layer<
+        'a,
+        R: Read + 'a,
+        NextLayer: Next<'a, R, Self::NextState, Self::NextPathParameters>,
+        W: ResponseWriter<Error = R::Error>,
+    >(
+        &self,
+        next: NextLayer,
+        state: &State,
+        path_parameters: PathParameters,
+        request_parts: RequestParts<'_>,
+        response_writer: W,
+    ) -> Result<ResponseSent, W::Error> {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+
+            let span = tracing::info_span!(
+                "request",
+                method = request_parts.method(),
+                path = request_parts.path().encoded(),
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+            );
+
+            next.run(
+                state,
+                path_parameters,
+                TraceResponseWriter {
+                    span: span.clone(),
+                    start_time: request_parts.now(),
+                    request_parts,
+                    response_writer,
+                },
+            )
+            .instrument(span)
+            .await
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        {
+            next.run(
+                state,
+                path_parameters,
+                TraceResponseWriter {
+                    request_parts,
+                    response_writer,
+                },
+            )
+            .await
+        }
+    }
+}
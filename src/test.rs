@@ -0,0 +1,135 @@
+//! Utilities for testing routers, extractors, and responses without a network connection.
+//!
+//! Requests are written as raw HTTP, and the raw HTTP response is returned, so tests can check exactly what is sent to the client.
+//!
+//! ```
+//! use picoserve::routing::get;
+//!
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let app = picoserve::Router::new().route("/", get(|| async { "Hello World" }));
+//!
+//! let response = picoserve::test::send_request(&app, b"GET / HTTP/1.1\r\n\r\n").await;
+//!
+//! let response = core::str::from_utf8(&response).unwrap();
+//!
+//! assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+//! assert!(response.ends_with("\r\n\r\nHello World"));
+//! # });
+//! ```
+
+use core::convert::Infallible;
+
+use crate::{io, routing::PathRouter, time::Timer, Config, Router, Server, Timeouts};
+
+/// An in-memory [Socket](io::Socket), which reads the request from a slice and writes the response into a [Vec].
+pub struct MemorySocket<'a> {
+    /// The data sent by the client, which is read by the server.
+    pub request: &'a [u8],
+    /// The data written by the server.
+    pub response: &'a mut Vec<u8>,
+}
+
+impl<'a> io::Socket for MemorySocket<'a> {
+    type Error = Infallible;
+
+    type ReadHalf<'b> = &'b mut &'a [u8] where 'a: 'b;
+    type WriteHalf<'b> = &'b mut Vec<u8> where 'a: 'b;
+
+    fn split(&mut self) -> (Self::ReadHalf<'_>, Self::WriteHalf<'_>) {
+        (&mut self.request, self.response)
+    }
+
+    async fn shutdown<T: Timer>(
+        self,
+        _timeouts: &Timeouts<T::Duration>,
+        _timer: &mut T,
+    ) -> Result<(), crate::Error<Self::Error>> {
+        Ok(())
+    }
+}
+
+/// A [Timer] which never times out, but can read the current time, so that time-based extractors such as [RequestStart](crate::extract::RequestStart),
+/// and [connection_max_lifetime](Timeouts::connection_max_lifetime), can be tested.
+pub struct NoTimeoutTimer;
+
+impl Timer for NoTimeoutTimer {
+    type Duration = core::time::Duration;
+    type TimeoutError = Infallible;
+
+    async fn run_with_timeout<F: core::future::Future>(
+        &mut self,
+        _duration: Self::Duration,
+        future: F,
+    ) -> Result<F::Output, Self::TimeoutError> {
+        Ok(future.await)
+    }
+
+    fn now() -> Option<crate::time::Instant> {
+        static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+
+        let elapsed = EPOCH.get_or_init(std::time::Instant::now).elapsed();
+
+        Some(crate::time::Instant::from_micros(
+            elapsed.as_micros().try_into().unwrap_or(u64::MAX),
+        ))
+    }
+
+    fn duration_as_micros(duration: &Self::Duration) -> Option<u64> {
+        Some(duration.as_micros().try_into().unwrap_or(u64::MAX))
+    }
+}
+
+/// Serve the raw HTTP `request` using `app`, which has no state, and return the raw HTTP response.
+///
+/// The connection is kept alive, so `request` may contain several pipelined requests, and the responses are concatenated.
+pub async fn send_request<P: PathRouter>(app: &Router<P>, request: &[u8]) -> Vec<u8> {
+    send_request_with_state(app, &(), request).await
+}
+
+/// Serve the raw HTTP `request` using `app`, which has a state of `State`, and return the raw HTTP response.
+///
+/// The connection is kept alive, so `request` may contain several pipelined requests, and the responses are concatenated.
+pub async fn send_request_with_state<State, P: PathRouter<State>>(
+    app: &Router<P, State>,
+    state: &State,
+    request: &[u8],
+) -> Vec<u8> {
+    let config = Config::new(Timeouts::new(None, None, None)).keep_connection_alive();
+
+    send_request_with_config(app, &config, state, request).await
+}
+
+/// Serve the raw HTTP `request` using `app` and `config`, which has a state of `State`, and return the raw HTTP response.
+pub async fn send_request_with_config<State, P: PathRouter<State>>(
+    app: &Router<P, State>,
+    config: &Config<core::time::Duration>,
+    state: &State,
+    request: &[u8],
+) -> Vec<u8> {
+    let mut http_buffer = [0; 2048];
+    let mut response = Vec::new();
+
+    let result = Server::new(app, config, &mut http_buffer)
+        .serve_and_shutdown(
+            NoTimeoutTimer,
+            MemorySocket {
+                request,
+                response: &mut response,
+            },
+            state,
+            core::future::pending(),
+        )
+        .await;
+
+    match result {
+        Ok(_) => (),
+        Err(crate::Error::Read(infallible) | crate::Error::Write(infallible)) => {
+            match infallible {}
+        }
+        Err(crate::Error::ReadTimeout | crate::Error::WriteTimeout) => {
+            unreachable!("NoTimeoutTimer never times out")
+        }
+    }
+
+    response
+}
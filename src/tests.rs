@@ -174,6 +174,35 @@ impl<TX: io::Write<Error = Infallible>, RX: io::Read<Error = Infallible>> io::So
     }
 }
 
+/// A socket which reports `remote_address` as the address of the remote peer.
+struct WithRemoteAddress<S> {
+    socket: S,
+    remote_address: Option<core::net::SocketAddr>,
+}
+
+impl<S: io::Socket> io::Socket for WithRemoteAddress<S> {
+    type Error = S::Error;
+
+    type ReadHalf<'a> = S::ReadHalf<'a> where S: 'a;
+    type WriteHalf<'a> = S::WriteHalf<'a> where S: 'a;
+
+    fn split(&mut self) -> (Self::ReadHalf<'_>, Self::WriteHalf<'_>) {
+        self.socket.split()
+    }
+
+    fn remote_address(&self) -> Option<core::net::SocketAddr> {
+        self.remote_address
+    }
+
+    async fn shutdown<Timer: time::Timer>(
+        self,
+        timeouts: &Timeouts<Timer::Duration>,
+        timer: &mut Timer,
+    ) -> Result<(), Error<Self::Error>> {
+        self.socket.shutdown(timeouts, timer).await
+    }
+}
+
 impl<TX: Unpin, RX: hyper::rt::Read + Unpin> hyper::rt::Read for TestSocket<TX, RX> {
     fn poll_read(
         self: Pin<&mut Self>,
@@ -205,6 +234,19 @@ impl<TX: hyper::rt::Write + Unpin, RX: Unpin> hyper::rt::Write for TestSocket<TX
     }
 }
 
+async fn serve_and_shutdown<State, T: Timer, P: routing::PathRouter<State>, S: io::Socket>(
+    app: &Router<P, State>,
+    timer: T,
+    config: &Config<T::Duration>,
+    buffer: &mut [u8],
+    socket: S,
+    state: &State,
+) -> Result<u64, Error<S::Error>> {
+    Server::new(app, config, buffer)
+        .serve_and_shutdown(timer, socket, state, core::future::pending())
+        .await
+}
+
 async fn run_single_request_test(
     app: &Router<impl PathRouter>,
     request: hyper::Request<http_body_util::Full<hyper::body::Bytes>>,
@@ -212,11 +254,7 @@ async fn run_single_request_test(
     let (request_tx, request_rx) = pipe();
     let (response_tx, response_rx) = pipe();
 
-    let config = Config::new(Timeouts {
-        start_read_request: None,
-        read_request: None,
-        write: None,
-    });
+    let config = Config::new(Timeouts::new(None, None, None));
 
     let mut http_buffer = [0; 2048];
 
@@ -294,6 +332,54 @@ async fn not_found() {
     assert_eq!(response_parts.status, StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+/// Test that content wrapped in `Html`, `Text`, `Css`, or `JavaScript` is sent with the matching content type
+async fn content_type_wrappers() {
+    let app = Router::new()
+        .route(
+            "/html",
+            routing::get(|| async { response::Html("<h1>Hello</h1>") }),
+        )
+        .route(
+            "/text",
+            routing::get(|| async { response::Text(&b"Hello"[..]) }),
+        )
+        .route(
+            "/css",
+            routing::get(|| async { response::Css(format_args!("h1 {{ color: red; }}")) }),
+        )
+        .route(
+            "/js",
+            routing::get(|| async { response::JavaScript("let x = 1;") }),
+        );
+
+    for (path, expected_content_type, expected_body) in [
+        ("/html", "text/html; charset=utf-8", "<h1>Hello</h1>"),
+        ("/text", "text/plain; charset=utf-8", "Hello"),
+        ("/css", "text/css", "h1 { color: red; }"),
+        ("/js", "application/javascript; charset=utf-8", "let x = 1;"),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK, "{path}");
+        assert_eq!(
+            parts.headers.get("Content-Type").unwrap(),
+            expected_content_type,
+            "{path}"
+        );
+        assert_eq!(
+            parts.headers.get("Content-Length").unwrap(),
+            &expected_body.len().to_string(),
+            "{path}"
+        );
+        assert_eq!(body, expected_body.as_bytes(), "{path}");
+    }
+}
+
 #[tokio::test]
 /// Test file and directory routing
 async fn file_routing() {
@@ -316,6 +402,8 @@ async fn file_routing() {
                 ..Directory::DEFAULT
             },
         )],
+        index_file: None,
+        not_found_file: None,
     };
 
     let app = Router::new().nest_service(STATIC_DIR, FILES);
@@ -365,6 +453,289 @@ async fn file_routing() {
     }
 }
 
+#[tokio::test]
+/// Test that HEAD requests for files report the "Content-Length" of the file without sending the body
+async fn file_head_request() {
+    use response::fs::{Directory, File};
+
+    const HTML: &str = "<h1>Hello World</h1>";
+    const CSS: &str = "h1 { font-weight: bold; }";
+
+    const FILES: Directory = Directory {
+        files: &[("index.css", File::css(CSS))],
+        ..Directory::DEFAULT
+    };
+
+    let app = Router::new()
+        .route("/", routing::get_service(File::html(HTML)))
+        .nest_service("/static", FILES);
+
+    for (path, expected_status, expected_content_length) in [
+        ("/", "200 OK", Some(HTML.len())),
+        ("/static/index.css", "200 OK", Some(CSS.len())),
+        ("/static/unknown.css", "404 Not Found", None),
+    ] {
+        let response = String::from_utf8(
+            test::send_request(
+                &app,
+                format!("HEAD {path} HTTP/1.1\r\nConnection: close\r\n\r\n").as_bytes(),
+            )
+            .await,
+        )
+        .unwrap();
+
+        assert!(
+            response.starts_with(&format!("HTTP/1.1 {expected_status}\r\n")),
+            "{path}: {response:?}"
+        );
+        assert!(response.ends_with("\r\n\r\n"), "{path}: {response:?}");
+
+        if let Some(content_length) = expected_content_length {
+            assert!(
+                response.contains(&format!("\r\nContent-Length: {content_length}\r\n")),
+                "{path}: {response:?}"
+            );
+        }
+    }
+
+    let response = test::send_request(
+        &app,
+        b"POST /static/index.css HTTP/1.1\r\nContent-Length: 0\r\n\r\n",
+    )
+    .await;
+
+    assert!(response.starts_with(b"HTTP/1.1 405 Method Not Allowed\r\n"));
+}
+
+#[tokio::test]
+/// Test that the not found file is served if no file matches the request path
+async fn directory_not_found_file() {
+    use response::fs::{Directory, File};
+
+    const HTML: &str = "<h1>Hello World</h1>";
+    const CSS: &str = "h1 { font-weight: bold; }";
+
+    const FILES: Directory = Directory {
+        files: &[
+            ("index.html", File::html(HTML)),
+            ("index.css", File::css(CSS)),
+        ],
+        not_found_file: Some("index.html"),
+        ..Directory::DEFAULT
+    };
+
+    let app = Router::new().nest_service("/app", FILES);
+
+    for (path, expected_body) in [
+        ("/app/index.css", CSS),
+        ("/app/index.html", HTML),
+        ("/app/unknown/route", HTML),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK, "{path}");
+        assert_eq!(body, expected_body.as_bytes(), "{path}");
+    }
+
+    let (parts, _body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/unknown")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+/// Test that a directory can serve the root of the router, including index files
+async fn directory_at_root() {
+    use response::fs::{Directory, File};
+
+    const HTML: &str = "<h1>Hello World</h1>";
+    const CSS: &str = "h1 { font-weight: bold; }";
+    const DOCS: &str = "<h1>Documentation</h1>";
+
+    const FILES: Directory = Directory {
+        files: &[
+            ("index.html", File::html(HTML)),
+            ("index.css", File::css(CSS)),
+        ],
+        sub_directories: &[(
+            "docs",
+            Directory {
+                files: &[("index.html", File::html(DOCS))],
+                index_file: Some("index.html"),
+                ..Directory::DEFAULT
+            },
+        )],
+        index_file: Some("index.html"),
+        ..Directory::DEFAULT
+    };
+
+    let app = Router::from_service(FILES).route("/api", routing::get(|| async { "API" }));
+
+    for (path, expected_body) in [
+        ("/", HTML),
+        ("/index.html", HTML),
+        ("/index.css", CSS),
+        ("/docs", DOCS),
+        ("/docs/", DOCS),
+        ("/docs/index.html", DOCS),
+        ("/api", "API"),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK, "{path}");
+        assert_eq!(body, expected_body.as_bytes(), "{path}");
+    }
+
+    let (parts, _body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/unknown")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+/// Test that the gzip-compressed copy of a file is only sent to clients which accept it
+async fn file_gzip_negotiation() {
+    const JS: &str = "console.log('Hello World')";
+    const GZIP: &[u8] = b"\x1f\x8b compressed";
+
+    let app = Router::new().route(
+        "/app.js",
+        routing::get_service(response::File::javascript(JS).with_gzip(GZIP)),
+    );
+
+    let mut etags = Vec::new();
+
+    for (accept_encoding, expected_body, expected_content_encoding) in [
+        (None, JS.as_bytes(), None),
+        (Some("gzip"), GZIP, Some("gzip")),
+        (Some("deflate, GZIP;q=0.5"), GZIP, Some("gzip")),
+        (Some("gzip;q=0, *"), JS.as_bytes(), None),
+        (Some("*"), GZIP, Some("gzip")),
+        (Some("br"), JS.as_bytes(), None),
+    ] {
+        let mut request = hyper::Request::get("/app.js");
+
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header("Accept-Encoding", accept_encoding);
+        }
+
+        let (parts, body) =
+            run_single_request_test(&app, request.body(Default::default()).unwrap()).await;
+
+        assert_eq!(parts.status, StatusCode::OK, "{accept_encoding:?}");
+        assert_eq!(body, expected_body, "{accept_encoding:?}");
+        assert_eq!(
+            parts
+                .headers
+                .get("Content-Encoding")
+                .map(|value| value.to_str().unwrap()),
+            expected_content_encoding,
+            "{accept_encoding:?}"
+        );
+        assert_eq!(parts.headers["Vary"], "Accept-Encoding");
+
+        etags.push((accept_encoding, parts.headers["ETag"].clone()));
+    }
+
+    let (_, plain_etag) = &etags[0];
+    let (_, gzip_etag) = &etags[1];
+
+    assert_ne!(plain_etag, gzip_etag);
+
+    // Each representation is only "Not Modified" if its own ETag matches
+    for (accept_encoding, etag, expected_status) in [
+        ("gzip", gzip_etag, StatusCode::NOT_MODIFIED),
+        ("identity", gzip_etag, StatusCode::OK),
+        ("identity", plain_etag, StatusCode::NOT_MODIFIED),
+    ] {
+        let (parts, _body) = run_single_request_test(
+            &app,
+            hyper::Request::get("/app.js")
+                .header("Accept-Encoding", accept_encoding)
+                .header("If-None-Match", etag)
+                .body(Default::default())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, expected_status, "{accept_encoding} {etag:?}");
+        assert_eq!(parts.headers["Vary"], "Accept-Encoding");
+    }
+}
+
+#[tokio::test]
+/// Test that a Single-Page Application serves its index file to unknown paths, but not to API routes
+async fn spa_router() {
+    use response::fs::{Directory, File};
+
+    const HTML: &str = "<script src=\"/assets/app.js\"></script>";
+    const JS: &str = "console.log(location.pathname)";
+    const GZIP: &[u8] = b"\x1f\x8b compressed";
+
+    const FILES: Directory = Directory {
+        files: &[("index.html", File::html(HTML))],
+        sub_directories: &[(
+            "assets",
+            Directory {
+                files: &[("app.js", File::javascript(JS).with_gzip(GZIP))],
+                ..Directory::DEFAULT
+            },
+        )],
+        ..Directory::DEFAULT
+    };
+
+    let app =
+        Router::spa(FILES, "index.html").route("/api/status", routing::get(|| async { "OK" }));
+
+    for (path, expected_body) in [
+        ("/", HTML.as_bytes()),
+        ("/index.html", HTML.as_bytes()),
+        ("/settings/network", HTML.as_bytes()),
+        ("/assets/app.js", GZIP),
+        ("/api/status", b"OK"),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path)
+                .header("Accept-Encoding", "gzip, deflate")
+                .body(Default::default())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK, "{path}");
+        assert_eq!(body, expected_body, "{path}");
+    }
+
+    let (parts, _body) = run_single_request_test(
+        &app,
+        hyper::Request::post("/api/status")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::METHOD_NOT_ALLOWED);
+}
+
 #[tokio::test]
 /// Test file and directory routing
 async fn file_etag_based_cache() {
@@ -413,89 +784,350 @@ async fn file_etag_based_cache() {
 }
 
 #[tokio::test]
-/// Test that only a single request is handled if configured to close the connection
-async fn only_one_request() {
-    let (request_tx, request_rx) = pipe();
-    let (response_tx, response_rx) = pipe();
+async fn file_last_modified_based_cache() {
+    const HTML: &str = "<h1>Hello World</h1>";
+    const LAST_MODIFIED: &str = "Tue, 15 Nov 1994 12:45:26 GMT";
 
-    let app = Router::new().route("/", routing::get(|| async move { "Hello World" }));
+    let app = Router::new().route(
+        "/",
+        routing::get_service(response::File::html(HTML).with_last_modified(LAST_MODIFIED)),
+    );
 
-    let config = Config::new(Timeouts {
-        start_read_request: None,
-        read_request: None,
-        write: None,
-    });
+    for (if_modified_since, expected_status) in [
+        (None, StatusCode::OK),
+        (Some("Tue, 15 Nov 1994 12:45:25 GMT"), StatusCode::OK),
+        (Some("Mon, 14 Nov 1994 23:59:59 GMT"), StatusCode::OK),
+        (Some(LAST_MODIFIED), StatusCode::NOT_MODIFIED),
+        (
+            Some("Thu, 01 Dec 1994 00:00:00 GMT"),
+            StatusCode::NOT_MODIFIED,
+        ),
+        (
+            Some("Wed, 01 Jan 2025 00:00:00 GMT"),
+            StatusCode::NOT_MODIFIED,
+        ),
+        (Some("not a date"), StatusCode::OK),
+    ] {
+        let mut request = hyper::Request::get("/");
 
-    let mut http_buffer = [0; 2048];
+        if let Some(if_modified_since) = if_modified_since {
+            request = request.header("If-Modified-Since", if_modified_since);
+        }
 
-    let server = serve_and_shutdown(
-        &app,
-        time::TokioTimer,
-        &config,
-        &mut http_buffer,
-        TestSocket {
-            rx: request_rx,
-            tx: response_tx,
-        },
-        &(),
-    );
+        let (parts, body) =
+            run_single_request_test(&app, request.body(Default::default()).unwrap()).await;
 
-    request_tx
-        .0
-        .send(
-            "GET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n"
-                .as_bytes()
-                .into(),
-        )
-        .unwrap();
+        assert_eq!(parts.status, expected_status, "{if_modified_since:?}");
+        assert_eq!(parts.headers.get("Last-Modified").unwrap(), LAST_MODIFIED);
 
-    drop(request_tx);
+        if expected_status == StatusCode::OK {
+            assert_eq!(body, HTML.as_bytes());
+        } else {
+            assert_eq!(&body[..], b"");
+        }
+    }
 
-    assert_eq!(
-        server.now_or_never().expect("Server has stalled").unwrap(),
-        1
-    );
+    // A mismatched "If-None-Match" takes precedence over "If-Modified-Since"
+    let (parts, _) = run_single_request_test(
+        &app,
+        hyper::Request::get("/")
+            .header("If-None-Match", "\"abc\"")
+            .header("If-Modified-Since", LAST_MODIFIED)
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
 
-    drop(response_rx);
+    assert_eq!(parts.status, StatusCode::OK);
 }
 
 #[tokio::test]
-/// Test that multiple requests are handled if the connection is kept alive
-async fn keep_alive() {
-    let app = Router::new().route("/", routing::get(|| async move { "Hello World" }));
+/// Test that a file serves a single range of bytes
+async fn file_range_request() {
+    const HTML: &str = "<h1>Hello World</h1>";
 
-    let config = Config::new(Timeouts {
-        start_read_request: None,
-        read_request: None,
-        write: None,
-    })
-    .keep_connection_alive();
+    let app = Router::new().route("/", routing::get_service(response::File::html(HTML)));
 
-    let mut http_buffer = [0; 2048];
+    for (range, expected_status, expected_content_range, expected_body) in [
+        (None, StatusCode::OK, None, HTML),
+        (
+            Some("bytes=4-8"),
+            StatusCode::PARTIAL_CONTENT,
+            Some("bytes 4-8/20"),
+            "Hello",
+        ),
+        (
+            Some("bytes=15-"),
+            StatusCode::PARTIAL_CONTENT,
+            Some("bytes 15-19/20"),
+            "</h1>",
+        ),
+        (
+            Some("bytes=-5"),
+            StatusCode::PARTIAL_CONTENT,
+            Some("bytes 15-19/20"),
+            "</h1>",
+        ),
+        (
+            Some("bytes=10-100"),
+            StatusCode::PARTIAL_CONTENT,
+            Some("bytes 10-19/20"),
+            "World</h1>",
+        ),
+        (Some("bytes=0-1,4-8"), StatusCode::OK, None, HTML),
+        (Some("bytes=8-4"), StatusCode::OK, None, HTML),
+        (Some("lines=1-2"), StatusCode::OK, None, HTML),
+        (
+            Some("bytes=20-"),
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            Some("bytes */20"),
+            "Requested Range Not Satisfiable\r\n",
+        ),
+    ] {
+        let mut request = hyper::Request::get("/");
 
-    let server = serve_and_shutdown(
-        &app,
-        time::TokioTimer,
-        &config,
-        &mut http_buffer,
-        TestSocket {
-            rx: "GET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n".as_bytes(),
-            tx: Vec::new(),
-        },
-        &(),
-    );
+        if let Some(range) = range {
+            request = request.header("Range", range);
+        }
 
-    assert_eq!(
-        server.now_or_never().expect("Server has stalled").unwrap(),
-        2
-    );
+        let (parts, body) =
+            run_single_request_test(&app, request.body(Default::default()).unwrap()).await;
+
+        assert_eq!(parts.status, expected_status, "{range:?}");
+        assert_eq!(
+            parts
+                .headers
+                .get("Content-Range")
+                .map(|content_range| content_range.to_str().unwrap()),
+            expected_content_range,
+            "{range:?}"
+        );
+        assert_eq!(body, expected_body.as_bytes(), "{range:?}");
+    }
 }
 
 #[tokio::test]
-/// Test correctly processing reading a request with each of
-///  - A two different forced breaks in reading from the "client"
-///  - Each of
-///    - Not reading the body, and thus discarding it
+/// Test that a range is only sent if the "If-Range" header matches the file
+async fn file_if_range_request() {
+    const HTML: &str = "<h1>Hello World</h1>";
+    const LAST_MODIFIED: &str = "Tue, 15 Nov 1994 12:45:26 GMT";
+
+    let app = Router::new().route(
+        "/",
+        routing::get_service(response::File::html(HTML).with_last_modified(LAST_MODIFIED)),
+    );
+
+    let (parts, _body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/").body(Default::default()).unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.headers.get("Accept-Ranges").unwrap(), "bytes");
+
+    let etag = parts
+        .headers
+        .get("etag")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_owned();
+
+    for (if_range, expected_status, expected_body) in [
+        (etag.as_str(), StatusCode::PARTIAL_CONTENT, "Hello"),
+        (LAST_MODIFIED, StatusCode::PARTIAL_CONTENT, "Hello"),
+        ("\"abc\"", StatusCode::OK, HTML),
+        (&format!("W/{etag}"), StatusCode::OK, HTML),
+        ("Wed, 16 Nov 1994 12:45:26 GMT", StatusCode::OK, HTML),
+        ("not a validator", StatusCode::OK, HTML),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get("/")
+                .header("Range", "bytes=4-8")
+                .header("If-Range", if_range)
+                .body(Default::default())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, expected_status, "{if_range}");
+        assert_eq!(body, expected_body.as_bytes(), "{if_range}");
+
+        if expected_status == StatusCode::OK {
+            assert!(parts.headers.get("Content-Range").is_none(), "{if_range}");
+        }
+    }
+}
+
+#[tokio::test]
+/// Test that errors returned by handlers are mapped to responses by `CatchErrorLayer`
+async fn catch_error_layer() {
+    use response::HandlerError;
+
+    #[derive(Debug)]
+    enum AppError {
+        NotFound,
+        Forbidden,
+    }
+
+    async fn lookup(id: u8) -> Result<&'static str, HandlerError<AppError>> {
+        match id {
+            0 => Ok("zero"),
+            1 => Err(AppError::Forbidden)?,
+            _ => Err(AppError::NotFound.into()),
+        }
+    }
+
+    let routes = || {
+        Router::new()
+            .route(
+                ("/item", routing::parse_path_segment::<u8>()),
+                routing::get(lookup),
+            )
+            .route(
+                "/other",
+                routing::get(|| async { Err::<(), _>(HandlerError("unmapped")) }),
+            )
+    };
+
+    let app = routes().layer(routing::CatchErrorLayer::new(
+        |error: AppError| match error {
+            AppError::NotFound => (response::StatusCode::NOT_FOUND, "Item not found\n"),
+            AppError::Forbidden => (response::StatusCode::FORBIDDEN, "Item is private\n"),
+        },
+    ));
+
+    for (method, path, expected_status, expected_body) in [
+        ("GET", "/item/0", StatusCode::OK, "zero"),
+        ("GET", "/item/1", StatusCode::FORBIDDEN, "Item is private\n"),
+        ("GET", "/item/2", StatusCode::NOT_FOUND, "Item not found\n"),
+        ("HEAD", "/item/2", StatusCode::NOT_FOUND, ""),
+        (
+            "GET",
+            "/other",
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Internal Server Error\n",
+        ),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::builder()
+                .method(method)
+                .uri(path)
+                .body(Default::default())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, expected_status, "{method} {path}");
+        assert_eq!(body, expected_body.as_bytes(), "{method} {path}");
+    }
+
+    // Errors of other types pass through the layer, and can be caught by an outer layer
+    let app = app.layer(routing::CatchErrorLayer::new(|error: &'static str| {
+        (response::StatusCode::SERVICE_UNAVAILABLE, error)
+    }));
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/other")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body, b"unmapped"[..]);
+
+    // Without the layer, errors are sent as "Internal Server Error"
+    let (parts, body) = run_single_request_test(
+        &routes(),
+        hyper::Request::get("/item/2")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body, b"Internal Server Error\n"[..]);
+}
+
+#[tokio::test]
+/// Test that only a single request is handled if configured to close the connection
+async fn only_one_request() {
+    let (request_tx, request_rx) = pipe();
+    let (response_tx, response_rx) = pipe();
+
+    let app = Router::new().route("/", routing::get(|| async move { "Hello World" }));
+
+    let config = Config::new(Timeouts::new(None, None, None));
+
+    let mut http_buffer = [0; 2048];
+
+    let server = serve_and_shutdown(
+        &app,
+        time::TokioTimer,
+        &config,
+        &mut http_buffer,
+        TestSocket {
+            rx: request_rx,
+            tx: response_tx,
+        },
+        &(),
+    );
+
+    request_tx
+        .0
+        .send(
+            "GET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n"
+                .as_bytes()
+                .into(),
+        )
+        .unwrap();
+
+    drop(request_tx);
+
+    assert_eq!(
+        server.now_or_never().expect("Server has stalled").unwrap(),
+        1
+    );
+
+    drop(response_rx);
+}
+
+#[tokio::test]
+/// Test that multiple requests are handled if the connection is kept alive
+async fn keep_alive() {
+    let app = Router::new().route("/", routing::get(|| async move { "Hello World" }));
+
+    let config = Config::new(Timeouts::new(None, None, None)).keep_connection_alive();
+
+    let mut http_buffer = [0; 2048];
+
+    let server = serve_and_shutdown(
+        &app,
+        time::TokioTimer,
+        &config,
+        &mut http_buffer,
+        TestSocket {
+            rx: "GET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n".as_bytes(),
+            tx: Vec::new(),
+        },
+        &(),
+    );
+
+    assert_eq!(
+        server.now_or_never().expect("Server has stalled").unwrap(),
+        2
+    );
+}
+
+#[tokio::test]
+/// Test correctly processing reading a request with each of
+///  - A two different forced breaks in reading from the "client"
+///  - Each of
+///    - Not reading the body, and thus discarding it
 ///    - Reading part of the body into an external buffer
 ///    - Reading all of the body into an external buffer
 ///    - Attempting to read more than the entire body, testing that the body reader stops reading at the end of the body
@@ -622,6 +1254,7 @@ async fn upgrade_with_request_body() {
 
             response::Response {
                 status_code: response::StatusCode::OK,
+                reason_phrase: None,
                 headers: [("Content-Type", "text/plain"), ("Content-Length", "0")],
                 body: UpgradeCheck { upgrade_token },
             }
@@ -630,11 +1263,7 @@ async fn upgrade_with_request_body() {
         }
     }
 
-    let config = Config::new(Timeouts {
-        start_read_request: None,
-        read_request: None,
-        write: None,
-    });
+    let config = Config::new(Timeouts::new(None, None, None));
 
     let mut http_buffer = [0; 2048];
 
@@ -736,3 +1365,5735 @@ async fn huge_request() {
         assert_eq!(response.0.status, hyper::http::StatusCode::OK);
     }
 }
+
+#[tokio::test]
+/// Test that the entire request parts can be extracted by a service using `from_request_parts!`
+async fn extract_request_parts() {
+    struct EchoRequestParts;
+
+    impl routing::RequestHandlerService<()> for EchoRequestParts {
+        async fn call_request_handler_service<
+            R: Read,
+            W: response::ResponseWriter<Error = R::Error>,
+        >(
+            &self,
+            state: &(),
+            (): (),
+            request: request::Request<'_, R>,
+            response_writer: W,
+        ) -> Result<ResponseSent, W::Error> {
+            let request_parts =
+                crate::from_request_parts!(state, request, response_writer, request::RequestParts);
+
+            response_writer
+                .write_response(
+                    request.body_connection.finalize().await?,
+                    response::Response::ok(format_args!(
+                        "{} {} {:?} {} {:?}",
+                        request_parts.method(),
+                        request_parts.path().encoded(),
+                        request_parts.query().map(|query| query.0),
+                        request_parts.http_version(),
+                        request_parts
+                            .headers()
+                            .get("x-test")
+                            .and_then(|value| core::str::from_utf8(value.as_raw()).ok()),
+                    )),
+                )
+                .await
+        }
+    }
+
+    let (parts, body) = run_single_request_test(
+        &Router::new().route("/foo", routing::get_service(EchoRequestParts)),
+        hyper::Request::get("/foo?bar=baz")
+            .header("X-Test", "value")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(
+        body,
+        r#"GET /foo Some("bar=baz") HTTP/1.1 Some("value")"#.as_bytes()
+    );
+}
+
+#[tokio::test]
+/// Test deserializing path parameters using `extract::Path`
+async fn path_extractor() {
+    #[derive(serde::Deserialize)]
+    struct UserPost {
+        user_id: u32,
+        post: heapless::String<16>,
+    }
+
+    let app = Router::new()
+        .route(
+            ("/user", routing::parse_path_segment::<u32>()),
+            routing::get(|extract::Path(user_id): extract::Path<u32>| async move {
+                response::DebugValue(user_id)
+            }),
+        )
+        .route(
+            (
+                "/user",
+                routing::parse_path_segment::<u32>(),
+                "/post",
+                routing::parse_path_segment::<String>(),
+            ),
+            routing::get(
+                |extract::Path(UserPost { user_id, post }): extract::Path<UserPost>| async move {
+                    response::DebugValue((user_id, post))
+                },
+            ),
+        )
+        .route(
+            ("/pair", routing::parse_path_segment::<String>()),
+            routing::get(|extract::Path((value,)): extract::Path<(i8,)>| async move {
+                response::DebugValue(value)
+            }),
+        );
+
+    for (path, expected_status, expected_body) in [
+        ("/user/42", StatusCode::OK, Some("42")),
+        (
+            "/user/42/post/hello",
+            StatusCode::OK,
+            Some(r#"(42, "hello")"#),
+        ),
+        (
+            "/user/42/post/hello%20world",
+            StatusCode::OK,
+            Some(r#"(42, "hello world")"#),
+        ),
+        ("/pair/-5", StatusCode::OK, Some("-5")),
+        ("/pair/500", StatusCode::BAD_REQUEST, None),
+        (
+            "/user/42/post/much_too_long_for_the_destination",
+            StatusCode::BAD_REQUEST,
+            None,
+        ),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, expected_status, "{path}");
+
+        if let Some(expected_body) = expected_body {
+            assert_eq!(body, format!("{expected_body}\r\n").as_bytes(), "{path}");
+        }
+    }
+}
+
+#[tokio::test]
+/// Test that `parse_path_segment_or_400` responds with "Bad Request" if the path has the right shape but a bad value, and no other route matches
+async fn path_segment_or_400() {
+    let app = Router::new()
+        .route(
+            (
+                "/add",
+                routing::parse_path_segment::<f32>(),
+                routing::parse_path_segment::<f32>(),
+            ),
+            routing::get(|(a, b): (f32, f32)| async move { response::DebugValue(a + b) }),
+        )
+        .route(
+            (
+                "/add",
+                routing::parse_path_segment_or_400::<i32>(),
+                routing::parse_path_segment_or_400::<i32>(),
+            ),
+            routing::get(|(a, b): (i32, i32)| async move { response::DebugValue(a + b) }),
+        )
+        .route(
+            ("/add/max", routing::parse_path_segment::<i32>()),
+            routing::get(|b: i32| async move { response::DebugValue(i32::MAX.saturating_add(b)) }),
+        )
+        .route(
+            ("/negate", routing::parse_path_segment::<i32>()),
+            routing::get(|a: i32| async move { response::DebugValue(-a) }),
+        );
+
+    for (path, expected_status, expected_body) in [
+        ("/add/1/2", StatusCode::OK, "3\r\n"),
+        // Routes added after the route are checked first
+        ("/add/max/2", StatusCode::OK, "2147483647\r\n"),
+        // Routes added before the route with the same shape are also checked
+        ("/add/1.5/2", StatusCode::OK, "3.5\r\n"),
+        (
+            "/add/notanumber/2",
+            StatusCode::BAD_REQUEST,
+            "Failed to parse path segment \"notanumber\": invalid digit found in string\n",
+        ),
+        (
+            "/add/1/notanumber",
+            StatusCode::BAD_REQUEST,
+            "Failed to parse path segment \"notanumber\": invalid digit found in string\n",
+        ),
+        (
+            "/add/notanumber",
+            StatusCode::NOT_FOUND,
+            "/add/notanumber not found\r\n",
+        ),
+        (
+            "/add/1/2/3",
+            StatusCode::NOT_FOUND,
+            "/add/1/2/3 not found\r\n",
+        ),
+        (
+            "/negate/notanumber",
+            StatusCode::NOT_FOUND,
+            "/negate/notanumber not found\r\n",
+        ),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, expected_status, "{path}");
+        assert_eq!(body, expected_body.as_bytes(), "{path}");
+    }
+}
+
+#[cfg(feature = "cbor")]
+#[tokio::test]
+/// Test that CBOR request bodies are deserialized and CBOR responses are serialized
+async fn cbor_round_trip() {
+    #[derive(Debug, PartialEq, minicbor::Encode, minicbor::Decode)]
+    #[cbor(map)]
+    struct Point {
+        #[n(0)]
+        x: i32,
+        #[n(1)]
+        y: i32,
+    }
+
+    // {0: 1, 1: -2}
+    const POINT: &[u8] = &[0xa2, 0x00, 0x01, 0x01, 0x21];
+
+    let app = Router::new()
+        .route(
+            "/echo",
+            routing::post(|extract::Cbor(point): extract::Cbor<Point>| async move {
+                response::Cbor(point)
+            }),
+        )
+        .route(
+            "/long",
+            routing::get(|| async move { response::Cbor([0xabcdu16; 100].as_slice()) }),
+        );
+
+    {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::post("/echo").body(POINT.into()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(parts.headers["content-type"], "application/cbor");
+        assert_eq!(parts.headers["content-length"], POINT.len().to_string());
+        assert_eq!(body, POINT);
+    }
+
+    {
+        let (parts, _body) = run_single_request_test(
+            &app,
+            hyper::Request::post("/echo")
+                .body([0xa1, 0x61, 0x78].as_slice().into())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::BAD_REQUEST);
+    }
+
+    {
+        let (parts, _body) = run_single_request_test(
+            &app,
+            hyper::Request::post("/echo")
+                .body([POINT, &[0x00]].concat().into())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::BAD_REQUEST);
+    }
+
+    {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get("/long")
+                .body(Default::default())
+                .unwrap(),
+        )
+        .await;
+
+        // Array header of length 100, then 100 16-bit unsigned integers
+        let expected = [0x98, 100]
+            .into_iter()
+            .chain((0..100).flat_map(|_| [0x19, 0xab, 0xcd]))
+            .collect::<Vec<u8>>();
+
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(parts.headers["content-length"], expected.len().to_string());
+        assert_eq!(body, expected);
+    }
+}
+
+#[test]
+fn extract_keep_alive() {
+    let app = Router::new().route(
+        "/",
+        routing::get(|keep_alive: KeepAlive| async move { response::DebugValue(keep_alive) }),
+    );
+
+    let timeouts = Timeouts::new(None, None, None);
+
+    for (config, request, expected_keep_alive) in [
+        (
+            Config::new(timeouts.clone()).keep_connection_alive(),
+            "GET / HTTP/1.0\r\n\r\n",
+            KeepAlive::Close,
+        ),
+        (
+            Config::new(timeouts.clone()).keep_connection_alive(),
+            "GET / HTTP/1.1\r\n\r\n",
+            KeepAlive::KeepAlive,
+        ),
+        (
+            Config::new(timeouts.clone()).keep_connection_alive(),
+            "GET / HTTP/1.1\r\nConnection: close\r\n\r\n",
+            KeepAlive::Close,
+        ),
+        (
+            Config::new(timeouts.clone()),
+            "GET / HTTP/1.1\r\n\r\n",
+            KeepAlive::Close,
+        ),
+    ] {
+        let mut http_buffer = [0; 2048];
+        let mut response = Vec::new();
+
+        assert_eq!(
+            serve_and_shutdown(
+                &app,
+                time::TokioTimer,
+                &config,
+                &mut http_buffer,
+                TestSocket {
+                    rx: request.as_bytes(),
+                    tx: &mut response,
+                },
+                &(),
+            )
+            .now_or_never()
+            .expect("Server has stalled")
+            .unwrap(),
+            1
+        );
+
+        let response = String::from_utf8(response).unwrap();
+
+        assert!(
+            response.ends_with(&format!("\r\n\r\n{expected_keep_alive:?}\r\n")),
+            "{request:?}: {response:?}"
+        );
+    }
+}
+
+#[test]
+fn split_buffers() {
+    #[derive(serde::Deserialize)]
+    struct FormValue {
+        value: heapless::String<32>,
+    }
+
+    let app = Router::new().route(
+        "/",
+        routing::post(
+            |extract::Form(FormValue { value })| async move { response::DebugValue(value) },
+        ),
+    );
+
+    let config = Config::new(Timeouts::new(None, None, None)).keep_connection_alive();
+
+    let padding = "-".repeat(70);
+
+    // The first request has headers which leave no space in the header buffer for the body.
+    // The following requests are read into the header buffer along with the first request, and are carried over in the body buffer.
+    let request = format!(
+        "POST / HTTP/1.1\r\nX-Padding: {padding}\r\nContent-Length: 16\r\n\r\nvalue=first+body\
+        POST / HTTP/1.1\r\nContent-Length: 12\r\n\r\nvalue=second\
+        POST / HTTP/1.1\r\nContent-Length: 11\r\n\r\nvalue=third"
+    );
+
+    let mut header_buffer = [0; 128];
+    let mut body_buffer = [0; 96];
+    let mut response = Vec::new();
+
+    assert_eq!(
+        Server::with_buffers(&app, &config, &mut header_buffer, &mut body_buffer)
+            .serve_and_shutdown(
+                time::TokioTimer,
+                TestSocket {
+                    rx: request.as_bytes(),
+                    tx: &mut response,
+                },
+                &(),
+                core::future::pending(),
+            )
+            .now_or_never()
+            .expect("Server has stalled")
+            .unwrap(),
+        3,
+        "{}",
+        String::from_utf8_lossy(&response)
+    );
+
+    let response = String::from_utf8(response).unwrap();
+
+    let bodies = response
+        .split("HTTP/1.1 ")
+        .skip(1)
+        .map(|response| response.split_once("\r\n\r\n").unwrap().1)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        bodies,
+        ["\"first body\"\r\n", "\"second\"\r\n", "\"third\"\r\n"],
+        "{response}"
+    );
+}
+
+#[tokio::test]
+async fn trace_layer() {
+    let (parts, body) = run_single_request_test(
+        &Router::new()
+            .route("/", routing::get(|| async { "Hello World" }))
+            .layer(routing::TraceLayer),
+        hyper::Request::get("/").body(Default::default()).unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(body, "Hello World");
+}
+
+#[tokio::test]
+async fn method_override() {
+    let app = Router::new()
+        .route(
+            "/item",
+            routing::post(|| async { "POST" }).delete(|| async { "DELETE" }),
+        )
+        .layer(routing::MethodOverrideLayer);
+
+    for (request, expected_response) in [
+        (hyper::Request::post("/item"), Ok("POST")),
+        (
+            hyper::Request::post("/item").header("X-HTTP-Method-Override", "DELETE"),
+            Ok("DELETE"),
+        ),
+        (hyper::Request::post("/item?_method=delete"), Ok("DELETE")),
+        (
+            hyper::Request::post("/item").header("X-HTTP-Method-Override", "GET"),
+            Ok("POST"),
+        ),
+        (
+            hyper::Request::get("/item").header("X-HTTP-Method-Override", "DELETE"),
+            Err(StatusCode::METHOD_NOT_ALLOWED),
+        ),
+    ] {
+        let (parts, body) =
+            run_single_request_test(&app, request.body(Default::default()).unwrap()).await;
+
+        match expected_response {
+            Ok(expected_body) => {
+                assert_eq!(parts.status, StatusCode::OK);
+                assert_eq!(body, expected_body);
+            }
+            Err(expected_status) => assert_eq!(parts.status, expected_status),
+        }
+    }
+}
+
+#[tokio::test]
+/// Test that events sent to a client which has stopped reading can be dropped without closing the connection
+async fn sse_stalled_client() {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use response::sse::{EventSource, EventStream, EventWriter, TryWriteEventError};
+
+    struct StallableWriter {
+        output: Rc<RefCell<Vec<u8>>>,
+        is_stalled: Rc<Cell<bool>>,
+    }
+
+    impl io::ErrorType for StallableWriter {
+        type Error = Infallible;
+    }
+
+    impl io::Write for StallableWriter {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            if self.is_stalled.get() {
+                core::future::pending().await
+            } else {
+                self.output.borrow_mut().extend_from_slice(buf);
+
+                Ok(buf.len())
+            }
+        }
+    }
+
+    struct Events {
+        is_stalled: Rc<Cell<bool>>,
+    }
+
+    impl EventSource for Events {
+        async fn write_events<W: io::Write>(
+            self,
+            mut writer: EventWriter<W>,
+        ) -> Result<(), W::Error> {
+            if let Err(TryWriteEventError::Stalled(err) | TryWriteEventError::Io(err)) =
+                writer.try_write_event("message", "first").await
+            {
+                return Err(err);
+            }
+
+            self.is_stalled.set(true);
+
+            assert!(matches!(
+                writer.try_write_event("message", "second").await,
+                Err(TryWriteEventError::Stalled(_))
+            ));
+
+            self.is_stalled.set(false);
+
+            writer.write_event("message", "third").await
+        }
+    }
+
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let is_stalled = Rc::new(Cell::new(false));
+
+    let app = {
+        let is_stalled = is_stalled.clone();
+
+        Router::new().route(
+            "/",
+            routing::get(move || {
+                EventStream(Events {
+                    is_stalled: is_stalled.clone(),
+                })
+            }),
+        )
+    };
+
+    let config = Config::new(Timeouts::new(None, None, Some(Duration::from_millis(10))));
+
+    let (request_tx, request_rx) = pipe();
+
+    request_tx
+        .0
+        .send("GET / HTTP/1.1\r\n\r\n".as_bytes().into())
+        .unwrap();
+
+    let mut http_buffer = [0; 2048];
+
+    let request_count = tokio::time::timeout(
+        Duration::from_secs(1),
+        serve_and_shutdown(
+            &app,
+            time::TokioTimer,
+            &config,
+            &mut http_buffer,
+            TestSocket {
+                rx: request_rx,
+                tx: StallableWriter {
+                    output: output.clone(),
+                    is_stalled,
+                },
+            },
+            &(),
+        ),
+    )
+    .await
+    .expect("Server has stalled")
+    .unwrap();
+
+    assert_eq!(request_count, 1);
+
+    let output = String::from_utf8(output.take()).unwrap();
+
+    let (_headers, events) = output.split_once("\r\n\r\n").unwrap();
+
+    assert_eq!(
+        events,
+        "event:message\ndata:first\n\nevent:message\ndata:third\n\n"
+    );
+
+    drop(request_tx);
+}
+
+#[test]
+fn custom_status_code() {
+    use response::StatusCode;
+
+    assert_eq!(StatusCode::from_u16(99), None);
+    assert_eq!(StatusCode::from_u16(100), Some(StatusCode::CONTINUE));
+    assert_eq!(StatusCode::from_u16(599), Some(StatusCode::new(599)));
+    assert_eq!(StatusCode::from_u16(600), None);
+
+    let app = Router::new()
+        .route(
+            "/custom",
+            routing::get(|| async { (StatusCode::new(420), "Enhance Your Calm") }),
+        )
+        .route(
+            "/teapot",
+            routing::get(|| async { (StatusCode::IM_A_TEAPOT, "Short and Stout") }),
+        );
+
+    for (path, expected_status_line) in [
+        ("/custom", "HTTP/1.1 420 \r\n"),
+        ("/teapot", "HTTP/1.1 418 I'm a teapot\r\n"),
+        ("/missing", "HTTP/1.1 404 Not Found\r\n"),
+    ] {
+        let request = format!("GET {path} HTTP/1.1\r\n\r\n");
+        let mut http_buffer = [0; 2048];
+        let mut response = Vec::new();
+
+        serve_and_shutdown(
+            &app,
+            time::TokioTimer,
+            &Config::new(Timeouts::new(None, None, None)),
+            &mut http_buffer,
+            TestSocket {
+                rx: request.as_bytes(),
+                tx: &mut response,
+            },
+            &(),
+        )
+        .now_or_never()
+        .expect("Server has stalled")
+        .unwrap();
+
+        let response = String::from_utf8(response).unwrap();
+
+        assert!(
+            response.starts_with(expected_status_line),
+            "{path}: {response:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn byte_responses() {
+    let app = Router::new()
+        .route("/slice", routing::get(|| async { &b"\x00\x01"[..] }))
+        .route(
+            "/vec",
+            routing::get(|| async { heapless::Vec::<u8, 3>::from_slice(b"\x00\x01\x02").unwrap() }),
+        );
+
+    for (path, expected_body) in [("/slice", &b"\x00\x01"[..]), ("/vec", &b"\x00\x01\x02"[..])] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(parts.headers["Content-Type"], "application/octet-stream");
+        assert_eq!(
+            parts.headers["Content-Length"],
+            expected_body.len().to_string()
+        );
+        assert_eq!(body, expected_body);
+    }
+}
+
+#[test]
+fn describe_routes() {
+    use routing::{get, parse_path_segment, post};
+
+    let api = Router::<_, ()>::new()
+        .route("/status", get(|| async { "OK" }))
+        .route(
+            ("/user", parse_path_segment::<u32>()),
+            get(|id: u32| async move { response::DebugValue(id) })
+                .post(|id: u32| async move { response::DebugValue(id) }),
+        );
+
+    let app = Router::<_, ()>::new()
+        .route("/", get(|| async { "Hello World" }))
+        .nest("/api", api)
+        .nest_service("/static", response::fs::Directory::DEFAULT)
+        .route("/submit", post(|| async { "Submitted" }));
+
+    let mut routes = Vec::new();
+
+    app.describe(|route| {
+        routes.push((route.path.to_string(), route.methods.map(<[&str]>::to_vec)));
+    });
+
+    assert_eq!(
+        routes,
+        [
+            ("/".to_string(), Some(vec!["GET", "HEAD"])),
+            ("/api/status".to_string(), Some(vec!["GET", "HEAD"])),
+            (
+                "/api/user/{u32}".to_string(),
+                Some(vec!["GET", "HEAD", "POST"])
+            ),
+            ("/static".to_string(), None),
+            ("/submit".to_string(), Some(vec!["POST"])),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn streaming_response() {
+    let app = Router::new().route(
+        "/",
+        routing::get(|| async {
+            response::Streaming::new("text/plain", 1, |n: u32| async move {
+                (n <= 3).then_some((n, n + 1))
+            })
+        }),
+    );
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/").body(Default::default()).unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(parts.headers["Transfer-Encoding"], "chunked");
+    assert_eq!(body, "123".as_bytes());
+}
+
+#[test]
+fn header_validation() {
+    struct XFoo(Option<Vec<u8>>);
+
+    impl<'r, State> extract::FromRequestParts<'r, State> for XFoo {
+        type Rejection = Infallible;
+
+        async fn from_request_parts(
+            _state: &'r State,
+            request_parts: &request::RequestParts<'r>,
+        ) -> Result<Self, Self::Rejection> {
+            Ok(Self(
+                request_parts
+                    .headers()
+                    .get("x-foo")
+                    .map(|value| value.as_raw().to_vec()),
+            ))
+        }
+    }
+
+    let app = Router::new().route(
+        "/",
+        routing::get(|XFoo(value): XFoo| async move { response::DebugValue(value) }),
+    );
+
+    let timeouts = Timeouts::new(None, None, None);
+
+    for (config, request, expected_response) in [
+        (
+            Config::new(timeouts.clone()),
+            "GET / HTTP/1.1\r\nX-Foo: abc%20def\r\n\r\n".as_bytes(),
+            "Some([97, 98, 99, 37, 50, 48, 100, 101, 102])\r\n",
+        ),
+        (
+            Config::new(timeouts.clone()).strict_header_validation(),
+            "GET / HTTP/1.1\r\nX-Foo: abc%20def\r\n\r\n".as_bytes(),
+            "Some([97, 98, 99, 37, 50, 48, 100, 101, 102])\r\n",
+        ),
+        (
+            Config::new(timeouts.clone()),
+            b"GET / HTTP/1.1\r\nX-Foo: a\xffb\r\n\r\n".as_slice(),
+            "Some([97, 255, 98])\r\n",
+        ),
+        (
+            Config::new(timeouts.clone()).strict_header_validation(),
+            b"GET / HTTP/1.1\r\nX-Foo: a\xffb\r\n\r\n".as_slice(),
+            "Invalid Header line",
+        ),
+        (
+            Config::new(timeouts.clone()).strict_header_validation(),
+            b"GET / HTTP/1.1\r\nX-Foo: a\x07b\r\n\r\n".as_slice(),
+            "Invalid Header line",
+        ),
+        (
+            Config::new(timeouts.clone()).strict_header_validation(),
+            "GET / HTTP/1.1\r\nX Foo: abc\r\n\r\n".as_bytes(),
+            "Invalid Header line",
+        ),
+    ] {
+        let mut http_buffer = [0; 2048];
+        let mut response = Vec::new();
+
+        serve_and_shutdown(
+            &app,
+            time::TokioTimer,
+            &config,
+            &mut http_buffer,
+            TestSocket {
+                rx: request,
+                tx: &mut response,
+            },
+            &(),
+        )
+        .now_or_never()
+        .expect("Server has stalled")
+        .unwrap();
+
+        let response = String::from_utf8(response).unwrap();
+
+        assert!(
+            response.ends_with(&format!("\r\n\r\n{expected_response}")),
+            "{request:?}: {response:?}"
+        );
+    }
+}
+
+#[tokio::test]
+/// Test that comma-separated header lists are split into trimmed, non-empty elements, across repeated headers and respecting quoted strings
+async fn header_list() {
+    struct XList(Vec<String>);
+
+    impl<'r, State> extract::FromRequestParts<'r, State> for XList {
+        type Rejection = Infallible;
+
+        async fn from_request_parts(
+            _state: &'r State,
+            request_parts: &request::RequestParts<'r>,
+        ) -> Result<Self, Self::Rejection> {
+            Ok(Self(
+                request_parts
+                    .headers()
+                    .get_list("x-list")
+                    .map(String::from)
+                    .collect(),
+            ))
+        }
+    }
+
+    let app = Router::new().route(
+        "/",
+        routing::get(|XList(list): XList| async move { response::DebugValue(list) }),
+    );
+
+    for (headers, expected_list) in [
+        ("", &[][..]),
+        ("X-List: a, b,c\r\n", &["a", "b", "c"][..]),
+        ("x-list: a,,  , b ,\r\n", &["a", "b"][..]),
+        (
+            "X-List: a\r\nX-Other: z\r\nX-List: b, c\r\n",
+            &["a", "b", "c"][..],
+        ),
+        (
+            "X-List: \"x, y\", W/\"z\\\", w\"\r\n",
+            &["\"x, y\"", "W/\"z\\\", w\""][..],
+        ),
+    ] {
+        let response = String::from_utf8(
+            test::send_request(
+                &app,
+                format!("GET / HTTP/1.1\r\n{headers}Connection: close\r\n\r\n").as_bytes(),
+            )
+            .await,
+        )
+        .unwrap();
+
+        assert!(
+            response.ends_with(&format!("\r\n\r\n{expected_list:?}\r\n")),
+            "{headers:?}: {response:?}"
+        );
+    }
+}
+
+#[test]
+/// Test parsing the media type and parameters of a "Content-Type" header, including quoted values and case-insensitive parameter names
+fn content_type_parameters() {
+    let content_type =
+        request::ContentType::parse("multipart/form-data; boundary=\"abc 123\"").unwrap();
+
+    assert_eq!(content_type.media_type(), "multipart/form-data");
+    assert!(content_type.is("multipart/form-data"));
+    assert!(!content_type.is("multipart/mixed"));
+    assert_eq!(content_type.param("boundary"), Some("abc 123"));
+    assert_eq!(content_type.param("BOUNDARY"), Some("abc 123"));
+    assert_eq!(content_type.param("charset"), None);
+
+    let content_type =
+        request::ContentType::parse(" Text/HTML ;Charset=UTF-8 ; note=\"a; b=c\";; empty=\"\"")
+            .unwrap();
+
+    assert_eq!(content_type.media_type(), "Text/HTML");
+    assert!(content_type.is("text/html"));
+    assert_eq!(content_type.param("charset"), Some("UTF-8"));
+    assert_eq!(content_type.param("note"), Some("a; b=c"));
+    assert_eq!(content_type.param("b"), None);
+    assert_eq!(content_type.param("empty"), Some(""));
+    assert_eq!(
+        content_type.params().collect::<Vec<_>>(),
+        [("Charset", "UTF-8"), ("note", "a; b=c"), ("empty", "")]
+    );
+
+    let content_type = request::ContentType::parse("application/json").unwrap();
+
+    assert_eq!(content_type.media_type(), "application/json");
+    assert_eq!(content_type.params().count(), 0);
+
+    assert!(request::ContentType::parse("").is_none());
+    assert!(request::ContentType::parse(" ; charset=utf-8").is_none());
+}
+
+#[tokio::test]
+async fn matched_path() {
+    use extract::MatchedPath;
+    use routing::{get, parse_path_segment};
+
+    let api = Router::<_, ()>::new().route(
+        ("/user", parse_path_segment::<u32>(), "/posts"),
+        get(|_id: u32, MatchedPath(path): MatchedPath| async move { path }),
+    );
+
+    let app = Router::<_, ()>::new()
+        .route(
+            ("/user", parse_path_segment::<u32>()),
+            get(|_id: u32, MatchedPath(path): MatchedPath| async move { path }),
+        )
+        .nest("/api", api)
+        .route(
+            "/short",
+            get(|MatchedPath(path): MatchedPath<4>| async move { path }),
+        );
+
+    for (path, expected_status, expected_body) in [
+        ("/user/42", StatusCode::OK, "/user/{u32}"),
+        (
+            "/api/user/42/posts",
+            StatusCode::OK,
+            "/api/user/{u32}/posts",
+        ),
+        (
+            "/short",
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Matched Path is too long\n",
+        ),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, expected_status, "{path}");
+        assert_eq!(body, expected_body.as_bytes(), "{path}");
+    }
+}
+
+#[tokio::test]
+async fn read_all_into() {
+    const N: usize = 8;
+
+    struct ReadAllInto;
+
+    impl routing::RequestHandlerService<()> for ReadAllInto {
+        async fn call_request_handler_service<
+            R: Read,
+            W: response::ResponseWriter<Error = R::Error>,
+        >(
+            &self,
+            (): &(),
+            (): (),
+            mut request: request::Request<'_, R>,
+            response_writer: W,
+        ) -> Result<ResponseSent, W::Error> {
+            use response::IntoResponse;
+
+            let body = request.body_connection.body().read_all_into::<N>().await;
+
+            let connection = request.body_connection.finalize().await?;
+
+            match body {
+                Ok(body) => body.write_to(connection, response_writer).await,
+                Err(err) => {
+                    (
+                        response::StatusCode::PAYLOAD_TOO_LARGE,
+                        format_args!("{err:?}"),
+                    )
+                        .write_to(connection, response_writer)
+                        .await
+                }
+            }
+        }
+    }
+
+    let app = Router::new().route("/", routing::post_service(ReadAllInto));
+
+    {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::post("/")
+                .body("a".repeat(N).into_bytes().into())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(body, "a".repeat(N).as_bytes());
+    }
+
+    {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::post("/")
+                .body("a".repeat(N + 1).into_bytes().into())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body, "BufferIsTooSmall".as_bytes());
+    }
+}
+
+#[tokio::test]
+async fn bounded_body() {
+    const N: usize = 8;
+
+    async fn echo(extract::BoundedBody(body): extract::BoundedBody<N>) -> heapless::Vec<u8, N> {
+        body
+    }
+
+    let app = Router::new().route("/", routing::post(echo));
+
+    {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::post("/")
+                .body("a".repeat(N).into_bytes().into())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(body, "a".repeat(N).as_bytes());
+    }
+
+    {
+        let (parts, _) = run_single_request_test(
+            &app,
+            hyper::Request::post("/")
+                .body("a".repeat(N + 1).into_bytes().into())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
+
+#[test]
+fn web_socket_close_code() {
+    use response::ws::{CloseCode, Message, SocketRx};
+
+    for (frame, expected_code, expected_reason) in [
+        ([0x88, 0x00].as_slice(), None, ""),
+        (&[0x88, 0x02, 0x03, 0xe8], Some(CloseCode::Normal), ""),
+        (
+            &[0x88, 0x05, 0x03, 0xe9, b'b', b'y', b'e'],
+            Some(CloseCode::GoingAway),
+            "bye",
+        ),
+        (
+            &[
+                0x88,
+                0x86,
+                1,
+                2,
+                3,
+                4,
+                0x03 ^ 1,
+                0xef ^ 2,
+                b'b' ^ 3,
+                b'a' ^ 4,
+                b'd' ^ 1,
+                b'!' ^ 2,
+            ],
+            Some(CloseCode::InvalidPayload),
+            "bad!",
+        ),
+        (&[0x88, 0x02, 0x0f, 0xa0], Some(CloseCode::Other(4000)), ""),
+    ] {
+        let mut buffer = [0; 16];
+
+        let message = SocketRx::new(frame)
+            .next_message(&mut buffer)
+            .now_or_never()
+            .expect("Reading stalled")
+            .unwrap();
+
+        let Message::Close(reason) = message else {
+            panic!("Expected Close message, got {message:?}");
+        };
+
+        assert_eq!(reason.map(|(code, _)| code), expected_code);
+        assert_eq!(reason.map_or("", |(_, reason)| reason), expected_reason);
+    }
+
+    assert_eq!(u16::from(CloseCode::ProtocolError), 1002);
+    assert_eq!(CloseCode::from(1011), CloseCode::InternalError);
+    assert_eq!(u16::from(CloseCode::from(4321)), 4321);
+}
+
+#[test]
+fn serve_once() {
+    let app = Router::new().route("/", routing::get(|| async { "Hello World" }));
+
+    let config = Config::new(Timeouts::new(None, None, None)).keep_connection_alive();
+
+    {
+        let mut http_buffer = [0; 2048];
+        let mut response = Vec::new();
+
+        assert!(Server::new(&app, &config, &mut http_buffer)
+            .serve_once(
+                TestSocket {
+                    rx: "GET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n".as_bytes(),
+                    tx: &mut response,
+                },
+                &(),
+            )
+            .now_or_never()
+            .expect("Server has stalled")
+            .unwrap()
+            .is_some());
+
+        let response = String::from_utf8(response).unwrap();
+
+        assert_eq!(
+            response.matches("HTTP/1.1 200 OK").count(),
+            1,
+            "{response:?}"
+        );
+        assert!(response.contains("Connection: close\r\n"), "{response:?}");
+        assert!(response.ends_with("\r\n\r\nHello World"), "{response:?}");
+    }
+
+    {
+        let mut http_buffer = [0; 2048];
+        let mut response = Vec::new();
+
+        assert!(Server::new(&app, &config, &mut http_buffer)
+            .serve_once(
+                TestSocket {
+                    rx: "".as_bytes(),
+                    tx: &mut response,
+                },
+                &(),
+            )
+            .now_or_never()
+            .expect("Server has stalled")
+            .unwrap()
+            .is_none());
+
+        assert!(response.is_empty());
+    }
+}
+
+#[test]
+fn custom_reason_phrase() {
+    let app = Router::new()
+        .route(
+            "/custom",
+            routing::get(|| async {
+                response::Response::ok("Hello World").with_reason("Everything Is Fine")
+            }),
+        )
+        .route(
+            "/empty",
+            routing::get(|| async { response::Response::ok("Hello World").with_reason("") }),
+        )
+        .route(
+            "/invalid",
+            routing::get(|| async {
+                response::Response::ok("Hello World").with_reason("OK\r\nX-Injected: true")
+            }),
+        );
+
+    for (path, expected_status_line) in [
+        ("/custom", "HTTP/1.1 200 Everything Is Fine\r\n"),
+        ("/empty", "HTTP/1.1 200 \r\n"),
+        ("/invalid", "HTTP/1.1 200 OK\r\n"),
+    ] {
+        let request = format!("GET {path} HTTP/1.1\r\n\r\n");
+        let mut http_buffer = [0; 2048];
+        let mut response = Vec::new();
+
+        serve_and_shutdown(
+            &app,
+            time::TokioTimer,
+            &Config::new(Timeouts::new(None, None, None)),
+            &mut http_buffer,
+            TestSocket {
+                rx: request.as_bytes(),
+                tx: &mut response,
+            },
+            &(),
+        )
+        .now_or_never()
+        .expect("Server has stalled")
+        .unwrap();
+
+        let response = String::from_utf8(response).unwrap();
+
+        assert!(
+            response.starts_with(expected_status_line),
+            "{path}: {response:?}"
+        );
+        assert!(!response.contains("X-Injected"), "{path}: {response:?}");
+    }
+}
+
+#[tokio::test]
+async fn connection_filter() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let app = Router::new().route("/", routing::get(|| async { "Hello World" }));
+
+    let timeouts = Timeouts::new(None, None, None);
+
+    for (config, expected_request_count, expect_response) in [
+        (
+            Config::new(timeouts.clone()).connection_filter(|address| address.ip().is_loopback()),
+            1,
+            true,
+        ),
+        (
+            Config::new(timeouts.clone()).connection_filter(|address| !address.ip().is_loopback()),
+            0,
+            false,
+        ),
+    ] {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+        let client = tokio::spawn({
+            let address = listener.local_addr().unwrap();
+
+            async move {
+                let mut client = tokio::net::TcpStream::connect(address).await.unwrap();
+
+                client
+                    .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+                    .await
+                    .unwrap();
+
+                let mut response = Vec::new();
+                let _ = client.read_to_end(&mut response).await;
+
+                response
+            }
+        });
+
+        let (stream, _) = listener.accept().await.unwrap();
+
+        let mut http_buffer = [0; 2048];
+
+        let request_count = Server::new(&app, &config, &mut http_buffer)
+            .serve(stream)
+            .await
+            .unwrap();
+
+        assert_eq!(request_count, expected_request_count);
+
+        let response = client.await.unwrap();
+
+        assert_eq!(
+            response.starts_with(b"HTTP/1.1 200 OK\r\n"),
+            expect_response,
+            "{:?}",
+            String::from_utf8_lossy(&response)
+        );
+
+        if !expect_response {
+            assert!(response.is_empty());
+        }
+    }
+}
+
+#[test]
+/// Test that the connection filter also applies to `serve_once`, and closes connections whose remote address is not known
+fn connection_filter_serve_once() {
+    let app = Router::new().route("/", routing::get(|| async { "Hello World" }));
+
+    let config = Config::new(Timeouts::new(None, None, None))
+        .connection_filter(|address| address.ip().is_loopback());
+
+    for (remote_address, expected_response) in [
+        (
+            Some(core::net::SocketAddr::from(([127, 0, 0, 1], 12345))),
+            true,
+        ),
+        (
+            Some(core::net::SocketAddr::from(([10, 0, 0, 1], 12345))),
+            false,
+        ),
+        (None, false),
+    ] {
+        let mut http_buffer = [0; 2048];
+        let mut response = Vec::new();
+
+        let response_sent = Server::new(&app, &config, &mut http_buffer)
+            .serve_once(
+                WithRemoteAddress {
+                    socket: TestSocket {
+                        rx: &b"GET / HTTP/1.1\r\n\r\n"[..],
+                        tx: &mut response,
+                    },
+                    remote_address,
+                },
+                &(),
+            )
+            .now_or_never()
+            .expect("Server has stalled")
+            .unwrap();
+
+        assert_eq!(
+            response_sent.is_some(),
+            expected_response,
+            "{remote_address:?}"
+        );
+        assert_eq!(
+            response.starts_with(b"HTTP/1.1 200 OK\r\n"),
+            expected_response,
+            "{remote_address:?}"
+        );
+
+        if !expected_response {
+            assert!(response.is_empty(), "{remote_address:?}");
+        }
+    }
+}
+
+#[tokio::test]
+/// Test that a server with a state stops reading requests once the shutdown signal completes,
+/// both while waiting for the next request and once the request being handled has finished
+async fn graceful_shutdown_with_state() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    struct AppState<'a> {
+        value: u32,
+        shutdown: &'a tokio::sync::Notify,
+    }
+
+    impl<'a> extract::FromRef<AppState<'a>> for u32 {
+        fn from_ref(state: &AppState<'a>) -> Self {
+            state.value
+        }
+    }
+
+    impl<'a> extract::FromRef<AppState<'a>> for &'a tokio::sync::Notify {
+        fn from_ref(state: &AppState<'a>) -> Self {
+            state.shutdown
+        }
+    }
+
+    async fn get_value(extract::State(value): extract::State<u32>) -> response::DebugValue<u32> {
+        response::DebugValue(value)
+    }
+
+    async fn begin_shutdown(
+        extract::State(shutdown): extract::State<&tokio::sync::Notify>,
+    ) -> &'static str {
+        shutdown.notify_one();
+        "Shutting down\n"
+    }
+
+    let shutdown = tokio::sync::Notify::new();
+
+    let app = Router::new()
+        .route("/value", routing::get(get_value))
+        .route("/shutdown", routing::post(begin_shutdown));
+
+    let config = Config::new(Timeouts::new(None, None, None)).keep_connection_alive();
+
+    let state = AppState {
+        value: 42,
+        shutdown: &shutdown,
+    };
+
+    // The shutdown signal completes while the server is waiting for the next request
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let client = async {
+        let mut client = tokio::net::TcpStream::connect(address).await.unwrap();
+
+        client
+            .write_all(b"GET /value HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut response = Vec::new();
+
+        while !response.ends_with(b"\r\n\r\n42\r\n") {
+            let mut buffer = [0; 256];
+            let read_size = client.read(&mut buffer).await.unwrap();
+            assert_ne!(read_size, 0, "{:?}", String::from_utf8_lossy(&response));
+            response.extend_from_slice(&buffer[..read_size]);
+        }
+
+        shutdown.notify_one();
+
+        client.read_to_end(&mut response).await.unwrap();
+
+        String::from_utf8(response).unwrap()
+    };
+
+    let server = async {
+        let (stream, _) = listener.accept().await.unwrap();
+
+        let mut http_buffer = [0; 2048];
+
+        Server::new(&app, &config, &mut http_buffer)
+            .serve_with_state_and_graceful_shutdown(stream, &state, shutdown.notified())
+            .await
+            .unwrap()
+    };
+
+    let (response, request_count) = tokio::join!(client, server);
+
+    assert_eq!(request_count, 1);
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("\r\nConnection: keep-alive\r\n"));
+    assert!(response.ends_with("\r\n\r\n42\r\n"));
+
+    // The shutdown signal completes while a request is being handled, so pipelined requests are not handled
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let client = async {
+        let mut client = tokio::net::TcpStream::connect(address).await.unwrap();
+
+        client
+            .write_all(
+                b"POST /shutdown HTTP/1.1\r\nContent-Length: 0\r\n\r\nGET /value HTTP/1.1\r\n\r\n",
+            )
+            .await
+            .unwrap();
+
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).await.unwrap();
+
+        String::from_utf8(response).unwrap()
+    };
+
+    let server = async {
+        let (stream, _) = listener.accept().await.unwrap();
+
+        let mut http_buffer = [0; 2048];
+
+        Server::new(&app, &config, &mut http_buffer)
+            .serve_with_state_and_graceful_shutdown(stream, &state, shutdown.notified())
+            .await
+            .unwrap()
+    };
+
+    let (response, request_count) = tokio::join!(client, server);
+
+    assert_eq!(request_count, 1);
+    assert_eq!(response.matches("HTTP/1.1 ").count(), 1);
+    assert!(response.ends_with("\r\n\r\nShutting down\n"));
+}
+
+#[test]
+#[should_panic(expected = "declared Content-Length")]
+fn mismatched_content_length() {
+    struct MismatchedContent;
+
+    impl response::Content for MismatchedContent {
+        fn content_type(&self) -> &'static str {
+            "text/plain"
+        }
+
+        fn content_length(&self) -> usize {
+            10
+        }
+
+        async fn write_content<W: io::Write>(self, mut writer: W) -> Result<(), W::Error> {
+            writer.write_all(b"Hello").await
+        }
+    }
+
+    let app = Router::new().route(
+        "/",
+        routing::get(|| async { response::Response::ok(MismatchedContent) }),
+    );
+
+    let mut http_buffer = [0; 2048];
+    let mut response = Vec::new();
+
+    let _ = serve_and_shutdown(
+        &app,
+        time::TokioTimer,
+        &Config::new(Timeouts::new(None, None, None)),
+        &mut http_buffer,
+        TestSocket {
+            rx: "GET / HTTP/1.1\r\n\r\n".as_bytes(),
+            tx: &mut response,
+        },
+        &(),
+    )
+    .now_or_never();
+}
+
+#[tokio::test]
+async fn text_content_type_charset() {
+    let app = Router::new()
+        .route("/text", routing::get(|| async { "Hello World" }))
+        .route(
+            "/html",
+            routing::get_service(response::File::html("<h1>Hello World</h1>")),
+        )
+        .route(
+            "/custom",
+            routing::get(|| async {
+                response::Response::ok("Hello World")
+                    .with_content_type("text/plain; charset=us-ascii")
+            }),
+        );
+
+    for (path, expected_content_type) in [
+        ("/text", "text/plain; charset=utf-8"),
+        ("/html", "text/html; charset=utf-8"),
+        ("/custom", "text/plain; charset=us-ascii"),
+    ] {
+        let (parts, _) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(
+            parts.headers.get("Content-Type").unwrap(),
+            expected_content_type,
+            "{path}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn format_arguments_response() {
+    struct Sum(u32, u32);
+
+    impl response::IntoResponse for Sum {
+        async fn write_to<R: Read, W: response::ResponseWriter<Error = R::Error>>(
+            self,
+            connection: response::Connection<'_, R>,
+            response_writer: W,
+        ) -> Result<ResponseSent, W::Error> {
+            let Self(a, b) = self;
+
+            format_args!("{a} + {b} = {}", a + b)
+                .write_to(connection, response_writer)
+                .await
+        }
+    }
+
+    let app = Router::new()
+        .route("/", routing::get(|| async { format_args!("Hello World") }))
+        .route("/sum", routing::get(|| async { Sum(12, 30) }));
+
+    for (path, expected_body) in [("/", "Hello World"), ("/sum", "12 + 30 = 42")] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts.headers.get("Content-Type").unwrap(),
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(
+            parts.headers.get("Content-Length").unwrap(),
+            &expected_body.len().to_string()
+        );
+        assert_eq!(body, expected_body.as_bytes());
+    }
+}
+
+#[tokio::test]
+async fn layer_short_circuit() {
+    struct RequireAuthorization;
+
+    impl<State, PathParameters> routing::Layer<State, PathParameters> for RequireAuthorization {
+        type NextState = State;
+        type NextPathParameters = PathParameters;
+
+        async fn call_layer<
+            'a,
+            R: Read + 'a,
+            NextLayer: routing::Next<'a, R, Self::NextState, Self::NextPathParameters>,
+            W: response::ResponseWriter<Error = R::Error>,
+        >(
+            &self,
+            next: NextLayer,
+            state: &State,
+            path_parameters: PathParameters,
+            request_parts: request::RequestParts<'_>,
+            response_writer: W,
+        ) -> Result<ResponseSent, W::Error> {
+            use response::IntoResponse;
+
+            if request_parts
+                .headers()
+                .get("Authorization")
+                .is_some_and(|authorization| authorization.as_raw() == b"Bearer secret")
+            {
+                next.run(state, path_parameters, response_writer).await
+            } else {
+                (response::StatusCode::UNAUTHORIZED, "Unauthorized")
+                    .write_to(next.into_connection().await?, response_writer)
+                    .await
+            }
+        }
+    }
+
+    let handler_calls = std::sync::atomic::AtomicUsize::new(0);
+    let handler_calls = &handler_calls;
+
+    let app = Router::new()
+        .route(
+            "/",
+            routing::post(move || async move {
+                handler_calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                "Secret"
+            }),
+        )
+        .layer(RequireAuthorization);
+
+    for (authorization, expected_status, expected_body, expected_handler_calls) in [
+        (None, StatusCode::UNAUTHORIZED, "Unauthorized", 0),
+        (
+            Some("Bearer wrong"),
+            StatusCode::UNAUTHORIZED,
+            "Unauthorized",
+            0,
+        ),
+        (Some("Bearer secret"), StatusCode::OK, "Secret", 1),
+    ] {
+        let mut request = hyper::Request::post("/");
+
+        if let Some(authorization) = authorization {
+            request = request.header("Authorization", authorization);
+        }
+
+        let (parts, body) = run_single_request_test(
+            &app,
+            request
+                .body(hyper::body::Bytes::from("Request Body").into())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, expected_status, "{authorization:?}");
+        assert_eq!(body, expected_body.as_bytes());
+        assert_eq!(
+            handler_calls.load(std::sync::atomic::Ordering::Relaxed),
+            expected_handler_calls
+        );
+    }
+}
+
+#[tokio::test]
+async fn event_stream_with_shutdown_signal() {
+    struct Events;
+
+    impl response::sse::EventSourceWithShutdownSignal for Events {
+        async fn write_events<W: io::Write>(
+            self,
+            mut writer: response::sse::EventWriter<W>,
+            shutdown_signal: impl core::future::Future<Output = ()>,
+        ) -> Result<(), W::Error> {
+            writer.write_event("message", "Hello").await?;
+
+            shutdown_signal.await;
+
+            writer.write_event("server_closing", "Goodbye").await
+        }
+    }
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+
+    let app = Router::new().route(
+        "/events",
+        routing::get(move || response::EventStreamWithShutdownSignal {
+            source: Events,
+            shutdown_signal: {
+                let mut shutdown_rx = shutdown_rx.clone();
+                async move {
+                    let _ = shutdown_rx
+                        .wait_for(|&is_shutting_down| is_shutting_down)
+                        .await;
+                }
+            },
+        }),
+    );
+
+    let (request_tx, request_rx) = pipe();
+    let (response_tx, mut response_rx) = pipe();
+
+    let config = Config::new(Timeouts::new(None, None, None));
+
+    let mut http_buffer = [0; 2048];
+
+    let mut server = std::pin::pin!(serve_and_shutdown(
+        &app,
+        time::TokioTimer,
+        &config,
+        &mut http_buffer,
+        TestSocket {
+            rx: request_rx,
+            tx: response_tx,
+        },
+        &(),
+    ));
+
+    request_tx
+        .0
+        .send("GET /events HTTP/1.1\r\n\r\n".as_bytes().into())
+        .unwrap();
+
+    assert!((&mut server).now_or_never().is_none());
+
+    shutdown_tx.send(true).unwrap();
+
+    assert_eq!(
+        server.now_or_never().expect("Server has stalled").unwrap(),
+        1
+    );
+
+    drop(request_tx);
+
+    let mut response = Vec::new();
+    let mut buffer = [0; 1024];
+
+    loop {
+        match response_rx.read(&mut buffer).await.unwrap() {
+            0 => break,
+            read_size => response.extend_from_slice(&buffer[..read_size]),
+        }
+    }
+
+    let response = String::from_utf8(response).unwrap();
+
+    assert!(
+        response.ends_with("event:message\ndata:Hello\n\nevent:server_closing\ndata:Goodbye\n\n"),
+        "{response:?}"
+    );
+}
+
+#[tokio::test]
+async fn request_start_time() {
+    let app = Router::new().route(
+        "/",
+        routing::get(
+            |request_start: extract::RequestStart, extract::Now(now)| async move {
+                assert!(request_start.instant() <= now);
+
+                tokio::time::sleep(Duration::from_millis(20)).await;
+
+                let elapsed = request_start.elapsed();
+
+                assert!(elapsed >= Duration::from_millis(20), "{elapsed:?}");
+                assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+
+                "OK"
+            },
+        ),
+    );
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/").body(Default::default()).unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(body, "OK".as_bytes());
+}
+
+#[tokio::test]
+/// Test that a route can handle each method with a chained handler
+async fn method_routing() {
+    let app = Router::new().route(
+        "/item",
+        routing::get(|| async { "GET" })
+            .put(|| async { "PUT" })
+            .delete(|| async { "DELETE" })
+            .patch(|| async { "PATCH" })
+            .options(|| async { "OPTIONS" })
+            .trace(|| async { "TRACE" }),
+    );
+
+    for method in ["GET", "PUT", "DELETE", "PATCH", "OPTIONS", "TRACE"] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::builder()
+                .method(method)
+                .uri("/item")
+                .body(Default::default())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK, "{method}");
+        assert_eq!(body, method.as_bytes());
+    }
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::head("/item")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert!(body.is_empty());
+
+    let (parts, _body) = run_single_request_test(
+        &app,
+        hyper::Request::post("/item")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[tokio::test]
+async fn head_only_routing() {
+    let app = Router::new().route(
+        "/exists",
+        routing::head(|| async { (("X-Exists", "yes"), "This body is never sent") }),
+    );
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::head("/exists")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(parts.headers["X-Exists"], "yes");
+    assert!(body.is_empty());
+
+    for method in ["GET", "POST"] {
+        let (parts, _body) = run_single_request_test(
+            &app,
+            hyper::Request::builder()
+                .method(method)
+                .uri("/exists")
+                .body(Default::default())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::METHOD_NOT_ALLOWED, "{method}");
+    }
+}
+
+#[tokio::test]
+/// Test that the path parameters of a Web Socket route are available to the callback once the connection has been upgraded
+async fn web_socket_with_path_parameters() {
+    struct JoinRoom {
+        room_id: u32,
+    }
+
+    impl response::ws::WebSocketCallback for JoinRoom {
+        async fn run<R: Read, W: io::Write<Error = R::Error>>(
+            self,
+            _rx: response::ws::SocketRx<R>,
+            mut tx: response::ws::SocketTx<W>,
+        ) -> Result<(), W::Error> {
+            tx.send_text(&format!("Room {}", self.room_id)).await
+        }
+    }
+
+    let app = Router::new().route(
+        ("/rooms", routing::parse_path_segment::<u32>(), "/ws"),
+        routing::get(
+            |room_id: u32, upgrade: response::WebSocketUpgrade| async move {
+                upgrade.on_upgrade(JoinRoom { room_id })
+            },
+        ),
+    );
+
+    let handshake = |path: &str| {
+        format!(
+            concat!(
+                "GET {} HTTP/1.1\r\n",
+                "Connection: Upgrade\r\n",
+                "Upgrade: websocket\r\n",
+                "Sec-WebSocket-Version: 13\r\n",
+                "Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n",
+                "\r\n",
+            ),
+            path
+        )
+    };
+
+    let response = test::send_request(&app, handshake("/rooms/42/ws").as_bytes()).await;
+
+    assert!(
+        response.starts_with(b"HTTP/1.1 101 Switching Protocols\r\n"),
+        "{:?}",
+        String::from_utf8_lossy(&response)
+    );
+    assert!(
+        response.ends_with(b"\r\n\r\n\x81\x07Room 42"),
+        "{:?}",
+        String::from_utf8_lossy(&response)
+    );
+
+    let response = test::send_request(&app, handshake("/rooms/lobby/ws").as_bytes()).await;
+
+    assert!(response.starts_with(b"HTTP/1.1 404 Not Found\r\n"));
+}
+
+#[tokio::test]
+/// Test that a Web Socket handshake on a server which keeps connections alive upgrades the connection,
+/// and that the server does not attempt to read another request once the upgraded connection has finished
+async fn web_socket_upgrade_with_keep_alive() {
+    struct SendGreeting;
+
+    impl response::ws::WebSocketCallback for SendGreeting {
+        async fn run<R: Read, W: io::Write<Error = R::Error>>(
+            self,
+            _rx: response::ws::SocketRx<R>,
+            mut tx: response::ws::SocketTx<W>,
+        ) -> Result<(), W::Error> {
+            tx.send_text("Hello").await
+        }
+    }
+
+    let app = Router::new().route(
+        "/ws",
+        routing::get(|upgrade: response::WebSocketUpgrade| upgrade.on_upgrade(SendGreeting)),
+    );
+
+    let config = Config::new(Timeouts::new(None, None, None)).keep_connection_alive();
+
+    let mut http_buffer = [0; 2048];
+    let mut response = Vec::new();
+
+    let server = serve_and_shutdown(
+        &app,
+        time::TokioTimer,
+        &config,
+        &mut http_buffer,
+        TestSocket {
+            rx: concat!(
+                "GET /ws HTTP/1.1\r\n",
+                "Connection: keep-alive, Upgrade\r\n",
+                "Upgrade: websocket\r\n",
+                "Sec-WebSocket-Version: 13\r\n",
+                "Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n",
+                "\r\n",
+                "GET /ws HTTP/1.1\r\n\r\n",
+            )
+            .as_bytes(),
+            tx: &mut response,
+        },
+        &(),
+    );
+
+    assert_eq!(
+        server.now_or_never().expect("Server has stalled").unwrap(),
+        1
+    );
+
+    assert!(response.ends_with(b"\r\n\r\n\x81\x05Hello"));
+
+    let response = String::from_utf8_lossy(&response);
+
+    assert!(
+        response.starts_with("HTTP/1.1 101 Switching Protocols\r\n"),
+        "{response:?}"
+    );
+    assert!(
+        response.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"),
+        "{response:?}"
+    );
+    assert!(response.contains("Connection: upgrade\r\n"), "{response:?}");
+    assert!(!response.contains("Connection: close"), "{response:?}");
+
+    let (parts, _body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/ws")
+            .header("Upgrade", "websocket")
+            .header("Sec-WebSocket-Version", "13")
+            .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+/// Test that a Web Socket handshake with a long list of protocols is rejected with a clear error if the protocols don't fit,
+/// is accepted if `PROTOCOLS_SIZE` is increased, and that handshake headers which don't fit in the HTTP buffer are rejected
+async fn web_socket_upgrade_with_large_protocol_list() {
+    struct Disconnect;
+
+    impl response::ws::WebSocketCallback for Disconnect {
+        async fn run<R: Read, W: io::Write<Error = R::Error>>(
+            self,
+            _rx: response::ws::SocketRx<R>,
+            _tx: response::ws::SocketTx<W>,
+        ) -> Result<(), W::Error> {
+            Ok(())
+        }
+    }
+
+    fn last_protocol<const PROTOCOLS_SIZE: usize>(
+        upgrade: &response::WebSocketUpgrade<PROTOCOLS_SIZE>,
+    ) -> heapless::String<32> {
+        upgrade
+            .protocols()
+            .and_then(|protocols| protocols.last())
+            .and_then(|protocol| protocol.try_into().ok())
+            .unwrap_or_default()
+    }
+
+    let app = Router::new()
+        .route(
+            "/ws",
+            routing::get(|upgrade: response::WebSocketUpgrade| async move {
+                let protocol = last_protocol(&upgrade);
+                upgrade.on_upgrade(Disconnect).with_protocol(protocol)
+            }),
+        )
+        .route(
+            "/ws-large",
+            routing::get(|upgrade: response::WebSocketUpgrade<512>| async move {
+                let protocol = last_protocol(&upgrade);
+                upgrade.on_upgrade(Disconnect).with_protocol(protocol)
+            }),
+        );
+
+    let protocols = (0..20)
+        .map(|index| format!("chat.v{index}.example.com"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let handshake = |path: &str, protocols: &str| {
+        format!(
+            concat!(
+                "GET {} HTTP/1.1\r\n",
+                "Connection: Upgrade\r\n",
+                "Upgrade: websocket\r\n",
+                "Sec-WebSocket-Version: 13\r\n",
+                "Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n",
+                "Sec-WebSocket-Protocol: {}\r\n",
+                "\r\n",
+            ),
+            path, protocols,
+        )
+    };
+
+    let response =
+        String::from_utf8(test::send_request(&app, handshake("/ws", &protocols).as_bytes()).await)
+            .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large\r\n"));
+    assert!(response.ends_with("Websocket upgrade `Sec-WebSocket-Protocol` header is too long\n"));
+
+    let response = String::from_utf8(
+        test::send_request(&app, handshake("/ws-large", &protocols).as_bytes()).await,
+    )
+    .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 101 Switching Protocols\r\n"));
+    assert!(response.contains("\r\nSec-WebSocket-Protocol: chat.v19.example.com\r\n"));
+
+    let protocols = (0..200)
+        .map(|index| format!("chat.v{index}.example.com"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let response = String::from_utf8(
+        test::send_request(&app, handshake("/ws-large", &protocols).as_bytes()).await,
+    )
+    .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large\r\n"));
+    assert!(response.ends_with("\r\n\r\nRequest headers are too large"));
+}
+
+#[test]
+/// Test that the client IP address is read from trusted proxy headers, falling back to the address of the remote peer
+fn client_ip() {
+    let app = Router::new().route(
+        "/",
+        routing::get(
+            |client_ip: extract::ClientIp| async move { response::DebugValue(client_ip.0) },
+        ),
+    );
+
+    let remote_address = core::net::SocketAddr::from(([10, 0, 0, 1], 12345));
+
+    let client_ip =
+        |proxy_trust: ProxyTrust, remote_address: Option<core::net::SocketAddr>, headers: &str| {
+            let config = Config::new(Timeouts::new(None, None, None)).proxy_trust(proxy_trust);
+
+            let request = format!("GET / HTTP/1.1\r\n{headers}\r\n");
+
+            let mut http_buffer = [0; 2048];
+            let mut response = Vec::new();
+
+            Server::new(&app, &config, &mut http_buffer)
+                .serve_and_shutdown(
+                    time::TokioTimer,
+                    WithRemoteAddress {
+                        socket: TestSocket {
+                            rx: request.as_bytes(),
+                            tx: &mut response,
+                        },
+                        remote_address,
+                    },
+                    &(),
+                    core::future::pending(),
+                )
+                .now_or_never()
+                .expect("Server has stalled")
+                .unwrap();
+
+            let response = String::from_utf8(response).unwrap();
+
+            let (status_line, response) = response.split_once("\r\n").unwrap();
+            let (_headers, body) = response.split_once("\r\n\r\n").unwrap();
+
+            status_line
+                .starts_with("HTTP/1.1 200 ")
+                .then(|| body.trim_end().to_owned())
+        };
+
+    for (proxy_trust, headers, expected_client_ip) in [
+        (ProxyTrust::None, "", "10.0.0.1"),
+        (ProxyTrust::FirstHop, "", "10.0.0.1"),
+        (ProxyTrust::LastHop, "", "10.0.0.1"),
+        (ProxyTrust::None, "X-Forwarded-For: 192.0.2.1\r\n", "10.0.0.1"),
+        (
+            ProxyTrust::FirstHop,
+            "X-Forwarded-For: 192.0.2.1, 198.51.100.2\r\n",
+            "192.0.2.1",
+        ),
+        (
+            ProxyTrust::LastHop,
+            "X-Forwarded-For: 192.0.2.1, 198.51.100.2\r\n",
+            "198.51.100.2",
+        ),
+        (
+            ProxyTrust::FirstHop,
+            "X-Forwarded-For: 2001:db8::1\r\n",
+            "2001:db8::1",
+        ),
+        (
+            ProxyTrust::FirstHop,
+            "Forwarded: for=192.0.2.60;proto=http;by=203.0.113.43, for=\"[2001:db8:cafe::17]:4711\"\r\n",
+            "192.0.2.60",
+        ),
+        (
+            ProxyTrust::LastHop,
+            "Forwarded: for=192.0.2.60;proto=http;by=203.0.113.43, for=\"[2001:db8:cafe::17]:4711\"\r\n",
+            "2001:db8:cafe::17",
+        ),
+        (
+            ProxyTrust::LastHop,
+            "Forwarded: For=\"198.51.100.17:8080\"\r\nX-Forwarded-For: 192.0.2.1\r\n",
+            "198.51.100.17",
+        ),
+        (ProxyTrust::FirstHop, "Forwarded: for=unknown\r\n", "10.0.0.1"),
+    ] {
+        assert_eq!(
+            client_ip(proxy_trust, Some(remote_address), headers).as_deref(),
+            Some(expected_client_ip),
+            "{proxy_trust:?} {headers:?}"
+        );
+    }
+
+    assert_eq!(
+        client_ip(ProxyTrust::FirstHop, None, "X-Forwarded-For: 192.0.2.1\r\n").as_deref(),
+        Some("192.0.2.1")
+    );
+
+    assert_eq!(client_ip(ProxyTrust::FirstHop, None, ""), None);
+}
+
+#[cfg(feature = "derive")]
+#[tokio::test]
+/// Test that deriving FromRef allows each field of the state to be extracted
+async fn derive_from_ref() {
+    #[derive(Clone)]
+    struct Name(&'static str);
+
+    #[derive(extract::FromRef)]
+    struct AppState<'a, T> {
+        name: Name,
+        counter: &'a core::cell::Cell<u32>,
+        #[from_ref(skip)]
+        _other: T,
+    }
+
+    #[derive(extract::FromRef)]
+    struct TupleState(u32, #[from_ref(skip)] ());
+
+    async fn increment(extract::State(counter): extract::State<&core::cell::Cell<u32>>) {
+        counter.set(counter.get() + 1);
+    }
+
+    let counter = core::cell::Cell::new(0);
+
+    let state = AppState {
+        name: Name("picoserve"),
+        counter: &counter,
+        _other: (),
+    };
+
+    let app = Router::new()
+        .route(
+            "/name",
+            routing::get(|extract::State(Name(name)): extract::State<Name>| async move { name }),
+        )
+        .route("/increment", routing::get(increment));
+
+    for path in ["/name", "/increment", "/increment"] {
+        let config = Config::new(Timeouts::new(None, None, None));
+
+        let mut http_buffer = [0; 2048];
+        let mut response = Vec::new();
+
+        let request = format!("GET {path} HTTP/1.1\r\n\r\n");
+
+        serve_and_shutdown(
+            &app,
+            time::TokioTimer,
+            &config,
+            &mut http_buffer,
+            TestSocket {
+                rx: request.as_bytes(),
+                tx: &mut response,
+            },
+            &state,
+        )
+        .await
+        .unwrap();
+
+        let response = String::from_utf8(response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 "), "{response:?}");
+
+        if path == "/name" {
+            assert!(response.ends_with("\r\n\r\npicoserve"), "{response:?}");
+        }
+    }
+
+    assert_eq!(counter.get(), 2);
+
+    assert_eq!(
+        <u32 as extract::FromRef<TupleState>>::from_ref(&TupleState(1, ())),
+        1
+    );
+}
+
+#[tokio::test]
+async fn absolute_redirect() {
+    let app = Router::new()
+        .route(
+            "/relative",
+            routing::get(|| response::Redirect::to("../login")),
+        )
+        .route(
+            "/absolute",
+            routing::get(|| {
+                response::Redirect::to_absolute("https", "example.com:8443", "/login?next=%2F")
+            }),
+        );
+
+    for (path, expected_location) in [
+        ("/relative", "../login"),
+        ("/absolute", "https://example.com:8443/login?next=%2F"),
+    ] {
+        let (parts, _body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::SEE_OTHER, "{path}");
+        assert_eq!(parts.headers["Location"], expected_location, "{path}");
+    }
+}
+
+#[tokio::test]
+async fn redirect_status_codes() {
+    let app = Router::new()
+        .route(
+            "/see_other",
+            routing::post(|| response::Redirect::see_other("/")),
+        )
+        .route("/found", routing::post(|| response::Redirect::found("/")))
+        .route(
+            "/temporary",
+            routing::post(|| response::Redirect::temporary("/")),
+        )
+        .route(
+            "/permanent",
+            routing::post(|| response::Redirect::permanent("/")),
+        );
+
+    for (path, expected_status) in [
+        ("/see_other", StatusCode::SEE_OTHER),
+        ("/found", StatusCode::FOUND),
+        ("/temporary", StatusCode::TEMPORARY_REDIRECT),
+        ("/permanent", StatusCode::PERMANENT_REDIRECT),
+    ] {
+        let (parts, _body) = run_single_request_test(
+            &app,
+            hyper::Request::post(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, expected_status, "{path}");
+        assert_eq!(parts.headers["Location"], "/", "{path}");
+    }
+}
+
+#[tokio::test]
+async fn json_rejection() {
+    #[derive(serde::Deserialize)]
+    struct Reading {
+        value: u32,
+    }
+
+    let app = Router::new().route(
+        "/",
+        routing::post(
+            |extract::Json(Reading { value }): extract::Json<_>| async move {
+                response::DebugValue(value)
+            },
+        ),
+    );
+
+    for (request_body, expected_message) in [
+        (
+            r#"{"value":"secret""#,
+            "Failed to parse JSON body: Invalid type",
+        ),
+        (
+            r#"{"value":12"#,
+            "Failed to parse JSON body: EOF while parsing an object.",
+        ),
+        (
+            r#"{"value" 12}"#,
+            "Failed to parse JSON body: Expected this character to be a `':'`.",
+        ),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::post("/")
+                .body(request_body.as_bytes().to_vec().into())
+                .unwrap(),
+        )
+        .await;
+
+        let body = std::str::from_utf8(&body).unwrap();
+
+        assert_eq!(parts.status, StatusCode::BAD_REQUEST, "{request_body}");
+        assert_eq!(body.trim_end(), expected_message, "{request_body}");
+        assert!(!body.contains("secret"), "{request_body}");
+    }
+}
+
+#[tokio::test]
+/// Test that unread request bodies up to the limit are discarded, keeping the connection alive, and that larger unread bodies close the connection
+async fn auto_drain_body() {
+    let app = Router::new().route("/", routing::post(|| async { "Ignored" }));
+
+    let request_payload = format!(
+        "POST / HTTP/1.1\r\nContent-Length: 4\r\n\r\nBODY\
+        POST / HTTP/1.1\r\nContent-Length: 100\r\n\r\n{}\
+        POST / HTTP/1.1\r\n\r\n",
+        "a".repeat(100)
+    );
+
+    for (auto_drain_body, expected_request_count) in [(None, 3), (Some(8), 2)] {
+        let config = Config {
+            auto_drain_body,
+            ..Config::new(Timeouts::new(None, None, None)).keep_connection_alive()
+        };
+
+        let mut http_buffer = [0; 64];
+        let mut response = Vec::new();
+
+        let server = serve_and_shutdown(
+            &app,
+            time::TokioTimer,
+            &config,
+            &mut http_buffer,
+            TestSocket {
+                rx: request_payload.as_bytes(),
+                tx: &mut response,
+            },
+            &(),
+        );
+
+        assert_eq!(
+            server.now_or_never().expect("Server has stalled").unwrap(),
+            expected_request_count
+        );
+
+        let response = String::from_utf8_lossy(&response);
+
+        assert_eq!(
+            response.matches("Connection: close\r\n").count(),
+            usize::from(auto_drain_body.is_some()),
+            "{response:?}"
+        );
+    }
+}
+
+#[test]
+/// Test that a fragmented Text message, with a Ping message between its frames, is read in chunks no larger than the buffer
+fn web_socket_message_chunks() {
+    use response::ws::{MessageChunk, MessageOpcode, ReadMessageError, SocketRx};
+
+    fn masked_frame(first: u8, data: &[u8]) -> Vec<u8> {
+        let mask = [0x12, 0x34, 0x56, 0x78];
+
+        [first, 0x80 | data.len() as u8]
+            .into_iter()
+            .chain(mask)
+            .chain(data.iter().zip(mask.iter().cycle()).map(|(d, m)| d ^ m))
+            .collect()
+    }
+
+    let frames = [
+        masked_frame(0x01, b"Hello"),
+        masked_frame(0x89, b"ping"),
+        masked_frame(0x00, b", "),
+        masked_frame(0x80, b"World!"),
+    ]
+    .concat();
+
+    let mut rx = SocketRx::new(frames.as_slice());
+
+    let mut chunks = Vec::new();
+
+    loop {
+        let mut buffer = [0; 4];
+
+        match rx
+            .next_chunk(&mut buffer)
+            .now_or_never()
+            .expect("Reading stalled")
+        {
+            Ok(MessageChunk {
+                opcode,
+                is_start,
+                is_end,
+                data,
+            }) => chunks.push((
+                opcode,
+                is_start,
+                is_end,
+                String::from_utf8(data.into()).unwrap(),
+            )),
+            Err(ReadMessageError::ReadFrameError(response::ws::ReadFrameError::UnexpectedEof)) => {
+                break
+            }
+            Err(err) => panic!("{err:?}"),
+        }
+    }
+
+    let expected_chunks = [
+        (MessageOpcode::Text, true, false, "Hell"),
+        (MessageOpcode::Text, false, false, "o"),
+        (MessageOpcode::Ping, true, true, "ping"),
+        (MessageOpcode::Text, false, false, ", "),
+        (MessageOpcode::Text, false, false, "Worl"),
+        (MessageOpcode::Text, false, true, "d!"),
+    ]
+    .map(|(opcode, is_start, is_end, data)| (opcode, is_start, is_end, data.to_owned()));
+
+    assert_eq!(chunks, expected_chunks);
+
+    let mut buffer = [0; 4];
+
+    assert!(matches!(
+        SocketRx::new(masked_frame(0x80, b"oops").as_slice())
+            .next_chunk(&mut buffer)
+            .now_or_never()
+            .expect("Reading stalled"),
+        Err(ReadMessageError::MessageStartsWithContinuation)
+    ));
+}
+
+#[tokio::test]
+/// Test that the public test utilities serve raw pipelined requests and return the raw responses
+async fn test_utilities() {
+    let app = Router::new()
+        .route("/", routing::get(|| async { "Hello" }))
+        .route(
+            "/state",
+            routing::get(|extract::State(value): extract::State<u32>| async move {
+                response::DebugValue(value)
+            }),
+        );
+
+    let response = test::send_request_with_state(
+        &app,
+        &42,
+        b"GET / HTTP/1.1\r\n\r\nGET /state HTTP/1.1\r\n\r\nGET /missing HTTP/1.1\r\n\r\n",
+    )
+    .await;
+
+    let response = String::from_utf8(response).unwrap();
+
+    assert_eq!(
+        response.matches("HTTP/1.1 200 OK\r\n").count(),
+        2,
+        "{response:?}"
+    );
+    assert_eq!(
+        response.matches("HTTP/1.1 404 Not Found\r\n").count(),
+        1,
+        "{response:?}"
+    );
+    assert!(response.contains("\r\n\r\nHello"), "{response:?}");
+    assert!(response.contains("\r\n\r\n42\r\n"), "{response:?}");
+}
+
+#[tokio::test]
+/// Test that headers with the same name are all written, in order
+async fn repeated_response_headers() {
+    let app = Router::new().route(
+        "/",
+        routing::get(|| async {
+            let mut cookies = response::Headers::<_, 4>::new();
+
+            for cookie in ["a=1", "b=2; Path=/", "c=3; HttpOnly"] {
+                cookies.push("Set-Cookie", cookie).unwrap();
+            }
+
+            (
+                ("X-Tuple", "first"),
+                cookies,
+                ("X-Tuple", "second"),
+                "Cookies",
+            )
+        }),
+    );
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/").body(Default::default()).unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(body, "Cookies");
+    assert_eq!(
+        parts
+            .headers
+            .get_all("Set-Cookie")
+            .iter()
+            .collect::<Vec<_>>(),
+        ["a=1", "b=2; Path=/", "c=3; HttpOnly"]
+    );
+    assert_eq!(
+        parts.headers.get_all("X-Tuple").iter().collect::<Vec<_>>(),
+        ["first", "second"]
+    );
+}
+
+#[tokio::test]
+/// Test that each response is flushed before the next request is read, and that the socket is asked to disable Nagle's algorithm if configured
+async fn response_is_flushed_before_next_request() {
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Event {
+        SetNodelay,
+        Read,
+        Write,
+        Flush,
+    }
+
+    type Events = Rc<RefCell<Vec<Event>>>;
+
+    struct RecordingReader<'a> {
+        data: &'a [u8],
+        events: Events,
+    }
+
+    impl io::ErrorType for RecordingReader<'_> {
+        type Error = Infallible;
+    }
+
+    impl io::Read for RecordingReader<'_> {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            self.events.borrow_mut().push(Event::Read);
+
+            // Only provide a single request at a time, so each request is read separately
+            let read_size = buf.len().min(self.data.len()).min(18);
+            let (data, rest) = self.data.split_at(read_size);
+            buf[..read_size].copy_from_slice(data);
+            self.data = rest;
+
+            Ok(read_size)
+        }
+    }
+
+    struct RecordingWriter(Events);
+
+    impl io::ErrorType for RecordingWriter {
+        type Error = Infallible;
+    }
+
+    impl io::Write for RecordingWriter {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.0.borrow_mut().push(Event::Write);
+            Ok(buf.len())
+        }
+
+        async fn flush(&mut self) -> Result<(), Self::Error> {
+            self.0.borrow_mut().push(Event::Flush);
+            Ok(())
+        }
+    }
+
+    struct RecordingSocket<'a> {
+        rx: RecordingReader<'a>,
+        tx: RecordingWriter,
+    }
+
+    impl<'a> io::Socket for RecordingSocket<'a> {
+        type Error = Infallible;
+
+        type ReadHalf<'b> = &'b mut RecordingReader<'a> where 'a: 'b;
+        type WriteHalf<'b> = &'b mut RecordingWriter where 'a: 'b;
+
+        fn split(&mut self) -> (Self::ReadHalf<'_>, Self::WriteHalf<'_>) {
+            (&mut self.rx, &mut self.tx)
+        }
+
+        fn set_nodelay(&mut self) {
+            self.tx.0.borrow_mut().push(Event::SetNodelay);
+        }
+
+        async fn shutdown<Timer: time::Timer>(
+            self,
+            _timeouts: &Timeouts<Timer::Duration>,
+            _timer: &mut Timer,
+        ) -> Result<(), Error<Self::Error>> {
+            Ok(())
+        }
+    }
+
+    /// A body which is not flushed once written
+    struct UnflushedBody;
+
+    impl response::Body for UnflushedBody {
+        async fn write_response_body<R: io::Read, W: io::Write<Error = R::Error>>(
+            self,
+            _connection: response::Connection<'_, R>,
+            mut writer: W,
+        ) -> Result<(), W::Error> {
+            writer.write_all(b"Hello").await
+        }
+    }
+
+    let app = Router::new().route(
+        "/",
+        routing::get(|| async {
+            response::Response {
+                status_code: response::StatusCode::OK,
+                reason_phrase: None,
+                headers: ("Content-Length", 5),
+                body: UnflushedBody,
+            }
+        }),
+    );
+
+    let config = Config::new(Timeouts::new(None, None, None))
+        .keep_connection_alive()
+        .tcp_nodelay();
+
+    let events = Events::default();
+
+    let mut http_buffer = [0; 2048];
+
+    let server = serve_and_shutdown(
+        &app,
+        time::TokioTimer,
+        &config,
+        &mut http_buffer,
+        RecordingSocket {
+            rx: RecordingReader {
+                data: b"GET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n",
+                events: events.clone(),
+            },
+            tx: RecordingWriter(events.clone()),
+        },
+        &(),
+    );
+
+    assert_eq!(
+        server.now_or_never().expect("Server has stalled").unwrap(),
+        2
+    );
+
+    let events = events.borrow();
+
+    assert_eq!(events.first(), Some(&Event::SetNodelay), "{events:?}");
+
+    // Ignore consecutive reads, and check that every read after a write is preceded by a flush
+    for window in events.windows(2) {
+        if let [previous, Event::Read] = window {
+            assert_ne!(*previous, Event::Write, "{events:?}");
+        }
+    }
+
+    assert_eq!(events.last(), Some(&Event::Read), "{events:?}");
+    assert_eq!(
+        events.iter().rev().nth(1),
+        Some(&Event::Flush),
+        "{events:?}"
+    );
+}
+
+#[tokio::test]
+/// Test that a chunked body which flushes after a chunk sends that chunk before later chunks are written
+async fn chunked_body_flush_timing() {
+    /// Collects written data, recording the data sent by each flush
+    #[derive(Default)]
+    struct FlushRecorder {
+        pending: Vec<u8>,
+        flushed: Vec<String>,
+    }
+
+    impl io::ErrorType for FlushRecorder {
+        type Error = Infallible;
+    }
+
+    impl io::Write for FlushRecorder {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.pending.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        async fn flush(&mut self) -> Result<(), Self::Error> {
+            if !self.pending.is_empty() {
+                self.flushed
+                    .push(String::from_utf8(core::mem::take(&mut self.pending)).unwrap());
+            }
+
+            Ok(())
+        }
+    }
+
+    struct SlowChunks;
+
+    impl response::chunked::Chunks for SlowChunks {
+        fn content_type(&self) -> &'static str {
+            "text/plain"
+        }
+
+        async fn write_chunks<W: io::Write>(
+            self,
+            mut chunk_writer: response::chunked::ChunkWriter<W>,
+        ) -> Result<response::chunked::ChunksWritten, W::Error> {
+            chunk_writer.write_chunk(b"first").await?;
+            chunk_writer.flush().await?;
+
+            chunk_writer.write_chunk(b"second").await?;
+            chunk_writer.write_chunk(b"third").await?;
+
+            chunk_writer.finalize().await
+        }
+    }
+
+    let app = Router::new().route(
+        "/",
+        routing::get(|| async { response::chunked::ChunkedResponse::new(SlowChunks) }),
+    );
+
+    let config = Config::new(Timeouts::new(None, None, None));
+
+    let mut http_buffer = [0; 2048];
+    let mut recorder = FlushRecorder::default();
+
+    let server = serve_and_shutdown(
+        &app,
+        time::TokioTimer,
+        &config,
+        &mut http_buffer,
+        TestSocket {
+            rx: b"GET / HTTP/1.1\r\n\r\n".as_slice(),
+            tx: &mut recorder,
+        },
+        &(),
+    );
+
+    assert_eq!(
+        server.now_or_never().expect("Server has stalled").unwrap(),
+        1
+    );
+
+    assert!(recorder.pending.is_empty(), "{:?}", recorder.pending);
+
+    let [headers, first, rest] = recorder.flushed.as_slice() else {
+        panic!("{:?}", recorder.flushed);
+    };
+
+    assert!(headers.starts_with("HTTP/1.1 200 OK\r\n"), "{headers:?}");
+    assert!(headers.ends_with("\r\n\r\n"), "{headers:?}");
+    assert_eq!(first, "5\r\nfirst\r\n");
+    assert_eq!(rest, "6\r\nsecond\r\n5\r\nthird\r\n0\r\n\r\n");
+}
+
+#[tokio::test]
+/// Test that very long header values and many headers are written in full, as headers are not buffered
+async fn long_response_headers() {
+    const LOCATION_LENGTH: usize = 64 * 1024;
+    const COOKIE_COUNT: usize = 100;
+
+    async fn redirect_with_cookies() -> impl response::IntoResponse {
+        let location = "a".repeat(LOCATION_LENGTH);
+
+        let mut cookies = response::Headers::<_, COOKIE_COUNT>::new();
+
+        for index in 0..COOKIE_COUNT {
+            cookies
+                .push("Set-Cookie", format!("cookie{index}=value"))
+                .unwrap();
+        }
+
+        (
+            response::StatusCode::SEE_OTHER,
+            ("Location", location),
+            cookies,
+            "Redirecting",
+        )
+    }
+
+    let app = Router::new().route("/", routing::get(redirect_with_cookies));
+
+    let response = test::send_request(&app, b"GET / HTTP/1.1\r\n\r\n").await;
+
+    let response = String::from_utf8(response).unwrap();
+
+    let (headers, body) = response.split_once("\r\n\r\n").unwrap();
+
+    assert!(headers.starts_with("HTTP/1.1 303 See Other\r\n"));
+    assert_eq!(body, "Redirecting");
+
+    let location = headers
+        .lines()
+        .find_map(|line| line.strip_prefix("Location: "))
+        .unwrap();
+
+    assert_eq!(location.len(), LOCATION_LENGTH);
+    assert!(location.bytes().all(|b| b == b'a'));
+
+    let cookies = headers
+        .lines()
+        .filter_map(|line| line.strip_prefix("Set-Cookie: "))
+        .collect::<Vec<_>>();
+
+    assert_eq!(cookies.len(), COOKIE_COUNT);
+    assert_eq!(cookies.last(), Some(&"cookie99=value"));
+}
+
+#[tokio::test]
+async fn from_request_parts_extraction_order() {
+    struct ExtractionLog(std::cell::RefCell<Vec<&'static str>>);
+
+    struct First;
+    struct Second;
+    struct Rejected;
+
+    impl<'r> extract::FromRequestParts<'r, ExtractionLog> for First {
+        type Rejection = Infallible;
+
+        async fn from_request_parts(
+            state: &'r ExtractionLog,
+            _request_parts: &request::RequestParts<'r>,
+        ) -> Result<Self, Self::Rejection> {
+            state.0.borrow_mut().push("first");
+            Ok(Self)
+        }
+    }
+
+    impl<'r> extract::FromRequestParts<'r, ExtractionLog> for Second {
+        type Rejection = Infallible;
+
+        async fn from_request_parts(
+            state: &'r ExtractionLog,
+            _request_parts: &request::RequestParts<'r>,
+        ) -> Result<Self, Self::Rejection> {
+            state.0.borrow_mut().push("second");
+            Ok(Self)
+        }
+    }
+
+    impl<'r> extract::FromRequestParts<'r, ExtractionLog> for Rejected {
+        type Rejection = (response::StatusCode, &'static str);
+
+        async fn from_request_parts(
+            state: &'r ExtractionLog,
+            _request_parts: &request::RequestParts<'r>,
+        ) -> Result<Self, Self::Rejection> {
+            state.0.borrow_mut().push("rejected");
+            Err((response::StatusCode::FORBIDDEN, "Rejected"))
+        }
+    }
+
+    let app = Router::new()
+        .route(
+            "/ordered",
+            routing::get(|Second, First, Second| async { "Handled" }),
+        )
+        .route(
+            "/rejected",
+            routing::get(|First, Rejected, Second| async { "Handled" }),
+        );
+
+    let state = ExtractionLog(Default::default());
+
+    let response =
+        test::send_request_with_state(&app, &state, b"GET /ordered HTTP/1.1\r\n\r\n").await;
+
+    assert!(response.ends_with(b"\r\n\r\nHandled"));
+    assert_eq!(*state.0.borrow(), ["second", "first", "second"]);
+
+    state.0.borrow_mut().clear();
+
+    let response =
+        test::send_request_with_state(&app, &state, b"GET /rejected HTTP/1.1\r\n\r\n").await;
+
+    assert!(response.starts_with(b"HTTP/1.1 403 Forbidden\r\n"));
+    assert_eq!(*state.0.borrow(), ["first", "rejected"]);
+}
+
+#[tokio::test]
+async fn error_responses_keep_connection_alive_when_safe() {
+    let app = Router::new().route(
+        "/",
+        routing::post(|extract::Json(value): extract::Json<u32>| async move {
+            response::DebugValue(value)
+        }),
+    );
+
+    // A rejection from an extractor leaves the request framing intact, so the connection is kept alive
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            b"POST / HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: 3\r\n\r\nabc\
+              POST / HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n42",
+        )
+        .await,
+    )
+    .unwrap();
+
+    let (first_response, second_response) = response.split_once("HTTP/1.1 200 OK\r\n").unwrap();
+
+    assert!(first_response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert!(!first_response.contains("Connection: close"));
+    assert!(second_response.ends_with("\r\n\r\n42\r\n"));
+
+    // The body length is not known, so the connection is closed
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            b"POST / HTTP/1.1\r\nContent-Type: application/json\r\nTransfer-Encoding: gzip, chunked\r\n\r\n2\r\n42\r\n0\r\n\r\n\
+              POST / HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n42",
+        )
+        .await,
+    )
+    .unwrap();
+
+    assert_eq!(response.matches("HTTP/1.1 ").count(), 1);
+    assert!(response.contains("Connection: close\r\n"));
+
+    // The request cannot be parsed, so the connection is closed
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            b"POST / HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: abc\r\n\r\n42\
+              POST / HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n42",
+        )
+        .await,
+    )
+    .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert!(response.contains("Connection: close\r\n"));
+    assert!(response.ends_with("\r\n\r\nInvalid Content-Length"));
+}
+
+#[tokio::test]
+async fn set_response_header_layer() {
+    let app = Router::new()
+        .route("/", routing::get(|| async { "Hello World" }))
+        .route(
+            "/override",
+            routing::get(|| async {
+                response::Response::ok("Overridden").with_header("x-frame-options", "SAMEORIGIN")
+            }),
+        )
+        .layer(routing::SetResponseHeaderLayer::new([
+            ("X-Content-Type-Options", "nosniff"),
+            ("X-Frame-Options", "DENY"),
+        ]));
+
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            b"GET / HTTP/1.1\r\n\r\nGET /override HTTP/1.1\r\n\r\n",
+        )
+        .await,
+    )
+    .unwrap();
+
+    let (default_response, overridden_response) = response.split_once("Hello World").unwrap();
+
+    assert!(default_response.contains("\r\nX-Content-Type-Options: nosniff\r\n"));
+    assert!(default_response.contains("\r\nX-Frame-Options: DENY\r\n"));
+
+    assert!(overridden_response.contains("\r\nX-Content-Type-Options: nosniff\r\n"));
+    assert!(overridden_response.contains("\r\nx-frame-options: SAMEORIGIN\r\n"));
+    assert!(!overridden_response.contains("DENY"));
+}
+
+#[tokio::test]
+async fn write_json_event() {
+    #[derive(serde::Serialize)]
+    struct Reading<'a> {
+        sensor: &'a str,
+        values: &'a [u32],
+    }
+
+    let values = (0..100).collect::<Vec<u32>>();
+
+    let mut output = Vec::new();
+
+    let mut writer = response::sse::EventWriter {
+        writer: &mut output,
+    };
+
+    writer
+        .write_json_event(
+            "reading",
+            Reading {
+                sensor: "line one\nline two",
+                values: &values,
+            },
+        )
+        .await
+        .unwrap();
+
+    writer
+        .write_json_event("count", values.len())
+        .await
+        .unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+
+    let (reading, count) = output.split_once("\n\n").unwrap();
+
+    let data = reading.strip_prefix("event:reading\ndata:").unwrap();
+
+    assert!(!data.contains('\n'));
+
+    let expected_values = values
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+
+    assert_eq!(
+        data,
+        format!(r#"{{"sensor":"line one\nline two","values":[{expected_values}]}}"#)
+    );
+
+    assert_eq!(count, "event:count\ndata:100\n\n");
+}
+
+#[tokio::test]
+async fn event_stream_initial_comment_and_headers() {
+    struct Events;
+
+    impl response::sse::EventSource for Events {
+        async fn write_events<W: io::Write>(
+            self,
+            mut writer: response::sse::EventWriter<W>,
+        ) -> Result<(), W::Error> {
+            writer.write_event("message", "Hello").await
+        }
+    }
+
+    let mut output = Vec::new();
+
+    let response::EventStream(events) =
+        response::EventStream(Events).with_initial_comment("ok", 100);
+
+    response::sse::EventSource::write_events(
+        events,
+        response::sse::EventWriter {
+            writer: &mut output,
+        },
+    )
+    .await
+    .unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+
+    assert_eq!(
+        output,
+        format!(":ok\n:{}\n\nevent:message\ndata:Hello\n\n", " ".repeat(100))
+    );
+
+    let app = Router::new().route(
+        "/events",
+        routing::get(|| async {
+            response::EventStream(Events).with_headers([("X-Accel-Buffering", "no")])
+        }),
+    );
+
+    let response = String::from_utf8(
+        test::send_request(&app, b"GET /events HTTP/1.1\r\nConnection: close\r\n\r\n").await,
+    )
+    .unwrap();
+
+    let (headers, _) = response.split_once("\r\n\r\n").unwrap();
+
+    assert!(headers.contains("\r\nContent-Type: text/event-stream\r\n"));
+    assert!(headers.contains("\r\nCache-Control: no-cache\r\n"));
+    assert!(headers.contains("\r\nX-Accel-Buffering: no\r\n"));
+}
+
+#[tokio::test]
+/// Test that a request for the prefix of a nested router, with or without a trailing slash, is routed to its "/" route
+async fn nest_prefix_normalization() {
+    let app = Router::new().nest(
+        "/api",
+        Router::new()
+            .route("/", routing::get(|| async { "index" }))
+            .route("/value", routing::get(|| async { "value" })),
+    );
+
+    for (path, expected_body) in [
+        ("/api", "index"),
+        ("/api/", "index"),
+        ("/api/value", "value"),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK, "{path}");
+        assert_eq!(body, expected_body.as_bytes(), "{path}");
+    }
+
+    for path in ["/apivalue", "/api/value/", "/api//"] {
+        let (parts, _body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::NOT_FOUND, "{path}");
+    }
+}
+
+#[tokio::test]
+/// Test that a route added with `route_with_tsr` handles its path with and without a trailing slash, and no other paths
+async fn route_with_trailing_slash() {
+    let app = Router::new()
+        .route_with_tsr("/items", routing::get(|| async { "items" }))
+        .route_with_tsr(
+            ("/items", routing::parse_path_segment::<u32>()),
+            routing::get(|id: u32| async move { response::DebugValue(id) }),
+        )
+        .route("/other", routing::get(|| async { "other" }));
+
+    for (path, expected_body) in [
+        ("/items", "items"),
+        ("/items/", "items"),
+        ("/items/5", "5\r\n"),
+        ("/items/5/", "5\r\n"),
+        ("/other", "other"),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK, "{path}");
+        assert_eq!(body, expected_body.as_bytes(), "{path}");
+    }
+
+    for path in ["/items//", "/itemsx", "/items/5//", "/items/x/", "/other/"] {
+        let (parts, _body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::NOT_FOUND, "{path}");
+    }
+}
+
+#[tokio::test]
+/// Test that the body is deserialized in the format given to the `Body` extractor, and that the format's rejection is sent
+async fn body_extractor_formats() {
+    use extract::body::{Body, Json, UrlEncoded};
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Reading {
+        sensor: u8,
+        value: u16,
+    }
+
+    let app = Router::new()
+        .route(
+            "/json",
+            routing::post(|Body(reading, _): Body<Reading, Json>| async move {
+                response::DebugValue((reading.sensor, reading.value))
+            }),
+        )
+        .route(
+            "/form",
+            routing::post(|reading: Body<Reading, UrlEncoded>| async move {
+                let Reading { sensor, value } = reading.into_inner();
+                response::DebugValue((sensor, value))
+            }),
+        );
+
+    for (path, body, expected_status, expected_body) in [
+        (
+            "/json",
+            r#"{"sensor":3,"value":500}"#,
+            StatusCode::OK,
+            "(3, 500)\r\n",
+        ),
+        (
+            "/form",
+            "sensor=3&value=500",
+            StatusCode::OK,
+            "(3, 500)\r\n",
+        ),
+        (
+            "/json",
+            r#"{"sensor":3}"#,
+            StatusCode::BAD_REQUEST,
+            "Failed to parse JSON body: ",
+        ),
+        ("/form", "sensor=3", StatusCode::BAD_REQUEST, "Bad Form\n"),
+    ] {
+        let (parts, response_body) =
+            run_single_request_test(&app, hyper::Request::post(path).body(body.into()).unwrap())
+                .await;
+
+        assert_eq!(parts.status, expected_status, "{path} {body}");
+        assert!(
+            response_body.starts_with(expected_body.as_bytes()),
+            "{path} {body}: {response_body:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn nest_parameterized() {
+    let app = Router::new()
+        .nest_parameterized(("/user", routing::parse_path_segment::<u32>()), |router| {
+            router
+                .route(
+                    "/name",
+                    routing::get(|user_id: u32| async move { response::DebugValue(user_id) }),
+                )
+                .nest_parameterized(("/post", routing::parse_path_segment::<u16>()), |router| {
+                    router.route(
+                        "/title",
+                        routing::get(|(user_id, post_id): (u32, u16)| async move {
+                            response::DebugValue((user_id, post_id))
+                        }),
+                    )
+                })
+        })
+        .nest(
+            ("/item", routing::parse_path_segment::<u8>()),
+            Router::<_, (), routing::OnePathParameter<u8>>::new().route(
+                "/",
+                routing::get(|item_id: u8| async move { response::DebugValue(item_id) }),
+            ),
+        );
+
+    for (path, expected_body) in [
+        ("/user/5/name", "5\r\n"),
+        ("/user/5/post/12/title", "(5, 12)\r\n"),
+        ("/item/3/", "3\r\n"),
+    ] {
+        let response = String::from_utf8(
+            test::send_request(&app, format!("GET {path} HTTP/1.1\r\n\r\n").as_bytes()).await,
+        )
+        .unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{path}");
+        assert!(response.ends_with(expected_body), "{path}: {response:?}");
+    }
+
+    let response = test::send_request(&app, b"GET /user/abc/name HTTP/1.1\r\n\r\n").await;
+
+    assert!(response.starts_with(b"HTTP/1.1 404 Not Found\r\n"));
+}
+
+#[tokio::test]
+async fn tuple_responses_with_raw_body() {
+    struct Greeting;
+
+    impl response::Body for Greeting {
+        async fn write_response_body<R: io::Read, W: io::Write<Error = R::Error>>(
+            self,
+            _connection: response::Connection<'_, R>,
+            mut writer: W,
+        ) -> Result<(), W::Error> {
+            writer.write_all(b"Hello").await
+        }
+    }
+
+    let app = Router::new()
+        .route(
+            "/created",
+            routing::get(|| async {
+                (
+                    response::StatusCode::CREATED,
+                    ("Location", "/x"),
+                    ("Content-Length", 5),
+                    response::RawBody(Greeting),
+                )
+            }),
+        )
+        .route(
+            "/ok",
+            routing::get(|| async { (("Content-Length", 5), response::RawBody(Greeting)) }),
+        );
+
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            b"GET /created HTTP/1.1\r\n\r\nGET /ok HTTP/1.1\r\n\r\n",
+        )
+        .await,
+    )
+    .unwrap();
+
+    let (created, ok) = response.split_once("Hello").unwrap();
+
+    assert!(created.starts_with("HTTP/1.1 201 Created\r\n"));
+    assert!(created.contains("\r\nLocation: /x\r\n"));
+    assert!(created.contains("\r\nContent-Length: 5\r\n"));
+    assert!(!created.contains("Content-Type"));
+
+    assert!(ok.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(ok.ends_with("\r\n\r\nHello"));
+}
+
+#[tokio::test]
+async fn malformed_request_lines() {
+    let app = Router::new().route("/", routing::get(|| async { "Hello" }));
+
+    for request_line in [
+        "GET  HTTP/1.1",
+        " / HTTP/1.1",
+        "/ HTTP/1.1",
+        "GET /",
+        "GET / ",
+        "GET",
+        "GET  / HTTP/1.1",
+        "GET / HTTP/1.1 ",
+        " GET / HTTP/1.1",
+        "GET\t/ HTTP/1.1",
+        "GET / HTTP/1.1 extra",
+        "G(T / HTTP/1.1",
+        "GET / FOO/1.1",
+        "GET /\x07 HTTP/1.1",
+    ] {
+        let response = String::from_utf8(
+            test::send_request(&app, format!("{request_line}\r\n\r\n").as_bytes()).await,
+        )
+        .unwrap();
+
+        assert!(
+            response.starts_with("HTTP/1.1 400 Bad Request\r\n"),
+            "{request_line:?}: {response:?}"
+        );
+        assert!(response.ends_with("Bad Request Line"), "{request_line:?}");
+    }
+
+    // Empty lines before the request line are ignored
+    let response = test::send_request(&app, b"\r\n\nGET / HTTP/1.1\r\n\r\n").await;
+
+    assert!(response.starts_with(b"HTTP/1.1 200 OK\r\n"));
+    assert!(response.ends_with(b"\r\n\r\nHello"));
+
+    // Randomly generated request lines never panic, and are either handled or rejected
+    let mut seed = 0x2545_f491_u32;
+
+    let mut next_random = move |bound: usize| {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as usize % bound
+    };
+
+    for _ in 0..1000 {
+        const PARTS: &[&str] = &[
+            "GET", "/", "/a", "HTTP/1.1", "HTTP/1.0", "", " ", "\t", "\r",
+        ];
+
+        let request_line = (0..next_random(6))
+            .map(|_| PARTS[next_random(PARTS.len())])
+            .collect::<Vec<_>>()
+            .join([" ", "  ", ""][next_random(3)]);
+
+        let response = String::from_utf8(
+            test::send_request(&app, format!("{request_line}\r\n\r\n").as_bytes()).await,
+        )
+        .unwrap();
+
+        assert!(
+            [
+                "HTTP/1.1 200 OK\r\n",
+                "HTTP/1.1 404 Not Found\r\n",
+                "HTTP/1.1 400 Bad Request\r\n"
+            ]
+            .iter()
+            .any(|status_line| response.starts_with(status_line))
+                || response.is_empty(),
+            "{request_line:?}: {response:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn max_headers() {
+    let app = Router::new().route("/", routing::get(|| async { "Hello" }));
+
+    let config = Config::new(Timeouts::new(None, None, None))
+        .keep_connection_alive()
+        .max_headers(8);
+
+    let request_with_headers = |header_count: usize| {
+        let mut request = String::from("GET / HTTP/1.1\r\n");
+
+        for index in 0..header_count {
+            request.push_str(&format!("X-{index}: {index}\r\n"));
+        }
+
+        request.push_str("\r\nGET / HTTP/1.1\r\n\r\n");
+
+        request
+    };
+
+    let response = String::from_utf8(
+        test::send_request_with_config(&app, &config, &(), request_with_headers(8).as_bytes())
+            .await,
+    )
+    .unwrap();
+
+    assert_eq!(response.matches("HTTP/1.1 200 OK\r\n").count(), 2);
+
+    let response = String::from_utf8(
+        test::send_request_with_config(&app, &config, &(), request_with_headers(1000).as_bytes())
+            .await,
+    )
+    .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large\r\n"));
+    assert!(response.contains("\r\nConnection: close\r\n"));
+    assert!(response.ends_with("\r\n\r\nToo many headers"));
+}
+
+#[tokio::test]
+/// Test that the response to a request which can't be parsed uses the configured format
+async fn parse_error_format() {
+    let app = Router::new().route("/", routing::get(|| async { "Hello" }));
+
+    let config = || Config::new(Timeouts::new(None, None, None)).keep_connection_alive();
+
+    let json_config = config().parse_error_format(ParseErrorFormat {
+        content_type: "application/json",
+        write_body: |error, f| write!(f, r#"{{"error":"{error}"}}"#),
+    });
+
+    let status_only_config = config().parse_error_format(ParseErrorFormat::STATUS_ONLY);
+
+    let bad_request = b"GET / HTTP/1.1\r\nNo Colon\r\n\r\n";
+
+    let response =
+        String::from_utf8(test::send_request_with_config(&app, &config(), &(), bad_request).await)
+            .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert!(response.ends_with("\r\n\r\nInvalid Header line: No ':' character"));
+
+    let response = String::from_utf8(
+        test::send_request_with_config(&app, &json_config, &(), bad_request).await,
+    )
+    .unwrap();
+
+    let expected_body = r#"{"error":"Invalid Header line: No ':' character"}"#;
+
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert!(response.contains("\r\nContent-Type: application/json\r\n"));
+    assert!(response.contains(&format!("\r\nContent-Length: {}\r\n", expected_body.len())));
+    assert!(response.contains("\r\nConnection: close\r\n"));
+    assert!(response.ends_with(&format!("\r\n\r\n{expected_body}")));
+
+    let response = String::from_utf8(
+        test::send_request_with_config(&app, &status_only_config, &(), bad_request).await,
+    )
+    .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert!(response.contains("\r\nContent-Length: 0\r\n"));
+    assert!(response.ends_with("\r\n\r\n"));
+}
+
+#[tokio::test]
+async fn handler_requested_connection_close() {
+    let app = Router::new()
+        .route("/", routing::get(|| async { "Hello" }))
+        .route(
+            "/close",
+            routing::get(|| async { (("Connection", "Close"), "Goodbye") }),
+        )
+        .route(
+            "/other",
+            routing::get(|| async { (("Connection", "closed, x-close"), "Still open") }),
+        );
+
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            b"GET / HTTP/1.1\r\n\r\nGET /close HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n",
+        )
+        .await,
+    )
+    .unwrap();
+
+    assert_eq!(response.matches("HTTP/1.1 200 OK\r\n").count(), 2);
+    assert!(response.ends_with("\r\nConnection: Close\r\n\r\nGoodbye"));
+
+    let response = String::from_utf8(
+        test::send_request(&app, b"GET /other HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n").await,
+    )
+    .unwrap();
+
+    assert_eq!(response.matches("HTTP/1.1 200 OK\r\n").count(), 2);
+    assert!(response.ends_with("Hello"));
+}
+
+#[tokio::test]
+async fn custom_method_not_allowed() {
+    struct CustomMethodNotAllowed;
+
+    impl routing::MethodNotAllowedService for CustomMethodNotAllowed {
+        async fn call_method_not_allowed_service<
+            R: io::Read,
+            W: response::ResponseWriter<Error = R::Error>,
+        >(
+            &self,
+            _state: &(),
+            request_parts: request::RequestParts<'_>,
+            allowed_methods: routing::AllowedMethods,
+            connection: response::Connection<'_, R>,
+            response_writer: W,
+        ) -> Result<ResponseSent, W::Error> {
+            use response::IntoResponse;
+
+            (
+                response::StatusCode::METHOD_NOT_ALLOWED,
+                [("Allow", allowed_methods)],
+                ("X-Custom", "yes"),
+                format_args!(
+                    "{} {} must be one of {allowed_methods}",
+                    request_parts.method(),
+                    request_parts.path()
+                ),
+            )
+                .write_to(connection, response_writer)
+                .await
+        }
+    }
+
+    let default_app = Router::new().route(
+        "/",
+        routing::get(|| async { "Hello" }).post(|| async { "Posted" }),
+    );
+
+    let response =
+        String::from_utf8(test::send_request(&default_app, b"PUT / HTTP/1.1\r\n\r\n").await)
+            .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+    assert!(response.contains("\r\nAllow: GET, HEAD, POST\r\n"));
+    assert!(response.ends_with("\r\n\r\nMethod PUT not allowed for /\r\n"));
+
+    let app = default_app
+        .route("/head", routing::head(|| async { "Hello" }))
+        .method_not_allowed(CustomMethodNotAllowed);
+
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            b"DELETE / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\nPOST /head HTTP/1.1\r\n\r\nBREW / HTTP/1.1\r\n\r\n",
+        )
+        .await,
+    )
+    .unwrap();
+
+    let mut responses = response.split("HTTP/1.1 ").skip(1);
+
+    let delete = responses.next().unwrap();
+    assert!(delete.starts_with("405 Method Not Allowed\r\n"));
+    assert!(delete.contains("\r\nAllow: GET, HEAD, POST\r\n"));
+    assert!(delete.contains("\r\nX-Custom: yes\r\n"));
+    assert!(delete.ends_with("\r\n\r\nDELETE / must be one of GET, HEAD, POST"));
+
+    assert!(responses.next().unwrap().ends_with("\r\n\r\nHello"));
+
+    let post = responses.next().unwrap();
+    assert!(post.contains("\r\nAllow: HEAD\r\n"));
+    assert!(post.ends_with("\r\n\r\nPOST /head must be one of HEAD"));
+
+    assert!(responses
+        .next()
+        .unwrap()
+        .ends_with("\r\n\r\nBREW / must be one of GET, HEAD, POST"));
+
+    assert!(responses.next().is_none());
+}
+
+#[tokio::test]
+async fn multipart_stream() {
+    static FRAMES: [&[u8]; 2] = [b"\xff\xd8frame 0\xff\xd9", b"\xff\xd8frame 1\xff\xd9"];
+
+    let app = Router::new().route(
+        "/",
+        routing::get(|| async {
+            response::MultipartStream::new("frame", 0, |n: usize| async move {
+                FRAMES.get(n).map(|&frame| (("image/jpeg", frame), n + 1))
+            })
+        }),
+    );
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/").body(Default::default()).unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(
+        parts.headers["Content-Type"],
+        "multipart/x-mixed-replace; boundary=frame"
+    );
+    assert_eq!(parts.headers["Transfer-Encoding"], "chunked");
+    assert_eq!(
+        body,
+        &b"--frame\r\nContent-Type: image/jpeg\r\nContent-Length: 11\r\n\r\n\xff\xd8frame 0\xff\xd9\r\n\
+        --frame\r\nContent-Type: image/jpeg\r\nContent-Length: 11\r\n\r\n\xff\xd8frame 1\xff\xd9\r\n\
+        --frame--\r\n"[..]
+    );
+}
+
+#[tokio::test]
+async fn query_map() {
+    use url_encoded::UrlEncodedString;
+
+    let query = extract::QueryMap::new(UrlEncodedString("a=1&&b=hello+world&flag&c=%E2%9C%93&a=2"));
+
+    assert_eq!(query.get("a").unwrap().0, "1");
+    assert_eq!(query.get("b").unwrap().0, "hello+world");
+    assert_eq!(
+        query.get_decoded::<16>("b").unwrap().unwrap(),
+        "hello world"
+    );
+    assert_eq!(query.get_decoded::<4>("c").unwrap().unwrap(), "✓");
+    assert!(matches!(
+        query.get_decoded::<2>("c").unwrap(),
+        Err(url_encoded::DecodeError::NoSpace)
+    ));
+
+    let mut buffer = [0; 16];
+
+    assert_eq!(
+        query.get_decoded_into("b", &mut buffer).unwrap().unwrap(),
+        "hello world"
+    );
+    assert_eq!(
+        query
+            .get_decoded_into("c", &mut buffer[..3])
+            .unwrap()
+            .unwrap(),
+        "✓"
+    );
+    assert!(matches!(
+        query.get_decoded_into("c", &mut buffer[..2]).unwrap(),
+        Err(url_encoded::DecodeError::NoSpace)
+    ));
+    assert!(query.get_decoded_into("missing", &mut buffer).is_none());
+    assert!(matches!(
+        UrlEncodedString("%G0").decode_into(&mut buffer),
+        Err(url_encoded::DecodeError::BadUrlEncodedCharacter(_))
+    ));
+
+    assert!(query.contains_key("flag"));
+    assert_eq!(query.get("flag").unwrap().0, "");
+    assert!(query.get("missing").is_none());
+    assert_eq!(
+        query
+            .iter()
+            .map(|(key, value)| (key.0, value.0))
+            .collect::<Vec<_>>(),
+        [
+            ("a", "1"),
+            ("b", "hello+world"),
+            ("flag", ""),
+            ("c", "%E2%9C%93"),
+            ("a", "2")
+        ]
+    );
+
+    struct Greet;
+
+    impl routing::RequestHandlerService<()> for Greet {
+        async fn call_request_handler_service<
+            R: io::Read,
+            W: response::ResponseWriter<Error = R::Error>,
+        >(
+            &self,
+            state: &(),
+            (): (),
+            request: request::Request<'_, R>,
+            response_writer: W,
+        ) -> Result<ResponseSent, W::Error> {
+            use response::IntoResponse;
+
+            let query = from_request_parts!(state, request, response_writer, extract::QueryMap<'_>);
+
+            let name = query.get("name").unwrap_or(UrlEncodedString("nobody"));
+
+            format_args!("Hello {}", name.0)
+                .write_to(request.body_connection.finalize().await?, response_writer)
+                .await
+        }
+    }
+
+    let app = Router::new().route("/", routing::get_service(Greet));
+
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            b"GET /?name=Ferris HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n",
+        )
+        .await,
+    )
+    .unwrap();
+
+    assert!(response.contains("\r\n\r\nHello Ferris"));
+    assert!(response.ends_with("\r\n\r\nHello nobody"));
+}
+
+#[tokio::test]
+/// Test that the response has a single "Connection" header matching whether the connection is kept alive
+async fn negotiated_connection_header() {
+    let app = Router::new().route("/", routing::get(|| async { "Hello" }));
+
+    let config = |keep_alive: bool| {
+        let config = Config::new(Timeouts::new(None, None, None));
+
+        if keep_alive {
+            config.keep_connection_alive()
+        } else {
+            config.close_connection_after_response()
+        }
+    };
+
+    for (keep_alive, request, expected_connection_header) in [
+        (true, "GET / HTTP/1.1\r\n\r\n", "keep-alive"),
+        (true, "GET / HTTP/1.1\r\nConnection: close\r\n\r\n", "close"),
+        (true, "GET / HTTP/1.0\r\n\r\n", "close"),
+        (
+            true,
+            "GET / HTTP/1.0\r\nConnection: keep-alive\r\n\r\n",
+            "keep-alive",
+        ),
+        (false, "GET / HTTP/1.1\r\n\r\n", "close"),
+        (
+            false,
+            "GET / HTTP/1.1\r\nConnection: keep-alive\r\n\r\n",
+            "close",
+        ),
+    ] {
+        let response = String::from_utf8(
+            test::send_request_with_config(&app, &config(keep_alive), &(), request.as_bytes())
+                .await,
+        )
+        .unwrap();
+
+        let (headers, body) = response.split_once("\r\n\r\n").unwrap();
+
+        assert_eq!(body, "Hello", "{request:?}");
+        assert_eq!(
+            headers
+                .lines()
+                .filter(|line| line.to_ascii_lowercase().starts_with("connection:"))
+                .collect::<Vec<_>>(),
+            [format!("Connection: {expected_connection_header}")],
+            "{request:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn connection_max_lifetime() {
+    let app = Router::new().route("/", routing::get(|| async { "Hello" }));
+
+    let config_with_max_lifetime = |connection_max_lifetime| {
+        Config::new(
+            Timeouts::new(None, None, None).with_connection_max_lifetime(connection_max_lifetime),
+        )
+        .keep_connection_alive()
+    };
+
+    let requests = b"GET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n";
+
+    let response = String::from_utf8(
+        test::send_request_with_config(
+            &app,
+            &config_with_max_lifetime(Duration::from_secs(3600)),
+            &(),
+            requests,
+        )
+        .await,
+    )
+    .unwrap();
+
+    assert_eq!(response.matches("HTTP/1.1 200 OK\r\n").count(), 3);
+    assert_eq!(
+        response.matches("\r\nConnection: keep-alive\r\n").count(),
+        3
+    );
+
+    // The connection has expired by the time the first request has been read, so it is handled and then the connection is closed
+    let response = String::from_utf8(
+        test::send_request_with_config(
+            &app,
+            &config_with_max_lifetime(Duration::ZERO),
+            &(),
+            requests,
+        )
+        .await,
+    )
+    .unwrap();
+
+    assert_eq!(response.matches("HTTP/1.1 200 OK\r\n").count(), 1);
+    assert!(response.contains("\r\nConnection: close\r\n"));
+    assert!(response.ends_with("\r\n\r\nHello"));
+}
+
+#[tokio::test]
+/// Test that Web Socket messages sent to a client which has stopped reading can be dropped without closing the connection
+async fn web_socket_stalled_client() {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use response::ws::{SocketRx, SocketTx, TrySendError, WebSocketCallback};
+
+    struct StallableWriter {
+        output: Rc<RefCell<Vec<u8>>>,
+        is_stalled: Rc<Cell<bool>>,
+    }
+
+    impl io::ErrorType for StallableWriter {
+        type Error = Infallible;
+    }
+
+    impl io::Write for StallableWriter {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            if self.is_stalled.get() {
+                core::future::pending().await
+            } else {
+                self.output.borrow_mut().extend_from_slice(buf);
+
+                Ok(buf.len())
+            }
+        }
+    }
+
+    struct Broadcast {
+        is_stalled: Rc<Cell<bool>>,
+    }
+
+    impl WebSocketCallback for Broadcast {
+        async fn run<R: Read, W: io::Write<Error = R::Error>>(
+            self,
+            _rx: SocketRx<R>,
+            mut tx: SocketTx<W>,
+        ) -> Result<(), W::Error> {
+            if let Err(TrySendError::Stalled(err) | TrySendError::Io(err)) =
+                tx.try_send_text("first").await
+            {
+                return Err(err);
+            }
+
+            self.is_stalled.set(true);
+
+            assert!(matches!(
+                tx.try_send_binary(b"second").await,
+                Err(TrySendError::Stalled(_))
+            ));
+
+            self.is_stalled.set(false);
+
+            tx.send_text("third").await
+        }
+    }
+
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let is_stalled = Rc::new(Cell::new(false));
+
+    let app = {
+        let is_stalled = is_stalled.clone();
+
+        Router::new().route(
+            "/ws",
+            routing::get(move |upgrade: response::WebSocketUpgrade| {
+                let is_stalled = is_stalled.clone();
+
+                async move { upgrade.on_upgrade(Broadcast { is_stalled }) }
+            }),
+        )
+    };
+
+    let config = Config::new(Timeouts::new(None, None, Some(Duration::from_millis(10))));
+
+    let (request_tx, request_rx) = pipe();
+
+    request_tx
+        .0
+        .send(
+            concat!(
+                "GET /ws HTTP/1.1\r\n",
+                "Connection: Upgrade\r\n",
+                "Upgrade: websocket\r\n",
+                "Sec-WebSocket-Version: 13\r\n",
+                "Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n",
+                "\r\n",
+            )
+            .as_bytes()
+            .into(),
+        )
+        .unwrap();
+
+    let mut http_buffer = [0; 2048];
+
+    let request_count = tokio::time::timeout(
+        Duration::from_secs(1),
+        serve_and_shutdown(
+            &app,
+            time::TokioTimer,
+            &config,
+            &mut http_buffer,
+            TestSocket {
+                rx: request_rx,
+                tx: StallableWriter {
+                    output: output.clone(),
+                    is_stalled,
+                },
+            },
+            &(),
+        ),
+    )
+    .await
+    .expect("Server has stalled")
+    .unwrap();
+
+    assert_eq!(request_count, 1);
+
+    let output = output.take();
+
+    assert!(output.starts_with(b"HTTP/1.1 101 Switching Protocols\r\n"));
+    assert!(output.ends_with(b"\r\n\r\n\x81\x05first\x81\x05third"));
+
+    drop(request_tx);
+}
+
+#[tokio::test]
+async fn empty_and_status_only_responses_are_delimited() {
+    struct Unsized;
+
+    impl response::Body for Unsized {
+        async fn write_response_body<R: Read, W: io::Write<Error = R::Error>>(
+            self,
+            _connection: response::Connection<'_, R>,
+            mut writer: W,
+        ) -> Result<(), W::Error> {
+            writer.write_all(b"until close").await
+        }
+    }
+
+    let app = Router::new()
+        .route("/", routing::get(|| async { "Hello" }))
+        .route("/empty", routing::get(|| async { "" }))
+        .route(
+            "/redirect",
+            routing::get(|| async { response::Redirect::to("/") }),
+        )
+        .route(
+            "/not-found",
+            routing::get(|| async { response::StatusCode::NOT_FOUND }),
+        )
+        .route(
+            "/no-content",
+            routing::get(|| async { response::StatusCode::NO_CONTENT }),
+        )
+        .route(
+            "/unsized",
+            routing::get(|| async { (response::StatusCode::OK, response::RawBody(Unsized)) }),
+        );
+
+    let request = |path: &str| format!("GET {path} HTTP/1.1\r\n\r\n");
+
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            ["/empty", "/redirect", "/not-found", "/no-content", "/"]
+                .map(request)
+                .concat()
+                .as_bytes(),
+        )
+        .await,
+    )
+    .unwrap();
+
+    let mut responses = response.split("HTTP/1.1 ").skip(1);
+
+    let empty = responses.next().unwrap();
+    assert!(empty.contains("\r\nContent-Length: 0\r\n"), "{empty:?}");
+    assert!(empty.ends_with("\r\nConnection: keep-alive\r\n\r\n"));
+
+    let redirect = responses.next().unwrap();
+    assert!(redirect.starts_with("303 See Other\r\n"), "{redirect:?}");
+    assert!(
+        redirect.contains("\r\nContent-Length: 2\r\n"),
+        "{redirect:?}"
+    );
+    assert!(redirect.ends_with("\r\n\r\n/\n"));
+
+    let not_found = responses.next().unwrap();
+    assert!(not_found.starts_with("404 Not Found\r\n"), "{not_found:?}");
+    assert!(not_found.contains("\r\nContent-Length: 9\r\n"));
+    assert!(not_found.ends_with("\r\n\r\nError 404"));
+
+    let no_content = responses.next().unwrap();
+    assert_eq!(
+        no_content,
+        "204 No Content\r\nConnection: keep-alive\r\n\r\n"
+    );
+
+    assert!(responses.next().unwrap().ends_with("\r\n\r\nHello"));
+    assert!(responses.next().is_none());
+
+    // The client can only tell where an unsized body ends when the connection closes
+    let response = String::from_utf8(
+        test::send_request(&app, ["/unsized", "/"].map(request).concat().as_bytes()).await,
+    )
+    .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.ends_with("\r\nConnection: close\r\n\r\nuntil close"));
+}
+
+#[tokio::test]
+async fn with_state_from() {
+    #[derive(Clone, Copy)]
+    struct Counter(&'static str);
+
+    struct AppState {
+        counter: Counter,
+        name: &'static str,
+    }
+
+    impl extract::FromRef<AppState> for Counter {
+        fn from_ref(state: &AppState) -> Self {
+            state.counter
+        }
+    }
+
+    impl extract::FromRef<AppState> for &'static str {
+        fn from_ref(state: &AppState) -> Self {
+            state.name
+        }
+    }
+
+    let counter_router: Router<_, Counter> = Router::new().route(
+        "/name",
+        routing::get(|extract::State(Counter(name)): extract::State<Counter>| async move { name }),
+    );
+
+    let app = Router::new()
+        .route(
+            "/",
+            routing::get(|extract::State(name): extract::State<&'static str>| async move { name }),
+        )
+        .nest("/counter", counter_router.with_state_from());
+
+    let response = String::from_utf8(
+        test::send_request_with_state(
+            &app,
+            &AppState {
+                counter: Counter("counter"),
+                name: "app",
+            },
+            b"GET / HTTP/1.1\r\n\r\nGET /counter/name HTTP/1.1\r\n\r\n",
+        )
+        .await,
+    )
+    .unwrap();
+
+    let mut responses = response.split("HTTP/1.1 ").skip(1);
+
+    assert!(responses.next().unwrap().ends_with("\r\n\r\napp"));
+    assert!(responses.next().unwrap().ends_with("\r\n\r\ncounter"));
+}
+
+#[tokio::test]
+async fn fragments_content() {
+    static FRAGMENTS: &[&[u8]] = &[b"\x00\x01", b"", b"\x02"];
+
+    let app = Router::new()
+        .route(
+            "/page",
+            routing::get(|| async { response::Fragments::html(["<p>", "Hello", "</p>"]) }),
+        )
+        .route(
+            "/bytes",
+            routing::get(|| async {
+                response::Fragments::new("application/octet-stream", FRAGMENTS)
+            }),
+        );
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/page")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.headers["Content-Type"], "text/html; charset=utf-8");
+    assert_eq!(parts.headers["Content-Length"], "12");
+    assert_eq!(body, "<p>Hello</p>");
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/bytes")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.headers["Content-Length"], "3");
+    assert_eq!(body, &b"\x00\x01\x02"[..]);
+}
+
+#[tokio::test]
+async fn attachment_content_disposition() {
+    const PDF: response::File =
+        response::File::with_content_type("application/pdf", b"%PDF").as_attachment("résumé €.pdf");
+
+    let app = Router::new()
+        .route(
+            "/report.csv",
+            routing::get(|| async { (response::Attachment::new("report.csv"), "a,b\n") }),
+        )
+        .route(
+            "/quoted",
+            routing::get(|| async { (response::Attachment::new(r#"say "hi"\.txt"#), &b"hi"[..]) }),
+        )
+        .route("/resume.pdf", routing::get_service(PDF));
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/report.csv")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(
+        parts.headers["Content-Disposition"],
+        r#"attachment; filename="report.csv""#
+    );
+    assert_eq!(body, "a,b\n");
+
+    let (parts, _) = run_single_request_test(
+        &app,
+        hyper::Request::get("/quoted")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(
+        parts.headers["Content-Disposition"],
+        r#"attachment; filename="say \"hi\"\\.txt""#
+    );
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/resume.pdf")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.headers["Content-Type"], "application/pdf");
+    assert_eq!(
+        parts.headers["Content-Disposition"],
+        r#"attachment; filename="r_sum_ _.pdf"; filename*=UTF-8''r%C3%A9sum%C3%A9%20%E2%82%AC.pdf"#
+    );
+    assert_eq!(body, "%PDF");
+}
+
+#[tokio::test]
+async fn json_borrowed_fields() {
+    #[derive(serde::Deserialize)]
+    struct Greeting<'a> {
+        name: &'a str,
+    }
+
+    struct Greet;
+
+    impl routing::RequestHandlerService<()> for Greet {
+        async fn call_request_handler_service<
+            R: io::Read,
+            W: response::ResponseWriter<Error = R::Error>,
+        >(
+            &self,
+            _state: &(),
+            (): (),
+            request: request::Request<'_, R>,
+            response_writer: W,
+        ) -> Result<ResponseSent, W::Error> {
+            use response::IntoResponse;
+
+            let (body, connection) = request.body_connection.read_all_and_finalize().await?;
+
+            let Some(body) = body else {
+                return (response::StatusCode::PAYLOAD_TOO_LARGE, "Too Large")
+                    .write_to(connection, response_writer)
+                    .await;
+            };
+
+            match extract::Json::<Greeting>::from_slice(body) {
+                Ok(extract::Json(Greeting { name })) => {
+                    format_args!("Hello {name}")
+                        .write_to(connection, response_writer)
+                        .await
+                }
+                Err(rejection) => rejection.write_to(connection, response_writer).await,
+            }
+        }
+    }
+
+    let app = Router::new().route("/", routing::post_service(Greet));
+
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            concat!(
+                "POST / HTTP/1.1\r\nContent-Length: 17\r\n\r\n{\"name\":\"Ferris\"}",
+                "POST / HTTP/1.1\r\nContent-Length: 17\r\n\r\n{\"name\":\"F\\\"s\"}  ",
+                "POST / HTTP/1.1\r\nContent-Length: 14\r\n\r\n{\"name\":\"Ada\"}",
+            )
+            .as_bytes(),
+        )
+        .await,
+    )
+    .unwrap();
+
+    let mut responses = response.split("HTTP/1.1 ").skip(1);
+
+    assert!(responses.next().unwrap().ends_with("\r\n\r\nHello Ferris"));
+    assert!(responses.next().unwrap().starts_with("400 "));
+    assert!(responses.next().unwrap().ends_with("\r\n\r\nHello Ada"));
+    assert!(responses.next().is_none());
+}
+
+#[tokio::test]
+async fn timer_sleep_in_handler() {
+    use time::Timer;
+
+    async fn debounced<T: Timer + Clone>(
+        extract::State(mut timer): extract::State<T>,
+    ) -> &'static str
+    where
+        T::Duration: From<std::time::Duration>,
+    {
+        timer
+            .sleep(std::time::Duration::from_millis(50).into())
+            .await;
+
+        "Done"
+    }
+
+    let app = Router::new().route("/", routing::get(debounced::<time::TokioTimer>));
+
+    let start = std::time::Instant::now();
+
+    let response = String::from_utf8(
+        test::send_request_with_state(&app, &time::TokioTimer, b"GET / HTTP/1.1\r\n\r\n").await,
+    )
+    .unwrap();
+
+    assert!(response.ends_with("\r\n\r\nDone"));
+    assert!(start.elapsed() >= std::time::Duration::from_millis(50));
+}
+
+#[tokio::test]
+async fn json_resource_methods() {
+    use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+    #[derive(Clone, serde::Serialize, serde::Deserialize)]
+    struct Item {
+        name: heapless::String<8>,
+        count: u32,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct ItemPatch {
+        name: Option<heapless::String<8>>,
+        count: Option<u32>,
+    }
+
+    type Items = Rc<RefCell<BTreeMap<u8, Item>>>;
+
+    const NOT_FOUND: (response::StatusCode, &str) = (response::StatusCode::NOT_FOUND, "Not Found");
+
+    let app = Router::new().route(
+        ("/items", routing::parse_path_segment::<u8>()),
+        routing::get(
+            |id: u8, extract::State(items): extract::State<Items>| async move {
+                items
+                    .borrow()
+                    .get(&id)
+                    .cloned()
+                    .map(response::Json)
+                    .ok_or(NOT_FOUND)
+            },
+        )
+        .post(
+            |id: u8,
+             extract::State(items): extract::State<Items>,
+             extract::Json(item): extract::Json<Item>| async move {
+                items.borrow_mut().insert(id, item);
+                response::StatusCode::CREATED
+            },
+        )
+        .put(
+            |id: u8,
+             extract::State(items): extract::State<Items>,
+             extract::Json(item): extract::Json<Item>| async move {
+                let mut items = items.borrow_mut();
+                let existing_item = items.get_mut(&id).ok_or(NOT_FOUND)?;
+                *existing_item = item;
+                Ok::<_, (response::StatusCode, &str)>(response::Json(existing_item.clone()))
+            },
+        )
+        .patch(
+            |id: u8,
+             extract::State(items): extract::State<Items>,
+             extract::Json(ItemPatch { name, count }): extract::Json<ItemPatch>| async move {
+                let mut items = items.borrow_mut();
+                let item = items.get_mut(&id).ok_or(NOT_FOUND)?;
+
+                if let Some(name) = name {
+                    item.name = name;
+                }
+
+                if let Some(count) = count {
+                    item.count = count;
+                }
+
+                Ok::<_, (response::StatusCode, &str)>(response::Json(item.clone()))
+            },
+        )
+        .delete(
+            |id: u8, extract::State(items): extract::State<Items>| async move {
+                items
+                    .borrow_mut()
+                    .remove(&id)
+                    .map(|_| response::StatusCode::NO_CONTENT)
+                    .ok_or(NOT_FOUND)
+            },
+        ),
+    );
+
+    let items = Items::default();
+
+    let mut responses = Vec::new();
+
+    for (method, body) in [
+        ("POST", r#"{"name":"apple","count":1}"#),
+        ("GET", ""),
+        ("PATCH", r#"{"count":5}"#),
+        ("PUT", r#"{"name":"pear","count":2}"#),
+        ("PATCH", r#"{"name":"plum"}"#),
+        ("DELETE", ""),
+        ("GET", ""),
+        ("PATCH", r#"{"count":5}"#),
+    ] {
+        let response = String::from_utf8(
+            test::send_request_with_state(
+                &app,
+                &items,
+                format!(
+                    "{method} /items/3 HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}",
+                    body.len()
+                )
+                .as_bytes(),
+            )
+            .await,
+        )
+        .unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+
+        responses.push((head.lines().next().unwrap().to_owned(), body.to_owned()));
+    }
+
+    assert_eq!(
+        responses,
+        [
+            ("HTTP/1.1 201 Created", ""),
+            ("HTTP/1.1 200 OK", r#"{"name":"apple","count":1}"#),
+            ("HTTP/1.1 200 OK", r#"{"name":"apple","count":5}"#),
+            ("HTTP/1.1 200 OK", r#"{"name":"pear","count":2}"#),
+            ("HTTP/1.1 200 OK", r#"{"name":"plum","count":2}"#),
+            ("HTTP/1.1 204 No Content", ""),
+            ("HTTP/1.1 404 Not Found", "Not Found"),
+            ("HTTP/1.1 404 Not Found", "Not Found"),
+        ]
+        .map(|(status_line, body)| (status_line.to_owned(), body.to_owned()))
+    );
+}
+
+#[tokio::test]
+async fn request_into_parts() {
+    struct EchoWithPrefix;
+
+    impl routing::RequestHandlerService<()> for EchoWithPrefix {
+        async fn call_request_handler_service<
+            R: io::Read,
+            W: response::ResponseWriter<Error = R::Error>,
+        >(
+            &self,
+            _state: &(),
+            (): (),
+            request: request::Request<'_, R>,
+            response_writer: W,
+        ) -> Result<ResponseSent, W::Error> {
+            use response::IntoResponse;
+
+            let (parts, mut body_connection) = request.into_parts();
+
+            let prefix = parts.headers().get("X-Prefix").unwrap();
+
+            let body = body_connection.body().read_all().await.unwrap();
+
+            let response = format!(
+                "{}{}",
+                prefix.as_str().unwrap(),
+                core::str::from_utf8(body).unwrap()
+            );
+
+            response
+                .write_to(body_connection.finalize().await?, response_writer)
+                .await
+        }
+    }
+
+    let app = Router::new().route("/", routing::post_service(EchoWithPrefix));
+
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            b"POST / HTTP/1.1\r\nX-Prefix: Hello,\r\nContent-Length: 6\r\n\r\nFerris",
+        )
+        .await,
+    )
+    .unwrap();
+
+    assert!(response.ends_with("\r\n\r\nHello,Ferris"));
+}
+
+#[tokio::test]
+async fn vary_header() {
+    const JS: &str = "console.log('Hello World')";
+    const GZIP: &[u8] = b"\x1f\x8b compressed";
+
+    let app = Router::new()
+        .route(
+            "/negotiated",
+            routing::get(|| async { (response::Vary(&["Accept", "Accept-Language"]), "Hello") }),
+        )
+        .route(
+            "/not_negotiated",
+            routing::get(|| async { (response::Vary(&[]), "Hello") }),
+        )
+        .route(
+            "/app.js",
+            routing::get_service(response::File::javascript(JS).with_gzip(GZIP)),
+        );
+
+    let (parts, _body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/negotiated")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.headers["Vary"], "Accept, Accept-Language");
+
+    let (parts, _body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/not_negotiated")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert!(parts.headers.get("Vary").is_none());
+
+    // Partial and unsatisfiable range responses also depend on the negotiated encoding
+    for (range, expected_status) in [
+        ("bytes=0-3", StatusCode::PARTIAL_CONTENT),
+        ("bytes=100-", StatusCode::RANGE_NOT_SATISFIABLE),
+    ] {
+        let (parts, _body) = run_single_request_test(
+            &app,
+            hyper::Request::get("/app.js")
+                .header("Accept-Encoding", "gzip")
+                .header("Range", range)
+                .body(Default::default())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, expected_status, "{range}");
+        assert_eq!(parts.headers["Vary"], "Accept-Encoding", "{range}");
+    }
+}
+
+#[tokio::test]
+async fn fallback_sees_allowed_methods() {
+    let app = Router::new()
+        .route("/item", routing::get(|| async { "GET" }))
+        .route("/item", routing::post(|| async { "POST" }));
+
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            b"GET /item HTTP/1.1\r\n\r\nPOST /item HTTP/1.1\r\n\r\nPUT /item HTTP/1.1\r\n\r\nPUT /other HTTP/1.1\r\n\r\n",
+        )
+        .await,
+    )
+    .unwrap();
+
+    let mut responses = response.split("HTTP/1.1 ").skip(1);
+
+    assert!(responses.next().unwrap().ends_with("\r\n\r\nGET"));
+    assert!(responses.next().unwrap().ends_with("\r\n\r\nPOST"));
+
+    let put = responses.next().unwrap();
+    assert!(put.starts_with("405 Method Not Allowed\r\n"));
+    assert!(put.contains("\r\nAllow: GET, HEAD, POST\r\n"));
+
+    assert!(responses.next().unwrap().starts_with("404 Not Found\r\n"));
+
+    struct Fallback;
+
+    impl routing::PathRouterService<()> for Fallback {
+        async fn call_request_handler_service<
+            R: io::Read,
+            W: response::ResponseWriter<Error = R::Error>,
+        >(
+            &self,
+            _state: &(),
+            _path_parameters: (),
+            _path: request::Path<'_>,
+            request: request::Request<'_, R>,
+            response_writer: W,
+        ) -> Result<ResponseSent, W::Error> {
+            use response::IntoResponse;
+
+            let allowed_methods = request.parts.allowed_methods();
+
+            let status_code = if allowed_methods.is_empty() {
+                response::StatusCode::NOT_FOUND
+            } else {
+                response::StatusCode::METHOD_NOT_ALLOWED
+            };
+
+            (status_code, ("Allow", allowed_methods), "Fallback")
+                .write_to(request.body_connection.finalize().await?, response_writer)
+                .await
+        }
+    }
+
+    let app = Router::from_service(Fallback)
+        .route("/item", routing::get(|| async { "GET" }))
+        .route("/item", routing::delete(|| async { "DELETE" }));
+
+    for (request, expected_status, expected_allow) in [
+        (hyper::Request::get("/item"), StatusCode::OK, None),
+        (hyper::Request::delete("/item"), StatusCode::OK, None),
+        (
+            hyper::Request::post("/item"),
+            StatusCode::METHOD_NOT_ALLOWED,
+            Some("GET, HEAD, DELETE"),
+        ),
+        (
+            hyper::Request::post("/other"),
+            StatusCode::NOT_FOUND,
+            Some(""),
+        ),
+    ] {
+        let (parts, _) =
+            run_single_request_test(&app, request.body(Default::default()).unwrap()).await;
+
+        assert_eq!(parts.status, expected_status);
+        assert_eq!(
+            parts
+                .headers
+                .get("Allow")
+                .map(|allow| allow.to_str().unwrap()),
+            expected_allow
+        );
+    }
+}
+
+#[test]
+fn status_code_classes() {
+    use response::StatusCode;
+
+    for (status_code, class) in [
+        (100, 1),
+        (199, 1),
+        (200, 2),
+        (299, 2),
+        (300, 3),
+        (399, 3),
+        (400, 4),
+        (499, 4),
+        (500, 5),
+        (599, 5),
+        (99, 0),
+        (600, 0),
+    ] {
+        let status_code = StatusCode::new(status_code);
+
+        assert_eq!(
+            [
+                status_code.is_informational(),
+                status_code.is_success(),
+                status_code.is_redirection(),
+                status_code.is_client_error(),
+                status_code.is_server_error(),
+            ],
+            core::array::from_fn(|index| index + 1 == class),
+            "{status_code}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn length_prefixed_body() {
+    let app = Router::new()
+        .route(
+            "/frame",
+            routing::get(|| async {
+                response::LengthPrefixed::new("application/x-frame", &b"\x01\x02\x03"[..])
+            }),
+        )
+        .route(
+            "/empty",
+            routing::get(|| async { response::LengthPrefixed::octet_stream("") }),
+        );
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/frame")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(parts.headers["Content-Type"], "application/x-frame");
+    assert_eq!(parts.headers["Content-Length"], "7");
+    assert_eq!(body.as_ref(), b"\x00\x00\x00\x03\x01\x02\x03");
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/empty")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.headers["Content-Type"], "application/octet-stream");
+    assert_eq!(body.as_ref(), b"\x00\x00\x00\x00");
+}
+
+#[tokio::test]
+async fn form_field() {
+    struct Pin;
+
+    impl extract::FormFieldName for Pin {
+        const NAME: &'static str = "pin";
+    }
+
+    let app = Router::new().route(
+        "/unlock",
+        routing::post(
+            |extract::FormField(pin, _): extract::FormField<Pin, 4>| async move {
+                if pin == "1 2%" {
+                    "Unlocked"
+                } else {
+                    "Wrong PIN"
+                }
+            },
+        ),
+    );
+
+    for (body, expected_status, expected_body) in [
+        ("remember=on&pin=1+2%25", StatusCode::OK, "Unlocked"),
+        ("pin=0000&pin=1+2%25", StatusCode::OK, "Wrong PIN"),
+        (
+            "pin=12345",
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "Form field \"pin\" is longer than 4 bytes\n",
+        ),
+        (
+            "pin=%ZZ",
+            StatusCode::BAD_REQUEST,
+            "Form field \"pin\" is badly encoded\n",
+        ),
+        (
+            "remember=on",
+            StatusCode::BAD_REQUEST,
+            "Missing form field \"pin\"\n",
+        ),
+    ] {
+        let (parts, response_body) = run_single_request_test(
+            &app,
+            hyper::Request::post("/unlock")
+                .header("Content-Type", "application/x-www-form-urlencoded")
+                .body(body.into())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, expected_status, "{body}");
+        assert_eq!(response_body, expected_body, "{body}");
+    }
+}
+
+#[tokio::test]
+async fn debug_value_formatting() {
+    const STATUS: (&str, [u8; 2]) = ("ok", [4, 2]);
+
+    let app = Router::new()
+        .route(
+            "/compact",
+            routing::get(|| async { response::DebugValue(STATUS) }),
+        )
+        .route(
+            "/pretty",
+            routing::get(|| async { response::DebugValue(STATUS).pretty() }),
+        )
+        .route(
+            "/custom",
+            routing::get(|| async {
+                response::DebugValue(STATUS)
+                    .with_content_type("text/x-rust")
+                    .pretty()
+                    .compact()
+            }),
+        );
+
+    for (path, expected_content_type, expected_body) in [
+        (
+            "/compact",
+            "text/plain; charset=utf-8",
+            "(\"ok\", [4, 2])\r\n",
+        ),
+        (
+            "/pretty",
+            "text/plain; charset=utf-8",
+            "(\n    \"ok\",\n    [\n        4,\n        2,\n    ],\n)\r\n",
+        ),
+        ("/custom", "text/x-rust", "(\"ok\", [4, 2])\r\n"),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::OK, "{path}");
+        assert_eq!(
+            parts.headers["Content-Type"], expected_content_type,
+            "{path}"
+        );
+        assert_eq!(
+            parts.headers["Content-Length"],
+            expected_body.len().to_string().as_str(),
+            "{path}"
+        );
+        assert_eq!(body, expected_body, "{path}");
+    }
+}
+
+#[tokio::test]
+async fn file_system_service() {
+    use response::fs::{FileInfo, FileSource, FileSystemService};
+
+    #[derive(Debug)]
+    struct FlashError;
+
+    /// Files are stored one after another, and reads fail at `bad_address` to simulate a flash error.
+    struct MockFlash {
+        data: &'static [u8],
+        bad_address: usize,
+    }
+
+    impl FileSource for MockFlash {
+        type Handle = core::ops::Range<usize>;
+        type Error = FlashError;
+
+        async fn open(&self, path: &str) -> Result<Option<FileInfo<Self::Handle>>, Self::Error> {
+            Ok(match path {
+                "index.html" => Some(FileInfo {
+                    handle: 0..13,
+                    length: 13,
+                    content_type: "text/html",
+                }),
+                "data/hello world.txt" => Some(FileInfo {
+                    handle: 13..24,
+                    length: 11,
+                    content_type: "text/plain",
+                }),
+                "bad.bin" => Some(FileInfo {
+                    handle: 24..32,
+                    length: 8,
+                    content_type: "application/octet-stream",
+                }),
+                "unreadable" => return Err(FlashError),
+                path if path
+                    .split('/')
+                    .any(|segment| matches!(segment, "" | "." | "..")) =>
+                {
+                    panic!("Path {path:?} was not confined to the source")
+                }
+                _ => None,
+            })
+        }
+
+        async fn read(
+            &self,
+            handle: &Self::Handle,
+            offset: usize,
+            buffer: &mut [u8],
+        ) -> Result<usize, Self::Error> {
+            let address = handle.start + offset;
+
+            if (address..(address + buffer.len())).contains(&self.bad_address) {
+                return Err(FlashError);
+            }
+
+            buffer.copy_from_slice(&self.data[address..(address + buffer.len())]);
+
+            Ok(buffer.len())
+        }
+    }
+
+    let app = Router::from_service(FileSystemService::<_, 32, 4> {
+        source: MockFlash {
+            data: b"<h1>Home</h1>Hello Flash01234567",
+            bad_address: 30,
+        },
+        index_file: Some("index.html"),
+    });
+
+    for (path, expected_status, expected_content_type, expected_body) in [
+        ("/", StatusCode::OK, Some("text/html"), "<h1>Home</h1>"),
+        (
+            "/data/hello%20world.txt",
+            StatusCode::OK,
+            Some("text/plain"),
+            "Hello Flash",
+        ),
+        (
+            "/missing",
+            StatusCode::NOT_FOUND,
+            None,
+            "/missing not found\r\n",
+        ),
+        (
+            "/a-path-which-is-longer-than-the-buffer",
+            StatusCode::NOT_FOUND,
+            None,
+            "/a-path-which-is-longer-than-the-buffer not found\r\n",
+        ),
+        (
+            "/%2e%2e%2findex.html",
+            StatusCode::NOT_FOUND,
+            None,
+            "/%2e%2e%2findex.html not found\r\n",
+        ),
+        (
+            "/data/../index.html",
+            StatusCode::NOT_FOUND,
+            None,
+            "/data/../index.html not found\r\n",
+        ),
+        (
+            "/data%2f.%2fhello%20world.txt",
+            StatusCode::NOT_FOUND,
+            None,
+            "/data%2f.%2fhello%20world.txt not found\r\n",
+        ),
+        (
+            "/data//hello%20world.txt",
+            StatusCode::NOT_FOUND,
+            None,
+            "/data//hello%20world.txt not found\r\n",
+        ),
+        (
+            "/unreadable",
+            StatusCode::INTERNAL_SERVER_ERROR,
+            None,
+            "Failed to read file\r\n",
+        ),
+    ] {
+        let (parts, body) = run_single_request_test(
+            &app,
+            hyper::Request::get(path).body(Default::default()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(parts.status, expected_status, "{path}");
+        if let Some(expected_content_type) = expected_content_type {
+            assert_eq!(
+                parts.headers["Content-Type"], expected_content_type,
+                "{path}"
+            );
+        }
+        assert_eq!(body, expected_body, "{path}");
+    }
+
+    let response = String::from_utf8(
+        test::send_request(&app, b"HEAD / HTTP/1.1\r\n\r\nPOST / HTTP/1.1\r\n\r\n").await,
+    )
+    .unwrap();
+
+    let mut responses = response.split("HTTP/1.1 ").skip(1);
+
+    let head = responses.next().unwrap();
+    assert!(head.starts_with("200 OK\r\n"));
+    assert!(head.contains("\r\nContent-Length: 13\r\n"));
+    assert!(head.ends_with("\r\n\r\n"));
+
+    assert!(responses
+        .next()
+        .unwrap()
+        .starts_with("405 Method Not Allowed\r\n"));
+
+    // The read error occurs after the headers have been sent, so the body is cut short and the connection is closed
+    let response = String::from_utf8(
+        test::send_request(&app, b"GET /bad.bin HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n").await,
+    )
+    .unwrap();
+
+    assert_eq!(response.matches("HTTP/1.1 ").count(), 1);
+    assert!(response.contains("\r\nContent-Length: 8\r\n"));
+    assert!(response.ends_with("\r\n\r\n0123"));
+}
+
+#[test]
+fn base64_rfc_4648_vectors() {
+    use crate::base64;
+
+    for (decoded, encoded) in [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ] {
+        assert_eq!(base64::encoded_len(decoded.len()), encoded.len());
+        assert_eq!(
+            base64::encode_into(decoded.as_bytes(), &mut [0; 8]),
+            Ok(encoded)
+        );
+        assert_eq!(
+            base64::Encoded(decoded.as_bytes()).to_string(),
+            encoded,
+            "{decoded}"
+        );
+        assert_eq!(
+            base64::decode_into(encoded.as_bytes(), &mut [0; 6]).map(|decoded| &*decoded),
+            Ok(decoded.as_bytes())
+        );
+    }
+
+    let long_value = [0xfb; 100];
+    let mut buffer = [0; 136];
+    assert_eq!(
+        base64::Encoded(&long_value).to_string(),
+        base64::encode_into(&long_value, &mut buffer).unwrap()
+    );
+
+    assert_eq!(
+        base64::encode_into(b"foob", &mut [0; 7]),
+        Err(base64::BufferTooSmall)
+    );
+    assert_eq!(
+        base64::decode_into(b"Zm9vYg==", &mut [0; 5]).map(|decoded| &*decoded),
+        Err(base64::DecodeError::BufferTooSmall)
+    );
+    assert_eq!(
+        base64::decode_into(b"Zm9vY", &mut [0; 6]).map(|decoded| &*decoded),
+        Err(base64::DecodeError::InvalidLength)
+    );
+    assert_eq!(
+        base64::decode_into(b"Zm9v!mFy", &mut [0; 6]).map(|decoded| &*decoded),
+        Err(base64::DecodeError::InvalidCharacter { position: 4 })
+    );
+}
+
+#[tokio::test]
+async fn disconnection_byte_counts() {
+    static DISCONNECTIONS: std::sync::Mutex<Vec<DisconnectionInfo>> =
+        std::sync::Mutex::new(Vec::new());
+
+    let app = Router::new().route("/", routing::get(|| async { "Hello World" }));
+
+    let config = Config::new(Timeouts::new(None, None, None))
+        .keep_connection_alive()
+        .on_disconnection(|info| DISCONNECTIONS.lock().unwrap().push(*info));
+
+    let request = b"GET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\nConnection: close\r\n\r\n";
+
+    let response = test::send_request_with_config(&app, &config, &(), request).await;
+
+    assert_eq!(
+        DISCONNECTIONS.lock().unwrap().as_slice(),
+        [DisconnectionInfo {
+            remote_address: None,
+            bytes_read: request.len() as u64,
+            bytes_written: response.len() as u64,
+        }]
+    );
+    assert_eq!(
+        String::from_utf8(response)
+            .unwrap()
+            .matches("\r\n\r\nHello World")
+            .count(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn method_set_router() {
+    use routing::AllowedMethods;
+
+    let app = Router::new()
+        .route(
+            "/",
+            routing::on(AllowedMethods::GET.or(AllowedMethods::DELETE), || async {
+                "Read or Delete"
+            }),
+        )
+        .route(
+            "/write",
+            routing::on(AllowedMethods::POST.or(AllowedMethods::PUT), || async {
+                "Written"
+            }),
+        );
+
+    let response = String::from_utf8(
+        test::send_request(
+            &app,
+            b"GET / HTTP/1.1\r\n\r\nDELETE / HTTP/1.1\r\n\r\nHEAD / HTTP/1.1\r\n\r\nPOST / HTTP/1.1\r\n\r\nPUT /write HTTP/1.1\r\n\r\nGET /write HTTP/1.1\r\n\r\n",
+        )
+        .await,
+    )
+    .unwrap();
+
+    let mut responses = response.split("HTTP/1.1 ").skip(1);
+
+    assert!(responses
+        .next()
+        .unwrap()
+        .ends_with("\r\n\r\nRead or Delete"));
+    assert!(responses
+        .next()
+        .unwrap()
+        .ends_with("\r\n\r\nRead or Delete"));
+
+    let head = responses.next().unwrap();
+    assert!(head.starts_with("200 OK\r\n"));
+    assert!(head.contains("\r\nContent-Length: 14\r\n"));
+    assert!(head.ends_with("\r\n\r\n"));
+
+    let post = responses.next().unwrap();
+    assert!(post.starts_with("405 Method Not Allowed\r\n"));
+    assert!(post.contains("\r\nAllow: GET, HEAD, DELETE\r\n"));
+
+    assert!(responses.next().unwrap().ends_with("\r\n\r\nWritten"));
+
+    let get = responses.next().unwrap();
+    assert!(get.starts_with("405 Method Not Allowed\r\n"));
+    assert!(get.contains("\r\nAllow: POST, PUT\r\n"));
+
+    assert!(responses.next().is_none());
+}
+
+#[tokio::test]
+/// Test that a handler can read part of the request body and then stream a response for longer than the configured timeouts,
+/// as the timeouts only apply to reading the request head and to each individual write.
+async fn streaming_response_outlives_short_timeouts() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    const STEP: Duration = Duration::from_millis(50);
+
+    struct LongPollChunks {
+        request_id: [u8; 4],
+    }
+
+    impl response::chunked::Chunks for LongPollChunks {
+        fn content_type(&self) -> &'static str {
+            "text/plain"
+        }
+
+        async fn write_chunks<W: io::Write>(
+            self,
+            mut chunk_writer: response::chunked::ChunkWriter<W>,
+        ) -> Result<response::chunked::ChunksWritten, W::Error> {
+            chunk_writer.write_chunk(&self.request_id).await?;
+            chunk_writer.flush().await?;
+
+            for index in 0..5 {
+                tokio::time::sleep(3 * STEP).await;
+
+                write!(chunk_writer, "event {index}").await?;
+                chunk_writer.flush().await?;
+            }
+
+            chunk_writer.finalize().await
+        }
+    }
+
+    struct LongPoll;
+
+    impl routing::RequestHandlerService<()> for LongPoll {
+        async fn call_request_handler_service<
+            R: embedded_io_async::Read,
+            W: response::ResponseWriter<Error = R::Error>,
+        >(
+            &self,
+            (): &(),
+            (): (),
+            mut request: request::Request<'_, R>,
+            response_writer: W,
+        ) -> Result<ResponseSent, W::Error> {
+            use response::IntoResponse;
+
+            let mut request_id = [0; 4];
+
+            request
+                .body_connection
+                .body()
+                .reader()
+                .read_exact(&mut request_id)
+                .await
+                .unwrap();
+
+            let connection = request.body_connection.finalize().await?;
+
+            response::chunked::ChunkedResponse::new(LongPollChunks { request_id })
+                .write_to(connection, response_writer)
+                .await
+        }
+    }
+
+    let app = Router::new().route("/poll", routing::post_service(LongPoll));
+
+    let config = Config::new(Timeouts::new(
+        Some(2 * STEP),
+        Some(2 * STEP),
+        Some(2 * STEP),
+    ));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+    let client = tokio::spawn({
+        let address = listener.local_addr().unwrap();
+
+        async move {
+            let mut client = tokio::net::TcpStream::connect(address).await.unwrap();
+
+            client
+                .write_all(b"POST /poll HTTP/1.1\r\nConnection: close\r\nContent-Length: 12\r\n\r\nabcdpadding!")
+                .await
+                .unwrap();
+
+            let mut response = Vec::new();
+            client.read_to_end(&mut response).await.unwrap();
+
+            String::from_utf8(response).unwrap()
+        }
+    });
+
+    let (stream, _) = listener.accept().await.unwrap();
+
+    let mut http_buffer = [0; 2048];
+
+    let request_count = Server::new(&app, &config, &mut http_buffer)
+        .serve(stream)
+        .await
+        .unwrap();
+
+    assert_eq!(request_count, 1);
+
+    let response = client.await.unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response:?}");
+    assert!(
+        response.ends_with(
+            "\r\n\r\n4\r\nabcd\r\n7\r\nevent 0\r\n7\r\nevent 1\r\n7\r\nevent 2\r\n7\r\nevent 3\r\n7\r\nevent 4\r\n0\r\n\r\n"
+        ),
+        "{response:?}"
+    );
+}
+
+#[test]
+fn status_code_reason_phrases() {
+    use response::StatusCode;
+
+    assert!(StatusCode::ALL
+        .windows(2)
+        .all(|pair| pair[0].as_u16() < pair[1].as_u16()));
+
+    for &status_code in StatusCode::ALL {
+        assert_eq!(
+            StatusCode::from_u16(status_code.as_u16()),
+            Some(status_code)
+        );
+
+        let reason_phrase = status_code.canonical_reason().unwrap();
+
+        // The `http` crate omits the hyphen from "Non-Authoritative Information"
+        if status_code.as_u16() != 203 {
+            assert_eq!(
+                Some(reason_phrase),
+                hyper::StatusCode::from_u16(status_code.as_u16())
+                    .unwrap()
+                    .canonical_reason(),
+                "{status_code}"
+            );
+        }
+    }
+
+    // Every status code known to the `http` crate has a constant
+    for code in 100..600 {
+        let status_code = StatusCode::new(code);
+
+        assert_eq!(
+            StatusCode::ALL.contains(&status_code),
+            StatusCode::from_u16(code)
+                .unwrap()
+                .canonical_reason()
+                .is_some(),
+            "{status_code}"
+        );
+
+        assert_eq!(
+            status_code.canonical_reason().is_some(),
+            hyper::StatusCode::from_u16(code)
+                .unwrap()
+                .canonical_reason()
+                .is_some(),
+            "{status_code}"
+        );
+    }
+
+    for (status_code, reason_phrase) in [
+        (StatusCode::CREATED, "Created"),
+        (StatusCode::ACCEPTED, "Accepted"),
+        (StatusCode::NO_CONTENT, "No Content"),
+        (StatusCode::PARTIAL_CONTENT, "Partial Content"),
+        (StatusCode::NOT_MODIFIED, "Not Modified"),
+        (StatusCode::TEMPORARY_REDIRECT, "Temporary Redirect"),
+        (StatusCode::PERMANENT_REDIRECT, "Permanent Redirect"),
+        (StatusCode::UNAUTHORIZED, "Unauthorized"),
+        (StatusCode::FORBIDDEN, "Forbidden"),
+        (StatusCode::CONFLICT, "Conflict"),
+        (StatusCode::PAYLOAD_TOO_LARGE, "Payload Too Large"),
+        (StatusCode::RANGE_NOT_SATISFIABLE, "Range Not Satisfiable"),
+        (StatusCode::UNPROCESSABLE_ENTITY, "Unprocessable Entity"),
+        (StatusCode::UPGRADE_REQUIRED, "Upgrade Required"),
+        (StatusCode::TOO_MANY_REQUESTS, "Too Many Requests"),
+        (
+            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+            "Request Header Fields Too Large",
+        ),
+        (StatusCode::SERVICE_UNAVAILABLE, "Service Unavailable"),
+        (StatusCode::EARLY_HINTS, "Early Hints"),
+        (StatusCode::TOO_EARLY, "Too Early"),
+    ] {
+        assert_eq!(status_code.canonical_reason(), Some(reason_phrase));
+    }
+}
+
+#[cfg(feature = "decompression")]
+#[tokio::test]
+/// Test that gzip and deflate request bodies are decompressed before reaching the handler, and that the decompressed size is limited
+async fn decompress_request_body() {
+    const READING: &[u8] = br#"{"sensor":1,"value":2.5}"#;
+
+    // `READING` repeated 20 times, compressed using fixed Huffman codes
+    const GZIP_FIXED: &[u8] = b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\xab\x56\x2a\x4e\xcd\x2b\xce\x2f\x52\xb2\x32\xd4\x51\x2a\x4b\xcc\x29\x4d\x55\xb2\x32\xd2\x33\xad\xad\x1e\x15\x1f\x16\xe2\x00\x4c\xc0\x4f\x3e\xe0\x01\x00\x00";
+
+    // `READING` in a stored block
+    const ZLIB_STORED: &[u8] = b"\x78\x01\x01\x18\x00\xe7\xff\x7b\x22\x73\x65\x6e\x73\x6f\x72\x22\x3a\x31\x2c\x22\x76\x61\x6c\x75\x65\x22\x3a\x32\x2e\x35\x7d\x64\x9c\x07\x9e";
+
+    // 4096 zero bytes, compressed using dynamic Huffman codes
+    const GZIP_ZEROS: &[u8] = b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\xed\xc1\x01\x0d\x00\x00\x00\xc2\xa0\xf7\x4f\x6d\x0f\x07\x14\x00\x00\x00\xf0\x6e\x11\x00\x1c\xc7\x00\x10\x00\x00";
+
+    let corrupted_checksum = {
+        let mut body = GZIP_FIXED.to_vec();
+        let checksum_index = body.len() - 8;
+        body[checksum_index] ^= 1;
+        body
+    };
+
+    let corrupted_adler32 = {
+        let mut body = ZLIB_STORED.to_vec();
+        *body.last_mut().unwrap() ^= 1;
+        body
+    };
+
+    let truncated = &GZIP_FIXED[..GZIP_FIXED.len() / 2];
+
+    let echo = || {
+        routing::post(|extract::BoundedBody(body): extract::BoundedBody<4096>| async move { body })
+    };
+
+    let app = Router::new()
+        .route("/small", echo())
+        .layer(routing::DecompressRequestBodyLayer::<1024>)
+        .route(
+            "/large",
+            echo().layer(routing::DecompressRequestBodyLayer::<4096>),
+        );
+
+    for (path, content_encoding, body, expected_status, expected_body) in [
+        (
+            "/small",
+            None,
+            READING.to_vec(),
+            StatusCode::OK,
+            READING.to_vec(),
+        ),
+        (
+            "/small",
+            Some("identity"),
+            READING.to_vec(),
+            StatusCode::OK,
+            READING.to_vec(),
+        ),
+        (
+            "/small",
+            Some("gzip"),
+            GZIP_FIXED.to_vec(),
+            StatusCode::OK,
+            READING.repeat(20),
+        ),
+        (
+            "/small",
+            Some("deflate"),
+            ZLIB_STORED.to_vec(),
+            StatusCode::OK,
+            READING.to_vec(),
+        ),
+        (
+            "/large",
+            Some("GZIP"),
+            GZIP_ZEROS.to_vec(),
+            StatusCode::OK,
+            vec![0; 4096],
+        ),
+        (
+            "/small",
+            Some("gzip"),
+            GZIP_ZEROS.to_vec(),
+            StatusCode::PAYLOAD_TOO_LARGE,
+            b"Body is too large\n".to_vec(),
+        ),
+        (
+            "/small",
+            Some("gzip"),
+            corrupted_checksum,
+            StatusCode::BAD_REQUEST,
+            b"Body is not correctly compressed\n".to_vec(),
+        ),
+        (
+            "/small",
+            Some("deflate"),
+            corrupted_adler32,
+            StatusCode::BAD_REQUEST,
+            b"Body is not correctly compressed\n".to_vec(),
+        ),
+        (
+            "/small",
+            Some("gzip"),
+            truncated.to_vec(),
+            StatusCode::BAD_REQUEST,
+            b"Body is not correctly compressed\n".to_vec(),
+        ),
+        (
+            "/small",
+            Some("deflate"),
+            GZIP_FIXED.to_vec(),
+            StatusCode::BAD_REQUEST,
+            b"Body is not correctly compressed\n".to_vec(),
+        ),
+        (
+            "/small",
+            Some("br"),
+            READING.to_vec(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            b"Unsupported Content-Encoding\n".to_vec(),
+        ),
+    ] {
+        let mut request = hyper::Request::post(path);
+
+        if let Some(content_encoding) = content_encoding {
+            request = request.header("Content-Encoding", content_encoding);
+        }
+
+        let (parts, response_body) =
+            run_single_request_test(&app, request.body(body.into()).unwrap()).await;
+
+        assert_eq!(parts.status, expected_status, "{path} {content_encoding:?}");
+        assert_eq!(response_body, expected_body, "{path} {content_encoding:?}");
+    }
+
+    // The connection is kept alive after a decompressed request
+    let mut requests = Vec::from(
+        &b"POST /small HTTP/1.1\r\nContent-Encoding: deflate\r\nContent-Length: 35\r\n\r\n"[..],
+    );
+    requests.extend_from_slice(ZLIB_STORED);
+    requests.extend_from_slice(b"POST /small HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}");
+
+    let response = String::from_utf8(test::send_request(&app, &requests).await).unwrap();
+
+    assert_eq!(
+        response.matches("HTTP/1.1 200 OK\r\n").count(),
+        2,
+        "{response:?}"
+    );
+    assert!(
+        response.contains(core::str::from_utf8(READING).unwrap()),
+        "{response:?}"
+    );
+    assert!(response.ends_with("\r\n\r\n{}"), "{response:?}");
+}
+
+#[tokio::test]
+/// Test that `AllParts` gives a plain handler function an owned copy of the request parts
+async fn extract_all_parts() {
+    async fn describe<const N: usize>(parts: extract::AllParts<N>) -> heapless::String<128> {
+        use core::fmt::Write;
+
+        let mut description = heapless::String::new();
+
+        write!(
+            description,
+            "{} {} {:?} {:?}",
+            parts.method(),
+            parts.path().encoded(),
+            parts.query().map(|query| query.0),
+            parts
+                .headers()
+                .get("X-Custom")
+                .map(|value| core::str::from_utf8(value.as_raw()).unwrap()),
+        )
+        .unwrap();
+
+        description
+    }
+
+    let app = Router::new()
+        .route("/foo", routing::get(describe::<256>))
+        .route("/small", routing::get(describe::<16>));
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/foo?bar=baz")
+            .header("X-Custom", "value")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(body, r#"GET /foo Some("bar=baz") Some("value")"#.as_bytes());
+
+    let (parts, body) = run_single_request_test(
+        &app,
+        hyper::Request::get("/small")
+            .header("X-Custom", "value")
+            .body(Default::default())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(parts.status, StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+    assert_eq!(body, "Request is too large\n".as_bytes());
+}
+
+#[test]
+/// Test that chunked request bodies are decoded, and that their trailers are available to handlers
+fn chunked_request_body_trailers() {
+    struct EchoBodyAndTrailers;
+
+    impl routing::RequestHandlerService<()> for EchoBodyAndTrailers {
+        async fn call_request_handler_service<
+            R: Read,
+            W: response::ResponseWriter<Error = R::Error>,
+        >(
+            &self,
+            (): &(),
+            (): (),
+            request: request::Request<'_, R>,
+            response_writer: W,
+        ) -> Result<ResponseSent, W::Error> {
+            let trailers = request.body_connection.trailers();
+
+            let (body, connection) = request.body_connection.read_all_and_finalize().await?;
+
+            let mut response = heapless::String::<128>::new();
+
+            response
+                .push_str(core::str::from_utf8(body.unwrap()).unwrap())
+                .unwrap();
+
+            for (name, value) in trailers {
+                core::fmt::Write::write_fmt(
+                    &mut response,
+                    format_args!(";{}={}", name.as_str().unwrap(), value.as_str().unwrap()),
+                )
+                .unwrap();
+            }
+
+            response_writer
+                .write_response(connection, response::Response::ok(response))
+                .await
+        }
+    }
+
+    let app = Router::new().route("/", routing::post_service(EchoBodyAndTrailers));
+
+    let config = Config::new(Timeouts::new(None, None, None)).keep_connection_alive();
+
+    let serve = |header_buffer: &mut [u8], body_buffer: &mut [u8], request: &[u8]| {
+        let mut response = Vec::new();
+
+        Server::with_buffers(&app, &config, header_buffer, body_buffer)
+            .serve_and_shutdown(
+                time::TokioTimer,
+                TestSocket {
+                    rx: request,
+                    tx: &mut response,
+                },
+                &(),
+                core::future::pending(),
+            )
+            .now_or_never()
+            .expect("Server has stalled")
+            .unwrap();
+
+        let response = String::from_utf8(response).unwrap();
+
+        response
+            .split("HTTP/1.1 ")
+            .skip(1)
+            .map(|response| {
+                let (head, body) = response.split_once("\r\n\r\n").unwrap();
+
+                (
+                    head.lines().next().unwrap().to_owned(),
+                    head.contains("Connection: close"),
+                    body.to_owned(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let ok = |body: &str| ("200 OK".to_owned(), false, body.to_owned());
+
+    // A chunked body with chunk extensions and trailers, followed by a request with a "Content-Length"
+    let request = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n\
+        5\r\nHello\r\n7;name=value\r\n, World\r\n0\r\ngrpc-status: 0\r\ngrpc-message: OK\r\n\r\n\
+        POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\nHi";
+
+    assert_eq!(
+        serve(&mut [0; 256], &mut [0; 256], request),
+        [ok("Hello, World;grpc-status=0;grpc-message=OK"), ok("Hi")]
+    );
+
+    // The chunked body is decoded into the body buffer, which is too small for the encoded body but not the decoded body,
+    // and the following request is carried over to the next request
+    let request = [
+        &b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n"[..],
+        &b"1\r\na\r\n".repeat(20),
+        b"0\r\nx-count: 20\r\n\r\nPOST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nHi\r\n0\r\n\r\n",
+    ]
+    .concat();
+
+    assert_eq!(
+        serve(&mut [0; 256], &mut [0; 64], &request),
+        [ok(&format!("{};x-count=20", "a".repeat(20))), ok("Hi")]
+    );
+
+    // The chunked body does not fit into the buffer
+    let request = [
+        &b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n100\r\n"[..],
+        &[b'a'; 256],
+        b"\r\n0\r\n\r\n",
+    ]
+    .concat();
+
+    assert_eq!(
+        serve(&mut [0; 128], &mut [0; 64], &request),
+        [(
+            "413 Payload Too Large".to_owned(),
+            true,
+            "Chunked body is too large".to_owned()
+        )]
+    );
+
+    // The chunk size is not valid
+    assert_eq!(
+        serve(
+            &mut [0; 256],
+            &mut [0; 256],
+            b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n+5\r\nHello\r\n0\r\n\r\n"
+        ),
+        [(
+            "400 Bad Request".to_owned(),
+            true,
+            "Invalid chunked body".to_owned()
+        )]
+    );
+
+    // A request with both "Content-Length" and "Transfer-Encoding" is rejected, whichever comes first
+    for request in [
+        &b"POST / HTTP/1.1\r\nContent-Length: 0\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nHi\r\n0\r\n\r\n"[..],
+        b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\nContent-Length: 13\r\n\r\n2\r\nHi\r\n0\r\n\r\n",
+        b"POST / HTTP/1.1\r\nTransfer-Encoding: gzip\r\nContent-Length: 2\r\n\r\nHi",
+    ] {
+        assert_eq!(
+            serve(&mut [0; 256], &mut [0; 256], request),
+            [(
+                "400 Bad Request".to_owned(),
+                true,
+                "Both Content-Length and Transfer-Encoding are present".to_owned()
+            )]
+        );
+    }
+
+    // A request with several "Content-Length" headers is rejected, even if they agree
+    for request in [
+        &b"POST / HTTP/1.1\r\nContent-Length: 2\r\nContent-Length: 0\r\n\r\nHi"[..],
+        b"POST / HTTP/1.1\r\nContent-Length: 2\r\nContent-Length: 2\r\n\r\nHi",
+    ] {
+        assert_eq!(
+            serve(&mut [0; 256], &mut [0; 256], request),
+            [(
+                "400 Bad Request".to_owned(),
+                true,
+                "Invalid Content-Length".to_owned()
+            )]
+        );
+    }
+}
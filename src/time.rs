@@ -1,4 +1,28 @@
-//! [Timer] for creating timeouts during request parsing and request handling.
+//! [Timer] for creating timeouts during request parsing and request handling, and [Instant] for measuring time.
+
+/// A reading of a monotonic clock, measured in microseconds since an arbitrary fixed point, such as when the device booted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Instant {
+    micros: u64,
+}
+
+impl Instant {
+    /// Create an instant from the number of microseconds since the fixed point of the clock.
+    pub const fn from_micros(micros: u64) -> Self {
+        Self { micros }
+    }
+
+    /// Return the number of microseconds since the fixed point of the clock.
+    pub const fn as_micros(self) -> u64 {
+        self.micros
+    }
+
+    /// Return the time elapsed between `earlier` and `self`, or zero if `earlier` is later than `self`.
+    pub const fn duration_since(self, earlier: Self) -> core::time::Duration {
+        core::time::Duration::from_micros(self.micros.saturating_sub(earlier.micros))
+    }
+}
 
 /// A timer which can be used to abort futures if they take to long to resolve.
 pub trait Timer {
@@ -13,6 +37,87 @@ pub trait Timer {
         duration: Self::Duration,
         future: F,
     ) -> Result<F::Output, Self::TimeoutError>;
+
+    /// Read the current time, or `None` if the timer cannot measure time, which is the default.
+    ///
+    /// The precision of the reading depends on the runtime, such as the tick rate of the embassy time driver.
+    fn now() -> Option<Instant> {
+        None
+    }
+
+    /// Convert `duration` into microseconds, so that it can be compared with readings from [now](Self::now), or `None` if the timer cannot measure time, which is the default.
+    fn duration_as_micros(_duration: &Self::Duration) -> Option<u64> {
+        None
+    }
+
+    /// Wait until `duration` has elapsed, such as to debounce or retry an operation inside a handler.
+    ///
+    /// Handlers which are generic over the [Timer], which can be provided as part of the application state,
+    /// are portable between runtimes, such as when testing an application using `tokio` which runs on `embassy`.
+    ///
+    /// ```
+    /// use picoserve::{extract::State, response::DebugValue, routing::get, time::Timer};
+    ///
+    /// #[derive(Clone)]
+    /// struct AppState<T: Timer> {
+    ///     timer: T,
+    ///     retry_delay: T::Duration,
+    /// }
+    ///
+    /// fn try_read_sensor(attempt: u8) -> Option<u16> {
+    ///     (attempt > 0).then_some(42)
+    /// }
+    ///
+    /// async fn read_sensor<T: Timer + Clone>(
+    ///     State(AppState { mut timer, retry_delay }): State<AppState<T>>,
+    /// ) -> DebugValue<Option<u16>> {
+    ///     for attempt in 0..3 {
+    ///         if let Some(value) = try_read_sensor(attempt) {
+    ///             return DebugValue(Some(value));
+    ///         }
+    ///
+    ///         timer.sleep(retry_delay.clone()).await;
+    ///     }
+    ///
+    ///     DebugValue(None)
+    /// }
+    ///
+    /// fn make_app<T: Timer + Clone + 'static>(
+    /// ) -> picoserve::Router<impl picoserve::routing::PathRouter<AppState<T>>, AppState<T>> {
+    ///     picoserve::Router::new().route("/sensor", get(read_sensor::<T>))
+    /// }
+    ///
+    /// #[cfg(feature = "tokio")]
+    /// async fn serve_using_tokio(
+    ///     config: &picoserve::Config<std::time::Duration>,
+    ///     stream: tokio::net::TcpStream,
+    /// ) {
+    ///     let state = AppState {
+    ///         timer: picoserve::time::TokioTimer,
+    ///         retry_delay: std::time::Duration::from_millis(10),
+    ///     };
+    ///
+    ///     let _ = picoserve::serve_with_state(&make_app(), config, &mut [0; 2048], stream, &state).await;
+    /// }
+    ///
+    /// #[cfg(feature = "embassy")]
+    /// async fn serve_using_embassy(
+    ///     config: &picoserve::Config<embassy_time::Duration>,
+    ///     socket: embassy_net::tcp::TcpSocket<'_>,
+    /// ) {
+    ///     let state = AppState {
+    ///         timer: picoserve::time::EmbassyTimer,
+    ///         retry_delay: embassy_time::Duration::from_millis(10),
+    ///     };
+    ///
+    ///     let _ = picoserve::serve_with_state(&make_app(), config, &mut [0; 2048], socket, &state).await;
+    /// }
+    /// ```
+    async fn sleep(&mut self, duration: Self::Duration) {
+        let _ = self
+            .run_with_timeout(duration, core::future::pending::<()>())
+            .await;
+    }
 }
 
 pub(crate) trait TimerExt: Timer {
@@ -31,8 +136,10 @@ pub(crate) trait TimerExt: Timer {
 
 impl<T: Timer> TimerExt for T {}
 
+/// The [Timer] used when serving connections using `tokio`, which can be passed to handlers to [sleep](Timer::sleep).
 #[cfg(any(feature = "tokio", test))]
-pub(crate) struct TokioTimer;
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioTimer;
 
 #[cfg(any(feature = "tokio", test))]
 impl Timer for TokioTimer {
@@ -46,10 +153,26 @@ impl Timer for TokioTimer {
     ) -> Result<F::Output, Self::TimeoutError> {
         tokio::time::timeout(duration, future).await
     }
+
+    fn now() -> Option<Instant> {
+        static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+
+        let elapsed = EPOCH.get_or_init(std::time::Instant::now).elapsed();
+
+        Some(Instant::from_micros(
+            elapsed.as_micros().try_into().unwrap_or(u64::MAX),
+        ))
+    }
+
+    fn duration_as_micros(duration: &Self::Duration) -> Option<u64> {
+        Some(duration.as_micros().try_into().unwrap_or(u64::MAX))
+    }
 }
 
+/// The [Timer] used when serving connections using `embassy`, which can be passed to handlers to [sleep](Timer::sleep).
 #[cfg(feature = "embassy")]
-pub(crate) struct EmbassyTimer;
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbassyTimer;
 
 #[cfg(feature = "embassy")]
 impl Timer for EmbassyTimer {
@@ -63,6 +186,16 @@ impl Timer for EmbassyTimer {
     ) -> Result<F::Output, Self::TimeoutError> {
         embassy_time::with_timeout(duration, future).await
     }
+
+    fn now() -> Option<Instant> {
+        Some(Instant::from_micros(
+            embassy_time::Instant::now().as_micros(),
+        ))
+    }
+
+    fn duration_as_micros(duration: &Self::Duration) -> Option<u64> {
+        Some(duration.as_micros())
+    }
 }
 
 pub(crate) struct WriteWithTimeout<'t, W: embedded_io_async::Write, T: Timer> {
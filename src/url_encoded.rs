@@ -247,6 +247,26 @@ impl<'a> UrlEncodedString<'a> {
         Ok(str)
     }
 
+    /// Try decoding the chars into `buffer`, returning the decoded string, which borrows from `buffer`.
+    ///
+    /// Unlike [try_into_string](Self::try_into_string), the capacity is chosen at runtime, so a single buffer can be reused to decode several strings.
+    pub fn decode_into(self, buffer: &mut [u8]) -> Result<&str, DecodeError> {
+        let mut length = 0;
+
+        for c in self.chars() {
+            let c = c.map_err(DecodeError::BadUrlEncodedCharacter)?.into_char();
+
+            let end = length + c.len_utf8();
+
+            c.encode_utf8(buffer.get_mut(length..end).ok_or(DecodeError::NoSpace)?);
+
+            length = end;
+        }
+
+        // The buffer only contains whole encoded characters, so is always valid UTF-8
+        Ok(core::str::from_utf8(&buffer[..length]).unwrap_or_default())
+    }
+
     #[cfg(feature = "std")]
     /// Try decoding the chars into a std::string::String.
     pub fn try_into_std_string(